@@ -1,10 +1,251 @@
 fn main() {
     linker_be_nice();
+    println!("cargo:rustc-env=BUILD_TIMESTAMP={}", build_timestamp());
+    check_lvgl_fonts();
+    launcher_tiles();
+    loading_frames();
     println!("cargo:rustc-link-arg=-Tdefmt.x");
     // make sure linkall.x is the last linker script (otherwise might cause problems with flip-link)
     println!("cargo:rustc-link-arg=-Tlinkall.x");
 }
 
+/// UTC build time as `YYYY-MM-DD HH:MM`, honoring `SOURCE_DATE_EPOCH` for reproducible builds
+fn build_timestamp() -> String {
+    let secs = std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|epoch| epoch.parse::<u64>().ok())
+        .unwrap_or_else(|| {
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_secs()
+        });
+
+    // Civil from days, see http://howardhinnant.github.io/date_algorithms.html
+    let days = (secs / 86400) as i64 + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}",
+        year,
+        month,
+        day,
+        secs / 3600 % 24,
+        secs / 60 % 60
+    )
+}
+
+/// Font features, the `lv_conf.h` define that builds each font and whether
+/// it is on there without a `-D` flag
+const LVGL_FONTS: [(&str, &str, bool); 5] = [
+    ("font-12", "LV_FONT_MONTSERRAT_12", false),
+    ("font-16", "LV_FONT_MONTSERRAT_16", false),
+    ("font-24", "LV_FONT_MONTSERRAT_24", false),
+    ("font-32", "LV_FONT_MONTSERRAT_32", true),
+    ("font-cjk", "LV_FONT_SOURCE_HAN_SANS_SC_16_CJK", true),
+];
+
+/// LVGL is compiled by the build script of its own crate, which cannot see
+/// the features of this one, so like `LV_DEMO_LOTTIE` the fonts are picked
+/// with `-D` flags in `CFLAGS`. This makes them agree with the `font-*`
+/// features: a font feature without its font fails here rather than with a
+/// missing symbol, and a font built in without its feature is reported,
+/// since it would take flash for nothing.
+fn check_lvgl_fonts() {
+    println!("cargo:rerun-if-env-changed=CFLAGS");
+    let cflags = std::env::var("CFLAGS").unwrap_or_default();
+    for (feature, define, default) in LVGL_FONTS {
+        let variable = format!("CARGO_FEATURE_{}", feature.to_uppercase().replace('-', "_"));
+        let enabled = std::env::var_os(variable).is_some();
+        let prefix = format!("-D{define}=");
+        let built = cflags
+            .split_whitespace()
+            .find_map(|flag| flag.strip_prefix(&prefix))
+            .map_or(default, |value| value != "0");
+        if enabled && !built {
+            panic!(
+                "The `{feature}` feature needs LVGL built with the font, set \
+                 CFLAGS=\"-D{define}=1\" BINDGEN_EXTRA_CLANG_ARGS=\"-D{define}=1\""
+            );
+        }
+        if !enabled && built {
+            println!(
+                "cargo:warning={define} is built into LVGL without the `{feature}` feature, \
+                 add -D{define}=0 to CFLAGS and BINDGEN_EXTRA_CLANG_ARGS to leave it out"
+            );
+        }
+    }
+}
+
+/// Launcher tile art, one image per `lv_imagebutton` state, in this order
+const TILE_STATES: [&str; 3] = ["released", "pressed", "disabled"];
+/// Width of the rounded ends of a tile, the columns between them are
+/// repeated to fit the label
+const TILE_CAP: usize = 10;
+
+/// Cuts the tiles in `assets/launcher` into left end, middle and right end,
+/// converts them to LVGL's RGB565A8 and writes `launcher_tiles.rs` to
+/// `OUT_DIR` for `ui::launcher` to include.
+///
+/// The tiles are PAM files with an alpha channel, which any PNG converts to
+/// with `magick tile.png tile.pam`.
+fn launcher_tiles() {
+    let out_dir = std::path::PathBuf::from(std::env::var("OUT_DIR").unwrap());
+    let mut code = String::new();
+    let mut table = String::from("static SLICES: [[Slice; 3]; 3] = [\n");
+    for state in TILE_STATES {
+        let path = format!("assets/launcher/{state}.pam");
+        let bytes = std::fs::read(&path).unwrap_or_else(|error| panic!("{path}: {error}"));
+        let (width, height, pixels) =
+            read_pam(&bytes).unwrap_or_else(|| panic!("{path}: not an RGBA PAM file"));
+        assert!(width > 2 * TILE_CAP, "{path}: narrower than its two ends");
+
+        table.push_str("    [\n");
+        let parts = [
+            (0, TILE_CAP),
+            (TILE_CAP, width - TILE_CAP),
+            (width - TILE_CAP, width),
+        ];
+        for ((from, to), part) in parts.into_iter().zip(["left", "middle", "right"]) {
+            let slice_width = to - from;
+            let image = rgb565a8(pixels, width, from..to);
+            let name = format!("{state}_{part}");
+            code.push_str(&embed(&out_dir, &name, &image));
+            table.push_str(&format!(
+                "        Slice {{ width: {slice_width}, height: {height}, data: &{}.0 }},\n",
+                name.to_uppercase()
+            ));
+        }
+        table.push_str("    ],\n");
+    }
+    table.push_str("];\n");
+    code.push_str(&table);
+    std::fs::write(out_dir.join("launcher_tiles.rs"), code).unwrap();
+}
+
+/// Frames of the `ui::animated_image` demo, square
+const FRAME_SIZE: usize = 64;
+const FRAME_COUNT: usize = 8;
+/// Dots around the ring, one lit up per frame
+const FRAME_DOTS: usize = 8;
+const FRAME_COLOR: [u8; 3] = [0x21, 0x96, 0xF3];
+
+/// Draws a loading animation, a ring of dots with a fading tail that goes
+/// around once over the frames, and writes `loading_frames.rs` to `OUT_DIR`
+/// for `ui::animated_image` to include.
+///
+/// It is drawn here rather than kept as images, so the frame count and size
+/// can change without redrawing the art.
+fn loading_frames() {
+    let out_dir = std::path::PathBuf::from(std::env::var("OUT_DIR").unwrap());
+    let mut code = format!("const FRAME_SIZE: u32 = {FRAME_SIZE};\n");
+    let mut table = format!("static FRAMES: [&[u8]; {FRAME_COUNT}] = [\n");
+    let center = FRAME_SIZE as f32 / 2.0;
+    let ring = center * 0.7;
+    let dot = center * 0.18;
+    for frame in 0..FRAME_COUNT {
+        let head = frame as f32 * FRAME_DOTS as f32 / FRAME_COUNT as f32;
+        let mut pixels = vec![0u8; FRAME_SIZE * FRAME_SIZE * 4];
+        for index in 0..FRAME_DOTS {
+            // Full for the head, a quarter for the dot behind the tail
+            let behind = (head - index as f32).rem_euclid(FRAME_DOTS as f32);
+            let strength = 1.0 - behind / FRAME_DOTS as f32 * 0.75;
+            let angle = index as f32 / FRAME_DOTS as f32 * std::f32::consts::TAU;
+            let (dot_x, dot_y) = (center + ring * angle.sin(), center - ring * angle.cos());
+            for y in 0..FRAME_SIZE {
+                for x in 0..FRAME_SIZE {
+                    let distance = (x as f32 + 0.5 - dot_x).hypot(y as f32 + 0.5 - dot_y);
+                    // One pixel of edge smoothing
+                    let coverage = (dot + 0.5 - distance).clamp(0.0, 1.0);
+                    let alpha = (coverage * strength * 255.0).round() as u8;
+                    let pixel = &mut pixels[(y * FRAME_SIZE + x) * 4..][..4];
+                    if alpha > pixel[3] {
+                        pixel[..3].copy_from_slice(&FRAME_COLOR);
+                        pixel[3] = alpha;
+                    }
+                }
+            }
+        }
+        let name = format!("loading_{frame}");
+        code.push_str(&embed(
+            &out_dir,
+            &name,
+            &rgb565a8(&pixels, FRAME_SIZE, 0..FRAME_SIZE),
+        ));
+        table.push_str(&format!("    &{}.0,\n", name.to_uppercase()));
+    }
+    table.push_str("];\n");
+    code.push_str(&table);
+    std::fs::write(out_dir.join("loading_frames.rs"), code).unwrap();
+}
+
+/// Converts the columns `columns` of a `width` wide RGBA image to RGB565A8,
+/// the color plane followed by the alpha plane.
+fn rgb565a8(pixels: &[u8], width: usize, columns: std::ops::Range<usize>) -> Vec<u8> {
+    let height = pixels.len() / 4 / width;
+    let mut colors = Vec::with_capacity(columns.len() * height * 2);
+    let mut alphas = Vec::with_capacity(columns.len() * height);
+    for y in 0..height {
+        for x in columns.clone() {
+            let [r, g, b, a] = pixels[(y * width + x) * 4..][..4] else {
+                unreachable!()
+            };
+            let rgb565 =
+                ((u16::from(r) >> 3) << 11) | ((u16::from(g) >> 2) << 5) | (u16::from(b) >> 3);
+            colors.extend_from_slice(&rgb565.to_le_bytes());
+            alphas.push(a);
+        }
+    }
+    colors.extend_from_slice(&alphas);
+    colors
+}
+
+/// Writes `bytes` to `OUT_DIR` and returns the line that includes them as
+/// the static `NAME`, aligned for LVGL
+fn embed(out_dir: &std::path::Path, name: &str, bytes: &[u8]) -> String {
+    let file = out_dir.join(format!("{name}.bin"));
+    std::fs::write(&file, bytes).unwrap();
+    format!(
+        "static {}: Aligned<{}> = Aligned(*include_bytes!({:?}));\n",
+        name.to_uppercase(),
+        bytes.len(),
+        file.display().to_string()
+    )
+}
+
+/// Width, height and RGBA bytes of a PAM image with `TUPLTYPE RGB_ALPHA`
+fn read_pam(bytes: &[u8]) -> Option<(usize, usize, &[u8])> {
+    const END: &[u8] = b"ENDHDR\n";
+    let header_len = bytes.windows(END.len()).position(|window| window == END)? + END.len();
+    let header = std::str::from_utf8(&bytes[..header_len]).ok()?;
+    let mut lines = header.lines();
+    if lines.next()? != "P7" {
+        return None;
+    }
+    let (mut width, mut height, mut depth, mut maxval, mut rgba) = (0, 0, 0, 0, false);
+    for line in lines {
+        let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+        match key {
+            "WIDTH" => width = value.parse().ok()?,
+            "HEIGHT" => height = value.parse().ok()?,
+            "DEPTH" => depth = value.parse().ok()?,
+            "MAXVAL" => maxval = value.parse().ok()?,
+            "TUPLTYPE" => rgba = value == "RGB_ALPHA",
+            _ => {}
+        }
+    }
+    let pixels = bytes.get(header_len..header_len + width * height * 4)?;
+    (depth == 4 && maxval == 255 && rgba).then_some((width, height, pixels))
+}
+
 fn linker_be_nice() {
     let args: Vec<String> = std::env::args().collect();
     if args.len() > 1 {
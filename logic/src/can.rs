@@ -0,0 +1,228 @@
+use alloc::string::String;
+
+use crate::json::Value;
+
+/// How a signal is shown.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Widget {
+    Gauge,
+    Bar,
+    /// On when the value is not zero, for fault flags
+    Led,
+}
+
+/// A value packed into the frames of one ID.
+///
+/// Its raw bits are `length` bits from bit `start` of the data, read as a
+/// little endian number, or as a big endian one with `big_endian`. The value
+/// is then `raw * factor / divisor + offset`.
+pub struct Signal {
+    pub name: String,
+    pub unit: String,
+    pub id: u32,
+    pub start: u32,
+    pub length: u32,
+    pub big_endian: bool,
+    pub signed: bool,
+    pub factor: i32,
+    pub divisor: i32,
+    pub offset: i32,
+    /// Range of the gauge or bar
+    pub min: i32,
+    pub max: i32,
+    pub widget: Widget,
+}
+
+impl Signal {
+    /// The value in `data`, `None` when the frame is too short for it or longer
+    /// than the 8 bytes CAN allows.
+    pub fn decode(&self, data: &[u8]) -> Option<i32> {
+        let end = self.start.checked_add(self.length)?;
+        if data.len() > 8 || end > data.len() as u32 * 8 {
+            return None;
+        }
+        let mut bytes = [0; 8];
+        bytes[..data.len()].copy_from_slice(data);
+        let packed = if self.big_endian {
+            // Bit 0 is then the last bit of the last byte
+            u64::from_be_bytes(bytes) >> (64 - data.len() as u32 * 8)
+        } else {
+            u64::from_le_bytes(bytes)
+        };
+        let mask = if self.length == 64 {
+            u64::MAX
+        } else {
+            (1 << self.length) - 1
+        };
+        let bits = (packed >> self.start) & mask;
+        let raw = if self.signed && self.length < 64 && bits >> (self.length - 1) != 0 {
+            bits as i64 - (1i64 << self.length)
+        } else {
+            bits as i64
+        };
+        // A 64 bit raw value times any factor still fits
+        let value = i128::from(raw) * i128::from(self.factor) / i128::from(self.divisor)
+            + i128::from(self.offset);
+        Some(value.clamp(i128::from(i32::MIN), i128::from(i32::MAX)) as i32)
+    }
+}
+
+/// One entry of the `signals` array of a signal table, `None` when it misses
+/// a required field or has a bad one.
+///
+/// Each signal needs a `name`, an `id` and a `length` in bits. Optional are
+/// `start` (0), `big_endian` and `signed` (false), `factor` and `divisor`
+/// (1), `offset` (0), `min` (0) and `max` (100), `unit`, and `widget` out of
+/// `gauge`, `bar` and `led` (gauge).
+pub fn signal(description: &Value) -> Option<Signal> {
+    let name = description.get("name").and_then(Value::as_str)?;
+    let number = |key| description.get(key).and_then(Value::as_i32);
+    let flag = |key| description.get(key).and_then(Value::as_bool);
+    let widget = match description.get("widget").and_then(Value::as_str) {
+        None | Some("gauge") => Widget::Gauge,
+        Some("bar") => Widget::Bar,
+        Some("led") => Widget::Led,
+        Some(_) => return None,
+    };
+    let id = number("id").and_then(|id| u32::try_from(id).ok())?;
+    let start = number("start").unwrap_or(0);
+    let length = number("length").filter(|length| (1..=64).contains(length))?;
+    let divisor = number("divisor").unwrap_or(1);
+    // `length` is at most 64, so this cannot overflow where `start + length` could
+    if start < 0 || start > 64 - length || divisor == 0 {
+        return None;
+    }
+    Some(Signal {
+        name: String::from(name),
+        unit: String::from(
+            description
+                .get("unit")
+                .and_then(Value::as_str)
+                .unwrap_or(""),
+        ),
+        id,
+        start: start as u32,
+        length: length as u32,
+        big_endian: flag("big_endian").unwrap_or(false),
+        signed: flag("signed").unwrap_or(false),
+        factor: number("factor").unwrap_or(1),
+        divisor,
+        offset: number("offset").unwrap_or(0),
+        min: number("min").unwrap_or(0),
+        max: number("max").unwrap_or(100),
+        widget,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json;
+
+    fn parsed(text: &str) -> Option<Signal> {
+        signal(&json::parse(text).unwrap())
+    }
+
+    fn bits(start: u32, length: u32) -> Signal {
+        Signal {
+            name: String::new(),
+            unit: String::new(),
+            id: 0,
+            start,
+            length,
+            big_endian: false,
+            signed: false,
+            factor: 1,
+            divisor: 1,
+            offset: 0,
+            min: 0,
+            max: 100,
+            widget: Widget::Gauge,
+        }
+    }
+
+    #[test]
+    fn little_endian() {
+        let rpm = bits(0, 16);
+        assert_eq!(rpm.decode(&[0x34, 0x12]), Some(0x1234));
+        let nibble = bits(4, 4);
+        assert_eq!(nibble.decode(&[0xA5]), Some(0xA));
+    }
+
+    #[test]
+    fn big_endian() {
+        let signal = Signal {
+            big_endian: true,
+            ..bits(0, 16)
+        };
+        assert_eq!(signal.decode(&[0x12, 0x34]), Some(0x1234));
+        // Counted from the end of the frame
+        assert_eq!(signal.decode(&[0x12, 0x34, 0x56]), Some(0x3456));
+        let high = Signal {
+            big_endian: true,
+            ..bits(8, 8)
+        };
+        assert_eq!(high.decode(&[0x12, 0x34]), Some(0x12));
+    }
+
+    #[test]
+    fn signed() {
+        let signal = Signal {
+            signed: true,
+            ..bits(0, 8)
+        };
+        assert_eq!(signal.decode(&[0xFF]), Some(-1));
+        assert_eq!(signal.decode(&[0x80]), Some(-128));
+        assert_eq!(signal.decode(&[0x7F]), Some(127));
+        let wide = Signal {
+            signed: true,
+            ..bits(0, 64)
+        };
+        assert_eq!(wide.decode(&[0xFF; 8]), Some(-1));
+    }
+
+    #[test]
+    fn scaled_and_clamped() {
+        let temperature = Signal {
+            factor: 5,
+            divisor: 10,
+            offset: -40,
+            ..bits(0, 8)
+        };
+        assert_eq!(temperature.decode(&[100]), Some(10));
+        let huge = bits(0, 63);
+        assert_eq!(huge.decode(&[0xFF; 8]), Some(i32::MAX));
+    }
+
+    #[test]
+    fn frames_of_the_wrong_length() {
+        let signal = bits(8, 16);
+        assert_eq!(signal.decode(&[0, 0]), None);
+        assert_eq!(signal.decode(&[0, 0x34, 0x12]), Some(0x1234));
+        assert_eq!(bits(0, 8).decode(&[0; 9]), None);
+    }
+
+    #[test]
+    fn table_entries() {
+        let rpm = parsed(r#"{"name": "RPM", "id": 256, "length": 16, "max": 8000, "unit": "rpm"}"#)
+            .unwrap();
+        assert_eq!((rpm.id, rpm.start, rpm.length, rpm.max), (256, 0, 16, 8000));
+        assert_eq!((rpm.factor, rpm.divisor, rpm.offset, rpm.min), (1, 1, 0, 0));
+        assert_eq!(rpm.unit, "rpm");
+        assert_eq!(rpm.widget, Widget::Gauge);
+        let led = parsed(r#"{"name": "Check", "id": 258, "length": 1, "widget": "led"}"#).unwrap();
+        assert_eq!(led.widget, Widget::Led);
+    }
+
+    #[test]
+    fn bad_entries_are_skipped() {
+        assert!(parsed(r#"{"id": 1, "length": 8}"#).is_none());
+        assert!(parsed(r#"{"name": "A", "length": 8}"#).is_none());
+        assert!(parsed(r#"{"name": "A", "id": -1, "length": 8}"#).is_none());
+        assert!(parsed(r#"{"name": "A", "id": 1, "length": 65}"#).is_none());
+        assert!(parsed(r#"{"name": "A", "id": 1, "length": 8, "start": 57}"#).is_none());
+        assert!(parsed(r#"{"name": "A", "id": 1, "length": 8, "divisor": 0}"#).is_none());
+        assert!(parsed(r#"{"name": "A", "id": 1, "length": 8, "widget": "dial"}"#).is_none());
+        assert!(parsed(r#"{"name": "A", "id": 1, "length": 8, "start": 56}"#).is_some());
+    }
+}
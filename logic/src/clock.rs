@@ -0,0 +1,117 @@
+/// Broken down wall clock time.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct DateTime {
+    pub year: i32,
+    /// 1 to 12
+    pub month: u8,
+    /// 1 to 31
+    pub day: u8,
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+    /// 0 is Monday
+    pub weekday: u8,
+}
+
+impl DateTime {
+    pub fn from_unix(secs: u32) -> Self {
+        let days = (secs / 86400) as i32;
+        let seconds_of_day = secs % 86400;
+
+        // Civil from days, see http://howardhinnant.github.io/date_algorithms.html
+        let z = days + 719468;
+        let era = z.div_euclid(146097);
+        let day_of_era = z.rem_euclid(146097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let mp = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = year_of_era + era * 400 + i32::from(month <= 2);
+
+        Self {
+            year,
+            month: month as u8,
+            day: day as u8,
+            hour: (seconds_of_day / 3600) as u8,
+            minute: (seconds_of_day / 60 % 60) as u8,
+            second: (seconds_of_day % 60) as u8,
+            // 1970-01-01 was a Thursday
+            weekday: ((days + 3) % 7) as u8,
+        }
+    }
+
+    /// Minutes since midnight
+    pub fn minute_of_day(&self) -> u32 {
+        self.hour as u32 * 60 + self.minute as u32
+    }
+}
+
+/// Days since 1970-01-01 of a date, the inverse of the conversion in
+/// [`DateTime::from_unix`]
+pub fn days_from_civil(year: i32, month: u8, day: u8) -> i32 {
+    let (month, day) = (month as i32, day as i32);
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(secs: u32) -> (i32, u8, u8, u8) {
+        let time = DateTime::from_unix(secs);
+        (time.year, time.month, time.day, time.weekday)
+    }
+
+    #[test]
+    fn epoch() {
+        assert_eq!(
+            DateTime::from_unix(0),
+            DateTime {
+                year: 1970,
+                month: 1,
+                day: 1,
+                hour: 0,
+                minute: 0,
+                second: 0,
+                // Thursday
+                weekday: 3,
+            }
+        );
+    }
+
+    #[test]
+    fn known_dates() {
+        // 2000-02-29 12:34:56, a Tuesday in a leap year of a century
+        let time = DateTime::from_unix(951_827_696);
+        assert_eq!((time.year, time.month, time.day), (2000, 2, 29));
+        assert_eq!((time.hour, time.minute, time.second), (12, 34, 56));
+        assert_eq!(time.weekday, 1);
+        assert_eq!(time.minute_of_day(), 12 * 60 + 34);
+        // 2024-12-31, then 2025-01-01
+        assert_eq!(date(1_735_603_200), (2024, 12, 31, 1));
+        assert_eq!(date(1_735_689_600), (2025, 1, 1, 2));
+        // The last second a u32 holds
+        assert_eq!(date(u32::MAX), (2106, 2, 7, 6));
+    }
+
+    #[test]
+    fn days_round_trip() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(1969, 12, 31), -1);
+        assert_eq!(days_from_civil(2000, 3, 1), 11_017);
+        for days in (0..=49_710).step_by(7) {
+            let time = DateTime::from_unix(days * 86400);
+            assert_eq!(
+                days_from_civil(time.year, time.month, time.day),
+                days as i32
+            );
+        }
+    }
+}
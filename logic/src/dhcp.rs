@@ -0,0 +1,195 @@
+use alloc::vec::Vec;
+use core::net::Ipv4Addr;
+
+/// Start of the options, after the fixed fields and the magic cookie
+const OPTIONS: usize = 240;
+const MAGIC_COOKIE: [u8; 4] = [99, 130, 83, 99];
+const LEASE_SECS: u32 = 3600;
+/// Handed out from .100 up, in the order clients show up
+const FIRST_HOST: u8 = 100;
+pub const MAX_CLIENTS: usize = 16;
+
+const DISCOVER: u8 = 1;
+const OFFER: u8 = 2;
+const REQUEST: u8 = 3;
+const ACK: u8 = 5;
+
+const OPTION_SUBNET_MASK: u8 = 1;
+const OPTION_ROUTER: u8 = 3;
+const OPTION_DNS: u8 = 6;
+const OPTION_LEASE_TIME: u8 = 51;
+const OPTION_MESSAGE_TYPE: u8 = 53;
+const OPTION_SERVER_ID: u8 = 54;
+const OPTION_END: u8 = 255;
+
+/// The OFFER or ACK answering a DISCOVER or REQUEST from a client, `None`
+/// for other messages, and for new clients once [`MAX_CLIENTS`] have an
+/// address.
+///
+/// Clients get addresses in the /24 of `server` in the order they show up,
+/// remembered by their MAC in `clients`. `server` is also the router and
+/// DNS server.
+pub fn reply(request: &[u8], clients: &mut Vec<[u8; 6]>, server: Ipv4Addr) -> Option<Vec<u8>> {
+    if request.len() < OPTIONS || request[0] != 1 || request[236..240] != MAGIC_COOKIE {
+        return None;
+    }
+    let reply_type = match message_type(&request[OPTIONS..])? {
+        DISCOVER => OFFER,
+        REQUEST => ACK,
+        _ => return None,
+    };
+
+    let mac: [u8; 6] = request[28..34].try_into().unwrap();
+    let index = match clients.iter().position(|client| *client == mac) {
+        Some(index) => index,
+        None if clients.len() < MAX_CLIENTS => {
+            clients.push(mac);
+            clients.len() - 1
+        }
+        None => return None,
+    };
+    let [a, b, c, _] = server.octets();
+    let client_address = [a, b, c, FIRST_HOST + index as u8];
+    let server = server.octets();
+
+    let mut reply = Vec::with_capacity(300);
+    // Boot reply, Ethernet, 6 byte addresses, no hops
+    reply.extend_from_slice(&[2, 1, 6, 0]);
+    // Transaction id, seconds and flags
+    reply.extend_from_slice(&request[4..12]);
+    reply.extend_from_slice(&[0; 4]);
+    reply.extend_from_slice(&client_address);
+    reply.extend_from_slice(&server);
+    reply.extend_from_slice(&[0; 4]);
+    // Client hardware address, server name and boot file
+    reply.extend_from_slice(&request[28..44]);
+    reply.extend_from_slice(&[0; 192]);
+    reply.extend_from_slice(&MAGIC_COOKIE);
+
+    reply.extend_from_slice(&[OPTION_MESSAGE_TYPE, 1, reply_type]);
+    reply.extend_from_slice(&[OPTION_SERVER_ID, 4]);
+    reply.extend_from_slice(&server);
+    reply.extend_from_slice(&[OPTION_LEASE_TIME, 4]);
+    reply.extend_from_slice(&LEASE_SECS.to_be_bytes());
+    reply.extend_from_slice(&[OPTION_SUBNET_MASK, 4, 255, 255, 255, 0]);
+    reply.extend_from_slice(&[OPTION_ROUTER, 4]);
+    reply.extend_from_slice(&server);
+    reply.extend_from_slice(&[OPTION_DNS, 4]);
+    reply.extend_from_slice(&server);
+    reply.push(OPTION_END);
+    Some(reply)
+}
+
+fn message_type(mut options: &[u8]) -> Option<u8> {
+    loop {
+        match *options {
+            [OPTION_MESSAGE_TYPE, 1, kind, ..] => return Some(kind),
+            [OPTION_END, ..] | [] => return None,
+            // Padding
+            [0, ref rest @ ..] => options = rest,
+            [_, len, ref rest @ ..] => options = rest.get(len as usize..)?,
+            [_] => return None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+
+    const SERVER: Ipv4Addr = Ipv4Addr::new(192, 168, 4, 1);
+
+    fn request(kind: u8, mac: u8) -> Vec<u8> {
+        let mut request = vec![0; OPTIONS];
+        request[..4].copy_from_slice(&[1, 1, 6, 0]);
+        request[4..8].copy_from_slice(&[0xDE, 0xAD, 0xBE, 0xEF]);
+        request[28..34].copy_from_slice(&[2, 0, 0, 0, 0, mac]);
+        request[236..240].copy_from_slice(&MAGIC_COOKIE);
+        // Padding and a parameter request list before the message type
+        request.extend_from_slice(&[0, 55, 2, 1, 3, OPTION_MESSAGE_TYPE, 1, kind, OPTION_END]);
+        request
+    }
+
+    /// Value of the option `code` in `reply`.
+    fn option(reply: &[u8], code: u8) -> &[u8] {
+        let mut options = &reply[OPTIONS..];
+        loop {
+            let [kind, len, ref rest @ ..] = *options else {
+                panic!("no option {code}");
+            };
+            if kind == code {
+                return &rest[..len as usize];
+            }
+            options = &rest[len as usize..];
+        }
+    }
+
+    #[test]
+    fn offer_then_ack() {
+        let mut clients = Vec::new();
+        let offer = reply(&request(DISCOVER, 1), &mut clients, SERVER).unwrap();
+        assert_eq!(offer[0], 2);
+        // Same transaction
+        assert_eq!(offer[4..8], [0xDE, 0xAD, 0xBE, 0xEF]);
+        assert_eq!(offer[16..20], [192, 168, 4, 100]);
+        assert_eq!(offer[20..24], SERVER.octets());
+        assert_eq!(offer[28..34], [2, 0, 0, 0, 0, 1]);
+        assert_eq!(offer[236..240], MAGIC_COOKIE);
+        assert_eq!(option(&offer, OPTION_MESSAGE_TYPE), [OFFER]);
+        assert_eq!(option(&offer, OPTION_SERVER_ID), SERVER.octets());
+        assert_eq!(option(&offer, OPTION_ROUTER), SERVER.octets());
+        assert_eq!(option(&offer, OPTION_DNS), SERVER.octets());
+        assert_eq!(option(&offer, OPTION_SUBNET_MASK), [255, 255, 255, 0]);
+        assert_eq!(option(&offer, OPTION_LEASE_TIME), LEASE_SECS.to_be_bytes());
+
+        let ack = reply(&request(REQUEST, 1), &mut clients, SERVER).unwrap();
+        assert_eq!(option(&ack, OPTION_MESSAGE_TYPE), [ACK]);
+        assert_eq!(ack[16..20], [192, 168, 4, 100]);
+    }
+
+    #[test]
+    fn clients_get_their_own_address() {
+        let mut clients = Vec::new();
+        let first = reply(&request(DISCOVER, 1), &mut clients, SERVER).unwrap();
+        let second = reply(&request(DISCOVER, 2), &mut clients, SERVER).unwrap();
+        let again = reply(&request(REQUEST, 1), &mut clients, SERVER).unwrap();
+        assert_eq!(first[19], 100);
+        assert_eq!(second[19], 101);
+        assert_eq!(again[19], 100);
+    }
+
+    #[test]
+    fn addresses_run_out() {
+        let mut clients = Vec::new();
+        for mac in 0..MAX_CLIENTS as u8 {
+            assert!(reply(&request(DISCOVER, mac), &mut clients, SERVER).is_some());
+        }
+        assert!(reply(&request(DISCOVER, 0xFF), &mut clients, SERVER).is_none());
+        assert!(reply(&request(REQUEST, 0), &mut clients, SERVER).is_some());
+    }
+
+    #[test]
+    fn ignored_messages() {
+        let mut clients = Vec::new();
+        // RELEASE
+        assert!(reply(&request(7, 1), &mut clients, SERVER).is_none());
+        let mut no_cookie = request(DISCOVER, 1);
+        no_cookie[236] = 0;
+        assert!(reply(&no_cookie, &mut clients, SERVER).is_none());
+        let mut from_a_server = request(DISCOVER, 1);
+        from_a_server[0] = 2;
+        assert!(reply(&from_a_server, &mut clients, SERVER).is_none());
+        assert!(reply(&request(DISCOVER, 1)[..100], &mut clients, SERVER).is_none());
+        assert!(clients.is_empty());
+    }
+
+    #[test]
+    fn options_without_a_message_type() {
+        assert_eq!(message_type(&[0, 0, OPTION_END]), None);
+        assert_eq!(message_type(&[12, 200, 1]), None);
+        assert_eq!(message_type(&[12]), None);
+        assert_eq!(message_type(&[]), None);
+    }
+}
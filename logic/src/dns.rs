@@ -0,0 +1,99 @@
+use alloc::vec::Vec;
+use core::net::Ipv4Addr;
+
+const HEADER: usize = 12;
+const TYPE_A: u16 = 1;
+const TTL_SECS: u32 = 60;
+
+/// The answer to a DNS query, giving `address` for any name. Only A
+/// lookups get an address, the others an empty answer.
+pub fn answer(query: &[u8], address: Ipv4Addr) -> Option<Vec<u8>> {
+    // Only plain queries with a single question
+    if query.len() < HEADER || query[2] & 0x80 != 0 || query[4..6] != [0, 1] {
+        return None;
+    }
+    let mut end = HEADER;
+    loop {
+        match *query.get(end)? {
+            0 => break,
+            len => end += 1 + len as usize,
+        }
+    }
+    let question_end = end + 5;
+    let question = query.get(HEADER..question_end)?;
+    let kind = u16::from_be_bytes([question[question.len() - 4], question[question.len() - 3]]);
+
+    let mut answer = Vec::with_capacity(question_end + 16);
+    answer.extend_from_slice(&query[..2]);
+    // Response, recursion desired and available, no error
+    answer.extend_from_slice(&[0x81, 0x80]);
+    let answers = u16::from(kind == TYPE_A);
+    answer.extend_from_slice(&[0, 1]);
+    answer.extend_from_slice(&answers.to_be_bytes());
+    answer.extend_from_slice(&[0, 0, 0, 0]);
+    answer.extend_from_slice(question);
+    if kind == TYPE_A {
+        // Pointer to the name in the question
+        answer.extend_from_slice(&[0xC0, HEADER as u8]);
+        answer.extend_from_slice(&TYPE_A.to_be_bytes());
+        answer.extend_from_slice(&[0, 1]);
+        answer.extend_from_slice(&TTL_SECS.to_be_bytes());
+        answer.extend_from_slice(&[0, 4]);
+        answer.extend_from_slice(&address.octets());
+    }
+    Some(answer)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+
+    const ADDRESS: Ipv4Addr = Ipv4Addr::new(192, 168, 4, 1);
+
+    /// A query for `example.com` of `kind`, with recursion desired.
+    fn query(kind: u16) -> Vec<u8> {
+        let mut query = vec![0x12, 0x34, 0x01, 0x00, 0, 1, 0, 0, 0, 0, 0, 0];
+        query.extend_from_slice(b"\x07example\x03com\x00");
+        query.extend_from_slice(&kind.to_be_bytes());
+        query.extend_from_slice(&[0, 1]);
+        query
+    }
+
+    #[test]
+    fn a_lookup() {
+        let query = query(TYPE_A);
+        let answer = answer(&query, ADDRESS).unwrap();
+        assert_eq!(answer[..2], [0x12, 0x34]);
+        assert_eq!(answer[2..4], [0x81, 0x80]);
+        // One question and one answer
+        assert_eq!(answer[4..12], [0, 1, 0, 1, 0, 0, 0, 0]);
+        assert_eq!(answer[HEADER..query.len()], query[HEADER..]);
+        assert_eq!(
+            answer[query.len()..],
+            [0xC0, 12, 0, 1, 0, 1, 0, 0, 0, 60, 0, 4, 192, 168, 4, 1]
+        );
+    }
+
+    #[test]
+    fn other_lookups_get_no_address() {
+        // AAAA
+        let query = query(28);
+        let answer = answer(&query, ADDRESS).unwrap();
+        assert_eq!(answer[6..8], [0, 0]);
+        assert_eq!(answer.len(), query.len());
+    }
+
+    #[test]
+    fn ignored() {
+        let mut response = query(TYPE_A);
+        response[2] |= 0x80;
+        assert_eq!(answer(&response, ADDRESS), None);
+        let mut two_questions = query(TYPE_A);
+        two_questions[5] = 2;
+        assert_eq!(answer(&two_questions, ADDRESS), None);
+        assert_eq!(answer(&query(TYPE_A)[..20], ADDRESS), None);
+        assert_eq!(answer(&[0; 4], ADDRESS), None);
+    }
+}
@@ -0,0 +1,334 @@
+/// NMEA 0183 allows 82 characters, some modules go a little over
+const MAX_SENTENCE: usize = 100;
+/// Longest number field read, a longitude like `dddmm.mmmmmm`
+const MAX_FIELD: usize = 12;
+
+/// Quality of the position, the fix indicator of GGA sentences.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum Fix {
+    #[default]
+    None,
+    Gps,
+    /// Corrected by SBAS or a reference station
+    Differential,
+    Rtk,
+    /// Dead reckoning, from the last position
+    Estimated,
+}
+
+/// What the module last said about where it is.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct Position {
+    pub fix: Fix,
+    /// In ten millionths of a degree, north and east positive
+    pub latitude: i32,
+    pub longitude: i32,
+    /// Above mean sea level, in decimetres
+    pub altitude: i32,
+    /// Over ground, in tenths of km/h
+    pub speed: u32,
+    /// Used for the fix
+    pub satellites: u8,
+    /// UTC hours, minutes and seconds
+    pub time: Option<(u8, u8, u8)>,
+}
+
+impl Position {
+    /// Takes in the checked body of a sentence. `false` for those it does
+    /// not read, such as satellites in view or DOP.
+    pub fn update(&mut self, body: &str) -> bool {
+        let mut parts = body.split(',');
+        // The talker, GP for GPS alone or GN for several constellations, is
+        // the first two letters
+        let kind = parts.next().and_then(|talker| talker.get(2..));
+        let fields = [(); 12].map(|()| parts.next().unwrap_or(""));
+        match kind {
+            Some("GGA") => gga(self, &fields),
+            Some("RMC") => rmc(self, &fields),
+            _ => return false,
+        }
+        true
+    }
+}
+
+/// Collects sentences out of the bytes coming from the module.
+pub struct Reader {
+    sentence: [u8; MAX_SENTENCE],
+    length: usize,
+}
+
+impl Reader {
+    pub const fn new() -> Self {
+        Self {
+            sentence: [0; MAX_SENTENCE],
+            length: 0,
+        }
+    }
+
+    /// Takes in the next byte, giving the sentence it ends, from `$` up to
+    /// the line break. Sentences too long for NMEA are dropped.
+    pub fn push(&mut self, byte: u8) -> Option<&[u8]> {
+        match byte {
+            b'$' => {
+                self.sentence[0] = byte;
+                self.length = 1;
+            }
+            b'\r' | b'\n' if self.length > 0 => {
+                let length = core::mem::take(&mut self.length);
+                return Some(&self.sentence[..length]);
+            }
+            _ if self.length > 0 && self.length < MAX_SENTENCE => {
+                self.sentence[self.length] = byte;
+                self.length += 1;
+            }
+            // Too long or outside of a sentence
+            _ => self.length = 0,
+        }
+        None
+    }
+
+    /// Drops the sentence read so far, after bytes were lost.
+    pub fn reset(&mut self) {
+        self.length = 0;
+    }
+}
+
+impl Default for Reader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// What is between `$` and `*`, when the checksum after `*` matches.
+pub fn checked(sentence: &[u8]) -> Option<&str> {
+    let sentence = core::str::from_utf8(sentence).ok()?;
+    let (body, checksum) = sentence.strip_prefix('$')?.split_once('*')?;
+    let expected = u8::from_str_radix(checksum.get(..2)?, 16).ok()?;
+    let actual = body.bytes().fold(0, |sum, byte| sum ^ byte);
+    (actual == expected).then_some(body)
+}
+
+/// Time, position, fix quality, satellites and altitude.
+fn gga(position: &mut Position, fields: &[&str; 12]) {
+    position.fix = match fields[5] {
+        "1" => Fix::Gps,
+        "2" | "9" => Fix::Differential,
+        "4" | "5" => Fix::Rtk,
+        "6" => Fix::Estimated,
+        _ => Fix::None,
+    };
+    position.satellites = fields[6].parse().unwrap_or(0);
+    if let Some(time) = time(fields[0]) {
+        position.time = Some(time);
+    }
+    if position.fix == Fix::None {
+        return;
+    }
+    if let Some((latitude, longitude)) = coordinates(&fields[1..5]) {
+        position.latitude = latitude;
+        position.longitude = longitude;
+    }
+    let altitude = decimal(fields[8], 1).and_then(|altitude| i32::try_from(altitude).ok());
+    if let Some(altitude) = altitude {
+        position.altitude = altitude;
+    }
+}
+
+/// Time, position and speed. Without a fix only the time is there.
+fn rmc(position: &mut Position, fields: &[&str; 12]) {
+    if let Some(time) = time(fields[0]) {
+        position.time = Some(time);
+    }
+    if fields[1] != "A" {
+        position.speed = 0;
+        return;
+    }
+    if let Some((latitude, longitude)) = coordinates(&fields[2..6]) {
+        position.latitude = latitude;
+        position.longitude = longitude;
+    }
+    // Knots in thousandths, 1.852 km/h each
+    if let Some(speed) = decimal(fields[6], 3)
+        .and_then(|knots| knots.checked_mul(1852))
+        .and_then(|speed| u32::try_from(speed / 100_000).ok())
+    {
+        position.speed = speed;
+    }
+}
+
+/// `hhmmss.ss` as hours, minutes and seconds.
+fn time(field: &str) -> Option<(u8, u8, u8)> {
+    let number = |at: usize| -> Option<u8> { field.get(at..at + 2)?.parse().ok() };
+    Some((number(0)?, number(2)?, number(4)?))
+}
+
+/// Latitude `ddmm.mmmm`, `N` or `S`, longitude `dddmm.mmmm`, `E` or `W`, in
+/// ten millionths of a degree.
+fn coordinates(fields: &[&str]) -> Option<(i32, i32)> {
+    let latitude = degrees(fields[0], fields[1] == "S")?;
+    let longitude = degrees(fields[2], fields[3] == "W")?;
+    Some((latitude, longitude))
+}
+
+fn degrees(field: &str, negative: bool) -> Option<i32> {
+    // The minutes are the two digits before the point, and the fraction
+    let point = field.find('.').unwrap_or(field.len());
+    let split = point.checked_sub(2).filter(|&split| split <= 3)?;
+    let degrees = if split == 0 {
+        0
+    } else {
+        field.get(..split)?.parse::<i64>().ok()?
+    };
+    let minutes = decimal(field.get(split..)?, 6)?;
+    // A millionth of a minute is a sixth of a ten millionth of a degree
+    let value = degrees.checked_mul(10_000_000)?.checked_add(minutes / 6)?;
+    let value = if negative { -value } else { value };
+    i32::try_from(value).ok()
+}
+
+/// A decimal number in units of `10^-places`, extra digits cut off. Fields
+/// longer than [`MAX_FIELD`] are no NMEA number and give `None`.
+fn decimal(field: &str, places: usize) -> Option<i64> {
+    if field.len() > MAX_FIELD {
+        return None;
+    }
+    let (negative, field) = match field.strip_prefix('-') {
+        Some(field) => (true, field),
+        None => (false, field),
+    };
+    let (whole, fraction) = field.split_once('.').unwrap_or((field, ""));
+    if whole.is_empty() && fraction.is_empty() {
+        return None;
+    }
+    let mut value: i64 = 0;
+    let fraction = fraction
+        .bytes()
+        .chain(core::iter::repeat(b'0'))
+        .take(places);
+    for digit in whole.bytes().chain(fraction) {
+        if !digit.is_ascii_digit() || value > i64::MAX / 100 {
+            return None;
+        }
+        value = value * 10 + i64::from(digit - b'0');
+    }
+    Some(if negative { -value } else { value })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GGA: &[u8] = b"$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47";
+    const RMC: &[u8] = b"$GPRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W*6A";
+
+    fn read(sentence: &[u8], position: &mut Position) -> bool {
+        position.update(checked(sentence).unwrap())
+    }
+
+    #[test]
+    fn checksum() {
+        assert!(checked(GGA).is_some());
+        let mut garbled = GGA.to_vec();
+        garbled[10] = b'9';
+        assert_eq!(checked(&garbled), None);
+        assert_eq!(checked(b"GPGGA,1*00"), None);
+        assert_eq!(checked(b"$GPGGA,1"), None);
+        assert_eq!(checked(b"$GPGGA,1*Z"), None);
+    }
+
+    #[test]
+    fn gga() {
+        let mut position = Position::default();
+        assert!(read(GGA, &mut position));
+        assert_eq!(
+            position,
+            Position {
+                fix: Fix::Gps,
+                latitude: 481_173_000,
+                longitude: 115_166_666,
+                altitude: 5454,
+                speed: 0,
+                satellites: 8,
+                time: Some((12, 35, 19)),
+            }
+        );
+    }
+
+    #[test]
+    fn rmc() {
+        let mut position = Position::default();
+        assert!(read(RMC, &mut position));
+        // 22.4 knots
+        assert_eq!(position.speed, 414);
+        assert_eq!(position.latitude, 481_173_000);
+        assert_eq!(position.time, Some((12, 35, 19)));
+    }
+
+    #[test]
+    fn without_a_fix_only_the_time() {
+        let mut position = Position::default();
+        assert!(position.update("GNGGA,010203.00,,,,,0,00,99.99,,,,,,"));
+        assert_eq!(position.fix, Fix::None);
+        assert_eq!(position.time, Some((1, 2, 3)));
+        assert_eq!(position.latitude, 0);
+
+        position.speed = 10;
+        assert!(position.update("GNRMC,010204.00,V,,,,,,,,,,N"));
+        assert_eq!(position.speed, 0);
+        assert_eq!(position.time, Some((1, 2, 4)));
+    }
+
+    #[test]
+    fn southern_and_western() {
+        let mut position = Position::default();
+        assert!(position.update("GPGGA,000000,3351.000,S,15112.600,W,2,05,1.0,-10.5,M,,,,"));
+        assert_eq!(position.fix, Fix::Differential);
+        assert_eq!(position.latitude, -338_500_000);
+        assert_eq!(position.longitude, -1_512_100_000);
+        assert_eq!(position.altitude, -105);
+    }
+
+    #[test]
+    fn other_sentences_are_ignored() {
+        let mut position = Position::default();
+        assert!(!position.update("GPGSV,3,1,11,03,03,111,00"));
+        assert_eq!(position, Position::default());
+    }
+
+    #[test]
+    fn bad_numbers() {
+        assert_eq!(decimal("", 1), None);
+        assert_eq!(decimal("1x", 1), None);
+        assert_eq!(decimal("1234567890123", 0), None);
+        assert_eq!(decimal("1.23456", 2), Some(123));
+        assert_eq!(degrees("1", false), None);
+        assert_eq!(time("1234"), None);
+    }
+
+    #[test]
+    fn reader() {
+        let mut reader = Reader::new();
+        let mut sentences = 0;
+        let stream = [&b"noise"[..], GGA, b"\r\n", RMC, b"\r\n"].concat();
+        for &byte in &stream {
+            if let Some(sentence) = reader.push(byte) {
+                assert!(sentence == GGA || sentence == RMC);
+                sentences += 1;
+            }
+        }
+        assert_eq!(sentences, 2);
+    }
+
+    #[test]
+    fn reader_drops_long_sentences() {
+        let mut reader = Reader::new();
+        reader.push(b'$');
+        for _ in 0..MAX_SENTENCE {
+            assert_eq!(reader.push(b'A'), None);
+        }
+        assert_eq!(reader.push(b'\n'), None);
+        reader.push(b'$');
+        reader.reset();
+        assert_eq!(reader.push(b'\n'), None);
+    }
+}
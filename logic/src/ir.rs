@@ -0,0 +1,110 @@
+/// What a remote sent.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Frame {
+    /// Address, inverted address or its high byte, command and inverted
+    /// command, least significant byte first
+    Code(u32),
+    /// Sent every 108 ms while a key is held
+    Repeat,
+}
+
+/// Within 25 % of `expected` microseconds.
+fn near(duration: u32, expected: u32) -> bool {
+    duration * 4 > expected * 3 && duration * 4 < expected * 5
+}
+
+/// Reads an NEC frame out of alternating mark and space `durations` in
+/// microseconds, starting with a 9 ms mark. Bits are a 560 µs mark followed
+/// by a 560 µs space for zero and a 1690 µs one for one.
+pub fn decode(durations: impl IntoIterator<Item = u32>) -> Option<Frame> {
+    let mut durations = durations.into_iter();
+    if !near(durations.next()?, 9000) {
+        return None;
+    }
+    let space = durations.next()?;
+    if near(space, 2250) {
+        return Some(Frame::Repeat);
+    }
+    if !near(space, 4500) {
+        return None;
+    }
+    let mut code = 0u32;
+    for bit in 0..32 {
+        if !near(durations.next()?, 560) {
+            return None;
+        }
+        let space = durations.next()?;
+        if near(space, 1690) {
+            code |= 1 << bit;
+        } else if !near(space, 560) {
+            return None;
+        }
+    }
+    // Extended addresses have no inverse, only the command is checked
+    let [_, _, command, inverse] = code.to_le_bytes();
+    (command == !inverse).then_some(Frame::Code(code))
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    use super::*;
+
+    /// Durations of the frame sending `code`, a little off like a real
+    /// receiver's.
+    fn frame(code: u32) -> Vec<u32> {
+        let mut durations = vec![9100, 4400];
+        for bit in 0..32 {
+            let space = if code >> bit & 1 == 1 { 1650 } else { 600 };
+            durations.extend([530, space]);
+        }
+        // Stop bit
+        durations.push(560);
+        durations
+    }
+
+    #[test]
+    fn code() {
+        // Address 0x00, command 0x45
+        let code = 0xBA45_FF00;
+        assert_eq!(decode(frame(code)), Some(Frame::Code(code)));
+    }
+
+    #[test]
+    fn extended_address() {
+        let code = 0xE11E_7F80;
+        assert_eq!(decode(frame(code)), Some(Frame::Code(code)));
+    }
+
+    #[test]
+    fn repeat() {
+        assert_eq!(decode([9000, 2250, 560]), Some(Frame::Repeat));
+    }
+
+    #[test]
+    fn bad_command_inverse() {
+        assert_eq!(decode(frame(0xBA46_FF00)), None);
+    }
+
+    #[test]
+    fn truncated() {
+        let durations = frame(0xBA45_FF00);
+        assert_eq!(decode(durations[..40].iter().copied()), None);
+        assert_eq!(decode([]), None);
+    }
+
+    #[test]
+    fn timing_out_of_tolerance() {
+        let mut durations = frame(0xBA45_FF00);
+        durations[0] = 6000;
+        assert_eq!(decode(durations.iter().copied()), None);
+        let mut durations = frame(0xBA45_FF00);
+        durations[1] = 3000;
+        assert_eq!(decode(durations.iter().copied()), None);
+        let mut durations = frame(0xBA45_FF00);
+        durations[5] = 1100;
+        assert_eq!(decode(durations), None);
+    }
+}
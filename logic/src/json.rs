@@ -0,0 +1,376 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// A parsed JSON document.
+///
+/// Numbers are kept as integers, which is all the layouts read with it need.
+/// A fraction or an exponent is an error rather than quietly dropped.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(i64),
+    String(String),
+    Array(Vec<Value>),
+    /// Members in document order
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    /// Member `key` of an object, `None` for anything else.
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Object(members) => members
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    pub fn as_i32(&self) -> Option<i32> {
+        match self {
+            Value::Number(number) => i32::try_from(*number).ok(),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> &[Value] {
+        match self {
+            Value::Array(items) => items,
+            _ => &[],
+        }
+    }
+}
+
+/// Where and why a document could not be parsed.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Error {
+    /// Byte offset into the text
+    pub offset: usize,
+    pub message: &'static str,
+}
+
+/// Parses a whole document. Anything but whitespace after the value is an
+/// error.
+pub fn parse(text: &str) -> Result<Value, Error> {
+    let mut parser = Parser {
+        bytes: text.as_bytes(),
+        offset: 0,
+        depth: 0,
+    };
+    let value = parser.value()?;
+    parser.skip_whitespace();
+    if parser.offset != parser.bytes.len() {
+        return Err(parser.error("Trailing characters"));
+    }
+    Ok(value)
+}
+
+/// Deeper documents are rejected rather than running out of stack
+const MAX_DEPTH: usize = 16;
+
+struct Parser<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: &'static str) -> Error {
+        Error {
+            offset: self.offset,
+            message,
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.offset).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.offset += 1;
+        }
+    }
+
+    fn expect(&mut self, byte: u8, message: &'static str) -> Result<(), Error> {
+        self.skip_whitespace();
+        if self.peek() != Some(byte) {
+            return Err(self.error(message));
+        }
+        self.offset += 1;
+        Ok(())
+    }
+
+    fn keyword(&mut self, keyword: &str, value: Value) -> Result<Value, Error> {
+        if !self.bytes[self.offset..].starts_with(keyword.as_bytes()) {
+            return Err(self.error("Unknown keyword"));
+        }
+        self.offset += keyword.len();
+        Ok(value)
+    }
+
+    fn value(&mut self) -> Result<Value, Error> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b'{') => self.nested(Self::object),
+            Some(b'[') => self.nested(Self::array),
+            Some(b'"') => self.string().map(Value::String),
+            Some(b't') => self.keyword("true", Value::Bool(true)),
+            Some(b'f') => self.keyword("false", Value::Bool(false)),
+            Some(b'n') => self.keyword("null", Value::Null),
+            Some(b'-' | b'0'..=b'9') => self.number(),
+            Some(_) => Err(self.error("Unexpected character")),
+            None => Err(self.error("Unexpected end")),
+        }
+    }
+
+    fn nested(&mut self, parse: fn(&mut Self) -> Result<Value, Error>) -> Result<Value, Error> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error("Nested too deep"));
+        }
+        self.depth += 1;
+        let value = parse(self);
+        self.depth -= 1;
+        value
+    }
+
+    fn object(&mut self) -> Result<Value, Error> {
+        self.offset += 1;
+        let mut members = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(b'}') {
+            self.offset += 1;
+            return Ok(Value::Object(members));
+        }
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some(b'"') {
+                return Err(self.error("Expected a member name"));
+            }
+            let name = self.string()?;
+            self.expect(b':', "Expected ':'")?;
+            members.push((name, self.value()?));
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.offset += 1,
+                Some(b'}') => {
+                    self.offset += 1;
+                    return Ok(Value::Object(members));
+                }
+                _ => return Err(self.error("Expected ',' or '}'")),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<Value, Error> {
+        self.offset += 1;
+        let mut items = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(b']') {
+            self.offset += 1;
+            return Ok(Value::Array(items));
+        }
+        loop {
+            items.push(self.value()?);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.offset += 1,
+                Some(b']') => {
+                    self.offset += 1;
+                    return Ok(Value::Array(items));
+                }
+                _ => return Err(self.error("Expected ',' or ']'")),
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, Error> {
+        self.offset += 1;
+        let mut text = String::new();
+        loop {
+            // Runs without escapes are copied whole, they are valid UTF-8
+            // as the input is
+            let start = self.offset;
+            while !matches!(self.peek(), Some(b'"' | b'\\') | None) {
+                self.offset += 1;
+            }
+            text.push_str(core::str::from_utf8(&self.bytes[start..self.offset]).unwrap());
+            match self.peek() {
+                Some(b'"') => {
+                    self.offset += 1;
+                    return Ok(text);
+                }
+                Some(_) => {
+                    self.offset += 1;
+                    let escaped = match self.peek() {
+                        Some(b'"') => '"',
+                        Some(b'\\') => '\\',
+                        Some(b'/') => '/',
+                        Some(b'n') => '\n',
+                        Some(b't') => '\t',
+                        Some(b'r') => '\r',
+                        Some(b'b') => '\u{8}',
+                        Some(b'f') => '\u{c}',
+                        Some(b'u') => self.unicode()?,
+                        _ => return Err(self.error("Unknown escape")),
+                    };
+                    self.offset += 1;
+                    text.push(escaped);
+                }
+                None => return Err(self.error("Unterminated string")),
+            }
+        }
+    }
+
+    /// The code point of a `\uXXXX` escape, leaving the offset on its last
+    /// digit. Surrogate pairs are not joined.
+    fn unicode(&mut self) -> Result<char, Error> {
+        // `from_str_radix` would take a sign in front of the digits
+        let digits = self
+            .bytes
+            .get(self.offset + 1..self.offset + 5)
+            .filter(|digits| digits.iter().all(u8::is_ascii_hexdigit))
+            .and_then(|digits| core::str::from_utf8(digits).ok())
+            .and_then(|digits| u32::from_str_radix(digits, 16).ok())
+            .ok_or(self.error("Bad \\u escape"))?;
+        self.offset += 4;
+        Ok(char::from_u32(digits).unwrap_or(char::REPLACEMENT_CHARACTER))
+    }
+
+    fn number(&mut self) -> Result<Value, Error> {
+        let start = self.offset;
+        if self.peek() == Some(b'-') {
+            self.offset += 1;
+        }
+        while matches!(self.peek(), Some(b'0'..=b'9')) {
+            self.offset += 1;
+        }
+        let integer = core::str::from_utf8(&self.bytes[start..self.offset]).unwrap();
+        let number = integer
+            .parse::<i64>()
+            .map_err(|_| self.error("Bad number"))?;
+        if matches!(self.peek(), Some(b'.' | b'e' | b'E')) {
+            return Err(self.error("Only integers are supported"));
+        }
+        Ok(Value::Number(number))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::String;
+    use alloc::vec;
+
+    use super::*;
+
+    fn error(text: &str) -> Error {
+        parse(text).unwrap_err()
+    }
+
+    #[test]
+    fn scalars() {
+        assert_eq!(parse("null"), Ok(Value::Null));
+        assert_eq!(parse(" true "), Ok(Value::Bool(true)));
+        assert_eq!(parse("false"), Ok(Value::Bool(false)));
+        assert_eq!(parse("-42"), Ok(Value::Number(-42)));
+        assert_eq!(parse("\"hi\""), Ok(Value::String(String::from("hi"))));
+    }
+
+    #[test]
+    fn nested() {
+        let document = parse(r#"{"a": [1, {"b": null}], "c": "d"}"#).unwrap();
+        let a = document.get("a").unwrap().as_array();
+        assert_eq!(a[0].as_i32(), Some(1));
+        assert_eq!(a[1].get("b"), Some(&Value::Null));
+        assert_eq!(document.get("c").and_then(Value::as_str), Some("d"));
+        assert_eq!(document.get("missing"), None);
+        assert_eq!(parse("[]"), Ok(Value::Array(vec![])));
+        assert_eq!(parse("{ }"), Ok(Value::Object(vec![])));
+    }
+
+    #[test]
+    fn members_keep_document_order() {
+        let Ok(Value::Object(members)) = parse(r#"{"z": 1, "a": 2}"#) else {
+            panic!("not an object");
+        };
+        assert_eq!(members[0].0, "z");
+        assert_eq!(members[1].0, "a");
+    }
+
+    #[test]
+    fn escapes() {
+        let text = parse(r#""a\"b\\c\/d\n\té中""#).unwrap();
+        assert_eq!(text.as_str(), Some("a\"b\\c/d\n\té中"));
+        // Lone surrogates are not characters
+        assert_eq!(parse(r#""\ud800""#).unwrap().as_str(), Some("\u{fffd}"));
+        assert_eq!(error(r#""\x""#).message, "Unknown escape");
+        assert_eq!(error(r#""\u+123""#).message, "Bad \\u escape");
+        assert_eq!(error(r#""\u12""#).message, "Bad \\u escape");
+    }
+
+    #[test]
+    fn utf8_is_copied_whole() {
+        assert_eq!(parse("\"°C ✓\"").unwrap().as_str(), Some("°C ✓"));
+    }
+
+    #[test]
+    fn only_integers() {
+        assert_eq!(error("1.5").message, "Only integers are supported");
+        assert_eq!(error("1e3").message, "Only integers are supported");
+        assert_eq!(error("-").message, "Bad number");
+        assert_eq!(error("99999999999999999999").message, "Bad number");
+        assert_eq!(parse("3000000000").unwrap().as_i32(), None);
+    }
+
+    #[test]
+    fn errors_point_at_the_problem() {
+        assert_eq!(
+            error("[1, 2"),
+            Error {
+                offset: 5,
+                message: "Expected ',' or ']'"
+            }
+        );
+        assert_eq!(error("{\"a\" 1}").offset, 5);
+        assert_eq!(error("{1: 2}").message, "Expected a member name");
+        assert_eq!(error("\"open").message, "Unterminated string");
+        assert_eq!(error("tru").message, "Unknown keyword");
+        assert_eq!(error("").message, "Unexpected end");
+        assert_eq!(error("@").message, "Unexpected character");
+        assert_eq!(error("1 2").message, "Trailing characters");
+    }
+
+    #[test]
+    fn depth_is_limited() {
+        let deep = "[".repeat(MAX_DEPTH) + &"]".repeat(MAX_DEPTH);
+        assert!(parse(&deep).is_ok());
+        let deeper = "[".repeat(MAX_DEPTH + 1) + &"]".repeat(MAX_DEPTH + 1);
+        assert_eq!(error(&deeper).message, "Nested too deep");
+    }
+
+    #[test]
+    fn accessors_of_the_wrong_kind() {
+        let number = Value::Number(1);
+        assert_eq!(number.as_str(), None);
+        assert_eq!(number.as_bool(), None);
+        assert!(number.as_array().is_empty());
+        assert_eq!(number.get("a"), None);
+        assert_eq!(Value::Bool(true).as_i32(), None);
+    }
+}
@@ -0,0 +1,24 @@
+//! The parts of the firmware that need no hardware: parsers, protocol
+//! encoders and the like. They build for the host as well, where `cargo
+//! test` in this directory runs their tests.
+#![cfg_attr(not(test), no_std)]
+
+extern crate alloc;
+
+pub mod can;
+pub mod clock;
+pub mod dhcp;
+pub mod dns;
+pub mod gps;
+pub mod ir;
+pub mod json;
+pub mod metrics;
+pub mod modbus;
+pub mod mqtt;
+pub mod panel;
+pub mod pin;
+pub mod rooms;
+pub mod rotation;
+pub mod tar;
+pub mod timezone;
+pub mod websocket;
@@ -0,0 +1,101 @@
+/// Frames the percentiles are taken over, about four seconds of animation
+pub const WINDOW: usize = 128;
+
+/// Render times of the last [`WINDOW`] frames, oldest overwritten first.
+pub struct RenderTimes {
+    times_us: [u32; WINDOW],
+    next: usize,
+    len: usize,
+}
+
+impl RenderTimes {
+    pub const fn new() -> Self {
+        Self {
+            times_us: [0; WINDOW],
+            next: 0,
+            len: 0,
+        }
+    }
+
+    pub fn push(&mut self, time_us: u32) {
+        self.times_us[self.next] = time_us;
+        self.next = (self.next + 1) % WINDOW;
+        self.len = (self.len + 1).min(WINDOW);
+    }
+
+    /// The times as they stand, in no particular order.
+    pub fn recent(&self) -> &[u32] {
+        &self.times_us[..self.len]
+    }
+}
+
+impl Default for RenderTimes {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The `percentiles` of `times`, nearest rank rounded down, 0 without any
+/// times. Sorts `times` on the way.
+pub fn percentiles<const N: usize>(times: &mut [u32], percentiles: [u32; N]) -> [u32; N] {
+    times.sort_unstable();
+    percentiles.map(|percentile| {
+        let index = times.len().saturating_sub(1) * percentile as usize / 100;
+        times.get(index).copied().unwrap_or(0)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::*;
+
+    #[test]
+    fn empty() {
+        assert_eq!(percentiles(&mut [], [50, 90, 99]), [0, 0, 0]);
+        assert!(RenderTimes::new().recent().is_empty());
+    }
+
+    #[test]
+    fn one_time() {
+        assert_eq!(percentiles(&mut [7], [0, 50, 100]), [7, 7, 7]);
+    }
+
+    #[test]
+    fn ranks() {
+        let mut times: Vec<u32> = (1..=101).rev().collect();
+        assert_eq!(
+            percentiles(&mut times, [0, 50, 90, 99, 100]),
+            [1, 51, 91, 100, 101]
+        );
+        let mut times = [40, 10, 30, 20];
+        assert_eq!(percentiles(&mut times, [50, 99]), [20, 30]);
+    }
+
+    #[test]
+    fn window_keeps_the_latest() {
+        let mut times = RenderTimes::new();
+        for time in 0..WINDOW as u32 + 10 {
+            times.push(time);
+        }
+        let mut recent = times.recent().to_vec();
+        assert_eq!(recent.len(), WINDOW);
+        recent.sort_unstable();
+        assert_eq!(recent[0], 10);
+        assert_eq!(recent[WINDOW - 1], WINDOW as u32 + 9);
+    }
+
+    #[test]
+    fn spikes_show_in_the_high_percentiles() {
+        let mut times = RenderTimes::new();
+        for frame in 0..100 {
+            times.push(if frame % 20 == 0 { 50_000 } else { 10_000 });
+        }
+        let mut recent = times.recent().to_vec();
+        assert_eq!(
+            percentiles(&mut recent, [50, 90, 99]),
+            [10_000, 10_000, 50_000]
+        );
+    }
+}
@@ -0,0 +1,300 @@
+use alloc::format;
+use alloc::string::String;
+
+use crate::json::Value;
+
+pub const READ_HOLDING_REGISTERS: u8 = 0x03;
+pub const WRITE_SINGLE_REGISTER: u8 = 0x06;
+/// Set in the function code of a refusal
+pub const EXCEPTION: u8 = 0x80;
+
+/// Why a request got no usable reply.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Error {
+    /// Nothing came back in time, or not all of it
+    Timeout,
+    /// The reply was garbled on the way
+    Crc,
+    /// The reply was not for the request
+    Invalid,
+    /// The slave refused the request, with this exception code
+    Exception(u8),
+    /// The UART reported an error
+    Bus,
+}
+
+/// A request of the two functions used here, which both take an address
+/// and one more word.
+pub fn frame(slave: u8, function: u8, address: u16, word: u16) -> [u8; 8] {
+    let mut frame = [slave, function, 0, 0, 0, 0, 0, 0];
+    frame[2..4].copy_from_slice(&address.to_be_bytes());
+    frame[4..6].copy_from_slice(&word.to_be_bytes());
+    let checksum = crc(&frame[..6]);
+    frame[6..].copy_from_slice(&checksum.to_le_bytes());
+    frame
+}
+
+/// Checks a whole `reply` to `request` down to the CRC, before its contents
+/// are looked at.
+pub fn check(request: &[u8; 8], reply: &[u8]) -> Result<(), Error> {
+    if reply.len() < 5 {
+        return Err(Error::Invalid);
+    }
+    let (body, checksum) = reply.split_at(reply.len() - 2);
+    if crc(body).to_le_bytes() != checksum {
+        return Err(Error::Crc);
+    }
+    if reply[0] != request[0] {
+        return Err(Error::Invalid);
+    }
+    if reply[1] == request[1] | EXCEPTION {
+        return Err(Error::Exception(reply[2]));
+    }
+    if reply[1] != request[1] {
+        return Err(Error::Invalid);
+    }
+    Ok(())
+}
+
+/// CRC-16/MODBUS.
+pub fn crc(data: &[u8]) -> u16 {
+    data.iter().fold(0xFFFF, |crc, &byte| {
+        (0..8).fold(crc ^ u16::from(byte), |crc, _| match crc & 1 {
+            1 => (crc >> 1) ^ 0xA001,
+            _ => crc >> 1,
+        })
+    })
+}
+
+/// A holding register on the register table.
+pub struct Register {
+    pub name: String,
+    /// Shown after the value
+    pub unit: String,
+    pub slave: u8,
+    pub address: u16,
+    /// Read as two's complement
+    pub signed: bool,
+    /// Digits after the decimal point, 21.5 °C kept as 215 is 1
+    pub decimals: u32,
+    pub writable: bool,
+}
+
+impl Register {
+    /// `raw` as a number with the decimals and unit of the register.
+    pub fn format(&self, raw: u16) -> String {
+        let number = self.number(raw);
+        if self.unit.is_empty() {
+            number
+        } else {
+            format!("{} {}", number, self.unit)
+        }
+    }
+
+    /// `raw` as a number with the decimals of the register, in the form
+    /// [`Register::parse`] takes.
+    pub fn number(&self, raw: u16) -> String {
+        let value = if self.signed {
+            i32::from(raw as i16)
+        } else {
+            i32::from(raw)
+        };
+        let scale = 10u32.pow(self.decimals);
+        let sign = if value < 0 { "-" } else { "" };
+        let magnitude = value.unsigned_abs();
+        match self.decimals {
+            0 => format!("{}{}", sign, magnitude),
+            decimals => format!(
+                "{}{}.{:0width$}",
+                sign,
+                magnitude / scale,
+                magnitude % scale,
+                width = decimals as usize
+            ),
+        }
+    }
+
+    /// The raw word for a typed number, `None` when it is not one or does
+    /// not fit the register.
+    pub fn parse(&self, text: &str) -> Option<u16> {
+        let (negative, text) = match text.strip_prefix('-') {
+            Some(text) => (true, text),
+            None => (false, text),
+        };
+        let (whole, fraction) = text.split_once('.').unwrap_or((text, ""));
+        if whole.is_empty() || fraction.len() > self.decimals as usize {
+            return None;
+        }
+        let digits = whole.chars().chain(fraction.chars());
+        let padding = self.decimals as usize - fraction.len();
+        let mut magnitude: i64 = 0;
+        for digit in digits.chain(core::iter::repeat_n('0', padding)) {
+            magnitude = magnitude * 10 + i64::from(digit.to_digit(10)?);
+            if magnitude > 0xFFFF {
+                return None;
+            }
+        }
+        let value = if negative { -magnitude } else { magnitude };
+        if self.signed {
+            i16::try_from(value).ok().map(|value| value as u16)
+        } else {
+            u16::try_from(value).ok()
+        }
+    }
+}
+
+/// One entry of the `registers` array of a register table, `None` when it
+/// misses a required field or has a bad one.
+///
+/// Each register needs a `name`, the `slave` address and its own `address`.
+/// Optional are `unit`, `decimals` (0), `signed` and `writable` (false).
+pub fn register(description: &Value) -> Option<Register> {
+    let name = description.get("name").and_then(Value::as_str)?;
+    let number = |key| description.get(key).and_then(Value::as_i32);
+    let flag = |key| description.get(key).and_then(Value::as_bool);
+    // 0 is the broadcast address, which never replies
+    let slave = number("slave")
+        .and_then(|slave| u8::try_from(slave).ok())
+        .filter(|slave| (1..=247).contains(slave))?;
+    let address = number("address").and_then(|address| u16::try_from(address).ok())?;
+    let decimals = u32::try_from(number("decimals").unwrap_or(0))
+        .ok()
+        .filter(|decimals| *decimals <= 4)?;
+    Some(Register {
+        name: String::from(name),
+        unit: String::from(
+            description
+                .get("unit")
+                .and_then(Value::as_str)
+                .unwrap_or(""),
+        ),
+        slave,
+        address,
+        signed: flag("signed").unwrap_or(false),
+        decimals,
+        writable: flag("writable").unwrap_or(false),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json;
+
+    fn register_with(signed: bool, decimals: u32, unit: &str) -> Register {
+        Register {
+            name: String::from("Test"),
+            unit: String::from(unit),
+            slave: 1,
+            address: 0,
+            signed,
+            decimals,
+            writable: true,
+        }
+    }
+
+    fn parsed(text: &str) -> Option<Register> {
+        register(&json::parse(text).unwrap())
+    }
+
+    #[test]
+    fn crc_check_value() {
+        assert_eq!(crc(b"123456789"), 0x4B37);
+        assert_eq!(crc(&[]), 0xFFFF);
+    }
+
+    #[test]
+    fn read_request() {
+        assert_eq!(
+            frame(1, READ_HOLDING_REGISTERS, 0, 1),
+            [0x01, 0x03, 0x00, 0x00, 0x00, 0x01, 0x84, 0x0A]
+        );
+    }
+
+    #[test]
+    fn replies() {
+        let request = frame(1, READ_HOLDING_REGISTERS, 0, 1);
+        let mut reply = [0x01, 0x03, 0x02, 0x00, 0xD7, 0, 0];
+        let checksum = crc(&reply[..5]);
+        reply[5..].copy_from_slice(&checksum.to_le_bytes());
+        assert_eq!(check(&request, &reply), Ok(()));
+
+        let mut garbled = reply;
+        garbled[4] ^= 1;
+        assert_eq!(check(&request, &garbled), Err(Error::Crc));
+
+        let mut other_slave = reply;
+        other_slave[0] = 2;
+        let checksum = crc(&other_slave[..5]);
+        other_slave[5..].copy_from_slice(&checksum.to_le_bytes());
+        assert_eq!(check(&request, &other_slave), Err(Error::Invalid));
+
+        let mut refusal = [0x01, 0x83, 0x02, 0, 0];
+        let checksum = crc(&refusal[..3]);
+        refusal[3..].copy_from_slice(&checksum.to_le_bytes());
+        assert_eq!(check(&request, &refusal), Err(Error::Exception(2)));
+
+        assert_eq!(check(&request, &reply[..3]), Err(Error::Invalid));
+    }
+
+    #[test]
+    fn write_reply_repeats_the_request() {
+        let request = frame(1, WRITE_SINGLE_REGISTER, 1, 215);
+        assert_eq!(check(&request, &request), Ok(()));
+    }
+
+    #[test]
+    fn numbers() {
+        assert_eq!(register_with(false, 0, "").format(42), "42");
+        assert_eq!(register_with(false, 1, "°C").format(215), "21.5 °C");
+        assert_eq!(register_with(false, 2, "").number(5), "0.05");
+        assert_eq!(register_with(true, 1, "").number(0xFFFB), "-0.5");
+        assert_eq!(register_with(false, 0, "").number(0xFFFF), "65535");
+        assert_eq!(register_with(true, 0, "").number(0x8000), "-32768");
+    }
+
+    #[test]
+    fn parse() {
+        let tenths = register_with(false, 1, "");
+        assert_eq!(tenths.parse("21.5"), Some(215));
+        assert_eq!(tenths.parse("21"), Some(210));
+        assert_eq!(tenths.parse("21.55"), None);
+        assert_eq!(tenths.parse(".5"), None);
+        assert_eq!(tenths.parse("-1"), None);
+        assert_eq!(tenths.parse("6553.5"), Some(65535));
+        assert_eq!(tenths.parse("6553.6"), None);
+        assert_eq!(tenths.parse("1a"), None);
+        assert_eq!(tenths.parse(""), None);
+
+        let signed = register_with(true, 1, "");
+        assert_eq!(signed.parse("-0.5"), Some(0xFFFB));
+        assert_eq!(signed.parse("-3276.8"), Some(0x8000));
+        assert_eq!(signed.parse("3276.8"), None);
+    }
+
+    #[test]
+    fn parse_takes_what_number_gives() {
+        let register = register_with(true, 2, "");
+        for raw in [0, 1, 99, 100, 0x7FFF, 0x8000, 0xFFFF] {
+            assert_eq!(register.parse(&register.number(raw)), Some(raw));
+        }
+    }
+
+    #[test]
+    fn table_entries() {
+        let temperature = parsed(
+            r#"{"name": "Temperature", "slave": 1, "address": 0, "decimals": 1, "unit": "°C"}"#,
+        )
+        .unwrap();
+        assert_eq!(temperature.name, "Temperature");
+        assert_eq!((temperature.slave, temperature.address), (1, 0));
+        assert_eq!(temperature.decimals, 1);
+        assert!(!temperature.signed && !temperature.writable);
+
+        assert!(parsed(r#"{"name": "A", "slave": 0, "address": 0}"#).is_none());
+        assert!(parsed(r#"{"name": "A", "slave": 248, "address": 0}"#).is_none());
+        assert!(parsed(r#"{"name": "A", "slave": 1, "address": 65536}"#).is_none());
+        assert!(parsed(r#"{"name": "A", "slave": 1, "address": 0, "decimals": 5}"#).is_none());
+        assert!(parsed(r#"{"slave": 1, "address": 0}"#).is_none());
+    }
+}
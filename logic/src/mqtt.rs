@@ -0,0 +1,309 @@
+use alloc::vec::Vec;
+
+/// MQTT 3.1.1
+const PROTOCOL_LEVEL: u8 = 4;
+const CONNECT: u8 = 0x10;
+const CONNACK: u8 = 0x20;
+const PUBLISH: u8 = 0x30;
+const SUBSCRIBE: u8 = 0x82;
+const PINGREQ: u8 = 0xC0;
+const RETAIN: u8 = 0x01;
+const CLEAN_SESSION: u8 = 0x02;
+const WILL: u8 = 0x04;
+const WILL_RETAIN: u8 = 0x20;
+const PASSWORD: u8 = 0x40;
+const USERNAME: u8 = 0x80;
+
+/// A packet from the broker that is malformed or unexpected.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ProtocolError;
+
+pub struct Options<'a> {
+    pub client_id: &'a str,
+    pub keep_alive_secs: u16,
+    pub username: Option<&'a str>,
+    pub password: Option<&'a str>,
+    /// Topic and retained payload the broker publishes if the connection drops
+    pub will: Option<(&'a str, &'a [u8])>,
+}
+
+/// Message received on a subscribed topic
+#[derive(PartialEq, Eq, Debug)]
+pub struct Message {
+    pub topic: Vec<u8>,
+    pub payload: Vec<u8>,
+}
+
+/// CONNECT with a clean session.
+pub fn connect(options: &Options<'_>) -> Vec<u8> {
+    let mut flags = CLEAN_SESSION;
+    let mut body = Vec::new();
+    put_str(&mut body, "MQTT");
+    body.push(PROTOCOL_LEVEL);
+    let flags_at = body.len();
+    body.push(0);
+    body.extend_from_slice(&options.keep_alive_secs.to_be_bytes());
+    put_str(&mut body, options.client_id);
+    if let Some((topic, payload)) = options.will {
+        flags |= WILL | WILL_RETAIN;
+        put_str(&mut body, topic);
+        put_bytes(&mut body, payload);
+    }
+    if let Some(username) = options.username {
+        flags |= USERNAME;
+        put_str(&mut body, username);
+    }
+    if let Some(password) = options.password {
+        flags |= PASSWORD;
+        put_str(&mut body, password);
+    }
+    body[flags_at] = flags;
+    packet(CONNECT, &body)
+}
+
+/// PUBLISH with QoS 0.
+pub fn publish(topic: &str, payload: &[u8], retain: bool) -> Vec<u8> {
+    let mut body = Vec::with_capacity(topic.len() + payload.len() + 2);
+    put_str(&mut body, topic);
+    body.extend_from_slice(payload);
+    let kind = if retain { PUBLISH | RETAIN } else { PUBLISH };
+    packet(kind, &body)
+}
+
+/// SUBSCRIBE to `topic` with QoS 0.
+pub fn subscribe(packet_id: u16, topic: &str) -> Vec<u8> {
+    let mut body = Vec::new();
+    body.extend_from_slice(&packet_id.to_be_bytes());
+    put_str(&mut body, topic);
+    body.push(0);
+    packet(SUBSCRIBE, &body)
+}
+
+pub fn ping() -> Vec<u8> {
+    packet(PINGREQ, &[])
+}
+
+/// The return code of the CONNACK answering a CONNECT, 0 when the broker
+/// accepted it.
+pub fn connack(kind: u8, body: &[u8]) -> Result<u8, ProtocolError> {
+    match (kind, body) {
+        (CONNACK, [_, code]) => Ok(*code),
+        _ => Err(ProtocolError),
+    }
+}
+
+/// The message in a packet, `None` when it is not a PUBLISH.
+pub fn message(kind: u8, body: &[u8]) -> Result<Option<Message>, ProtocolError> {
+    if kind & 0xF0 != PUBLISH {
+        return Ok(None);
+    }
+    if kind & 0x06 != 0 {
+        // QoS 1 and 2 are never requested
+        return Err(ProtocolError);
+    }
+    let [high, low, ..] = body[..] else {
+        return Err(ProtocolError);
+    };
+    let topic_len = u16::from_be_bytes([high, low]) as usize;
+    if body.len() < 2 + topic_len {
+        return Err(ProtocolError);
+    }
+    Ok(Some(Message {
+        topic: body[2..2 + topic_len].to_vec(),
+        payload: body[2 + topic_len..].to_vec(),
+    }))
+}
+
+/// Reads the remaining length of a fixed header, a byte at a time.
+#[derive(Default)]
+pub struct Length {
+    value: usize,
+    shift: u32,
+}
+
+impl Length {
+    /// Takes in the next byte, giving the length once it is complete. It
+    /// takes four bytes at most.
+    pub fn push(&mut self, byte: u8) -> Result<Option<usize>, ProtocolError> {
+        self.value |= ((byte & 0x7F) as usize) << self.shift;
+        self.shift += 7;
+        if byte & 0x80 == 0 {
+            Ok(Some(self.value))
+        } else if self.shift == 28 {
+            Err(ProtocolError)
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+/// `body` behind a fixed header of `kind`.
+fn packet(kind: u8, body: &[u8]) -> Vec<u8> {
+    let mut packet = Vec::with_capacity(body.len() + 5);
+    packet.push(kind);
+    let mut len = body.len();
+    loop {
+        let byte = (len % 128) as u8;
+        len /= 128;
+        if len == 0 {
+            packet.push(byte);
+            break;
+        }
+        packet.push(byte | 0x80);
+    }
+    packet.extend_from_slice(body);
+    packet
+}
+
+fn put_str(buffer: &mut Vec<u8>, text: &str) {
+    put_bytes(buffer, text.as_bytes());
+}
+
+fn put_bytes(buffer: &mut Vec<u8>, bytes: &[u8]) {
+    buffer.extend_from_slice(&(bytes.len() as u16).to_be_bytes());
+    buffer.extend_from_slice(bytes);
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+
+    fn length(bytes: &[u8]) -> Result<Option<usize>, ProtocolError> {
+        let mut length = Length::default();
+        for (index, &byte) in bytes.iter().enumerate() {
+            if let Some(value) = length.push(byte)? {
+                assert_eq!(index, bytes.len() - 1, "ended early");
+                return Ok(Some(value));
+            }
+        }
+        Ok(None)
+    }
+
+    #[test]
+    fn minimal_connect() {
+        let options = Options {
+            client_id: "cyd",
+            keep_alive_secs: 60,
+            username: None,
+            password: None,
+            will: None,
+        };
+        assert_eq!(
+            connect(&options),
+            [
+                0x10,
+                15,
+                0,
+                4,
+                b'M',
+                b'Q',
+                b'T',
+                b'T',
+                4,
+                CLEAN_SESSION,
+                0,
+                60,
+                0,
+                3,
+                b'c',
+                b'y',
+                b'd'
+            ]
+        );
+    }
+
+    #[test]
+    fn connect_with_everything() {
+        let options = Options {
+            client_id: "c",
+            keep_alive_secs: 30,
+            username: Some("u"),
+            password: Some("p"),
+            will: Some(("t", b"off")),
+        };
+        let packet = connect(&options);
+        assert_eq!(
+            packet[9],
+            CLEAN_SESSION | WILL | WILL_RETAIN | USERNAME | PASSWORD
+        );
+        assert_eq!(
+            &packet[12..],
+            [
+                0, 1, b'c', 0, 1, b't', 0, 3, b'o', b'f', b'f', 0, 1, b'u', 0, 1, b'p'
+            ]
+        );
+        assert_eq!(packet[1] as usize, packet.len() - 2);
+    }
+
+    #[test]
+    fn publish_and_subscribe() {
+        assert_eq!(
+            publish("a/b", b"ON", true),
+            [0x31, 7, 0, 3, b'a', b'/', b'b', b'O', b'N']
+        );
+        assert_eq!(publish("a", b"", false), [0x30, 3, 0, 1, b'a']);
+        assert_eq!(
+            subscribe(0x0102, "a/+"),
+            [0x82, 8, 1, 2, 0, 3, b'a', b'/', b'+', 0]
+        );
+        assert_eq!(ping(), [0xC0, 0]);
+    }
+
+    #[test]
+    fn long_packets_take_more_length_bytes() {
+        let payload = vec![0; 200];
+        let packet = publish("t", &payload, false);
+        // 203 is 75 + 1 * 128
+        assert_eq!(packet[..3], [0x30, 0x80 | 75, 1]);
+        assert_eq!(packet.len(), 3 + 203);
+    }
+
+    #[test]
+    fn remaining_length() {
+        assert_eq!(length(&[0]), Ok(Some(0)));
+        assert_eq!(length(&[127]), Ok(Some(127)));
+        assert_eq!(length(&[0x80, 1]), Ok(Some(128)));
+        assert_eq!(length(&[0xFF, 0x7F]), Ok(Some(16_383)));
+        assert_eq!(length(&[0xFF, 0xFF, 0xFF, 0x7F]), Ok(Some(268_435_455)));
+        assert_eq!(length(&[0xFF, 0xFF, 0xFF, 0xFF]), Err(ProtocolError));
+        assert_eq!(length(&[0x80]), Ok(None));
+    }
+
+    #[test]
+    fn encoded_lengths_read_back() {
+        for len in [0, 1, 127, 128, 300, 16_383, 16_384] {
+            let body = vec![0; len];
+            let encoded = packet(PUBLISH, &body);
+            let header = encoded.len() - len;
+            assert_eq!(length(&encoded[1..header]), Ok(Some(len)));
+        }
+    }
+
+    #[test]
+    fn connack_codes() {
+        assert_eq!(connack(CONNACK, &[0, 0]), Ok(0));
+        assert_eq!(connack(CONNACK, &[0, 5]), Ok(5));
+        assert_eq!(connack(CONNACK, &[0]), Err(ProtocolError));
+        assert_eq!(connack(PUBLISH, &[0, 0]), Err(ProtocolError));
+    }
+
+    #[test]
+    fn messages() {
+        assert_eq!(
+            message(0x30, &[0, 3, b'a', b'/', b'b', b'2', b'1']),
+            Ok(Some(Message {
+                topic: b"a/b".to_vec(),
+                payload: b"21".to_vec(),
+            }))
+        );
+        // Retained ones too
+        assert!(matches!(message(0x31, &[0, 1, b'a']), Ok(Some(_))));
+        // A SUBACK
+        assert_eq!(message(0x90, &[0, 1, 0]), Ok(None));
+        assert_eq!(message(0x32, &[0, 1, b'a', 0, 1]), Err(ProtocolError));
+        assert_eq!(message(0x30, &[0]), Err(ProtocolError));
+        assert_eq!(message(0x30, &[0, 5, b'a']), Err(ProtocolError));
+    }
+}
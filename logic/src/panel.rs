@@ -0,0 +1,207 @@
+use embedded_graphics::pixelcolor::Rgb565;
+use embedded_graphics::prelude::DrawTarget;
+use embedded_graphics::primitives::Rectangle;
+use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::OutputPin;
+use mipidsi::interface::{Interface, InterfacePixelFormat};
+use mipidsi::models::Model;
+
+use crate::rotation::Rotation;
+
+/// What the UI draws to, picked by the board in `main`.
+///
+/// Any embedded-graphics target of RGB565 pixels will do, which covers the
+/// mipidsi SPI panels, parallel RGB drivers and the desktop simulator. The
+/// rest have defaults for panels that cannot rotate or sleep.
+pub trait Panel: DrawTarget<Color = Rgb565> {
+    /// Sends the pixels LVGL rendered for `area`, row by row.
+    ///
+    /// A driver with DMA can copy them to a buffer of its own, start the
+    /// transfer and return, so LVGL renders the next part meanwhile. Then
+    /// it has to finish in [`Panel::wait`].
+    fn flush(
+        &mut self,
+        area: &Rectangle,
+        colors: impl IntoIterator<Item = Rgb565>,
+    ) -> Result<(), Self::Error> {
+        self.fill_contiguous(area, colors)
+    }
+
+    /// Returns once the last flush is all on the panel. It is called before
+    /// the next flush and before sleeping.
+    fn wait(&mut self) {}
+
+    /// Turns the picture, which touch calibration expects
+    /// [`Rotation::Normal`].
+    fn rotate(&mut self, rotation: Rotation) -> Result<(), Self::Error> {
+        let _ = rotation;
+        Ok(())
+    }
+
+    /// Stops driving the display until the next reset, before deep sleep.
+    fn power_off(&mut self, delay: &mut impl DelayNs) -> Result<(), Self::Error> {
+        let _ = delay;
+        Ok(())
+    }
+}
+
+impl<DI, M, RST> Panel for mipidsi::Display<DI, M, RST>
+where
+    DI: Interface,
+    M: Model<ColorFormat = Rgb565>,
+    Rgb565: InterfacePixelFormat<DI::Word>,
+    RST: OutputPin,
+{
+    fn rotate(&mut self, rotation: Rotation) -> Result<(), Self::Error> {
+        self.set_orientation(rotation.orientation())
+    }
+
+    fn power_off(&mut self, delay: &mut impl DelayNs) -> Result<(), Self::Error> {
+        self.sleep(delay)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use core::convert::Infallible;
+
+    use embedded_graphics::pixelcolor::RgbColor;
+    use embedded_graphics::prelude::{OriginDimensions, Pixel, Point, Size};
+
+    use super::*;
+
+    const WIDTH: u32 = 8;
+    const HEIGHT: u32 = 4;
+
+    /// A framebuffer standing in for the display, keeping what was drawn
+    /// and how.
+    struct Mock {
+        pixels: Vec<Rgb565>,
+        /// Pixels drawn outside of the display
+        clipped: usize,
+        waits: usize,
+    }
+
+    impl Mock {
+        fn new() -> Self {
+            Self {
+                pixels: vec![Rgb565::BLACK; (WIDTH * HEIGHT) as usize],
+                clipped: 0,
+                waits: 0,
+            }
+        }
+
+        fn pixel(&self, x: u32, y: u32) -> Rgb565 {
+            self.pixels[(y * WIDTH + x) as usize]
+        }
+    }
+
+    impl OriginDimensions for Mock {
+        fn size(&self) -> Size {
+            Size::new(WIDTH, HEIGHT)
+        }
+    }
+
+    impl DrawTarget for Mock {
+        type Color = Rgb565;
+        type Error = Infallible;
+
+        fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+        where
+            I: IntoIterator<Item = Pixel<Self::Color>>,
+        {
+            for Pixel(point, color) in pixels {
+                let inside =
+                    (0..WIDTH as i32).contains(&point.x) && (0..HEIGHT as i32).contains(&point.y);
+                if inside {
+                    self.pixels[(point.y as u32 * WIDTH + point.x as u32) as usize] = color;
+                } else {
+                    self.clipped += 1;
+                }
+            }
+            Ok(())
+        }
+    }
+
+    impl Panel for Mock {
+        fn wait(&mut self) {
+            self.waits += 1;
+        }
+    }
+
+    struct NoDelay;
+
+    impl DelayNs for NoDelay {
+        fn delay_ns(&mut self, _ns: u32) {}
+    }
+
+    #[test]
+    fn flush_fills_the_area_row_by_row() {
+        let mut panel = Mock::new();
+        let area = Rectangle::new(Point::new(2, 1), Size::new(3, 2));
+        let colors = [
+            Rgb565::RED,
+            Rgb565::GREEN,
+            Rgb565::BLUE,
+            Rgb565::WHITE,
+            Rgb565::YELLOW,
+            Rgb565::CYAN,
+        ];
+        panel.flush(&area, colors).unwrap();
+        assert_eq!(panel.pixel(2, 1), Rgb565::RED);
+        assert_eq!(panel.pixel(4, 1), Rgb565::BLUE);
+        assert_eq!(panel.pixel(2, 2), Rgb565::WHITE);
+        assert_eq!(panel.pixel(4, 2), Rgb565::CYAN);
+        // Nothing around it
+        assert_eq!(panel.pixel(1, 1), Rgb565::BLACK);
+        assert_eq!(panel.pixel(5, 2), Rgb565::BLACK);
+        assert_eq!(panel.pixel(2, 0), Rgb565::BLACK);
+        assert_eq!(panel.pixel(2, 3), Rgb565::BLACK);
+        assert_eq!(panel.clipped, 0);
+    }
+
+    #[test]
+    fn flush_stops_with_the_colors() {
+        let mut panel = Mock::new();
+        let area = Rectangle::new(Point::zero(), Size::new(WIDTH, HEIGHT));
+        panel.flush(&area, [Rgb565::RED; 3]).unwrap();
+        assert_eq!(panel.pixel(2, 0), Rgb565::RED);
+        assert_eq!(panel.pixel(3, 0), Rgb565::BLACK);
+    }
+
+    #[test]
+    fn flush_past_the_edge_is_clipped() {
+        let mut panel = Mock::new();
+        let area = Rectangle::new(Point::new(WIDTH as i32 - 1, 0), Size::new(2, 1));
+        panel.flush(&area, [Rgb565::RED; 2]).unwrap();
+        assert_eq!(panel.pixel(WIDTH - 1, 0), Rgb565::RED);
+        assert_eq!(panel.clipped, 1);
+    }
+
+    #[test]
+    fn defaults_for_simple_panels() {
+        let mut panel = Mock::new();
+        panel.wait();
+        assert_eq!(panel.waits, 1);
+        assert!(panel.rotate(Rotation::UpsideDown).is_ok());
+        assert!(panel.power_off(&mut NoDelay).is_ok());
+        // The picture is left alone
+        assert!(panel.pixels.iter().all(|&pixel| pixel == Rgb565::BLACK));
+    }
+
+    #[test]
+    fn works_as_a_generic_panel() {
+        fn clear<P: Panel>(panel: &mut P, area: Rectangle) -> Result<(), P::Error> {
+            panel.wait();
+            let pixels = area.size.width * area.size.height;
+            panel.flush(&area, (0..pixels).map(|_| Rgb565::WHITE))
+        }
+        let mut panel = Mock::new();
+        let area = Rectangle::new(Point::zero(), panel.size());
+        clear(&mut panel, area).unwrap();
+        assert_eq!(panel.waits, 1);
+        assert!(panel.pixels.iter().all(|&pixel| pixel == Rgb565::WHITE));
+    }
+}
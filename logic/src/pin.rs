@@ -0,0 +1,82 @@
+pub const MIN_LEN: usize = 4;
+pub const MAX_LEN: usize = 8;
+/// Wrong guesses allowed before [`lockout_secs`] makes the next one wait
+const FREE_ATTEMPTS: u32 = 3;
+/// Doubles with every further wrong guess, up to [`MAX_LOCKOUT_SECS`]
+const FIRST_LOCKOUT_SECS: u64 = 30;
+const MAX_LOCKOUT_SECS: u64 = 15 * 60;
+/// Only slows down guessing through a copy of the flash a little, a PIN has
+/// too few digits for any hash to make that hard
+const ROUNDS: u32 = 4096;
+/// The stored hash without a PIN
+pub const NONE: u32 = 0;
+
+/// Salted and iterated FNV-1a, never [`NONE`] and never erased flash.
+///
+/// This keeps the PIN from being read straight out of the settings, nothing
+/// more. The lock is a UI lock.
+pub fn hash(salt: u32, pin: &str) -> u32 {
+    let mut hash = 0x811C_9DC5 ^ salt;
+    for _ in 0..ROUNDS {
+        for byte in pin.bytes() {
+            hash ^= byte as u32;
+            hash = hash.wrapping_mul(0x0100_0193);
+        }
+    }
+    hash.clamp(1, u32::MAX - 1)
+}
+
+/// How many seconds to wait after `failures` wrong guesses in a row before
+/// the next, once the free attempts are used up.
+pub fn lockout_secs(failures: u32) -> Option<u64> {
+    let extra = failures.checked_sub(FREE_ATTEMPTS)?;
+    let factor = 1u64.checked_shl(extra).unwrap_or(u64::MAX);
+    let secs = FIRST_LOCKOUT_SECS.saturating_mul(factor);
+    Some(secs.min(MAX_LOCKOUT_SECS))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hash_depends_on_pin_and_salt() {
+        assert_eq!(hash(1, "1234"), hash(1, "1234"));
+        assert_ne!(hash(1, "1234"), hash(1, "1235"));
+        assert_ne!(hash(1, "1234"), hash(2, "1234"));
+    }
+
+    #[test]
+    fn hash_is_never_a_blank_value() {
+        // Salts that give 0 and `u32::MAX` before a single round
+        assert_eq!(hash(0x811C_9DC5, ""), 1);
+        assert_eq!(hash(!0x811C_9DC5, ""), u32::MAX - 1);
+        for salt in 0..2000 {
+            let hash = hash(salt, "1234");
+            assert!(hash != NONE && hash != u32::MAX);
+        }
+    }
+
+    #[test]
+    fn free_attempts() {
+        for failures in 0..FREE_ATTEMPTS {
+            assert_eq!(lockout_secs(failures), None);
+        }
+    }
+
+    #[test]
+    fn lockout_doubles() {
+        assert_eq!(lockout_secs(3), Some(30));
+        assert_eq!(lockout_secs(4), Some(60));
+        assert_eq!(lockout_secs(5), Some(120));
+        assert_eq!(lockout_secs(7), Some(480));
+        assert_eq!(lockout_secs(8), Some(MAX_LOCKOUT_SECS));
+    }
+
+    #[test]
+    fn lockout_is_capped() {
+        assert_eq!(lockout_secs(63), Some(MAX_LOCKOUT_SECS));
+        assert_eq!(lockout_secs(64), Some(MAX_LOCKOUT_SECS));
+        assert_eq!(lockout_secs(u32::MAX), Some(MAX_LOCKOUT_SECS));
+    }
+}
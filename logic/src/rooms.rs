@@ -0,0 +1,269 @@
+use alloc::format;
+use alloc::string::String;
+
+use crate::mqtt::Message;
+
+/// Rooms on the dashboard, numbered from 0 in the topics
+pub const ROOMS: usize = 4;
+
+/// What the devices of a room last reported, `None` until they have.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct Report {
+    /// In tenths of a degree Celsius
+    pub temperature: Option<i32>,
+    pub light: Option<bool>,
+    /// 0 is open, 100 is closed
+    pub blinds: Option<i32>,
+}
+
+impl Report {
+    pub const NONE: Report = Report {
+        temperature: None,
+        light: None,
+        blinds: None,
+    };
+
+    /// Takes `command` as done.
+    pub fn apply(&mut self, command: Command) {
+        match command {
+            Command::Light(on) => self.light = Some(on),
+            Command::Blinds(percent) => self.blinds = Some(percent),
+        }
+    }
+}
+
+/// A change made on the dashboard, for the devices of a room.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Command {
+    Light(bool),
+    Blinds(i32),
+}
+
+/// The latest [`Command`] of each kind for a room, not sent yet.
+#[derive(Clone, Copy, Default)]
+pub struct Pending {
+    pub light: Option<bool>,
+    pub blinds: Option<i32>,
+}
+
+impl Pending {
+    /// Replaces the pending command of the same kind.
+    pub fn push(&mut self, command: Command) {
+        match command {
+            Command::Light(on) => self.light = Some(on),
+            Command::Blinds(percent) => self.blinds = Some(percent),
+        }
+    }
+
+    pub fn commands(self) -> impl Iterator<Item = Command> {
+        let light = self.light.map(Command::Light);
+        light.into_iter().chain(self.blinds.map(Command::Blinds))
+    }
+}
+
+/// Topic filter for the state topics of every room, such as
+/// `cyd_a1b2c3/room/0/temperature`.
+pub fn state_filter(id: &str) -> String {
+    format!("{id}/room/+/+")
+}
+
+/// Topic and payload telling the devices of `room` about `command`.
+pub fn command_message(id: &str, room: usize, command: Command) -> (String, String) {
+    match command {
+        Command::Light(on) => (
+            format!("{id}/room/{room}/light/set"),
+            String::from(if on { "ON" } else { "OFF" }),
+        ),
+        Command::Blinds(percent) => (format!("{id}/room/{room}/blinds/set"), format!("{percent}")),
+    }
+}
+
+/// Takes a message on one of the [`state_filter`] topics into `reports`,
+/// ignoring anything else.
+pub fn receive(id: &str, message: &Message, reports: &mut [Report; ROOMS]) {
+    let (Ok(topic), Ok(payload)) = (
+        core::str::from_utf8(&message.topic),
+        core::str::from_utf8(&message.payload),
+    ) else {
+        return;
+    };
+    let Some(rest) = topic
+        .strip_prefix(id)
+        .and_then(|rest| rest.strip_prefix("/room/"))
+    else {
+        return;
+    };
+    let Some((room, field)) = rest.split_once('/') else {
+        return;
+    };
+    let Some(report) = room
+        .parse::<usize>()
+        .ok()
+        .and_then(|room| reports.get_mut(room))
+    else {
+        return;
+    };
+    let payload = payload.trim();
+    match field {
+        "temperature" => {
+            if let Some(tenths) = tenths(payload) {
+                report.temperature = Some(tenths);
+            }
+        }
+        "light" => match payload {
+            "ON" => report.light = Some(true),
+            "OFF" => report.light = Some(false),
+            _ => {}
+        },
+        "blinds" => {
+            if let Ok(percent) = payload.parse::<i32>() {
+                report.blinds = Some(percent.clamp(0, 100));
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Parses degrees like `21.5` or `-3` into tenths, dropping further digits.
+fn tenths(degrees: &str) -> Option<i32> {
+    let (negative, degrees) = match degrees.strip_prefix('-') {
+        Some(degrees) => (true, degrees),
+        None => (false, degrees),
+    };
+    let (whole, fraction) = degrees.split_once('.').unwrap_or((degrees, "0"));
+    let whole = whole.parse::<u16>().ok()? as i32;
+    let tenth = match fraction.bytes().next() {
+        Some(digit @ b'0'..=b'9') => (digit - b'0') as i32,
+        _ => return None,
+    };
+    let tenths = whole * 10 + tenth;
+    Some(if negative { -tenths } else { tenths })
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::*;
+
+    const ID: &str = "cyd_a1b2c3";
+
+    fn received(messages: &[(&str, &str)]) -> [Report; ROOMS] {
+        let mut reports = [Report::NONE; ROOMS];
+        for (topic, payload) in messages {
+            let message = Message {
+                topic: topic.as_bytes().to_vec(),
+                payload: payload.as_bytes().to_vec(),
+            };
+            receive(ID, &message, &mut reports);
+        }
+        reports
+    }
+
+    #[test]
+    fn reports() {
+        let reports = received(&[
+            ("cyd_a1b2c3/room/0/temperature", "21.5"),
+            ("cyd_a1b2c3/room/0/light", "ON"),
+            ("cyd_a1b2c3/room/3/blinds", " 40\n"),
+            ("cyd_a1b2c3/room/3/light", "ON"),
+            ("cyd_a1b2c3/room/3/light", "OFF"),
+        ]);
+        assert_eq!(
+            reports[0],
+            Report {
+                temperature: Some(215),
+                light: Some(true),
+                blinds: None,
+            }
+        );
+        assert_eq!(reports[1], Report::NONE);
+        assert_eq!(reports[3].blinds, Some(40));
+        assert_eq!(reports[3].light, Some(false));
+    }
+
+    #[test]
+    fn blinds_are_clamped() {
+        let reports = received(&[
+            ("cyd_a1b2c3/room/0/blinds", "150"),
+            ("cyd_a1b2c3/room/1/blinds", "-5"),
+        ]);
+        assert_eq!(reports[0].blinds, Some(100));
+        assert_eq!(reports[1].blinds, Some(0));
+    }
+
+    #[test]
+    fn ignored_messages() {
+        let reports = received(&[
+            ("other/room/0/light", "ON"),
+            ("cyd_a1b2c3/room/4/light", "ON"),
+            ("cyd_a1b2c3/room/x/light", "ON"),
+            ("cyd_a1b2c3/room/0", "ON"),
+            ("cyd_a1b2c3/room/0/light", "on"),
+            ("cyd_a1b2c3/room/0/humidity", "50"),
+            ("cyd_a1b2c3/room/0/temperature", "warm"),
+            ("cyd_a1b2c3/room/0/blinds", "half"),
+        ]);
+        assert_eq!(reports, [Report::NONE; ROOMS]);
+        let mut reports = [Report::NONE; ROOMS];
+        let not_utf8 = Message {
+            topic: b"cyd_a1b2c3/room/0/light".to_vec(),
+            payload: [0xFF].to_vec(),
+        };
+        receive(ID, &not_utf8, &mut reports);
+        assert_eq!(reports[0], Report::NONE);
+    }
+
+    #[test]
+    fn tenths_of_degrees() {
+        assert_eq!(tenths("21.5"), Some(215));
+        assert_eq!(tenths("21.56"), Some(215));
+        assert_eq!(tenths("-3"), Some(-30));
+        assert_eq!(tenths("-0.5"), Some(-5));
+        assert_eq!(tenths("0"), Some(0));
+        assert_eq!(tenths("21."), None);
+        assert_eq!(tenths("1e2"), None);
+        assert_eq!(tenths(""), None);
+        assert_eq!(tenths("--1"), None);
+        assert_eq!(tenths("70000"), None);
+    }
+
+    #[test]
+    fn commands() {
+        assert_eq!(
+            command_message(ID, 2, Command::Light(true)),
+            (
+                String::from("cyd_a1b2c3/room/2/light/set"),
+                String::from("ON")
+            )
+        );
+        assert_eq!(
+            command_message(ID, 0, Command::Blinds(75)),
+            (
+                String::from("cyd_a1b2c3/room/0/blinds/set"),
+                String::from("75")
+            )
+        );
+        assert_eq!(state_filter(ID), "cyd_a1b2c3/room/+/+");
+    }
+
+    #[test]
+    fn latest_pending_command_of_each_kind() {
+        let mut pending = Pending::default();
+        assert_eq!(pending.commands().count(), 0);
+        pending.push(Command::Blinds(10));
+        pending.push(Command::Light(true));
+        pending.push(Command::Blinds(60));
+        let commands: Vec<_> = pending.commands().collect();
+        assert_eq!(commands, [Command::Light(true), Command::Blinds(60)]);
+    }
+
+    #[test]
+    fn applied_commands() {
+        let mut report = Report::NONE;
+        report.apply(Command::Light(false));
+        report.apply(Command::Blinds(30));
+        assert_eq!(report.light, Some(false));
+        assert_eq!(report.blinds, Some(30));
+    }
+}
@@ -0,0 +1,78 @@
+use embedded_graphics::prelude::Point;
+use mipidsi::options::Orientation;
+
+/// Which way up the board is mounted, kept in the settings as its index in
+/// [`Rotation::ALL`].
+///
+/// The panel rotates the picture itself, so this costs nothing per frame.
+/// Touch calibration is always done the normal way up and its points are
+/// turned with [`Rotation::map_touch`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Rotation {
+    Normal = 0,
+    UpsideDown = 1,
+}
+
+impl Rotation {
+    pub const ALL: [Rotation; 2] = [Rotation::Normal, Rotation::UpsideDown];
+
+    pub fn from_index(index: u32) -> Self {
+        Self::ALL
+            .get(index as usize)
+            .copied()
+            .unwrap_or(Rotation::Normal)
+    }
+
+    pub fn orientation(self) -> Orientation {
+        let rotation = match self {
+            Rotation::Normal => mipidsi::options::Rotation::Deg270,
+            Rotation::UpsideDown => mipidsi::options::Rotation::Deg90,
+        };
+        Orientation::default().rotate(rotation)
+    }
+
+    /// Turns a calibrated touch point on a `width` by `height` display.
+    pub fn map_touch(self, point: Point, width: i32, height: i32) -> Point {
+        match self {
+            Rotation::Normal => point,
+            Rotation::UpsideDown => Point::new(width - 1 - point.x, height - 1 - point.y),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn indices() {
+        for (index, rotation) in Rotation::ALL.into_iter().enumerate() {
+            assert_eq!(Rotation::from_index(index as u32), rotation);
+            assert_eq!(rotation as usize, index);
+        }
+        assert_eq!(Rotation::from_index(7), Rotation::Normal);
+    }
+
+    #[test]
+    fn touch_points() {
+        let point = Point::new(10, 20);
+        assert_eq!(Rotation::Normal.map_touch(point, 320, 240), point);
+        let turned = Rotation::UpsideDown.map_touch(point, 320, 240);
+        assert_eq!(turned, Point::new(309, 219));
+        assert_eq!(Rotation::UpsideDown.map_touch(turned, 320, 240), point);
+        assert_eq!(
+            Rotation::UpsideDown.map_touch(Point::zero(), 320, 240),
+            Point::new(319, 239)
+        );
+    }
+
+    #[test]
+    fn orientations_differ_by_half_a_turn() {
+        let normal = Rotation::Normal.orientation();
+        let upside_down = Rotation::UpsideDown.orientation();
+        assert_eq!(
+            normal.rotate(mipidsi::options::Rotation::Deg180),
+            upside_down
+        );
+    }
+}
@@ -0,0 +1,221 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Tar headers and file data come in blocks of this
+pub const BLOCK: u32 = 512;
+
+/// A regular file in the archive.
+#[derive(PartialEq, Eq, Debug)]
+pub struct Entry {
+    pub name: String,
+    /// Of the data, from the start of the archive
+    pub offset: u32,
+    pub size: u32,
+}
+
+/// Why [`index`] stopped before the end of the archive, at the header
+/// `offset` bytes into it.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Error {
+    Read {
+        offset: u32,
+    },
+    BadHeader {
+        offset: u32,
+    },
+    /// The entry is larger than what is left of the space
+    TooLong {
+        offset: u32,
+    },
+}
+
+/// Walks the tar headers of an archive taking up to `size` bytes, keeping
+/// the regular files, at most `max_files` of them. `read` fills a buffer
+/// from an offset into the archive, `false` when it cannot.
+///
+/// The files before an error are still returned along with it.
+pub fn index(
+    size: u32,
+    max_files: usize,
+    mut read: impl FnMut(u32, &mut [u8]) -> bool,
+) -> (Vec<Entry>, Option<Error>) {
+    let mut entries = Vec::new();
+    let mut offset = 0;
+    while offset + BLOCK <= size && entries.len() < max_files {
+        let mut header = [0u8; BLOCK as usize];
+        if !read(offset, &mut header) {
+            return (entries, Some(Error::Read { offset }));
+        }
+        // The archive ends with zeroed blocks, erased flash has no magic
+        if &header[257..262] != b"ustar" {
+            break;
+        }
+        let Some(length) = octal(&header[124..136]) else {
+            return (entries, Some(Error::BadHeader { offset }));
+        };
+        let regular = matches!(header[156], b'0' | 0);
+        let name = [&header[345..500], &header[..100]]
+            .map(|field| {
+                let end = field
+                    .iter()
+                    .position(|&byte| byte == 0)
+                    .unwrap_or(field.len());
+                core::str::from_utf8(&field[..end]).unwrap_or_default()
+            })
+            .into_iter()
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join("/");
+        // The loop keeps `offset + BLOCK` within the space, so this cannot
+        // wrap around like adding a huge `length` to it could
+        if length > size - offset - BLOCK {
+            return (entries, Some(Error::TooLong { offset }));
+        }
+        if regular {
+            entries.push(Entry {
+                name,
+                offset: offset + BLOCK,
+                size: length,
+            });
+        }
+        offset += BLOCK + length.div_ceil(BLOCK) * BLOCK;
+    }
+    (entries, None)
+}
+
+/// Parses a NUL or space terminated octal tar field.
+fn octal(field: &[u8]) -> Option<u32> {
+    let digits = field
+        .iter()
+        .take_while(|&&byte| byte != 0 && byte != b' ')
+        .collect::<Vec<_>>();
+    if digits.is_empty() {
+        return None;
+    }
+    digits.into_iter().try_fold(0u32, |value, &digit| {
+        let digit = (digit as char).to_digit(8)?;
+        value.checked_mul(8)?.checked_add(digit)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::format;
+    use alloc::vec;
+
+    use super::*;
+
+    /// A ustar header for `name` of `size` bytes, of type `kind`.
+    fn header(name: &str, prefix: &str, size: usize, kind: u8) -> Vec<u8> {
+        let mut header = vec![0; BLOCK as usize];
+        header[..name.len()].copy_from_slice(name.as_bytes());
+        let size = format!("{size:011o}\0");
+        header[124..136].copy_from_slice(size.as_bytes());
+        header[156] = kind;
+        header[257..263].copy_from_slice(b"ustar\0");
+        header[345..345 + prefix.len()].copy_from_slice(prefix.as_bytes());
+        header
+    }
+
+    fn archive(files: &[(&str, usize, u8)]) -> Vec<u8> {
+        let mut archive = Vec::new();
+        for &(name, size, kind) in files {
+            archive.extend(header(name, "", size, kind));
+            archive.extend(vec![b'x'; size.div_ceil(BLOCK as usize) * BLOCK as usize]);
+        }
+        // End of archive
+        archive.extend([0; 2 * BLOCK as usize]);
+        archive
+    }
+
+    fn read_from(archive: &[u8]) -> impl FnMut(u32, &mut [u8]) -> bool {
+        move |offset, buffer| {
+            let start = offset as usize;
+            match archive.get(start..start + buffer.len()) {
+                Some(data) => {
+                    buffer.copy_from_slice(data);
+                    true
+                }
+                None => false,
+            }
+        }
+    }
+
+    #[test]
+    fn regular_files() {
+        let archive = archive(&[
+            ("logo.png", 700, b'0'),
+            ("images/", 0, b'5'),
+            ("a.json", 0, 0),
+        ]);
+        let (entries, error) = index(archive.len() as u32, 64, read_from(&archive));
+        assert_eq!(error, None);
+        assert_eq!(
+            entries,
+            [
+                Entry {
+                    name: String::from("logo.png"),
+                    offset: 512,
+                    size: 700
+                },
+                // Past the two blocks of data and the directory
+                Entry {
+                    name: String::from("a.json"),
+                    offset: 512 + 1024 + 512 + 512,
+                    size: 0
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn prefixed_names() {
+        let mut archive = header("file.txt", "some/dir", 1, b'0');
+        archive.extend([0; 3 * BLOCK as usize]);
+        let (entries, _) = index(archive.len() as u32, 64, read_from(&archive));
+        assert_eq!(entries[0].name, "some/dir/file.txt");
+    }
+
+    #[test]
+    fn erased_flash_is_empty() {
+        let erased = vec![0xFF; 4 * BLOCK as usize];
+        assert_eq!(
+            index(erased.len() as u32, 64, read_from(&erased)),
+            (vec![], None)
+        );
+    }
+
+    #[test]
+    fn at_most_max_files() {
+        let archive = archive(&[("a", 1, b'0'), ("b", 1, b'0'), ("c", 1, b'0')]);
+        let (entries, error) = index(archive.len() as u32, 2, read_from(&archive));
+        assert_eq!(entries.len(), 2);
+        assert_eq!(error, None);
+    }
+
+    #[test]
+    fn errors_keep_the_files_before() {
+        let mut archive = archive(&[("a", 1, b'0'), ("b", 1, b'0')]);
+        archive[1024 + 124] = b'9';
+        let (entries, error) = index(archive.len() as u32, 64, read_from(&archive));
+        assert_eq!(entries.len(), 1);
+        assert_eq!(error, Some(Error::BadHeader { offset: 1024 }));
+
+        let archive = header("big", "", 10_000, b'0');
+        let (entries, error) = index(4096, 64, read_from(&archive));
+        assert!(entries.is_empty());
+        assert_eq!(error, Some(Error::TooLong { offset: 0 }));
+
+        let (_, error) = index(4096, 64, |_, _| false);
+        assert_eq!(error, Some(Error::Read { offset: 0 }));
+    }
+
+    #[test]
+    fn octal_fields() {
+        assert_eq!(octal(b"00000001274\0"), Some(700));
+        assert_eq!(octal(b"   "), None);
+        assert_eq!(octal(b"1274 "), Some(700));
+        assert_eq!(octal(b"8"), None);
+        assert_eq!(octal(b"77777777777"), None);
+    }
+}
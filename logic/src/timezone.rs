@@ -0,0 +1,199 @@
+use core::ffi::CStr;
+
+use crate::clock::{DateTime, days_from_civil};
+
+/// When a zone switches to daylight saving time, one hour ahead.
+///
+/// There is no tz database on the device, so only the rules of the zones in
+/// [`ZONES`] are known.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Dst {
+    None,
+    /// Last Sunday of March to last Sunday of October, at 01:00 UTC
+    Europe,
+    /// Second Sunday of March to first Sunday of November, at 02:00 local time
+    UnitedStates,
+    /// First Sunday of October to first Sunday of April, at 02:00 standard time
+    Australia,
+}
+
+pub struct Zone {
+    pub name: &'static CStr,
+    /// Standard time offset from UTC
+    pub offset_minutes: i32,
+    pub dst: Dst,
+}
+
+const fn zone(name: &'static CStr, offset_minutes: i32, dst: Dst) -> Zone {
+    Zone {
+        name,
+        offset_minutes,
+        dst,
+    }
+}
+
+/// Zones offered in the settings. New zones go at the end, as the index is
+/// what the settings store.
+pub const ZONES: [Zone; 16] = [
+    zone(c"UTC", 0, Dst::None),
+    zone(c"Europe/London", 0, Dst::Europe),
+    zone(c"Europe/Amsterdam", 60, Dst::Europe),
+    zone(c"Europe/Athens", 120, Dst::Europe),
+    zone(c"Europe/Moscow", 180, Dst::None),
+    zone(c"Asia/Dubai", 240, Dst::None),
+    zone(c"Asia/Kolkata", 330, Dst::None),
+    zone(c"Asia/Shanghai", 480, Dst::None),
+    zone(c"Asia/Tokyo", 540, Dst::None),
+    zone(c"Australia/Sydney", 600, Dst::Australia),
+    zone(c"Pacific/Honolulu", -600, Dst::None),
+    zone(c"America/Anchorage", -540, Dst::UnitedStates),
+    zone(c"America/Los_Angeles", -480, Dst::UnitedStates),
+    zone(c"America/Denver", -420, Dst::UnitedStates),
+    zone(c"America/Chicago", -360, Dst::UnitedStates),
+    zone(c"America/New_York", -300, Dst::UnitedStates),
+];
+
+impl Zone {
+    /// Offset from UTC in seconds at the Unix time `utc`, including DST.
+    pub fn offset_secs(&self, utc: u32) -> i32 {
+        let standard = self.offset_minutes * 60;
+        let year = DateTime::from_unix(utc).year;
+        let utc = utc as i64;
+        // Transition instants in UTC
+        let at = |month, nth: Nth, local_secs: i64, offset: i32| {
+            sunday(year, month, nth) * 86400 + local_secs - offset as i64
+        };
+        let daylight = match self.dst {
+            Dst::None => false,
+            Dst::Europe => (at(3, Nth::Last, 3600, 0)..at(10, Nth::Last, 3600, 0)).contains(&utc),
+            Dst::UnitedStates => (at(3, Nth::Second, 7200, standard)
+                ..at(11, Nth::First, 7200, standard + 3600))
+                .contains(&utc),
+            Dst::Australia => {
+                // Southern summer spans new year
+                utc < at(4, Nth::First, 7200, standard) || utc >= at(10, Nth::First, 7200, standard)
+            }
+        };
+        if daylight { standard + 3600 } else { standard }
+    }
+
+    /// Converts Unix time to local time in this zone.
+    pub fn to_local(&self, utc: u32) -> i64 {
+        utc as i64 + self.offset_secs(utc) as i64
+    }
+
+    /// Converts local time in this zone back to Unix time. Times in the hour
+    /// skipped or repeated by DST resolve to one of the candidates.
+    pub fn to_utc(&self, local: i64) -> u32 {
+        let guess = (local - self.offset_minutes as i64 * 60).max(0) as u32;
+        (local - self.offset_secs(guess) as i64).max(0) as u32
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Nth {
+    First,
+    Second,
+    Last,
+}
+
+/// Days since the Unix epoch of a Sunday in `month`
+fn sunday(year: i32, month: u8, nth: Nth) -> i64 {
+    // 0 is Monday, like `DateTime::weekday`
+    let weekday = |days: i32| (days + 3).rem_euclid(7);
+    let first_sunday = || {
+        let first = days_from_civil(year, month, 1);
+        first + (6 - weekday(first))
+    };
+    let days = match nth {
+        Nth::First => first_sunday(),
+        Nth::Second => first_sunday() + 7,
+        Nth::Last => {
+            let (next_year, next_month) = if month == 12 {
+                (year + 1, 1)
+            } else {
+                (year, month + 1)
+            };
+            let last = days_from_civil(next_year, next_month, 1) - 1;
+            last - (weekday(last) + 1) % 7
+        }
+    };
+    days as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn zone(name: &str) -> &'static Zone {
+        ZONES
+            .iter()
+            .find(|zone| zone.name.to_str() == Ok(name))
+            .unwrap()
+    }
+
+    /// Offsets in hours just before and at `switch`.
+    fn around(zone: &Zone, switch: u32) -> (i32, i32) {
+        (
+            zone.offset_secs(switch - 1) / 3600,
+            zone.offset_secs(switch) / 3600,
+        )
+    }
+
+    #[test]
+    fn europe() {
+        let amsterdam = zone("Europe/Amsterdam");
+        // 2025-03-30 01:00 and 2025-10-26 01:00 UTC
+        assert_eq!(around(amsterdam, 1_743_296_400), (1, 2));
+        assert_eq!(around(amsterdam, 1_761_440_400), (2, 1));
+        assert_eq!(around(zone("Europe/London"), 1_743_296_400), (0, 1));
+    }
+
+    #[test]
+    fn united_states() {
+        let new_york = zone("America/New_York");
+        // 02:00 local on 2025-03-09 and 2025-11-02
+        assert_eq!(around(new_york, 1_741_503_600), (-5, -4));
+        assert_eq!(around(new_york, 1_762_063_200), (-4, -5));
+    }
+
+    #[test]
+    fn australia() {
+        let sydney = zone("Australia/Sydney");
+        // 02:00 standard time on 2025-04-06 and 2025-10-05
+        assert_eq!(around(sydney, 1_743_868_800), (11, 10));
+        assert_eq!(around(sydney, 1_759_593_600), (10, 11));
+        // Over new year
+        assert_eq!(sydney.offset_secs(1_735_689_600) / 3600, 11);
+    }
+
+    #[test]
+    fn without_dst() {
+        let kolkata = zone("Asia/Kolkata");
+        assert_eq!(kolkata.offset_secs(1_743_296_400), 330 * 60);
+        assert_eq!(kolkata.offset_secs(1_761_440_400), 330 * 60);
+    }
+
+    #[test]
+    fn round_trip() {
+        for zone in &ZONES {
+            for utc in [1_735_689_600, 1_750_000_000, 1_761_500_000] {
+                assert_eq!(zone.to_utc(zone.to_local(utc)), utc, "{:?}", zone.name);
+            }
+        }
+    }
+
+    #[test]
+    fn skipped_hour() {
+        let amsterdam = zone("Europe/Amsterdam");
+        // 02:30 on 2025-03-30 does not exist there
+        let local = 1_743_301_800;
+        let utc = amsterdam.to_utc(local);
+        assert!(utc.abs_diff(1_743_301_800 - 3600) <= 3600);
+    }
+
+    #[test]
+    fn local_before_the_epoch() {
+        assert_eq!(zone("Asia/Tokyo").to_utc(-100), 0);
+    }
+}
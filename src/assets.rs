@@ -0,0 +1,224 @@
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::ffi::CString;
+use alloc::format;
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::ffi::{CStr, c_char, c_void};
+
+use logic::tar::{self, Entry};
+use lv_bevy_ecs::sys::{
+    LV_FS_MODE_WR, LV_FS_RES_HW_ERR, LV_FS_RES_INV_PARAM, LV_FS_RES_OK, LV_FS_SEEK_CUR,
+    LV_FS_SEEK_END, LV_FS_SEEK_SET, lv_fs_drv_init, lv_fs_drv_register, lv_fs_drv_t, lv_fs_mode_t,
+    lv_fs_res_t, lv_fs_whence_t,
+};
+
+use crate::settings::Settings;
+
+/// Start of the `assets` partition in `partitions.csv`, which holds a tar
+/// archive.
+///
+/// `tar -cf assets.tar *.png && espflash write-bin 0x330000 assets.tar`
+pub const ASSETS_OFFSET: u32 = 0x330000;
+const ASSETS_SIZE: u32 = 0xD0000;
+/// LVGL drive the archive is mounted as, so `A:logo.png` opens `logo.png`
+const LETTER: u8 = b'A';
+/// Keeps the index small even for an archive of tiny files
+const MAX_FILES: usize = 64;
+/// Largest text file read whole, many times any layout or table
+pub const MAX_TEXT: usize = 16 * 1024;
+
+/// What the LVGL callbacks get through the driver's `user_data`
+struct Drive {
+    settings: Rc<RefCell<Settings>>,
+    entries: Vec<Entry>,
+}
+
+/// An open file, handed to LVGL as its file pointer
+struct File {
+    /// Of the data, from the start of flash
+    offset: u32,
+    size: u32,
+    position: u32,
+}
+
+/// Files in the `assets` partition, readable by LVGL once [`mount`]ed.
+///
+/// Images can be shown straight from there with a decoder for their format,
+/// so they need not be converted and built into the firmware.
+#[derive(Clone, Copy)]
+pub struct Assets {
+    drive: &'static Drive,
+}
+
+impl Assets {
+    /// Names of the files, in archive order
+    pub fn names(&self) -> impl Iterator<Item = &'static str> {
+        self.drive.entries.iter().map(|entry| entry.name.as_str())
+    }
+
+    /// Contents of the file called `name`, for files read from Rust rather
+    /// than by LVGL. `None` as well when it is longer than `max` bytes, so a
+    /// bad archive cannot take the heap.
+    pub fn read(&self, name: &str, max: usize) -> Option<Vec<u8>> {
+        let entry = self.drive.entries.iter().find(|entry| entry.name == name)?;
+        if entry.size > ASSETS_SIZE || entry.size as usize > max {
+            defmt::warn!("{=str} is too large to read", name);
+            return None;
+        }
+        let mut data = vec![0; entry.size as usize];
+        let ok = self
+            .drive
+            .settings
+            .borrow_mut()
+            .read_flash(ASSETS_OFFSET + entry.offset, &mut data);
+        ok.then_some(data)
+    }
+
+    /// Text of the file called `name`, up to [`MAX_TEXT`] long, or
+    /// `built_in` when it is missing or not UTF-8.
+    pub fn read_text_or<'a>(&self, name: &str, built_in: &'a str) -> Cow<'a, str> {
+        self.read(name, MAX_TEXT)
+            .and_then(|data| String::from_utf8(data).ok())
+            .map_or(Cow::Borrowed(built_in), Cow::Owned)
+    }
+
+    /// LVGL path of the file called `name`, for `lv_image_set_src` and such
+    pub fn path(name: &str) -> CString {
+        CString::new(format!("{}:{}", LETTER as char, name)).unwrap_or_default()
+    }
+}
+
+/// Reads the index of the archive flashed at [`ASSETS_OFFSET`] and registers
+/// it with LVGL. Call it after `lv_init`.
+///
+/// An empty or erased partition mounts as a drive without files.
+pub fn mount(settings: Rc<RefCell<Settings>>) -> Assets {
+    let entries = read_index(&mut settings.borrow_mut());
+    defmt::info!("{} files in the assets partition", entries.len());
+    let drive: &'static Drive = Box::leak(Box::new(Drive { settings, entries }));
+
+    let driver: &'static mut lv_fs_drv_t = Box::leak(Box::new(unsafe { core::mem::zeroed() }));
+    unsafe {
+        lv_fs_drv_init(driver);
+        driver.letter = LETTER as c_char;
+        driver.open_cb = Some(open);
+        driver.close_cb = Some(close);
+        driver.read_cb = Some(read);
+        driver.seek_cb = Some(seek);
+        driver.tell_cb = Some(tell);
+        driver.user_data = (drive as *const Drive).cast_mut().cast();
+        lv_fs_drv_register(driver);
+    }
+    Assets { drive }
+}
+
+/// Reads the index of the archive, keeping the regular files. Their offsets
+/// are from the start of the partition.
+fn read_index(settings: &mut Settings) -> Vec<Entry> {
+    let (entries, error) = tar::index(ASSETS_SIZE, MAX_FILES, |offset, buffer| {
+        settings.read_flash(ASSETS_OFFSET + offset, buffer)
+    });
+    match error {
+        None => {}
+        Some(tar::Error::Read { .. }) => defmt::error!("Could not read the assets partition"),
+        Some(tar::Error::BadHeader { offset }) => {
+            defmt::warn!("Bad tar header at {:#x}", offset)
+        }
+        Some(tar::Error::TooLong { offset }) => {
+            defmt::warn!("Tar entry at {:#x} runs past the partition", offset)
+        }
+    }
+    entries
+}
+
+unsafe fn drive(driver: *mut lv_fs_drv_t) -> &'static Drive {
+    unsafe { &*(*driver).user_data.cast::<Drive>() }
+}
+
+unsafe extern "C" fn open(
+    driver: *mut lv_fs_drv_t,
+    path: *const c_char,
+    mode: lv_fs_mode_t,
+) -> *mut c_void {
+    if mode & LV_FS_MODE_WR != 0 {
+        return core::ptr::null_mut();
+    }
+    let drive = unsafe { drive(driver) };
+    let Ok(path) = unsafe { CStr::from_ptr(path) }.to_str() else {
+        return core::ptr::null_mut();
+    };
+    let path = path.trim_start_matches('/');
+    match drive.entries.iter().find(|entry| entry.name == path) {
+        Some(entry) => Box::into_raw(Box::new(File {
+            offset: ASSETS_OFFSET + entry.offset,
+            size: entry.size,
+            position: 0,
+        }))
+        .cast(),
+        None => core::ptr::null_mut(),
+    }
+}
+
+unsafe extern "C" fn close(_driver: *mut lv_fs_drv_t, file: *mut c_void) -> lv_fs_res_t {
+    drop(unsafe { Box::from_raw(file.cast::<File>()) });
+    LV_FS_RES_OK
+}
+
+unsafe extern "C" fn read(
+    driver: *mut lv_fs_drv_t,
+    file: *mut c_void,
+    buffer: *mut c_void,
+    to_read: u32,
+    read: *mut u32,
+) -> lv_fs_res_t {
+    let drive = unsafe { drive(driver) };
+    let file = unsafe { &mut *file.cast::<File>() };
+    let length = to_read.min(file.size - file.position);
+    let bytes = unsafe { core::slice::from_raw_parts_mut(buffer.cast::<u8>(), length as usize) };
+    let ok = drive
+        .settings
+        .borrow_mut()
+        .read_flash(file.offset + file.position, bytes);
+    if !ok {
+        unsafe { *read = 0 };
+        return LV_FS_RES_HW_ERR;
+    }
+    file.position += length;
+    unsafe { *read = length };
+    LV_FS_RES_OK
+}
+
+unsafe extern "C" fn seek(
+    _driver: *mut lv_fs_drv_t,
+    file: *mut c_void,
+    position: u32,
+    whence: lv_fs_whence_t,
+) -> lv_fs_res_t {
+    let file = unsafe { &mut *file.cast::<File>() };
+    let position = match whence {
+        LV_FS_SEEK_SET => Some(position),
+        LV_FS_SEEK_CUR => file.position.checked_add(position),
+        LV_FS_SEEK_END => file.size.checked_add(position),
+        _ => None,
+    };
+    match position {
+        Some(position) => {
+            file.position = position.min(file.size);
+            LV_FS_RES_OK
+        }
+        None => LV_FS_RES_INV_PARAM,
+    }
+}
+
+unsafe extern "C" fn tell(
+    _driver: *mut lv_fs_drv_t,
+    file: *mut c_void,
+    position: *mut u32,
+) -> lv_fs_res_t {
+    unsafe { *position = (*file.cast::<File>()).position };
+    LV_FS_RES_OK
+}
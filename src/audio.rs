@@ -0,0 +1,243 @@
+use core::cell::RefCell;
+
+use embassy_sync::blocking_mutex::Mutex;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use esp_hal::Blocking;
+use esp_hal::analog::dac::Dac;
+use esp_hal::handler;
+use esp_hal::peripherals::{DAC2, GPIO26, TIMG1};
+use esp_hal::time::Duration;
+use esp_hal::timer::PeriodicTimer;
+use esp_hal::timer::timg::TimerGroup;
+
+/// Output sample rate of [`start_tone`]
+const TONE_RATE: u32 = 16_000;
+/// DAC value of silence
+const MIDPOINT: i32 = 128;
+
+/// A WAV clip kept in flash, mono PCM with 8 or 16 bit samples.
+#[derive(Clone, Copy)]
+pub struct Clip {
+    samples: &'static [u8],
+    sample_rate: u32,
+    /// 1 or 2
+    bytes_per_sample: usize,
+}
+
+impl Clip {
+    /// Parses a WAV file, `None` for compressed, stereo or malformed ones.
+    pub fn from_wav(wav: &'static [u8]) -> Option<Self> {
+        if wav.get(..4)? != b"RIFF" || wav.get(8..12)? != b"WAVE" {
+            return None;
+        }
+        let mut format = None;
+        let mut chunks = wav.get(12..)?;
+        while chunks.len() >= 8 {
+            let id = &chunks[..4];
+            let len = u32::from_le_bytes(chunks[4..8].try_into().unwrap()) as usize;
+            let body = chunks.get(8..8 + len)?;
+            match id {
+                b"fmt " => format = Some(body),
+                b"data" => {
+                    let format = format?;
+                    let field = |at: usize| u16::from_le_bytes([format[at], format[at + 1]]);
+                    if format.len() < 16 || field(0) != 1 || field(2) != 1 {
+                        return None;
+                    }
+                    let sample_rate = u32::from_le_bytes(format[4..8].try_into().unwrap());
+                    let bytes_per_sample = match field(14) {
+                        8 => 1,
+                        16 => 2,
+                        _ => return None,
+                    };
+                    return Some(Self {
+                        samples: body,
+                        sample_rate,
+                        bytes_per_sample,
+                    });
+                }
+                _ => {}
+            }
+            // Chunks are padded to an even length
+            chunks = chunks.get(8 + len + len % 2..)?;
+        }
+        None
+    }
+
+    pub fn duration_ms(&self) -> u32 {
+        let samples = (self.samples.len() / self.bytes_per_sample) as u64;
+        (samples * 1000 / self.sample_rate.max(1) as u64) as u32
+    }
+
+    /// Sample `index` centered on 0, in the range of 8 bit samples
+    fn sample(&self, index: usize) -> Option<i32> {
+        let at = index * self.bytes_per_sample;
+        match self.bytes_per_sample {
+            1 => self.samples.get(at).map(|&sample| sample as i32 - MIDPOINT),
+            _ => self
+                .samples
+                .get(at..at + 2)
+                .map(|bytes| i16::from_le_bytes([bytes[0], bytes[1]]) as i32 >> 8),
+        }
+    }
+}
+
+enum Source {
+    Clip {
+        clip: Clip,
+        position: usize,
+    },
+    /// Square wave, flipping every `half_period` samples
+    Tone {
+        half_period: u32,
+        count: u32,
+    },
+}
+
+struct Engine {
+    timer: PeriodicTimer<'static, Blocking>,
+    dac: Dac<'static, DAC2<'static>>,
+    source: Option<Source>,
+    /// Percent
+    volume: u8,
+    muted: bool,
+}
+
+impl Engine {
+    fn start(&mut self, source: Source, sample_rate: u32) {
+        self.source = Some(source);
+        let period = Duration::from_micros(1_000_000 / sample_rate.max(1) as u64);
+        if self.timer.start(period).is_err() {
+            defmt::warn!("Could not start the audio timer");
+            self.source = None;
+        }
+    }
+
+    fn stop(&mut self) {
+        self.source = None;
+        let _ = self.timer.cancel();
+        self.dac.write(MIDPOINT as u8);
+    }
+
+    /// Writes the next sample, called from the timer interrupt
+    fn next(&mut self) {
+        let sample = match &mut self.source {
+            Some(Source::Clip { clip, position }) => {
+                let sample = clip.sample(*position);
+                *position += 1;
+                sample
+            }
+            Some(Source::Tone { half_period, count }) => {
+                *count = (*count + 1) % (*half_period * 2);
+                Some(if *count < *half_period { 100 } else { -100 })
+            }
+            None => None,
+        };
+        match sample {
+            Some(sample) => {
+                let volume = if self.muted { 0 } else { self.volume as i32 };
+                let value = MIDPOINT + sample * volume / 100;
+                self.dac.write(value.clamp(0, 255) as u8);
+            }
+            None => self.stop(),
+        }
+    }
+}
+
+static ENGINE: Mutex<CriticalSectionRawMutex, RefCell<Option<Engine>>> =
+    Mutex::new(RefCell::new(None));
+
+#[handler]
+fn on_sample() {
+    ENGINE.lock(|engine| {
+        if let Some(engine) = engine.borrow_mut().as_mut() {
+            engine.timer.clear_interrupt();
+            engine.next();
+        }
+    });
+}
+
+fn with_engine<R: Default>(f: impl FnOnce(&mut Engine) -> R) -> R {
+    ENGINE.lock(|engine| engine.borrow_mut().as_mut().map(f).unwrap_or_default())
+}
+
+/// Takes over the speaker output on GPIO26, which the CYD feeds from DAC2
+/// into its amplifier.
+///
+/// Samples are written from a timer interrupt, so playback keeps going while
+/// the UI loop renders. Call once at boot, before the [`Buzzer`] beeps.
+///
+/// [`Buzzer`]: crate::buzzer::Buzzer
+pub fn init(timer_group: TIMG1<'static>, dac: DAC2<'static>, pin: GPIO26<'static>, volume: u8) {
+    let timer_group = TimerGroup::new(timer_group);
+    let mut timer = PeriodicTimer::new(timer_group.timer0);
+    timer.set_interrupt_handler(on_sample);
+    timer.listen();
+    let mut dac = Dac::new(dac, pin);
+    dac.write(MIDPOINT as u8);
+    ENGINE.lock(|engine| {
+        *engine.borrow_mut() = Some(Engine {
+            timer,
+            dac,
+            source: None,
+            volume: volume.min(100),
+            muted: false,
+        })
+    });
+}
+
+/// Plays `clip` from the start, replacing whatever plays.
+pub fn play(clip: Clip) {
+    with_engine(|engine| engine.start(Source::Clip { clip, position: 0 }, clip.sample_rate));
+}
+
+/// Plays a square wave of `frequency_hz` until [`stop_tone`], replacing
+/// whatever plays.
+pub fn start_tone(frequency_hz: u32) {
+    let half_period = (TONE_RATE / (2 * frequency_hz.max(1))).max(1);
+    with_engine(|engine| {
+        engine.start(
+            Source::Tone {
+                half_period,
+                count: 0,
+            },
+            TONE_RATE,
+        )
+    });
+}
+
+/// Stops the tone, but not a clip that replaced it.
+pub fn stop_tone() {
+    with_engine(|engine| {
+        if matches!(engine.source, Some(Source::Tone { .. })) {
+            engine.stop();
+        }
+    });
+}
+
+/// Stops whatever plays.
+pub fn stop() {
+    with_engine(Engine::stop);
+}
+
+pub fn is_playing() -> bool {
+    with_engine(|engine| engine.source.is_some())
+}
+
+/// Playback volume in percent
+pub fn set_volume(percent: u8) {
+    with_engine(|engine| engine.volume = percent.min(100));
+}
+
+pub fn volume() -> u8 {
+    with_engine(|engine| engine.volume)
+}
+
+/// Silences everything without stopping it.
+pub fn set_muted(muted: bool) {
+    with_engine(|engine| engine.muted = muted);
+}
+
+pub fn is_muted() -> bool {
+    with_engine(|engine| engine.muted)
+}
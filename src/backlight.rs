@@ -0,0 +1,71 @@
+use esp_hal::gpio::DriveMode;
+use esp_hal::gpio::interconnect::PeripheralOutput;
+use esp_hal::ledc::channel::{self, Channel, ChannelIFace};
+use esp_hal::ledc::timer::{self, TimerIFace};
+use esp_hal::ledc::{Ledc, LowSpeed};
+use esp_hal::time::Rate;
+use static_cell::StaticCell;
+
+/// Well above what the eye or the camera of a phone picks up as flicker
+const PWM_FREQUENCY: Rate = Rate::from_khz(5);
+/// Dimmer than this and the display looks off
+pub const MIN_PERCENT: u8 = 5;
+
+/// Display backlight dimmed with PWM from the LEDC peripheral.
+pub struct Backlight {
+    channel: Channel<'static, LowSpeed>,
+    percent: u8,
+}
+
+impl Backlight {
+    /// Uses LEDC timer 1 and channel 1 of `ledc`, which must run from the APB clock.
+    pub fn new(
+        ledc: &'static Ledc<'static>,
+        pin: impl PeripheralOutput<'static>,
+        percent: u8,
+    ) -> Self {
+        static TIMER: StaticCell<timer::Timer<'static, LowSpeed>> = StaticCell::new();
+
+        let timer = TIMER.init(ledc.timer::<LowSpeed>(timer::Number::Timer1));
+        timer
+            .configure(timer::config::Config {
+                duty: timer::config::Duty::Duty8Bit,
+                clock_source: timer::LSClockSource::APBClk,
+                frequency: PWM_FREQUENCY,
+            })
+            .expect("Could not configure backlight timer");
+
+        let percent = percent.clamp(MIN_PERCENT, 100);
+        let mut channel = ledc.channel(channel::Number::Channel1, pin);
+        channel
+            .configure(channel::config::Config {
+                timer,
+                duty_pct: percent,
+                drive_mode: DriveMode::PushPull,
+            })
+            .expect("Could not configure backlight channel");
+
+        Self { channel, percent }
+    }
+
+    pub fn set_percent(&mut self, percent: u8) {
+        let percent = percent.clamp(MIN_PERCENT, 100);
+        if self.channel.set_duty(percent).is_err() {
+            defmt::warn!("Could not set backlight duty");
+            return;
+        }
+        self.percent = percent;
+    }
+
+    /// Darker than [`MIN_PERCENT`], for when the display is not in use. The
+    /// next [`Backlight::set_percent`] turns it back on.
+    pub fn off(&mut self) {
+        if self.channel.set_duty(0).is_err() {
+            defmt::warn!("Could not turn backlight off");
+        }
+    }
+
+    pub fn percent(&self) -> u8 {
+        self.percent
+    }
+}
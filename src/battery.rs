@@ -0,0 +1,87 @@
+use esp_hal::Blocking;
+use esp_hal::analog::adc::{Adc, AdcConfig, AdcPin, Attenuation};
+use esp_hal::gpio::{Input, InputConfig};
+use esp_hal::peripherals::{ADC1, GPIO34, GPIO35};
+
+/// Ratio of the resistor divider between the cell and the ADC pin
+const DIVIDER: u32 = 2;
+/// With 11 dB attenuation the full 12 bit range is roughly 3.3 V
+const FULL_SCALE_MV: u32 = 3300;
+const EMPTY_MV: u32 = 3300;
+const FULL_MV: u32 = 4200;
+/// Anything below this means no cell is connected to the divider
+const PRESENT_MV: u32 = 2500;
+/// Chargers stop at about 4.2 V, so a cell below this on external power is
+/// still charging
+const CHARGED_MV: u32 = 4150;
+/// Polls for a finished conversion, each well under a microsecond, before
+/// giving up on the ADC
+const READ_ATTEMPTS: u32 = 10_000;
+
+/// Single cell LiPo measured through a divider on GPIO35, and optionally
+/// USB power sensed through another one on GPIO34.
+///
+/// The CYD has no battery circuit of its own, so the cell and a 1:1 divider
+/// have to be wired to the P3 connector. For the USB sense, a 1:1 divider
+/// from the 5 V rail replaces the light sensor on GPIO34, see
+/// [`with_usb_sense`](Self::with_usb_sense).
+pub struct Battery {
+    adc: Adc<'static, ADC1<'static>, Blocking>,
+    pin: AdcPin<GPIO35<'static>, ADC1<'static>>,
+    usb_sense: Option<Input<'static>>,
+}
+
+impl Battery {
+    pub fn new(adc: ADC1<'static>, pin: GPIO35<'static>) -> Self {
+        let mut config = AdcConfig::new();
+        let pin = config.enable_pin(pin, Attenuation::_11dB);
+        Self {
+            adc: Adc::new(adc, config),
+            pin,
+            usb_sense: None,
+        }
+    }
+
+    /// Senses USB power on `pin`. Only for boards with the divider wired, as
+    /// an unmodified CYD has its light sensor there.
+    pub fn with_usb_sense(mut self, pin: GPIO34<'static>) -> Self {
+        self.usb_sense = Some(Input::new(pin, InputConfig::default()));
+        self
+    }
+
+    /// Whether the board runs from USB rather than the cell. Without the
+    /// sense line, only when no cell is connected.
+    pub fn external_power(&mut self) -> bool {
+        match &self.usb_sense {
+            Some(usb_sense) => usb_sense.is_high(),
+            None => self.percent().is_none(),
+        }
+    }
+
+    /// Whether a connected cell is being charged from USB
+    pub fn charging(&mut self) -> bool {
+        if !self.external_power() {
+            return false;
+        }
+        self.millivolts()
+            .is_some_and(|millivolts| (PRESENT_MV..CHARGED_MV).contains(&millivolts))
+    }
+
+    /// Voltage of the cell, `None` when the ADC does not finish a
+    /// conversion.
+    pub fn millivolts(&mut self) -> Option<u32> {
+        let raw = (0..READ_ATTEMPTS).find_map(|_| self.adc.read_oneshot(&mut self.pin).ok())?;
+        Some(raw as u32 * FULL_SCALE_MV / 4095 * DIVIDER)
+    }
+
+    /// Charge estimated linearly from the cell voltage, `None` without a cell
+    /// or when it cannot be measured.
+    pub fn percent(&mut self) -> Option<u8> {
+        let millivolts = self.millivolts()?;
+        if millivolts < PRESENT_MV {
+            return None;
+        }
+        let clamped = millivolts.clamp(EMPTY_MV, FULL_MV);
+        Some(((clamped - EMPTY_MV) * 100 / (FULL_MV - EMPTY_MV)) as u8)
+    }
+}
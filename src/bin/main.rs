@@ -7,35 +7,138 @@
 )]
 #![deny(clippy::large_stack_frames)]
 
+use alloc::rc::Rc;
 use alloc::{ffi::CString, string::ToString};
+use core::cell::RefCell;
 use defmt_serial as _;
 use embassy_executor::Spawner;
 use embassy_time::{Duration, Instant, Timer};
 use embedded_graphics::pixelcolor::Rgb565;
-use embedded_graphics::prelude::{DrawTarget, Point};
 use embedded_hal_bus::spi::ExclusiveDevice;
 use esp_backtrace as _;
 use esp_hal::clock::CpuClock;
 use esp_hal::delay::Delay;
 use esp_hal::gpio::{Level, Output, OutputConfig};
+#[cfg(feature = "rtc-ds3231")]
+use esp_hal::i2c::{self, master::I2c};
 use esp_hal::interrupt::software::SoftwareInterruptControl;
+use esp_hal::ledc::{LSGlobalClkSource, Ledc};
+use esp_hal::rmt::Rmt;
+use esp_hal::rng::Rng;
 use esp_hal::spi::master::Spi;
 use esp_hal::time::Rate;
 use esp_hal::timer::timg::TimerGroup;
-use esp_hal::uart::{Config, Uart};
+use esp_hal::uart::{Config, Uart, UartTx};
 use esp_hal::{Blocking, spi};
+use esp_storage::FlashStorage;
 use lv_bevy_ecs::display::{Display, DrawBuffer};
 use lv_bevy_ecs::events::EventCode;
 use lv_bevy_ecs::functions::{NextTimerPeriod, lv_tick_set_cb, lv_timer_handler};
-use lv_bevy_ecs::input::{BufferStatus, InputDevice, InputEvent, InputState, Pointer};
+use lv_bevy_ecs::input::{InputDevice, InputState, Pointer};
 use lv_bevy_ecs::support::{Align, LabelLongMode};
-use lv_bevy_ecs::widgets::{Arc, Label, Wdg};
+use lv_bevy_ecs::sys::{LV_EVENT_VALUE_CHANGED, lv_obj_send_event};
+use lv_bevy_ecs::widgets::{Arc, Wdg};
+use lvgl_bevy_demo_nostd::assets;
+use lvgl_bevy_demo_nostd::backlight::Backlight;
+use lvgl_bevy_demo_nostd::battery::Battery;
+use lvgl_bevy_demo_nostd::boot::{self, Stage};
+use lvgl_bevy_demo_nostd::buzzer::Buzzer;
+use lvgl_bevy_demo_nostd::calibration;
+#[cfg(feature = "can")]
+use lvgl_bevy_demo_nostd::can;
+use lvgl_bevy_demo_nostd::cpu_frequency::{self, CpuScaling};
+use lvgl_bevy_demo_nostd::deep_sleep::DeepSleep;
+#[cfg(feature = "rtc-ds3231")]
+use lvgl_bevy_demo_nostd::ds3231;
+#[cfg(feature = "gps")]
+use lvgl_bevy_demo_nostd::gps;
+#[cfg(feature = "hc-sr04")]
+use lvgl_bevy_demo_nostd::hc_sr04;
 use lvgl_bevy_demo_nostd::heap::get_memory_stats;
+use lvgl_bevy_demo_nostd::ir;
+use lvgl_bevy_demo_nostd::journal;
+use lvgl_bevy_demo_nostd::metrics;
+#[cfg(feature = "modbus")]
+use lvgl_bevy_demo_nostd::modbus;
+use lvgl_bevy_demo_nostd::panel::{self, Panel};
+use lvgl_bevy_demo_nostd::rotation::Rotation;
+use lvgl_bevy_demo_nostd::settings::{Key, Settings};
+use lvgl_bevy_demo_nostd::system::SystemInfo;
+use lvgl_bevy_demo_nostd::touch_polling::{self, TouchPolling};
+use lvgl_bevy_demo_nostd::ttf;
+use lvgl_bevy_demo_nostd::ui::about::About;
+use lvgl_bevy_demo_nostd::ui::accent;
+use lvgl_bevy_demo_nostd::ui::alarm::Alarm;
+use lvgl_bevy_demo_nostd::ui::animated_image::AnimatedImage;
+use lvgl_bevy_demo_nostd::ui::audio::Audio;
+use lvgl_bevy_demo_nostd::ui::battery_saver::{self, BatterySaver};
+use lvgl_bevy_demo_nostd::ui::builder;
+use lvgl_bevy_demo_nostd::ui::calculator::Calculator;
+#[cfg(feature = "can")]
+use lvgl_bevy_demo_nostd::ui::can_dashboard::CanDashboard;
+#[cfg(feature = "font-cjk")]
+use lvgl_bevy_demo_nostd::ui::cjk_demo::CjkDemo;
+use lvgl_bevy_demo_nostd::ui::console::{self, Command, Parameter};
+use lvgl_bevy_demo_nostd::ui::converter::Converter;
+use lvgl_bevy_demo_nostd::ui::dashboard::Dashboard;
+use lvgl_bevy_demo_nostd::ui::debug_menu::DebugMenu;
+use lvgl_bevy_demo_nostd::ui::debug_overlay::DebugOverlay;
+#[cfg(feature = "hc-sr04")]
+use lvgl_bevy_demo_nostd::ui::distance::Distance;
+use lvgl_bevy_demo_nostd::ui::focus::Focus;
+use lvgl_bevy_demo_nostd::ui::fonts::Font;
+use lvgl_bevy_demo_nostd::ui::gallery::Gallery;
+#[cfg(feature = "gps")]
+use lvgl_bevy_demo_nostd::ui::gps::Gps;
+use lvgl_bevy_demo_nostd::ui::harness::{self, Harness};
+use lvgl_bevy_demo_nostd::ui::i18n::{self, Language};
+use lvgl_bevy_demo_nostd::ui::images::Images;
+use lvgl_bevy_demo_nostd::ui::layout_demo::LayoutDemo;
+use lvgl_bevy_demo_nostd::ui::lock::Lock;
+#[cfg(feature = "lottie")]
+use lvgl_bevy_demo_nostd::ui::lottie::Lottie;
+use lvgl_bevy_demo_nostd::ui::memory::Memory;
+#[cfg(feature = "modbus")]
+use lvgl_bevy_demo_nostd::ui::modbus::Modbus;
+use lvgl_bevy_demo_nostd::ui::module::{Registry, Resources};
+use lvgl_bevy_demo_nostd::ui::night_mode::{self, NightMode};
+use lvgl_bevy_demo_nostd::ui::notifications::NotificationCenter;
+use lvgl_bevy_demo_nostd::ui::paint::Paint;
+use lvgl_bevy_demo_nostd::ui::pomodoro::Pomodoro;
+use lvgl_bevy_demo_nostd::ui::preferences::Preferences;
+use lvgl_bevy_demo_nostd::ui::quick_settings::QuickSettings;
+use lvgl_bevy_demo_nostd::ui::remote::Remote;
+use lvgl_bevy_demo_nostd::ui::rich_text::RichText;
+use lvgl_bevy_demo_nostd::ui::screen::Screen;
+use lvgl_bevy_demo_nostd::ui::screensaver::Screensaver;
+use lvgl_bevy_demo_nostd::ui::setup::Setup;
+use lvgl_bevy_demo_nostd::ui::smart_light::SmartLight;
+use lvgl_bevy_demo_nostd::ui::snake::Snake;
+use lvgl_bevy_demo_nostd::ui::splash::Splash;
+use lvgl_bevy_demo_nostd::ui::state;
+use lvgl_bevy_demo_nostd::ui::status_bar::StatusBar;
+use lvgl_bevy_demo_nostd::ui::stopwatch::Stopwatch;
+use lvgl_bevy_demo_nostd::ui::stress::Stress;
+#[cfg(not(any(
+    feature = "rtc-ds3231",
+    feature = "hc-sr04",
+    feature = "can",
+    feature = "modbus",
+    feature = "gps"
+)))]
+use lvgl_bevy_demo_nostd::ui::terminal::Terminal;
+use lvgl_bevy_demo_nostd::ui::toast::Toasts;
+use lvgl_bevy_demo_nostd::ui::ttf_demo::TtfDemo;
+use lvgl_bevy_demo_nostd::ui::wifi::WifiScanner;
+use lvgl_bevy_demo_nostd::wifi;
+use lvgl_bevy_demo_nostd::{
+    audio, download, mirror, net, portal, smart_light, timezone, touch, web,
+};
 use mipidsi::Builder;
 use mipidsi::interface::SpiInterface;
 use mipidsi::models::ST7789;
 use static_cell::StaticCell;
-use xpt2046::{CalibrationData, TouchEvent, TouchKind, TouchScreen, Xpt2046};
+use xpt2046::{CalibrationData, TouchScreen, Xpt2046};
 
 extern crate alloc;
 
@@ -43,7 +146,9 @@ extern crate alloc;
 // For more information see: <https://docs.espressif.com/projects/esp-idf/en/stable/esp32/api-reference/system/app_image_format.html#application-description>
 esp_bootloader_esp_idf::esp_app_desc!();
 
-static SERIAL: StaticCell<Uart<'static, Blocking>> = StaticCell::new();
+static SERIAL: StaticCell<UartTx<'static, Blocking>> = StaticCell::new();
+static LEDC: StaticCell<Ledc<'static>> = StaticCell::new();
+static RADIO: StaticCell<esp_radio::Controller<'static>> = StaticCell::new();
 
 // #[panic_handler]
 // pub fn panic(info: &::core::panic::PanicInfo) -> ! {
@@ -56,21 +161,26 @@ static SERIAL: StaticCell<Uart<'static, Blocking>> = StaticCell::new();
     reason = "it's not unusual to allocate larger buffers etc. in main"
 )]
 #[esp_rtos::main]
-async fn main(_spawner: Spawner) -> ! {
+async fn main(spawner: Spawner) -> ! {
     // generator version: 1.2.0
 
-    let config = esp_hal::Config::default().with_cpu_clock(CpuClock::max());
+    let cpu_clock = CpuClock::max();
+    let config = esp_hal::Config::default().with_cpu_clock(cpu_clock);
     let peripherals = esp_hal::init(config);
     let uart = Uart::new(peripherals.UART0, Config::default())
         .unwrap()
         .with_rx(peripherals.GPIO3)
         .with_tx(peripherals.GPIO1);
 
-    let serial = SERIAL.init(uart);
+    // The receive half takes UI test commands, see `ui::harness`
+    let (serial_rx, serial_tx) = uart.split();
+    let serial = SERIAL.init(serial_tx);
 
     defmt_serial::defmt_serial(serial);
 
     lvgl_bevy_demo_nostd::heap::setup_heap();
+    #[cfg(feature = "psram")]
+    lvgl_bevy_demo_nostd::heap::add_psram(peripherals.PSRAM);
 
     let timg0 = TimerGroup::new(peripherals.TIMG0);
     let swint = SoftwareInterruptControl::new(peripherals.SW_INTERRUPT);
@@ -78,9 +188,34 @@ async fn main(_spawner: Spawner) -> ! {
 
     defmt::info!("Embassy initialized!");
 
+    let mut flash = FlashStorage::new(peripherals.FLASH);
+    let ttf_font = ttf::read_from_flash(&mut flash);
+    let settings = Rc::new(RefCell::new(Settings::load(flash)));
+    // Sets the clock again after waking up, so before anything reads it
+    let deep_sleep = DeepSleep::new(peripherals.LPWR, peripherals.GPIO36);
+    let system_info = SystemInfo::collect(cpu_clock, &mut settings.borrow_mut());
+    // Before the splash screen, which is translated too
+    i18n::set_language(Language::from_index(settings.borrow().get(Key::Language)));
+    boot::complete(Stage::Storage);
+    let ledc = LEDC.init(Ledc::new(peripherals.LEDC));
+    ledc.set_global_slow_clock(LSGlobalClkSource::APBClk);
+    let ledc: &'static Ledc<'static> = ledc;
+    // Lights up the splash screen
+    let backlight = Rc::new(RefCell::new(Backlight::new(
+        ledc,
+        peripherals.GPIO21,
+        settings.borrow().get(Key::Brightness) as u8,
+    )));
+
+    lvgl_bevy_demo_nostd::heap::mark_lvgl_start();
     lv_bevy_ecs::functions::lv_init();
-    lv_bevy_ecs::logging::connect();
+    journal::capture_lvgl();
     lv_bevy_ecs::malloc::set_mem_monitor(get_memory_stats);
+    lv_tick_set_cb(|| {
+        let now = Instant::now();
+        now.as_millis() as u32
+    });
+    let assets = assets::mount(settings.clone());
 
     const HOR_RES: usize = 320;
     const VER_RES: usize = 240;
@@ -112,9 +247,8 @@ async fn main(_spawner: Spawner) -> ! {
 
     let mut tft_display = Builder::new(ST7789, di)
         .color_order(mipidsi::options::ColorOrder::Rgb)
-        .orientation(
-            mipidsi::options::Orientation::default().rotate(mipidsi::options::Rotation::Deg270),
-        ) // Mirror on text
+        // Calibration happens this way up, see `Rotation`
+        .orientation(Rotation::Normal.orientation()) // Mirror on text
         .reset_pin(Output::new(
             peripherals.GPIO4,
             Level::High,
@@ -146,49 +280,121 @@ async fn main(_spawner: Spawner) -> ! {
         delta_y: 250.0,
     };
 
-    let mut touch = Xpt2046::new(touch_driver, Some(calibration_data));
+    let stored_calibration = calibration::load(&settings.borrow());
+    let mut touch = Xpt2046::new(
+        touch_driver,
+        Some(stored_calibration.unwrap_or(calibration_data)),
+    );
 
     //===========================================================================================================
     //                               Create the User Interface
     //===========================================================================================================
 
-    Output::new(peripherals.GPIO21, Level::High, OutputConfig::default());
+    // Asked for from the debug menu, resolve that before we do anything else.
+    if calibration::requested(&settings.borrow()) {
+        match touch.intrusive_calibration(&mut tft_display, &mut Delay::default()) {
+            Ok(output) => {
+                calibration::store(&mut settings.borrow_mut(), &output);
+                defmt::debug!("{}", DebugCalibrationData(output));
+            }
+            Err(_error) => {
+                defmt::error!("Could not calibrate");
+                calibration::reset(&mut settings.borrow_mut());
+            }
+        }
+    }
+    boot::complete(Stage::Calibration);
 
-    // if !touch.calibrated() {
-    //     // Display is uncalibrated, resolve that before we do anything else.
-    //     let output = touch
-    //         .intrusive_calibration(&mut tft_display, &mut Delay::default())
-    //         .expect("Could not calibrate");
-    //     defmt::debug!("{}", DebugCalibrationData(output));
-    // }
+    let rotation = Rotation::from_index(settings.borrow().get(Key::Rotation));
+    tft_display
+        .rotate(rotation)
+        .expect("Could not rotate display");
 
+    // Shared with the main loop, which puts the panel to sleep
+    let panel = Rc::new(RefCell::new(tft_display));
     let mut display = Display::new(HOR_RES, VER_RES);
     let buffer =
         DrawBuffer::<{ HOR_RES * BUF_HEIGHT }, Rgb565>::new(HOR_RES, BUF_HEIGHT);
     defmt::info!("Display OK");
-    display.register(buffer, move |refresh| {
-        let area = refresh.rectangle;
-        let data = refresh.colors.iter().cloned();
-
-        tft_display
-            .fill_contiguous(&area, data)
-            .expect("Cannot fill display");
-
-    });
+    panel::attach(&mut display, buffer, panel.clone());
+    metrics::install();
 
     defmt::info!("Draw Buffer OK");
 
-    let mut arc = Arc::new();
-    arc.set_size(150, 150);
-    arc.set_rotation(135);
-    arc.set_bg_angles(0, 270);
-    arc.set_value(10);
-    arc.set_align(Align::Center.into());
+    timezone::set(settings.borrow().get(Key::Timezone));
+    accent::apply(settings.borrow().get(Key::Accent));
+    let rmt = Rmt::new(peripherals.RMT, Rate::from_mhz(80))
+        .expect("Cannot initialize RMT")
+        .into_async();
+    ir::start(
+        spawner,
+        rmt.channel0,
+        peripherals.GPIO17,
+        &settings.borrow(),
+    );
+
+    // The LVGL timers run between the steps from here on, so the splash
+    // shows them
+    let splash = Splash::new();
+    lv_timer_handler();
 
-    let mut label = Label::new();
-    label.set_long_mode(LabelLongMode::Clip.into());
-    label.set_text_static(c"asdasdasd");
-    label.set_align(Align::TopMid.into());
+    audio::init(
+        peripherals.TIMG1,
+        peripherals.DAC2,
+        peripherals.GPIO26,
+        settings.borrow().get(Key::Volume) as u8,
+    );
+    let buzzer = Rc::new(RefCell::new(Buzzer::new()));
+    let battery = Battery::new(peripherals.ADC1, peripherals.GPIO35);
+    #[cfg(feature = "usb-sense")]
+    let battery = battery.with_usb_sense(peripherals.GPIO34);
+    let battery = Rc::new(RefCell::new(battery));
+
+    let radio = RADIO.init(esp_radio::init().expect("Cannot initialize radio"));
+    let (wifi_controller, interfaces) =
+        esp_radio::wifi::new(radio, peripherals.WIFI, Default::default())
+            .expect("Cannot initialize WiFi");
+    spawner.spawn(wifi::run(wifi_controller).unwrap());
+    let rng = Rng::new();
+    let seed = ((rng.random() as u64) << 32) | rng.random() as u64;
+    let stack = net::start(spawner, interfaces.sta, seed);
+    spawner.spawn(web::serve(stack).unwrap());
+    spawner.spawn(smart_light::run(stack, system_info.mac_address).unwrap());
+    spawner.spawn(download::run(stack).unwrap());
+    spawner.spawn(net::sntp(stack).unwrap());
+    let credentials = settings.borrow_mut().wifi_credentials();
+    let setup_network = match credentials {
+        Some((ssid, password)) => {
+            wifi::connect(ssid, password);
+            None
+        }
+        None => {
+            let ssid = portal::ssid(system_info.mac_address);
+            let seed = ((rng.random() as u64) << 32) | rng.random() as u64;
+            portal::start(spawner, interfaces.ap, seed, ssid.clone());
+            boot::complete(Stage::Wifi);
+            // Nothing sets the clock over the setup network
+            boot::complete(Stage::TimeSync);
+            Some(ssid)
+        }
+    };
+    boot::complete(Stage::Peripherals);
+    lv_timer_handler();
+
+    let mut arc = builder::arc()
+        .size(150, 150)
+        .rotation(135)
+        .background_angles(0, 270)
+        .value(10)
+        .align(Align::Center)
+        .build();
+
+    let mut label = builder::label()
+        .long_mode(LabelLongMode::Clip)
+        .text_static(c"asdasdasd")
+        .align(Align::TopMid)
+        .font(Font::LARGEST)
+        .build();
 
     arc.add_event_cb(EventCode::ValueChanged, move |mut event| {
         let Some(obj) = event.get_target_obj() else {
@@ -200,26 +406,223 @@ async fn main(_spawner: Spawner) -> ! {
         label.set_text(text.as_c_str());
     });
 
+    let home = Screen::active();
+    home.reserve_status_bar();
+    // Created before the apps so their full screen alerts cover it
+    let _status_bar = StatusBar::new(buzzer.clone(), battery.clone());
+    let night_mode = Rc::new(NightMode::new(night_mode::Mode::from_index(
+        settings.borrow().get(Key::NightMode),
+    )));
+    let battery_saver = Rc::new(BatterySaver::new(
+        battery_saver::Mode::from_index(settings.borrow().get(Key::BatterySaver)),
+        battery.clone(),
+        backlight.clone(),
+        settings.clone(),
+    ));
+    let mut resources = Resources::new();
+    resources.insert(settings.clone());
+    resources.insert(buzzer.clone());
+    resources.insert(backlight.clone());
+    resources.insert(night_mode);
+    resources.insert(battery_saver);
+    resources.insert(system_info);
+    resources.insert(ttf_font);
+    resources.insert(assets);
+    // GPIO22 and GPIO27 are on the CN1 extension connector
+    #[cfg(not(any(
+        feature = "rtc-ds3231",
+        feature = "hc-sr04",
+        feature = "can",
+        feature = "modbus",
+        feature = "gps"
+    )))]
+    resources.insert(
+        Uart::new(peripherals.UART1, Config::default())
+            .unwrap()
+            .with_rx(peripherals.GPIO27)
+            .with_tx(peripherals.GPIO22),
+    );
+    #[cfg(feature = "rtc-ds3231")]
+    spawner.spawn(
+        ds3231::run(
+            I2c::new(peripherals.I2C0, i2c::master::Config::default())
+                .unwrap()
+                .with_sda(peripherals.GPIO22)
+                .with_scl(peripherals.GPIO27)
+                .into_async(),
+        )
+        .unwrap(),
+    );
+    #[cfg(feature = "hc-sr04")]
+    hc_sr04::start(
+        spawner,
+        rmt.channel1,
+        peripherals.GPIO22,
+        peripherals.GPIO27,
+    );
+    #[cfg(feature = "can")]
+    can::start(
+        spawner,
+        peripherals.TWAI0,
+        peripherals.GPIO22,
+        peripherals.GPIO27,
+    );
+    #[cfg(feature = "modbus")]
+    modbus::start(
+        spawner,
+        peripherals.UART1,
+        peripherals.GPIO22,
+        peripherals.GPIO27,
+    );
+    #[cfg(feature = "gps")]
+    gps::start(spawner, peripherals.UART1, peripherals.GPIO27);
+
+    let mut modules = Registry::new(home, resources);
+    modules.register::<Stopwatch>();
+    modules.register::<Calculator>();
+    modules.register::<Pomodoro>();
+    modules.register::<Paint>();
+    modules.register::<Snake>();
+    modules.register::<Dashboard>();
+    modules.register::<SmartLight>();
+    modules.register::<About>();
+    modules.register::<Memory>();
+    modules.register::<Gallery>();
+    modules.register::<RichText>();
+    modules.register::<AnimatedImage>();
+    modules.register::<Images>();
+    modules.register::<LayoutDemo>();
+    #[cfg(feature = "lottie")]
+    modules.register::<Lottie>();
+    modules.register::<Alarm>();
+    modules.register::<Audio>();
+    modules.register::<Converter>();
+    #[cfg(not(any(
+        feature = "rtc-ds3231",
+        feature = "hc-sr04",
+        feature = "can",
+        feature = "modbus",
+        feature = "gps"
+    )))]
+    modules.register::<Terminal>();
+    modules.register::<WifiScanner>();
+    modules.register::<Remote>();
+    #[cfg(feature = "hc-sr04")]
+    modules.register::<Distance>();
+    #[cfg(feature = "can")]
+    modules.register::<CanDashboard>();
+    #[cfg(feature = "modbus")]
+    modules.register::<Modbus>();
+    #[cfg(feature = "gps")]
+    modules.register::<Gps>();
+    modules.register::<Preferences>();
+    modules.register::<TtfDemo>();
+    #[cfg(feature = "font-cjk")]
+    modules.register::<CjkDemo>();
+    modules.register::<Stress>();
+    // Back to where the touch that woke the device up left off
+    if let Some(index) = deep_sleep.restored_module() {
+        modules.open(index);
+    }
+
+    // After the modules, so it covers their alerts
+    let _toasts = Toasts::new();
+    let _quick_settings = QuickSettings::new(buzzer.clone(), backlight.clone(), settings.clone());
+    let _notification_center = NotificationCenter::new();
+    let _setup = Setup::new(settings.clone(), setup_network);
+    let _debug_menu = DebugMenu::new(settings.clone());
+    let _lock = Lock::new(settings.clone());
+    let _screensaver = Screensaver::new(settings.clone());
+    let _focus = Focus::new();
+
+    let _harness = Harness::new(serial_rx);
+    #[cfg(feature = "ui-test")]
+    _harness.run_script(include_str!("../../ui-test.txt"));
+
     defmt::info!("Widgets OK");
 
+    spawner.spawn(touch::run(touch, touch_polling::idle_period_ms(&settings.borrow())).unwrap());
+    let mut touch_polling = TouchPolling::new(&settings.borrow());
     let _pointer = InputDevice::<Pointer>::new(|| {
-        let event = touch.get_touch_event();
-        if let Err(_error) = event {
-            defmt::error!("Error reading touch event");
+        let mut input = touch::latest();
+        input.data = rotation.map_touch(input.data, HOR_RES as i32, VER_RES as i32);
+        // Scripted taps are too short for the idle period
+        let scripted = harness::pointer();
+        let pressed = scripted.is_some() || matches!(input.state, InputState::Pressed);
+        touch_polling.update(pressed);
+        if pressed {
+            cpu_frequency::mark_busy();
         }
-        get_touch_input(event.ok().flatten())
+        scripted.unwrap_or(input)
     });
 
     defmt::info!("Pointer OK");
 
-    lv_tick_set_cb(|| {
-        let now = Instant::now();
-        now.as_millis() as u32
-    });
+    // After the pointer, so it can follow its events
+    let _debug_overlay = DebugOverlay::new(peripherals.GPIO0);
+    splash.raise();
 
+    let mut cpu_scaling = CpuScaling::new(cpu_clock);
     loop {
         let frame_start = Instant::now();
         let delay = lv_timer_handler();
+        cpu_scaling.update();
+        modules.update();
+        mirror::redraw_missed();
+        while let Some(command) = console::take() {
+            match command {
+                Command::Set(parameter, value) => {
+                    defmt::info!("cli: {} = {}", parameter, value);
+                    let mut settings = settings.borrow_mut();
+                    match parameter {
+                        Parameter::Brightness => {
+                            let mut backlight = backlight.borrow_mut();
+                            backlight.set_percent(value.min(100) as u8);
+                            settings.set(Key::Brightness, backlight.percent() as u32);
+                        }
+                        Parameter::Mute => buzzer.borrow_mut().set_muted(value != 0),
+                        Parameter::Volume => {
+                            audio::set_volume(value.min(100) as u8);
+                            settings.set(Key::Volume, audio::volume() as u32);
+                        }
+                        Parameter::Language => {
+                            let language = Language::from_index(value);
+                            i18n::set_language(language);
+                            settings.set(Key::Language, language as u32);
+                        }
+                        Parameter::Timezone => {
+                            timezone::set(value);
+                            settings.set(Key::Timezone, timezone::current_index());
+                        }
+                    }
+                }
+                Command::Goto(name) if name.eq_ignore_ascii_case("launcher") => {
+                    state::set_next(home)
+                }
+                Command::Goto(name) => match modules.find(&name) {
+                    Some(index) => modules.open(index),
+                    None => defmt::warn!("cli: no module called {=str}", name.as_str()),
+                },
+                Command::Arc(value) => {
+                    arc.set_value(value);
+                    unsafe {
+                        lv_obj_send_event(arc.raw(), LV_EVENT_VALUE_CHANGED, core::ptr::null_mut())
+                    };
+                }
+                Command::Stats => console::print_stats(),
+                Command::Help => console::print_help(),
+            }
+        }
+        // There is no cell to save while plugged in
+        if !battery.borrow_mut().external_power() && deep_sleep.is_due(&settings.borrow()) {
+            backlight.borrow_mut().off();
+            let mut panel = panel.borrow_mut();
+            panel.wait();
+            if panel.power_off(&mut Delay::default()).is_err() {
+                defmt::warn!("Could not put the panel to sleep");
+            }
+            deep_sleep.enter(modules.current_module(), &settings.borrow());
+        }
         match delay {
             NextTimerPeriod::Ready => {
                 continue;
@@ -238,56 +641,6 @@ async fn main(_spawner: Spawner) -> ! {
     // for inspiration have a look at the examples at https://github.com/esp-rs/esp-hal/tree/esp-hal-v1.0.0/examples
 }
 
-fn get_touch_input(event: Option<TouchEvent>) -> InputEvent<Pointer> {
-    // static IS_POINTER_DOWN: AtomicBool = AtomicBool::new(false);
-    // static LATEST_TOUCH_STATUS: Mutex<InputEvent<Pointer>> =
-    //     Mutex::new(InputEvent::new(Point::zero()));
-    static mut IS_POINTER_DOWN: bool = false;
-    static mut LATEST_TOUCH_STATUS: InputEvent<Pointer> = InputEvent::new(Point::zero());
-
-    unsafe {
-        let Some(event) = event else {
-            return LATEST_TOUCH_STATUS;
-        };
-
-        let mut next_touch_status = None;
-
-        match event.kind {
-            TouchKind::Start => {
-                next_touch_status = Some(InputEvent {
-                    status: BufferStatus::Once,
-                    state: InputState::Pressed,
-                    data: event.point,
-                });
-                IS_POINTER_DOWN = true;
-            }
-            TouchKind::Move => {
-                if IS_POINTER_DOWN {
-                    next_touch_status = Some(InputEvent {
-                        status: BufferStatus::Once,
-                        state: InputState::Pressed,
-                        data: event.point,
-                    });
-                }
-            }
-            TouchKind::End => {
-                next_touch_status = Some(InputEvent {
-                    status: BufferStatus::Once,
-                    state: InputState::Released,
-                    data: Point::new(0, 0),
-                });
-                IS_POINTER_DOWN = false;
-            }
-        }
-
-        if let Some(latest_touch_status) = next_touch_status {
-            LATEST_TOUCH_STATUS = latest_touch_status;
-        }
-        LATEST_TOUCH_STATUS
-    }
-}
-
-#[allow(unused)]
 struct DebugCalibrationData(CalibrationData);
 
 impl defmt::Format for DebugCalibrationData {
@@ -0,0 +1,54 @@
+use core::sync::atomic::{AtomicU8, Ordering};
+
+use crate::ui::i18n::Text;
+
+/// Startup steps shown on the splash screen, in the order they usually
+/// finish.
+///
+/// The first three run in `main` before the UI is built. Joining a network
+/// and setting the clock happen in their tasks and can take a while, or not
+/// happen at all.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    /// Settings and fonts read from flash
+    Storage = 0,
+    Calibration = 1,
+    /// Audio, battery and radio set up
+    Peripherals = 2,
+    /// Joined or failed to join the saved network, or hosting the setup one
+    Wifi = 3,
+    TimeSync = 4,
+}
+
+impl Stage {
+    pub const ALL: [Stage; 5] = [
+        Stage::Storage,
+        Stage::Calibration,
+        Stage::Peripherals,
+        Stage::Wifi,
+        Stage::TimeSync,
+    ];
+
+    pub fn name(self) -> Text {
+        match self {
+            Stage::Storage => Text::Storage,
+            Stage::Calibration => Text::Calibration,
+            Stage::Peripherals => Text::Peripherals,
+            Stage::Wifi => Text::Wifi,
+            Stage::TimeSync => Text::TimeSync,
+        }
+    }
+}
+
+/// One bit per [`Stage`]
+static COMPLETE: AtomicU8 = AtomicU8::new(0);
+
+/// Marks `stage` as done, or as not going to happen, like the time sync
+/// without a network. Safe to call from any task, and more than once.
+pub fn complete(stage: Stage) {
+    COMPLETE.fetch_or(1 << stage as u8, Ordering::Relaxed);
+}
+
+pub fn is_complete(stage: Stage) -> bool {
+    COMPLETE.load(Ordering::Relaxed) & (1 << stage as u8) != 0
+}
@@ -0,0 +1,57 @@
+use crate::audio;
+
+const TONE_HZ: u32 = 2000;
+
+/// What beeps the buzzer. Each turns its own beeping on and off, so one
+/// stopping does not silence another.
+#[derive(Clone, Copy)]
+pub enum Beeper {
+    Alarm,
+    Pomodoro,
+    Proximity,
+}
+
+/// Square wave tone on the speaker output, played through [`audio`] while
+/// any [`Beeper`] has it on.
+///
+/// Muting silences every sound, clips included.
+#[derive(Default)]
+pub struct Buzzer {
+    /// One bit per [`Beeper`]
+    beeping: u8,
+}
+
+impl Buzzer {
+    /// Call after [`audio::init`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts or stops the tone for `beeper`. Stays silent while muted.
+    pub fn set_beeping(&mut self, beeper: Beeper, on: bool) {
+        let bit = 1 << beeper as u8;
+        if on {
+            self.beeping |= bit;
+        } else {
+            self.beeping &= !bit;
+        }
+        self.update();
+    }
+
+    pub fn set_muted(&mut self, muted: bool) {
+        audio::set_muted(muted);
+        self.update();
+    }
+
+    pub fn is_muted(&self) -> bool {
+        audio::is_muted()
+    }
+
+    fn update(&self) {
+        if self.beeping != 0 && !audio::is_muted() {
+            audio::start_tone(TONE_HZ);
+        } else {
+            audio::stop_tone();
+        }
+    }
+}
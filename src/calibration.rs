@@ -0,0 +1,63 @@
+use xpt2046::CalibrationData;
+
+use crate::settings::{Key, Settings};
+
+/// Values of [`Key::TouchCalibration`]
+const BUILT_IN: u32 = 0;
+const STORED: u32 = 1;
+const REQUESTED: u32 = 2;
+
+const KEYS: [Key; 6] = [
+    Key::TouchAlphaX,
+    Key::TouchBetaX,
+    Key::TouchDeltaX,
+    Key::TouchAlphaY,
+    Key::TouchBetaY,
+    Key::TouchDeltaY,
+];
+
+/// Touch calibration saved by [`store`], `None` to use the built-in one.
+pub fn load(settings: &Settings) -> Option<CalibrationData> {
+    if settings.get(Key::TouchCalibration) != STORED {
+        return None;
+    }
+    let [alpha_x, beta_x, delta_x, alpha_y, beta_y, delta_y] =
+        KEYS.map(|key| f32::from_bits(settings.get(key)));
+    Some(CalibrationData {
+        alpha_x,
+        beta_x,
+        delta_x,
+        alpha_y,
+        beta_y,
+        delta_y,
+    })
+}
+
+pub fn store(settings: &mut Settings, data: &CalibrationData) {
+    let values = [
+        data.alpha_x,
+        data.beta_x,
+        data.delta_x,
+        data.alpha_y,
+        data.beta_y,
+        data.delta_y,
+    ];
+    for (key, value) in KEYS.into_iter().zip(values) {
+        settings.set(key, value.to_bits());
+    }
+    settings.set(Key::TouchCalibration, STORED);
+}
+
+/// Runs the interactive calibration on the next boot, before the UI starts.
+pub fn request(settings: &mut Settings) {
+    settings.set(Key::TouchCalibration, REQUESTED);
+}
+
+pub fn requested(settings: &Settings) -> bool {
+    settings.get(Key::TouchCalibration) == REQUESTED
+}
+
+/// Back to the built-in calibration, for when a stored one is unusable.
+pub fn reset(settings: &mut Settings) {
+    settings.set(Key::TouchCalibration, BUILT_IN);
+}
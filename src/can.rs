@@ -0,0 +1,133 @@
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use embassy_executor::Spawner;
+use embassy_sync::blocking_mutex::Mutex;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_time::Instant;
+use embedded_can::{Frame as _, Id};
+use esp_hal::Async;
+use esp_hal::peripherals::{GPIO22, GPIO27, TWAI0};
+use esp_hal::twai::{BaudRate, Twai, TwaiConfiguration, TwaiMode};
+
+use crate::json::{self, Value};
+
+pub use logic::can::{Signal, Widget, signal};
+
+const BAUD_RATE: BaudRate = BaudRate::B500K;
+/// Frames of further IDs are dropped, and counted as such
+const MAX_IDS: usize = 32;
+
+/// The last frame seen with an ID.
+#[derive(Clone, Copy)]
+pub struct Frame {
+    pub id: u32,
+    pub data: [u8; 8],
+    pub len: usize,
+    pub received: Instant,
+}
+
+impl Frame {
+    pub fn data(&self) -> &[u8] {
+        &self.data[..self.len]
+    }
+}
+
+static FRAMES: Mutex<CriticalSectionRawMutex, RefCell<Vec<Frame>>> =
+    Mutex::new(RefCell::new(Vec::new()));
+static RECEIVED: AtomicU32 = AtomicU32::new(0);
+static ERRORS: AtomicU32 = AtomicU32::new(0);
+static DROPPED: AtomicU32 = AtomicU32::new(0);
+
+/// Starts receiving at 500 kbit/s through a transceiver like the
+/// SN65HVD230 on the CN1 connector, TX on GPIO22 and RX on GPIO27.
+///
+/// The controller acknowledges frames like any other node, so it also works
+/// on a bench with a single sender. It never sends frames of its own.
+pub fn start(spawner: Spawner, twai: TWAI0<'static>, tx: GPIO22<'static>, rx: GPIO27<'static>) {
+    let config = TwaiConfiguration::new(twai, rx, tx, BAUD_RATE, TwaiMode::Normal).into_async();
+    spawner.spawn(run(config.start()).unwrap());
+}
+
+#[embassy_executor::task]
+async fn run(mut twai: Twai<'static, Async>) {
+    loop {
+        let frame = match twai.receive_async().await {
+            Ok(frame) => frame,
+            Err(_error) => {
+                ERRORS.fetch_add(1, Ordering::Relaxed);
+                continue;
+            }
+        };
+        if frame.is_remote_frame() {
+            continue;
+        }
+        RECEIVED.fetch_add(1, Ordering::Relaxed);
+        let id = match frame.id() {
+            Id::Standard(id) => u32::from(id.as_raw()),
+            Id::Extended(id) => id.as_raw(),
+        };
+        let mut data = [0; 8];
+        let len = frame.data().len().min(8);
+        data[..len].copy_from_slice(&frame.data()[..len]);
+        let frame = Frame {
+            id,
+            data,
+            len,
+            received: Instant::now(),
+        };
+        FRAMES.lock(|frames| {
+            let mut frames = frames.borrow_mut();
+            match frames.iter_mut().find(|known| known.id == id) {
+                Some(known) => *known = frame,
+                None if frames.len() < MAX_IDS => frames.push(frame),
+                None => {
+                    DROPPED.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+        });
+    }
+}
+
+/// The last frame with `id`, if any came.
+pub fn latest(id: u32) -> Option<Frame> {
+    FRAMES.lock(|frames| frames.borrow().iter().find(|frame| frame.id == id).copied())
+}
+
+/// Frames received, failed, and dropped for coming from too many IDs.
+pub fn counters() -> (u32, u32, u32) {
+    (
+        RECEIVED.load(Ordering::Relaxed),
+        ERRORS.load(Ordering::Relaxed),
+        DROPPED.load(Ordering::Relaxed),
+    )
+}
+
+/// Reads the signal table of the CAN dashboard.
+///
+/// The document is an object with a `signals` array of what [`signal`]
+/// takes.
+///
+/// ```text
+/// {"signals": [
+///   {"name": "RPM", "id": 256, "length": 16, "max": 8000, "unit": "rpm"},
+///   {"name": "Check engine", "id": 258, "start": 0, "length": 1, "widget": "led"}
+/// ]}
+/// ```
+///
+/// Signals missing a required field or with a bad one are skipped with a
+/// warning.
+pub fn signals(text: &str) -> Result<Vec<Signal>, json::Error> {
+    let document = json::parse(text)?;
+    let signals = document.get("signals").map_or(&[][..], Value::as_array);
+    let signals = signals.iter().filter_map(|description| {
+        let signal = signal(description);
+        if signal.is_none() {
+            let name = description.get("name").and_then(Value::as_str);
+            defmt::warn!("Skipped CAN signal {}", name);
+        }
+        signal
+    });
+    Ok(signals.collect())
+}
@@ -0,0 +1,60 @@
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::signal::Signal;
+use embassy_time::Instant;
+
+use crate::boot::{self, Stage};
+use crate::timezone;
+
+pub use logic::clock::{DateTime, days_from_civil};
+
+/// Unix time at boot, 0 while no time source has set the clock
+static BOOT_UNIX_SECS: AtomicU32 = AtomicU32::new(0);
+/// Latest time given to [`set_unix_time`]
+static SET: Signal<CriticalSectionRawMutex, u32> = Signal::new();
+
+fn uptime_secs() -> u32 {
+    Instant::now().as_secs() as u32
+}
+
+/// Sets the wall clock. Called by whatever time source is available.
+pub fn set_unix_time(secs: u32) {
+    restore_unix_time(secs);
+    SET.signal(secs);
+}
+
+/// Like [`set_unix_time`], for the battery backed clock it came from, so
+/// [`wait_for_set`] does not write it back.
+pub fn restore_unix_time(secs: u32) {
+    BOOT_UNIX_SECS.store(secs.saturating_sub(uptime_secs()).max(1), Ordering::Relaxed);
+    boot::complete(Stage::TimeSync);
+}
+
+/// Waits for the next [`set_unix_time`] and returns the time it set.
+pub async fn wait_for_set() -> u32 {
+    SET.wait().await
+}
+
+/// Current Unix time, `None` until the clock has been set.
+pub fn unix_time() -> Option<u32> {
+    match BOOT_UNIX_SECS.load(Ordering::Relaxed) {
+        0 => None,
+        boot => Some(boot + uptime_secs()),
+    }
+}
+
+/// Local time in the zone set with [`timezone::set`].
+pub fn now() -> Option<DateTime> {
+    unix_time().map(|utc| DateTime::from_unix(timezone::to_local(utc).max(0) as u32))
+}
+
+/// Keeps the current local date (or 1970-01-01 if unset) and replaces the
+/// local time of day.
+pub fn set_time_of_day(hour: u8, minute: u8) {
+    let local = unix_time().map_or(0, timezone::to_local);
+    let midnight = local - local.rem_euclid(86400);
+    set_unix_time(timezone::to_utc(
+        midnight + hour as i64 * 3600 + minute as i64 * 60,
+    ));
+}
@@ -0,0 +1,85 @@
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+use embassy_time::{Duration, Instant};
+use esp_hal::clock::CpuClock;
+use esp_hal::peripherals::DPORT;
+use lv_bevy_ecs::sys::lv_anim_count_running;
+
+/// Clock while nothing on the display changes
+pub const IDLE_MHZ: u32 = 80;
+/// Stays fast this long after the last busy frame, so the gaps between the
+/// frames of an animation do not bounce the clock
+const HOLD: Duration = Duration::from_millis(250);
+
+static CURRENT_MHZ: AtomicU32 = AtomicU32::new(0);
+static BUSY: AtomicBool = AtomicBool::new(false);
+
+// In the ESP32 ROM, keeps its delay loops in step with the clock
+unsafe extern "C" {
+    fn ets_update_cpu_frequency(ticks_per_us: u32);
+}
+
+/// The CPU clock right now
+pub fn current_mhz() -> u32 {
+    CURRENT_MHZ.load(Ordering::Relaxed)
+}
+
+/// Keeps the clock up for this frame, for instance because it is flushed to
+/// the panel.
+pub fn mark_busy() {
+    BUSY.store(true, Ordering::Relaxed);
+}
+
+/// Runs the CPU at [`IDLE_MHZ`] while the UI is static and at the clock
+/// given to `esp_hal::init` while it animates, is touched or is flushed.
+///
+/// There is no ESP-IDF power management on bare metal, so this switches the
+/// CPU clock divider directly. Every PLL based CPU clock of the ESP32 has an
+/// 80 MHz APB clock, so peripherals and timers do not notice. The first frame
+/// after a quiet spell still renders at the idle clock.
+pub struct CpuScaling {
+    max_mhz: u32,
+    busy_until: Instant,
+}
+
+impl CpuScaling {
+    /// `max` must be the clock passed to `esp_hal::init`.
+    pub fn new(max: CpuClock) -> Self {
+        CURRENT_MHZ.store(max.mhz(), Ordering::Relaxed);
+        Self {
+            max_mhz: max.mhz(),
+            busy_until: Instant::now() + HOLD,
+        }
+    }
+
+    /// Picks the clock for the next frame. Call from the UI loop after
+    /// `lv_timer_handler`.
+    pub fn update(&mut self) {
+        let now = Instant::now();
+        if BUSY.swap(false, Ordering::Relaxed) || unsafe { lv_anim_count_running() } > 0 {
+            self.busy_until = now + HOLD;
+        }
+        let mhz = if now < self.busy_until {
+            self.max_mhz
+        } else {
+            IDLE_MHZ.min(self.max_mhz)
+        };
+        set_mhz(mhz);
+    }
+}
+
+fn set_mhz(mhz: u32) {
+    if CURRENT_MHZ.swap(mhz, Ordering::Relaxed) == mhz {
+        return;
+    }
+    // Divider of the PLL clock: 80, 160 or 240 MHz
+    let period = match mhz {
+        80 => 0,
+        160 => 1,
+        _ => 2,
+    };
+    DPORT::regs()
+        .cpu_per_conf()
+        .modify(|_, w| unsafe { w.cpuperiod_sel().bits(period) });
+    unsafe { ets_update_cpu_frequency(mhz) };
+}
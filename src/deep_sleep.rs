@@ -0,0 +1,135 @@
+use core::time::Duration;
+
+use esp_hal::peripherals::{GPIO36, LPWR};
+use esp_hal::ram;
+use esp_hal::rtc_cntl::sleep::{Ext0WakeupSource, TimerWakeupSource, WakeupLevel};
+use esp_hal::rtc_cntl::{Rtc, SocResetReason, reset_reason};
+use esp_hal::system::Cpu;
+use lv_bevy_ecs::sys::lv_display_get_inactive_time;
+
+use crate::clock;
+use crate::settings::{Key, Settings};
+
+/// Choices offered for [`Key::SleepTimeout`], 0 is never
+pub const TIMEOUTS_MINUTES: [u32; 6] = [0, 1, 5, 15, 30, 60];
+/// Tells a saved state from whatever RTC memory holds after power on
+const MAGIC: u32 = 0x534C_5050;
+const NO_MODULE: u32 = u32::MAX;
+
+/// What the next boot restores
+#[derive(Clone, Copy)]
+struct Saved {
+    magic: u32,
+    /// Wall clock when going to sleep, 0 if it was not set
+    unix_secs: u32,
+    /// RTC timer at the same moment, which keeps counting in deep sleep
+    rtc_secs: u32,
+    /// Registration index of the module on display
+    module: u32,
+}
+
+// Kept powered in deep sleep, but not across resets of other kinds
+#[ram(unstable(rtc_fast, persistent))]
+static mut SAVED: Saved = Saved {
+    magic: 0,
+    unix_secs: 0,
+    rtc_secs: 0,
+    module: NO_MODULE,
+};
+
+/// Powers the chip down after [`Key::SleepTimeout`] minutes without input,
+/// until the touch screen is pressed.
+///
+/// The XPT2046 pulls its IRQ line on GPIO36 low on touch, which is an RTC
+/// pin and can wake the chip. Waking is a reset, so what is not in
+/// [`Settings`] is kept in RTC memory and restored at boot.
+pub struct DeepSleep {
+    rtc: Rtc<'static>,
+    wake_pin: GPIO36<'static>,
+    restored: Option<Saved>,
+}
+
+impl DeepSleep {
+    /// Also sets the wall clock again when waking from deep sleep. Create it
+    /// before anything reads the clock.
+    pub fn new(lpwr: LPWR<'static>, wake_pin: GPIO36<'static>) -> Self {
+        let rtc = Rtc::new(lpwr);
+        let saved = unsafe { SAVED };
+        let restored = (reset_reason(Cpu::ProCpu) == Some(SocResetReason::CoreDeepSleep)
+            && saved.magic == MAGIC)
+            .then_some(saved);
+        unsafe { SAVED.magic = 0 };
+
+        if let Some(saved) = restored.filter(|saved| saved.unix_secs != 0) {
+            let slept = rtc_secs(&rtc).wrapping_sub(saved.rtc_secs);
+            clock::set_unix_time(saved.unix_secs + slept);
+        }
+        if restored.is_some() {
+            defmt::info!("Woke up from deep sleep");
+        }
+
+        Self {
+            rtc,
+            wake_pin,
+            restored,
+        }
+    }
+
+    /// Registration index of the module that was on display when the device
+    /// went to sleep, `None` after other resets
+    pub fn restored_module(&self) -> Option<usize> {
+        self.restored
+            .map(|saved| saved.module)
+            .filter(|&module| module != NO_MODULE)
+            .map(|module| module as usize)
+    }
+
+    /// Whether the UI has been left alone for longer than the timeout in
+    /// `settings`
+    pub fn is_due(&self, settings: &Settings) -> bool {
+        let minutes = settings.get(Key::SleepTimeout);
+        if minutes == 0 {
+            return false;
+        }
+        let inactive_ms = unsafe { lv_display_get_inactive_time(core::ptr::null_mut()) };
+        inactive_ms / 60_000 >= minutes
+    }
+
+    /// Saves what to restore and sleeps until the screen is touched, or the
+    /// alarm is due. Turn the backlight and panel off first.
+    pub fn enter(mut self, module: Option<usize>, settings: &Settings) -> ! {
+        unsafe {
+            SAVED = Saved {
+                magic: MAGIC,
+                unix_secs: clock::unix_time().unwrap_or(0),
+                rtc_secs: rtc_secs(&self.rtc),
+                module: module.map_or(NO_MODULE, |module| module as u32),
+            };
+        }
+        defmt::info!("Going to deep sleep");
+
+        let touch = Ext0WakeupSource::new(self.wake_pin, WakeupLevel::Low);
+        match secs_until_alarm(settings) {
+            Some(secs) => {
+                let alarm = TimerWakeupSource::new(Duration::from_secs(secs.into()));
+                self.rtc.sleep_deep(&[&touch, &alarm])
+            }
+            None => self.rtc.sleep_deep(&[&touch]),
+        }
+    }
+}
+
+fn rtc_secs(rtc: &Rtc<'_>) -> u32 {
+    rtc.time_since_power_up().as_secs() as u32
+}
+
+/// Time left until the alarm goes off, if it is enabled and the clock is set
+fn secs_until_alarm(settings: &Settings) -> Option<u32> {
+    if settings.get(Key::AlarmEnabled) == 0 {
+        return None;
+    }
+    let now = clock::now()?;
+    let now_secs = now.minute_of_day() * 60 + now.second as u32;
+    let alarm_secs = settings.get(Key::AlarmMinute) % (24 * 60) * 60;
+    Some((alarm_secs + 86400 - now_secs - 1) % 86400 + 1)
+}
@@ -0,0 +1,221 @@
+use alloc::collections::VecDeque;
+use alloc::format;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::cell::{Cell, RefCell};
+use core::net::Ipv4Addr;
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use embassy_net::dns::DnsQueryType;
+use embassy_net::tcp::{self, TcpSocket};
+use embassy_net::{IpAddress, Stack};
+use embassy_sync::blocking_mutex::Mutex;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::signal::Signal;
+use embassy_time::Duration;
+
+use crate::ui::i18n::Text;
+use crate::ui::toast::{self, Severity};
+
+/// Space separated `http://` URLs of images to offer in the Images app, set
+/// at build time
+const URLS: Option<&str> = option_env!("IMAGE_URLS");
+const DEFAULT_PORT: u16 = 80;
+const TIMEOUT: Duration = Duration::from_secs(10);
+/// Larger responses are dropped, images this size are slow to decode anyway
+const MAX_SIZE: usize = 48 * 1024;
+/// Downloads kept in RAM, the least recently used is dropped past this
+const CACHE_SIZE: usize = 64 * 1024;
+
+#[derive(defmt::Format)]
+pub enum Error {
+    Tcp(tcp::Error),
+    /// Not an `http://host[:port]/path` URL
+    BadUrl,
+    /// The host name could not be resolved
+    Dns,
+    Connect(tcp::ConnectError),
+    /// Not a `200 OK` response, or a cut off one
+    BadResponse,
+    TooLarge,
+}
+
+impl From<tcp::Error> for Error {
+    fn from(error: tcp::Error) -> Self {
+        Error::Tcp(error)
+    }
+}
+
+static CACHE: Mutex<CriticalSectionRawMutex, RefCell<VecDeque<(&'static str, Arc<[u8]>)>>> =
+    Mutex::new(RefCell::new(VecDeque::new()));
+/// Bumped whenever a download ends, so waiting screens know to look again
+static REVISION: AtomicU32 = AtomicU32::new(0);
+/// URL of the download that ended last, whether it worked or not
+static FINISHED: Mutex<CriticalSectionRawMutex, Cell<Option<&'static str>>> =
+    Mutex::new(Cell::new(None));
+static REQUEST: Signal<CriticalSectionRawMutex, &'static str> = Signal::new();
+
+/// The URLs it was built with.
+pub fn urls() -> impl Iterator<Item = &'static str> {
+    URLS.unwrap_or_default().split_whitespace()
+}
+
+/// The body downloaded from `url`, if it is still cached.
+pub fn cached(url: &str) -> Option<Arc<[u8]>> {
+    CACHE.lock(|cache| {
+        let mut cache = cache.borrow_mut();
+        let index = cache.iter().position(|(cached, _)| *cached == url)?;
+        // Moved to the back, as the most recently used
+        let entry = cache.remove(index)?;
+        let data = entry.1.clone();
+        cache.push_back(entry);
+        Some(data)
+    })
+}
+
+/// Asks the download task for `url`, replacing a request it has not started
+/// yet. Watch [`revision`] and look in [`cached`] for the result.
+pub fn fetch(url: &'static str) {
+    REQUEST.signal(url);
+}
+
+pub fn revision() -> u32 {
+    REVISION.load(Ordering::Relaxed)
+}
+
+/// URL of the download that ended last. Failed if it is not [`cached`].
+pub fn finished() -> Option<&'static str> {
+    FINISHED.lock(|finished| finished.get())
+}
+
+fn finish(url: &'static str) {
+    FINISHED.lock(|finished| finished.set(Some(url)));
+    REVISION.fetch_add(1, Ordering::Relaxed);
+}
+
+fn insert(url: &'static str, data: Arc<[u8]>) {
+    CACHE.lock(|cache| {
+        let mut cache = cache.borrow_mut();
+        cache.retain(|(cached, _)| *cached != url);
+        let mut total = data.len() + cache.iter().map(|(_, data)| data.len()).sum::<usize>();
+        while total > CACHE_SIZE {
+            let Some((_, dropped)) = cache.pop_front() else {
+                break;
+            };
+            total -= dropped.len();
+        }
+        cache.push_back((url, data));
+    });
+}
+
+/// Downloads what [`fetch`] asks for into the cache, one at a time.
+#[embassy_executor::task]
+pub async fn run(stack: Stack<'static>) {
+    let mut rx = [0; 2048];
+    let mut tx = [0; 256];
+    loop {
+        let url = REQUEST.wait().await;
+        if cached(url).is_some() {
+            finish(url);
+            continue;
+        }
+        stack.wait_config_up().await;
+        let mut socket = TcpSocket::new(stack, &mut rx, &mut tx);
+        socket.set_timeout(Some(TIMEOUT));
+        match get(stack, &mut socket, url).await {
+            Ok(body) => {
+                defmt::info!("Downloaded {} bytes from {=str}", body.len(), url);
+                insert(url, body.into());
+            }
+            Err(error) => {
+                defmt::warn!("Could not download {=str}: {:?}", url, error);
+                toast::show(
+                    Severity::Warning,
+                    Text::CouldNotDownload.get().to_str().unwrap(),
+                );
+            }
+        }
+        socket.abort();
+        let _ = socket.flush().await;
+        finish(url);
+    }
+}
+
+/// Splits `http://host[:port]/path` into its parts.
+fn parse(url: &str) -> Option<(&str, u16, &str)> {
+    let rest = url.strip_prefix("http://")?;
+    let (authority, path) = match rest.find('/') {
+        Some(index) => rest.split_at(index),
+        None => (rest, "/"),
+    };
+    let (host, port) = match authority.split_once(':') {
+        Some((host, port)) => (host, port.parse().ok()?),
+        None => (authority, DEFAULT_PORT),
+    };
+    if host.is_empty() {
+        return None;
+    }
+    Some((host, port, path))
+}
+
+/// Fetches `url` over HTTP/1.0, which ends the body by closing the
+/// connection, so there is no chunked encoding to undo.
+async fn get(
+    stack: Stack<'static>,
+    socket: &mut TcpSocket<'_>,
+    url: &str,
+) -> Result<Vec<u8>, Error> {
+    let (host, port, path) = parse(url).ok_or(Error::BadUrl)?;
+    let address = match host.parse::<Ipv4Addr>() {
+        Ok(address) => IpAddress::Ipv4(address),
+        Err(_) => {
+            let addresses = stack
+                .dns_query(host, DnsQueryType::A)
+                .await
+                .map_err(|_| Error::Dns)?;
+            *addresses.first().ok_or(Error::Dns)?
+        }
+    };
+    socket
+        .connect((address, port))
+        .await
+        .map_err(Error::Connect)?;
+    let request = format!("GET {path} HTTP/1.0\r\nHost: {host}\r\nConnection: close\r\n\r\n");
+    let mut data = request.as_bytes();
+    while !data.is_empty() {
+        let written = socket.write(data).await?;
+        data = &data[written..];
+    }
+
+    let mut response = Vec::new();
+    let mut chunk = [0; 512];
+    loop {
+        let read = socket.read(&mut chunk).await?;
+        if read == 0 {
+            break;
+        }
+        // The headers come on top of the body
+        if response.len() + read > MAX_SIZE + 1024 {
+            return Err(Error::TooLarge);
+        }
+        response.extend_from_slice(&chunk[..read]);
+    }
+
+    let end = response
+        .windows(4)
+        .position(|window| window == b"\r\n\r\n")
+        .ok_or(Error::BadResponse)?;
+    let status = response
+        .split(|&byte| byte == b'\n')
+        .next()
+        .unwrap_or_default();
+    let status = core::str::from_utf8(status).unwrap_or_default();
+    if status.split_whitespace().nth(1) != Some("200") {
+        return Err(Error::BadResponse);
+    }
+    let body = response.split_off(end + 4);
+    if body.len() > MAX_SIZE {
+        return Err(Error::TooLarge);
+    }
+    Ok(body)
+}
@@ -0,0 +1,107 @@
+use esp_hal::Async;
+use esp_hal::i2c::master::{Error, I2c};
+
+use crate::clock::{self, DateTime, days_from_civil};
+
+const ADDRESS: u8 = 0x68;
+const REG_TIME: u8 = 0x00;
+const REG_STATUS: u8 = 0x0F;
+/// Set when the oscillator stopped, so the time is not to be trusted
+const OSCILLATOR_STOPPED: u8 = 0x80;
+/// In the month register, which otherwise only counts to 12
+const CENTURY: u8 = 0x80;
+/// In the hours register
+const TWELVE_HOUR: u8 = 0x40;
+
+/// Battery backed clock on I2C, kept in UTC.
+///
+/// Sets the wall clock at boot, so the clock, calendar and alarms work
+/// without WiFi, and stores the time again whenever it is set, by hand or by
+/// a network time source.
+#[embassy_executor::task]
+pub async fn run(mut i2c: I2c<'static, Async>) {
+    match read(&mut i2c).await {
+        Ok(Some(secs)) => {
+            defmt::info!("Clock set from the DS3231");
+            clock::restore_unix_time(secs);
+        }
+        Ok(None) => defmt::warn!("The DS3231 lost its time"),
+        Err(error) => defmt::warn!("Could not read the DS3231: {:?}", error),
+    }
+
+    loop {
+        clock::wait_for_set().await;
+        let Some(secs) = clock::unix_time() else {
+            continue;
+        };
+        if let Err(error) = write(&mut i2c, secs).await {
+            defmt::warn!("Could not write the DS3231: {:?}", error);
+        }
+    }
+}
+
+/// Unix time, `None` if the oscillator stopped since the time was written
+async fn read(i2c: &mut I2c<'static, Async>) -> Result<Option<u32>, Error> {
+    let mut status = [0];
+    i2c.write_read_async(ADDRESS, &[REG_STATUS], &mut status)
+        .await?;
+    if status[0] & OSCILLATOR_STOPPED != 0 {
+        return Ok(None);
+    }
+
+    let mut time = [0; 7];
+    i2c.write_read_async(ADDRESS, &[REG_TIME], &mut time)
+        .await?;
+    let second = bcd(time[0] & 0x7F);
+    let minute = bcd(time[1] & 0x7F);
+    let hour = if time[2] & TWELVE_HOUR != 0 {
+        // Bit 5 is PM in 12 hour mode
+        bcd(time[2] & 0x1F) % 12 + if time[2] & 0x20 != 0 { 12 } else { 0 }
+    } else {
+        bcd(time[2] & 0x3F)
+    };
+    let day = bcd(time[4] & 0x3F);
+    let month = bcd(time[5] & 0x1F);
+    let century = if time[5] & CENTURY != 0 { 2100 } else { 2000 };
+    let year = century + bcd(time[6]) as i32;
+
+    let days = days_from_civil(year, month, day);
+    let secs = days as i64 * 86400 + hour as i64 * 3600 + minute as i64 * 60 + second as i64;
+    Ok(u32::try_from(secs).ok())
+}
+
+async fn write(i2c: &mut I2c<'static, Async>, secs: u32) -> Result<(), Error> {
+    let time = DateTime::from_unix(secs);
+    let year = (time.year - 2000).clamp(0, 199) as u8;
+    let century = if year >= 100 { CENTURY } else { 0 };
+    i2c.write_async(
+        ADDRESS,
+        &[
+            REG_TIME,
+            to_bcd(time.second),
+            to_bcd(time.minute),
+            to_bcd(time.hour),
+            // 1 to 7, counted from Monday
+            time.weekday + 1,
+            to_bcd(time.day),
+            to_bcd(time.month) | century,
+            to_bcd(year % 100),
+        ],
+    )
+    .await?;
+
+    // The time is good again
+    let mut status = [0];
+    i2c.write_read_async(ADDRESS, &[REG_STATUS], &mut status)
+        .await?;
+    i2c.write_async(ADDRESS, &[REG_STATUS, status[0] & !OSCILLATOR_STOPPED])
+        .await
+}
+
+fn bcd(value: u8) -> u8 {
+    (value >> 4) * 10 + (value & 0x0F)
+}
+
+fn to_bcd(value: u8) -> u8 {
+    ((value / 10) << 4) | (value % 10)
+}
@@ -0,0 +1,93 @@
+use core::cell::Cell;
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use embassy_executor::Spawner;
+use embassy_sync::blocking_mutex::Mutex;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_time::Instant;
+use esp_hal::Async;
+use esp_hal::peripherals::{GPIO27, UART1};
+use esp_hal::uart::{Config, UartRx};
+use logic::gps::{self, Reader};
+
+pub use logic::gps::{Fix, Position};
+
+const BAUD_RATE: u32 = 9600;
+
+/// The position, and when the last valid sentence came
+static POSITION: Mutex<CriticalSectionRawMutex, Cell<(Position, Option<Instant>)>> =
+    Mutex::new(Cell::new((
+        Position {
+            fix: Fix::None,
+            latitude: 0,
+            longitude: 0,
+            altitude: 0,
+            speed: 0,
+            satellites: 0,
+            time: None,
+        },
+        None,
+    )));
+static SENTENCES: AtomicU32 = AtomicU32::new(0);
+static BAD_CHECKSUMS: AtomicU32 = AtomicU32::new(0);
+
+/// The position as of the last GGA and RMC sentences.
+pub fn position() -> Position {
+    POSITION.lock(Cell::get).0
+}
+
+/// When the last valid sentence came, `None` before one did.
+pub fn updated() -> Option<Instant> {
+    POSITION.lock(Cell::get).1
+}
+
+/// Sentences read, and those dropped for a wrong checksum.
+pub fn counters() -> (u32, u32) {
+    (
+        SENTENCES.load(Ordering::Relaxed),
+        BAD_CHECKSUMS.load(Ordering::Relaxed),
+    )
+}
+
+/// Starts reading NMEA from a serial GPS module at 9600 baud, the default
+/// of the common u-blox NEO-6M boards. Its TX goes to GPIO27 on the CN1
+/// connector. Nothing is sent to the module.
+pub fn start(spawner: Spawner, uart: UART1<'static>, rx: GPIO27<'static>) {
+    let config = Config::default().with_baudrate(BAUD_RATE);
+    match UartRx::new(uart, config) {
+        Ok(uart) => spawner.spawn(run(uart.with_rx(rx).into_async()).unwrap()),
+        Err(_error) => defmt::error!("Could not set up the GPS UART"),
+    }
+}
+
+#[embassy_executor::task]
+async fn run(mut uart: UartRx<'static, Async>) {
+    let mut buffer = [0u8; 64];
+    let mut reader = Reader::new();
+    loop {
+        let Ok(count) = uart.read_async(&mut buffer).await else {
+            // Framing or overrun errors lose part of a sentence
+            reader.reset();
+            continue;
+        };
+        for &byte in &buffer[..count] {
+            if let Some(sentence) = reader.push(byte) {
+                receive(sentence);
+            }
+        }
+    }
+}
+
+fn receive(sentence: &[u8]) {
+    let Some(body) = gps::checked(sentence) else {
+        BAD_CHECKSUMS.fetch_add(1, Ordering::Relaxed);
+        return;
+    };
+    SENTENCES.fetch_add(1, Ordering::Relaxed);
+    POSITION.lock(|state| {
+        let (mut position, _) = state.get();
+        if position.update(body) {
+            state.set((position, Some(Instant::now())));
+        }
+    });
+}
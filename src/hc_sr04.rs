@@ -0,0 +1,108 @@
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use embassy_executor::Spawner;
+use embassy_futures::join::join;
+use embassy_time::{Duration, Timer, with_timeout};
+use esp_hal::Async;
+use esp_hal::delay::Delay;
+use esp_hal::gpio::{Level, Output, OutputConfig};
+use esp_hal::peripherals::{GPIO22, GPIO27};
+use esp_hal::rmt::{Channel, ChannelCreator, PulseCode, Rx, RxChannelConfig, RxChannelCreator};
+
+use crate::power;
+
+/// Two microseconds per tick of the 80 MHz RMT clock, so the idle threshold
+/// covers the echo of a missed target
+const CLOCK_DIVIDER: u8 = 160;
+const TICK_US: u32 = 2;
+/// The longest the RMT counts without an edge. The sensor gives up after
+/// about 38 ms.
+const IDLE_TICKS: u16 = 32_767;
+const PULSES: usize = 8;
+/// Measurements are spaced this far, the sensor wants at least 60 ms so the
+/// last echoes die down
+const PERIOD: Duration = Duration::from_millis(100);
+/// Still quick enough for the proximity alarm
+const SAVER_PERIOD: Duration = Duration::from_millis(500);
+/// Beyond the 4 m the sensor is made for
+const MAX_ECHO_US: u32 = 25_000;
+/// Nothing came back, or there is no sensor
+const NONE: u32 = u32::MAX;
+
+static DISTANCE_MM: AtomicU32 = AtomicU32::new(NONE);
+
+/// Distance to whatever is in front of the sensor, `None` when nothing
+/// echoed back in range.
+pub fn distance_mm() -> Option<u32> {
+    match DISTANCE_MM.load(Ordering::Relaxed) {
+        NONE => None,
+        distance => Some(distance),
+    }
+}
+
+/// Starts measuring with an HC-SR04 on the CN1 connector, trigger on
+/// GPIO22 and echo on GPIO27.
+///
+/// The echo pulse is timed by the RMT rather than by a task waiting for its
+/// edges, which would be off by however long the UI took to render.
+pub fn start(
+    spawner: Spawner,
+    channel: ChannelCreator<'static, Async, 1>,
+    trigger: GPIO22<'static>,
+    echo: GPIO27<'static>,
+) {
+    let config = RxChannelConfig::default()
+        .with_clk_divider(CLOCK_DIVIDER)
+        .with_idle_threshold(IDLE_TICKS)
+        .with_carrier_modulation(false);
+    match channel.configure_rx(echo, config) {
+        Ok(channel) => {
+            let trigger = Output::new(trigger, Level::Low, OutputConfig::default());
+            spawner.spawn(run(channel, trigger).unwrap());
+        }
+        Err(_error) => defmt::error!("Could not set up the HC-SR04 echo input"),
+    }
+}
+
+#[embassy_executor::task]
+async fn run(mut channel: Channel<'static, Async, Rx>, mut trigger: Output<'static>) {
+    let mut pulses = [PulseCode::default(); PULSES];
+    loop {
+        // Receiving starts before the trigger, so the echo is not missed
+        let (received, ()) = join(with_timeout(PERIOD, channel.receive(&mut pulses)), async {
+            trigger.set_high();
+            Delay::new().delay_micros(10);
+            trigger.set_low();
+        })
+        .await;
+        let distance = match received {
+            Ok(Ok(_)) => echo_us(&pulses)
+                .filter(|&echo| echo <= MAX_ECHO_US)
+                // Sound travels 0.343 mm per µs, there and back
+                .map_or(NONE, |echo| echo * 343 / 2000),
+            _ => NONE,
+        };
+        DISTANCE_MM.store(distance, Ordering::Relaxed);
+        let period = if power::saver_active() {
+            SAVER_PERIOD
+        } else {
+            PERIOD
+        };
+        Timer::after(period).await;
+    }
+}
+
+/// Length of the first high pulse.
+fn echo_us(pulses: &[PulseCode]) -> Option<u32> {
+    pulses
+        .iter()
+        .flat_map(|pulse| {
+            [
+                (pulse.level1(), pulse.length1()),
+                (pulse.level2(), pulse.length2()),
+            ]
+        })
+        .take_while(|&(_, length)| length != 0)
+        .find(|&(level, _)| level == Level::High)
+        .map(|(_, length)| u32::from(length) * TICK_US)
+}
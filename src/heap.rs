@@ -1,3 +1,5 @@
+use core::sync::atomic::{AtomicUsize, Ordering};
+
 use lv_bevy_ecs::sys::lv_mem_monitor_t;
 use static_cell::StaticCell;
 
@@ -7,6 +9,9 @@ static BSS_HEAP: StaticCell<[u8; BSS_HEAP_SIZE]> = StaticCell::new();
 const SRAM1_START: usize = 0x3FFE_8001;
 const SRAM1_END: usize = 0x4000_0000;
 
+/// Heap in use right before `lv_init`, see [`mark_lvgl_start`]
+static LVGL_BASELINE: AtomicUsize = AtomicUsize::new(0);
+
 #[allow(static_mut_refs)]
 pub fn setup_heap() {
     unsafe {
@@ -28,6 +33,45 @@ pub fn setup_heap() {
     defmt::info!("{}", esp_alloc::HEAP.stats());
 }
 
+/// Adds the PSRAM of modules like the ESP32-WROVER to the heap, after the
+/// internal RAM so it is only used once that runs out or when asked for.
+#[cfg(feature = "psram")]
+pub fn add_psram(psram: esp_hal::peripherals::PSRAM<'static>) {
+    esp_alloc::psram_allocator!(psram, esp_hal::psram);
+    defmt::info!("{}", esp_alloc::HEAP.stats());
+}
+
+/// Size of the PSRAM regions in the heap, 0 without the `psram` feature or
+/// when the module has none.
+pub fn psram_size() -> usize {
+    esp_alloc::HEAP
+        .stats()
+        .region_stats
+        .iter()
+        .flatten()
+        .filter(|region| {
+            region
+                .capabilities
+                .contains(esp_alloc::MemoryCapability::External)
+        })
+        .map(|region| region.size)
+        .sum()
+}
+
+/// Remembers how much of the heap was in use before LVGL started.
+///
+/// LVGL allocates from the same heap, so what is allocated after that is an
+/// upper bound for the `LV_MEM_SIZE` pool a builtin allocator would need.
+pub fn mark_lvgl_start() {
+    LVGL_BASELINE.store(esp_alloc::HEAP.stats().current_usage, Ordering::Relaxed);
+}
+
+pub fn lvgl_baseline() -> usize {
+    LVGL_BASELINE.load(Ordering::Relaxed)
+}
+
+/// Fills in `lv_mem_monitor` from the ESP heap, which LVGL allocates from.
+/// The peak is as seen by the calls, and fragmentation is not known.
 #[allow(static_mut_refs)]
 pub fn get_memory_stats(monitor: &mut lv_mem_monitor_t) {
     unsafe {
@@ -0,0 +1,222 @@
+use core::cell::RefCell;
+
+use embassy_executor::Spawner;
+use embassy_sync::blocking_mutex::Mutex;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_time::{Duration, Instant};
+use esp_hal::Async;
+use esp_hal::peripherals::GPIO17;
+use esp_hal::rmt::{Channel, ChannelCreator, PulseCode, Rx, RxChannelConfig, RxChannelCreator};
+use logic::ir::{self, Frame};
+use lv_bevy_ecs::sys::{
+    LV_INDEV_STATE_PRESSED, LV_INDEV_STATE_RELEASED, LV_INDEV_TYPE_KEYPAD, LV_KEY_ENTER,
+    LV_KEY_ESC, LV_KEY_LEFT, LV_KEY_NEXT, LV_KEY_PREV, LV_KEY_RIGHT, lv_indev_create,
+    lv_indev_data_t, lv_indev_set_group, lv_indev_set_read_cb, lv_indev_set_type, lv_indev_t,
+};
+
+use crate::cpu_frequency;
+use crate::settings::{Key, Settings};
+use crate::ui::focus;
+
+/// One microsecond per tick of the 80 MHz RMT clock
+const CLOCK_DIVIDER: u8 = 80;
+/// A frame is over after this long without an edge, well past the longest
+/// NEC space of 4.5 ms
+const IDLE_US: u16 = 12_000;
+/// Glitches shorter than this many APB cycles are ignored
+const FILTER_CYCLES: u8 = 200;
+/// Enough pulse codes for a whole frame, two pulses each
+const PULSES: usize = 48;
+/// How long a key stays pressed after the last frame or repeat code.
+/// Remotes repeat every 108 ms while held.
+const HOLD: Duration = Duration::from_millis(150);
+
+/// Remote keys the keypad input understands.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Button {
+    Up,
+    Down,
+    Left,
+    Right,
+    Ok,
+    Back,
+}
+
+impl Button {
+    pub const ALL: [Button; 6] = [
+        Button::Up,
+        Button::Down,
+        Button::Left,
+        Button::Right,
+        Button::Ok,
+        Button::Back,
+    ];
+
+    /// Where the learned code is stored.
+    pub fn key(self) -> Key {
+        match self {
+            Button::Up => Key::IrUp,
+            Button::Down => Key::IrDown,
+            Button::Left => Key::IrLeft,
+            Button::Right => Key::IrRight,
+            Button::Ok => Key::IrOk,
+            Button::Back => Key::IrBack,
+        }
+    }
+
+    /// Up and down move the focus, left and right go to the focused widget,
+    /// which is how sliders, arcs and rollers change their value.
+    fn lv_key(self) -> u32 {
+        (match self {
+            Button::Up => LV_KEY_PREV,
+            Button::Down => LV_KEY_NEXT,
+            Button::Left => LV_KEY_LEFT,
+            Button::Right => LV_KEY_RIGHT,
+            Button::Ok => LV_KEY_ENTER,
+            Button::Back => LV_KEY_ESC,
+        }) as u32
+    }
+}
+
+struct State {
+    /// Learned codes, in [`Button::ALL`] order
+    codes: [u32; Button::ALL.len()],
+    /// Codes are only collected for [`take_received`] while learning, and
+    /// not turned into keys
+    learning: bool,
+    received: Option<u32>,
+    /// The last key and until when it is held
+    last: Option<(Button, Instant)>,
+}
+
+static STATE: Mutex<CriticalSectionRawMutex, RefCell<State>> = Mutex::new(RefCell::new(State {
+    codes: [0; Button::ALL.len()],
+    learning: false,
+    received: None,
+    last: None,
+}));
+
+/// Starts receiving NEC frames from an IR receiver module on GPIO17 and
+/// registers them as an LVGL keypad.
+///
+/// GPIO17 drives the blue channel of the RGB LED and is not on a connector,
+/// so the receiver output is soldered to the LED side of its resistor. The
+/// LED then flickers along with received frames.
+/// The keypad moves through [`focus::group`].
+pub fn start(
+    spawner: Spawner,
+    channel: ChannelCreator<'static, Async, 0>,
+    pin: GPIO17<'static>,
+    settings: &Settings,
+) {
+    reload(settings);
+
+    let config = RxChannelConfig::default()
+        .with_clk_divider(CLOCK_DIVIDER)
+        .with_idle_threshold(IDLE_US)
+        .with_filter_threshold(FILTER_CYCLES)
+        .with_carrier_modulation(false);
+    match channel.configure_rx(pin, config) {
+        Ok(channel) => spawner.spawn(run(channel).unwrap()),
+        Err(_error) => defmt::error!("Could not set up the IR receiver"),
+    }
+
+    unsafe {
+        let keypad = lv_indev_create();
+        lv_indev_set_type(keypad, LV_INDEV_TYPE_KEYPAD);
+        lv_indev_set_read_cb(keypad, Some(read_keypad));
+        lv_indev_set_group(keypad, focus::group());
+    }
+}
+
+/// Takes the learned codes from `settings` again.
+pub fn reload(settings: &Settings) {
+    let codes = Button::ALL.map(|button| settings.get(button.key()));
+    STATE.lock(|state| state.borrow_mut().codes = codes);
+}
+
+/// While learning, received codes are kept for [`take_received`] instead of
+/// pressing keys.
+pub fn set_learning(learning: bool) {
+    STATE.lock(|state| {
+        let mut state = state.borrow_mut();
+        state.learning = learning;
+        state.received = None;
+    });
+}
+
+/// The code last received while learning, once.
+pub fn take_received() -> Option<u32> {
+    STATE.lock(|state| state.borrow_mut().received.take())
+}
+
+#[embassy_executor::task]
+async fn run(mut channel: Channel<'static, Async, Rx>) {
+    let mut pulses = [PulseCode::default(); PULSES];
+    loop {
+        if channel.receive(&mut pulses).await.is_err() {
+            // Longer than a frame, like the noise of a fluorescent lamp
+            continue;
+        }
+        let durations = pulses
+            .iter()
+            .flat_map(|pulse| [pulse.length1(), pulse.length2()])
+            .take_while(|&duration| duration != 0)
+            .map(u32::from);
+        match ir::decode(durations) {
+            Some(Frame::Code(code)) => received(code),
+            Some(Frame::Repeat) => repeated(),
+            None => {}
+        }
+    }
+}
+
+fn received(code: u32) {
+    STATE.lock(|state| {
+        let mut state = state.borrow_mut();
+        if state.learning {
+            state.received = Some(code);
+            return;
+        }
+        let button = Button::ALL
+            .into_iter()
+            .zip(state.codes)
+            .find_map(|(button, known)| (known == code).then_some(button));
+        match button {
+            Some(button) => state.last = Some((button, Instant::now() + HOLD)),
+            None => defmt::debug!("Unknown IR code {=u32:#010x}", code),
+        }
+    });
+}
+
+/// Keeps holding the last key, as long as it still was.
+fn repeated() {
+    STATE.lock(|state| {
+        let mut state = state.borrow_mut();
+        let now = Instant::now();
+        if let Some((_, until)) = &mut state.last {
+            if *until > now {
+                *until = now + HOLD;
+            }
+        }
+    });
+}
+
+unsafe extern "C" fn read_keypad(_indev: *mut lv_indev_t, data: *mut lv_indev_data_t) {
+    let Some((button, until)) = STATE.lock(|state| state.borrow().last) else {
+        return;
+    };
+    let pressed = Instant::now() < until;
+    if pressed {
+        cpu_frequency::mark_busy();
+    }
+    unsafe {
+        // The key is reported on release too, so LVGL knows which it was
+        (*data).key = button.lv_key();
+        (*data).state = if pressed {
+            LV_INDEV_STATE_PRESSED
+        } else {
+            LV_INDEV_STATE_RELEASED
+        };
+    }
+}
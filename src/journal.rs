@@ -0,0 +1,55 @@
+use alloc::collections::VecDeque;
+use alloc::string::String;
+use core::cell::RefCell;
+use core::ffi::{CStr, c_char};
+
+use embassy_sync::blocking_mutex::Mutex;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use lv_bevy_ecs::sys::{lv_log_level_t, lv_log_register_print_cb};
+
+/// Lines kept for the log viewer
+const CAPACITY: usize = 32;
+
+static LINES: Mutex<CriticalSectionRawMutex, RefCell<VecDeque<String>>> =
+    Mutex::new(RefCell::new(VecDeque::new()));
+
+/// Keeps `line` for the on-device log viewer, dropping the oldest when full.
+///
+/// defmt output is encoded on the host, so messages that should be readable
+/// without a debugger attached are recorded here as well.
+pub fn record(line: String) {
+    LINES.lock(|lines| {
+        let mut lines = lines.borrow_mut();
+        if lines.len() == CAPACITY {
+            lines.pop_front();
+        }
+        lines.push_back(line);
+    });
+}
+
+/// Recorded lines, oldest first.
+pub fn lines() -> String {
+    LINES.lock(|lines| {
+        let lines = lines.borrow();
+        let mut joined = String::new();
+        for line in lines.iter() {
+            joined.push_str(line);
+            joined.push('\n');
+        }
+        joined
+    })
+}
+
+/// Sends LVGL's log output to defmt and records it.
+pub fn capture_lvgl() {
+    unsafe { lv_log_register_print_cb(Some(print)) };
+}
+
+unsafe extern "C" fn print(_level: lv_log_level_t, text: *const c_char) {
+    let text = unsafe { CStr::from_ptr(text) }
+        .to_str()
+        .unwrap_or("<invalid>")
+        .trim_end();
+    defmt::warn!("LVGL: {=str}", text);
+    record(String::from(text));
+}
@@ -1,3 +1,61 @@
 #![no_std]
 
+extern crate alloc;
+
+// Each of these takes GPIO22 and GPIO27 on the CN1 connector
+const _: () = assert!(
+    cfg!(feature = "rtc-ds3231") as u8
+        + cfg!(feature = "hc-sr04") as u8
+        + cfg!(feature = "can") as u8
+        + cfg!(feature = "modbus") as u8
+        + cfg!(feature = "gps") as u8
+        <= 1,
+    "only one of `rtc-ds3231`, `hc-sr04`, `can`, `modbus` and `gps` can use the CN1 connector"
+);
+
+pub mod assets;
+pub mod audio;
+pub mod backlight;
+pub mod battery;
+pub mod boot;
+pub mod buzzer;
+pub mod calibration;
+#[cfg(feature = "can")]
+pub mod can;
+pub mod clock;
+pub mod cpu_frequency;
+pub mod deep_sleep;
+pub mod download;
+#[cfg(feature = "rtc-ds3231")]
+pub mod ds3231;
+#[cfg(feature = "gps")]
+pub mod gps;
+#[cfg(feature = "hc-sr04")]
+pub mod hc_sr04;
 pub mod heap;
+pub mod ir;
+pub mod journal;
+pub mod metrics;
+pub mod mirror;
+#[cfg(feature = "modbus")]
+pub mod modbus;
+pub mod mqtt;
+pub mod net;
+pub mod panel;
+pub mod pin;
+pub mod portal;
+pub mod power;
+pub mod rooms;
+pub mod rotation;
+pub mod settings;
+pub mod smart_light;
+pub mod system;
+pub mod timezone;
+pub mod touch;
+pub mod touch_polling;
+pub mod ttf;
+pub mod ui;
+pub mod web;
+pub mod wifi;
+
+pub use logic::json;
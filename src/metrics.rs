@@ -0,0 +1,290 @@
+use alloc::format;
+use alloc::string::String;
+use core::cell::{Cell, RefCell};
+use core::fmt::Write;
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use embassy_sync::blocking_mutex::Mutex;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_time::{Duration, Instant};
+use logic::metrics::{self, RenderTimes, WINDOW};
+use lv_bevy_ecs::sys::{
+    LV_EVENT_REFR_READY, LV_EVENT_REFR_START, lv_display_add_event_cb, lv_display_get_default,
+    lv_event_t,
+};
+
+/// The frame rate is the number of refreshes in a window this long
+const FPS_WINDOW: Duration = Duration::from_secs(1);
+
+#[derive(Clone, Copy)]
+struct Counters {
+    frames: u32,
+    render_us: u64,
+    flushes: u32,
+    flushed_pixels: u64,
+    flush_us: u64,
+    /// Start of the current frame rate window and the frames before it
+    window: (Instant, u32),
+    fps: f32,
+}
+
+static COUNTERS: Mutex<CriticalSectionRawMutex, Cell<Counters>> = Mutex::new(Cell::new(Counters {
+    frames: 0,
+    render_us: 0,
+    flushes: 0,
+    flushed_pixels: 0,
+    flush_us: 0,
+    window: (Instant::from_ticks(0), 0),
+    fps: 0.0,
+}));
+static FRAMES: Mutex<CriticalSectionRawMutex, RefCell<RenderTimes>> =
+    Mutex::new(RefCell::new(RenderTimes::new()));
+/// When the refresh in progress started, in microseconds since boot
+static RENDER_START_US: AtomicU32 = AtomicU32::new(0);
+
+unsafe extern "C" fn render_started(_event: *mut lv_event_t) {
+    RENDER_START_US.store(Instant::now().as_micros() as u32, Ordering::Relaxed);
+}
+
+unsafe extern "C" fn render_finished(_event: *mut lv_event_t) {
+    let now = Instant::now();
+    let took_us = (now.as_micros() as u32).wrapping_sub(RENDER_START_US.load(Ordering::Relaxed));
+    COUNTERS.lock(|counters| {
+        let mut next = counters.get();
+        next.frames += 1;
+        next.render_us += took_us as u64;
+        let elapsed = now - next.window.0;
+        if elapsed >= FPS_WINDOW {
+            next.fps = fps(next.frames - next.window.1, elapsed);
+            next.window = (now, next.frames);
+        }
+        counters.set(next);
+    });
+    FRAMES.lock(|frames| frames.borrow_mut().push(took_us));
+}
+
+fn fps(frames: u32, elapsed: Duration) -> f32 {
+    frames as f32 * 1000.0 / elapsed.as_millis().max(1) as f32
+}
+
+/// Starts timing the refreshes of the default display. Call once, after it
+/// is registered.
+pub fn install() {
+    unsafe {
+        let display = lv_display_get_default();
+        lv_display_add_event_cb(
+            display,
+            Some(render_started),
+            LV_EVENT_REFR_START,
+            core::ptr::null_mut(),
+        );
+        lv_display_add_event_cb(
+            display,
+            Some(render_finished),
+            LV_EVENT_REFR_READY,
+            core::ptr::null_mut(),
+        );
+    }
+}
+
+/// Display refreshes since [`install`], for screens showing the rate they
+/// come at.
+pub fn refreshes() -> u32 {
+    COUNTERS.lock(|counters| counters.get().frames)
+}
+
+/// Refreshes a second between calls of [`per_second`](Self::per_second),
+/// for screens showing the frame rate.
+pub struct FrameRate {
+    last: (Instant, u32),
+}
+
+impl FrameRate {
+    pub fn new() -> Self {
+        Self {
+            last: (Instant::now(), refreshes()),
+        }
+    }
+
+    /// The rate since the last call, or since [`new`](Self::new).
+    pub fn per_second(&mut self) -> u32 {
+        let now = (Instant::now(), refreshes());
+        let millis = (now.0 - self.last.0).as_millis().max(1) as u32;
+        let frames = now.1.wrapping_sub(self.last.1);
+        self.last = now;
+        frames * 1000 / millis
+    }
+}
+
+impl Default for FrameRate {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Counts one flush of `pixels` to the panel that took `duration`, waiting
+/// for the one before included.
+pub fn record_flush(pixels: u32, duration: Duration) {
+    COUNTERS.lock(|counters| {
+        let mut next = counters.get();
+        next.flushes += 1;
+        next.flushed_pixels += pixels as u64;
+        next.flush_us += duration.as_micros();
+        counters.set(next);
+    });
+}
+
+/// Everything counted so far, along with the heap at the moment.
+pub struct Snapshot {
+    pub uptime: Duration,
+    pub frames: u32,
+    /// Refreshes a second over the last second, dropping towards 0 once the
+    /// display stays still
+    pub fps: f32,
+    pub render: Duration,
+    /// Median, 90th and 99th percentile of the recent frames' render times
+    pub frame_percentiles: [Duration; 3],
+    pub heap_used: usize,
+    pub heap_size: usize,
+    pub heap_free: usize,
+    pub flushes: u32,
+    pub flushed_pixels: u64,
+    pub flush: Duration,
+}
+
+/// Quantiles of [`Snapshot::frame_percentiles`]
+pub const PERCENTILES: [u32; 3] = [50, 90, 99];
+
+pub fn snapshot() -> Snapshot {
+    let now = Instant::now();
+    let counters = COUNTERS.lock(|counters| counters.get());
+    // Refreshes stop along with the animations, which would leave the last
+    // rate standing
+    let elapsed = now - counters.window.0;
+    let fps = if elapsed >= FPS_WINDOW {
+        fps(counters.frames - counters.window.1, elapsed)
+    } else {
+        counters.fps
+    };
+
+    let mut times_us = [0; WINDOW];
+    let len = FRAMES.lock(|frames| {
+        let frames = frames.borrow();
+        let recent = frames.recent();
+        times_us[..recent.len()].copy_from_slice(recent);
+        recent.len()
+    });
+    let frame_percentiles = metrics::percentiles(&mut times_us[..len], PERCENTILES)
+        .map(|time_us| Duration::from_micros(time_us as u64));
+
+    let heap = esp_alloc::HEAP.stats();
+    Snapshot {
+        uptime: Duration::from_micros(now.as_micros()),
+        frames: counters.frames,
+        fps,
+        render: Duration::from_micros(counters.render_us),
+        frame_percentiles,
+        heap_used: heap.current_usage,
+        heap_size: heap.size,
+        heap_free: esp_alloc::HEAP.free(),
+        flushes: counters.flushes,
+        flushed_pixels: counters.flushed_pixels,
+        flush: Duration::from_micros(counters.flush_us),
+    }
+}
+
+impl Snapshot {
+    /// Prometheus text exposition format, for a `/metrics` scrape.
+    pub fn prometheus(&self) -> String {
+        let mut text = String::new();
+        let mut metric = |name: &str, kind: &str, help: &str, value: &dyn core::fmt::Display| {
+            let _ = write!(
+                text,
+                "# HELP cyd_{name} {help}\n# TYPE cyd_{name} {kind}\ncyd_{name} {value}\n"
+            );
+        };
+        metric(
+            "uptime_seconds",
+            "counter",
+            "Time since boot",
+            &seconds(self.uptime),
+        );
+        metric(
+            "fps",
+            "gauge",
+            "Display refreshes in the last second",
+            &self.fps,
+        );
+        metric("heap_used_bytes", "gauge", "Heap in use", &self.heap_used);
+        metric("heap_size_bytes", "gauge", "Heap size", &self.heap_size);
+        metric("heap_free_bytes", "gauge", "Heap free", &self.heap_free);
+        metric(
+            "flushes_total",
+            "counter",
+            "Areas sent to the panel",
+            &self.flushes,
+        );
+        metric(
+            "flushed_pixels_total",
+            "counter",
+            "Pixels sent to the panel",
+            &self.flushed_pixels,
+        );
+        metric(
+            "flush_seconds_total",
+            "counter",
+            "Time spent sending areas to the panel",
+            &seconds(self.flush),
+        );
+
+        let _ = write!(
+            text,
+            "# HELP cyd_frame_seconds Render time of a display refresh, flushes included\n\
+             # TYPE cyd_frame_seconds summary\n"
+        );
+        for (percentile, time) in PERCENTILES.iter().zip(self.frame_percentiles) {
+            let _ = writeln!(
+                text,
+                "cyd_frame_seconds{{quantile=\"{}\"}} {}",
+                *percentile as f32 / 100.0,
+                seconds(time)
+            );
+        }
+        let _ = writeln!(text, "cyd_frame_seconds_sum {}", seconds(self.render));
+        let _ = writeln!(text, "cyd_frame_seconds_count {}", self.frames);
+        text
+    }
+
+    /// Compact JSON for an MQTT message. Times are in milliseconds.
+    pub fn json(&self) -> String {
+        let [p50, p90, p99] = self.frame_percentiles.map(millis);
+        format!(
+            concat!(
+                "{{\"uptime_s\":{},\"fps\":{:.1},\"frames\":{},",
+                "\"frame_ms\":{{\"p50\":{:.1},\"p90\":{:.1},\"p99\":{:.1}}},",
+                "\"heap\":{{\"used\":{},\"size\":{},\"free\":{}}},",
+                "\"flush\":{{\"count\":{},\"pixels\":{},\"ms\":{:.0}}}}}"
+            ),
+            self.uptime.as_secs(),
+            self.fps,
+            self.frames,
+            p50,
+            p90,
+            p99,
+            self.heap_used,
+            self.heap_size,
+            self.heap_free,
+            self.flushes,
+            self.flushed_pixels,
+            millis(self.flush),
+        )
+    }
+}
+
+fn seconds(duration: Duration) -> f64 {
+    duration.as_micros() as f64 / 1_000_000.0
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_micros() as f64 / 1000.0
+}
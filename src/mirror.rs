@@ -0,0 +1,147 @@
+use alloc::vec::Vec;
+use core::cell::Cell;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use embassy_futures::select::{Either, select};
+use embassy_sync::blocking_mutex::Mutex;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::channel::Channel;
+use embedded_graphics::pixelcolor::Rgb565;
+use embedded_graphics::prelude::IntoStorage;
+use embedded_graphics::primitives::Rectangle;
+use lv_bevy_ecs::sys::{lv_area_t, lv_display_get_default, lv_inv_area};
+
+use crate::web::{self, WebSocket};
+
+/// Regions waiting for the socket. A full screen does not fit, the rest is
+/// redrawn by [`redraw_missed`] once these are sent.
+const QUEUE_DEPTH: usize = 4;
+/// Region header: x, y, width and height as little endian `u16`s
+const HEADER_SIZE: usize = 8;
+
+/// Canvas that draws the regions from `/ws` as they arrive.
+pub const PAGE: &str = r#"<!DOCTYPE html>
+<html>
+<head><title>Display mirror</title></head>
+<body style="background:#202020">
+<canvas id="screen" width="320" height="240" style="width:640px;height:480px;image-rendering:pixelated"></canvas>
+<script>
+const screen = document.getElementById("screen").getContext("2d");
+const socket = new WebSocket("ws://" + location.host + "/ws");
+socket.binaryType = "arraybuffer";
+socket.onmessage = (message) => {
+  const data = new DataView(message.data);
+  const [x, y, w, h] = [0, 2, 4, 6].map((offset) => data.getUint16(offset, true));
+  const image = screen.createImageData(w, h);
+  for (let i = 0; i < w * h; i++) {
+    const pixel = data.getUint16(8 + i * 2, true);
+    image.data[i * 4] = (pixel >> 11) * 255 / 31;
+    image.data[i * 4 + 1] = ((pixel >> 5) & 63) * 255 / 63;
+    image.data[i * 4 + 2] = (pixel & 31) * 255 / 31;
+    image.data[i * 4 + 3] = 255;
+  }
+  screen.putImageData(image, x, y);
+};
+</script>
+</body>
+</html>
+"#;
+
+/// Set while a browser is connected, so regions are only copied when watched
+static WATCHING: AtomicBool = AtomicBool::new(false);
+static REGIONS: Channel<CriticalSectionRawMutex, Vec<u8>, QUEUE_DEPTH> = Channel::new();
+/// Bounding box of the regions that could not be queued
+static MISSED: Mutex<CriticalSectionRawMutex, Cell<Option<lv_area_t>>> =
+    Mutex::new(Cell::new(None));
+
+fn miss(area: lv_area_t) {
+    MISSED.lock(|missed| {
+        let union = match missed.get() {
+            Some(old) => lv_area_t {
+                x1: old.x1.min(area.x1),
+                y1: old.y1.min(area.y1),
+                x2: old.x2.max(area.x2),
+                y2: old.y2.max(area.y2),
+            },
+            None => area,
+        };
+        missed.set(Some(union));
+    });
+}
+
+/// Queues a flushed region for the browser. Call from the display flush
+/// callback with the same area and pixels sent to the panel.
+pub fn push(area: &Rectangle, colors: impl Iterator<Item = Rgb565>) {
+    if !WATCHING.load(Ordering::Relaxed) {
+        return;
+    }
+    let Some(bottom_right) = area.bottom_right() else {
+        return;
+    };
+
+    let size = HEADER_SIZE + area.size.width as usize * area.size.height as usize * 2;
+    let mut region = Vec::new();
+    if REGIONS.is_full() || region.try_reserve_exact(size).is_err() {
+        miss(lv_area_t {
+            x1: area.top_left.x,
+            y1: area.top_left.y,
+            x2: bottom_right.x,
+            y2: bottom_right.y,
+        });
+        return;
+    }
+    for value in [
+        area.top_left.x,
+        area.top_left.y,
+        area.size.width as i32,
+        area.size.height as i32,
+    ] {
+        region.extend_from_slice(&(value as u16).to_le_bytes());
+    }
+    for color in colors {
+        region.extend_from_slice(&color.into_storage().to_le_bytes());
+    }
+    // Cannot fail, the only producer checked for room above
+    let _ = REGIONS.try_send(region);
+}
+
+/// Invalidates whatever the mirror missed, once the queue has drained. Call
+/// from the LVGL loop.
+pub fn redraw_missed() {
+    if !REGIONS.is_empty() {
+        return;
+    }
+    if let Some(area) = MISSED.lock(|missed| missed.take()) {
+        unsafe { lv_inv_area(lv_display_get_default(), &area) };
+    }
+}
+
+/// Streams flushed regions to a connected browser until it goes away. A
+/// second browser is closed on right away, the regions go to one only.
+pub(crate) async fn stream(websocket: &mut WebSocket<'_, '_>) -> Result<(), web::Error> {
+    if WATCHING.swap(true, Ordering::Relaxed) {
+        return Ok(());
+    }
+    REGIONS.clear();
+    // Clipped to the display, so this redraws everything for the new client
+    miss(lv_area_t {
+        x1: 0,
+        y1: 0,
+        x2: i16::MAX as i32,
+        y2: i16::MAX as i32,
+    });
+
+    let result = loop {
+        let region = match select(REGIONS.receive(), websocket.closed()).await {
+            Either::First(region) => region,
+            Either::Second(()) => break Ok(()),
+        };
+        if let Err(error) = websocket.send_binary(&region).await {
+            break Err(error);
+        }
+    };
+
+    WATCHING.store(false, Ordering::Relaxed);
+    MISSED.lock(|missed| missed.set(None));
+    result
+}
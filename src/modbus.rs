@@ -0,0 +1,233 @@
+use alloc::vec::Vec;
+use core::cell::{Cell, RefCell};
+
+use embassy_executor::Spawner;
+use embassy_sync::blocking_mutex::Mutex;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::channel::Channel;
+use embassy_time::{Duration, Timer, with_timeout};
+use esp_hal::Async;
+use esp_hal::peripherals::{GPIO22, GPIO27, UART1};
+use esp_hal::uart::{Config, Uart};
+use logic::modbus::{self, EXCEPTION, READ_HOLDING_REGISTERS, WRITE_SINGLE_REGISTER, frame};
+
+use crate::json::{self, Value};
+
+pub use logic::modbus::{Error, Register, register};
+
+const BAUD_RATE: u32 = 9600;
+/// How long a slave gets for all of its reply
+const RESPONSE_TIMEOUT: Duration = Duration::from_millis(250);
+/// Silence between frames, a little over 3.5 characters at 9600 baud
+const FRAME_GAP: Duration = Duration::from_millis(5);
+const QUEUE: usize = 8;
+/// Readings of further registers are not kept
+const MAX_REGISTERS: usize = 64;
+
+#[derive(Clone, Copy)]
+enum Request {
+    Read { slave: u8, address: u16 },
+    Write { slave: u8, address: u16, value: u16 },
+}
+
+struct Reading {
+    slave: u8,
+    address: u16,
+    result: Result<u16, Error>,
+}
+
+static REQUESTS: Channel<CriticalSectionRawMutex, Request, QUEUE> = Channel::new();
+static READINGS: Mutex<CriticalSectionRawMutex, RefCell<Vec<Reading>>> =
+    Mutex::new(RefCell::new(Vec::new()));
+static WRITTEN: Mutex<CriticalSectionRawMutex, Cell<Option<Result<(), Error>>>> =
+    Mutex::new(Cell::new(None));
+
+/// Starts a Modbus RTU master at 9600 baud, 8N1, through an RS-485
+/// transceiver on the CN1 connector, TX on GPIO22 and RX on GPIO27.
+///
+/// CN1 has no pin left for driver enable, so the transceiver has to switch
+/// direction by itself, like the common "automatic flow control" modules do.
+pub fn start(spawner: Spawner, uart: UART1<'static>, tx: GPIO22<'static>, rx: GPIO27<'static>) {
+    let config = Config::default().with_baudrate(BAUD_RATE);
+    match Uart::new(uart, config) {
+        Ok(uart) => {
+            let uart = uart.with_rx(rx).with_tx(tx).into_async();
+            spawner.spawn(run(uart).unwrap());
+        }
+        Err(_error) => defmt::error!("Could not set up the Modbus UART"),
+    }
+}
+
+/// Queues a read of holding register `address` of `slave`, for
+/// [`value`] once answered. `false` when the queue is full.
+pub fn read(slave: u8, address: u16) -> bool {
+    REQUESTS.try_send(Request::Read { slave, address }).is_ok()
+}
+
+/// Queues writing `value` to holding register `address` of `slave`. The
+/// outcome comes from [`take_written`]. `false` when the queue is full.
+pub fn write(slave: u8, address: u16, value: u16) -> bool {
+    REQUESTS
+        .try_send(Request::Write {
+            slave,
+            address,
+            value,
+        })
+        .is_ok()
+}
+
+/// The register as last read or written, or why that failed. `None` before
+/// it was asked for.
+pub fn value(slave: u8, address: u16) -> Option<Result<u16, Error>> {
+    READINGS.lock(|readings| {
+        readings
+            .borrow()
+            .iter()
+            .find(|reading| reading.slave == slave && reading.address == address)
+            .map(|reading| reading.result)
+    })
+}
+
+/// Whether requests are waiting behind the one being answered.
+pub fn busy() -> bool {
+    !REQUESTS.is_empty()
+}
+
+/// Outcome of the last write, once it is known and only once.
+pub fn take_written() -> Option<Result<(), Error>> {
+    WRITTEN.lock(Cell::take)
+}
+
+#[embassy_executor::task]
+async fn run(mut uart: Uart<'static, Async>) {
+    let mut reply = [0; 8];
+    loop {
+        match REQUESTS.receive().await {
+            Request::Read { slave, address } => {
+                let request = frame(slave, READ_HOLDING_REGISTERS, address, 1);
+                // Slave, function, byte count, the register and the CRC
+                let result = transact(&mut uart, &request, &mut reply[..7])
+                    .await
+                    .and_then(|reply| match reply[2] {
+                        2 => Ok(u16::from_be_bytes([reply[3], reply[4]])),
+                        _ => Err(Error::Invalid),
+                    });
+                store(slave, address, result);
+            }
+            Request::Write {
+                slave,
+                address,
+                value,
+            } => {
+                let request = frame(slave, WRITE_SINGLE_REGISTER, address, value);
+                // The reply repeats the request
+                let result = transact(&mut uart, &request, &mut reply)
+                    .await
+                    .and_then(|reply| {
+                        if reply == request {
+                            Ok(())
+                        } else {
+                            Err(Error::Invalid)
+                        }
+                    });
+                if result.is_ok() {
+                    store(slave, address, Ok(value));
+                }
+                WRITTEN.lock(|written| written.set(Some(result)));
+            }
+        }
+        Timer::after(FRAME_GAP).await;
+    }
+}
+
+fn store(slave: u8, address: u16, result: Result<u16, Error>) {
+    READINGS.lock(|readings| {
+        let mut readings = readings.borrow_mut();
+        match readings
+            .iter_mut()
+            .find(|reading| reading.slave == slave && reading.address == address)
+        {
+            Some(reading) => reading.result = result,
+            None if readings.len() < MAX_REGISTERS => readings.push(Reading {
+                slave,
+                address,
+                result,
+            }),
+            None => {}
+        }
+    });
+}
+
+/// Sends `request` and waits for a reply as long as `reply`, or for the
+/// shorter refusal. Checked down to the CRC.
+async fn transact<'a>(
+    uart: &mut Uart<'static, Async>,
+    request: &[u8; 8],
+    reply: &'a mut [u8],
+) -> Result<&'a [u8], Error> {
+    // Whatever is left of a reply that came too late would be taken for
+    // this one
+    let mut stale = [0; 16];
+    while uart.read_ready() {
+        if uart.read_buffered(&mut stale).is_err() {
+            break;
+        }
+    }
+    let mut sent = 0;
+    while sent < request.len() {
+        sent += uart
+            .write_async(&request[sent..])
+            .await
+            .map_err(|_| Error::Bus)?;
+    }
+    uart.flush_async().await.map_err(|_| Error::Bus)?;
+
+    let received = with_timeout(RESPONSE_TIMEOUT, async {
+        let mut length = 0;
+        let mut expected = reply.len();
+        while length < expected {
+            length += uart
+                .read_async(&mut reply[length..expected])
+                .await
+                .map_err(|_| Error::Bus)?;
+            if length >= 2 && reply[1] & EXCEPTION != 0 {
+                expected = 5;
+            }
+        }
+        Ok::<_, Error>(length)
+    })
+    .await;
+    let length = received.map_err(|_| Error::Timeout)??;
+    let reply = &reply[..length];
+
+    modbus::check(request, reply)?;
+    Ok(reply)
+}
+
+/// Reads the register table of the Modbus app.
+///
+/// The document is an object with a `registers` array of what [`register`]
+/// takes.
+///
+/// ```text
+/// {"registers": [
+///   {"name": "Temperature", "slave": 1, "address": 0, "decimals": 1, "unit": "°C"},
+///   {"name": "Setpoint", "slave": 1, "address": 1, "decimals": 1, "writable": true}
+/// ]}
+/// ```
+///
+/// Registers missing a required field or with a bad one are skipped with a
+/// warning.
+pub fn registers(text: &str) -> Result<Vec<Register>, json::Error> {
+    let document = json::parse(text)?;
+    let registers = document.get("registers").map_or(&[][..], Value::as_array);
+    let registers = registers.iter().filter_map(|description| {
+        let register = register(description);
+        if register.is_none() {
+            let name = description.get("name").and_then(Value::as_str);
+            defmt::warn!("Skipped Modbus register {}", name);
+        }
+        register
+    });
+    Ok(registers.collect())
+}
@@ -0,0 +1,132 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use embassy_net::tcp::{self, TcpSocket};
+use logic::mqtt::{self, Length, ProtocolError};
+
+pub use logic::mqtt::{Message, Options};
+
+/// Larger packets from the broker are refused rather than buffered
+const MAX_PACKET: usize = 1024;
+
+#[derive(defmt::Format)]
+pub enum Error {
+    Tcp(tcp::Error),
+    /// Return code of a refused connection
+    Refused(u8),
+    Closed,
+    /// Malformed or unexpected packet from the broker
+    Protocol,
+}
+
+impl From<tcp::Error> for Error {
+    fn from(error: tcp::Error) -> Self {
+        Error::Tcp(error)
+    }
+}
+
+impl From<ProtocolError> for Error {
+    fn from(_: ProtocolError) -> Self {
+        Error::Protocol
+    }
+}
+
+/// Minimal MQTT client on an already connected socket.
+///
+/// Only QoS 0 is supported, which is all a local smart home broker needs.
+pub struct Client<'a, 'b> {
+    socket: &'a mut TcpSocket<'b>,
+    next_packet_id: u16,
+}
+
+impl<'a, 'b> Client<'a, 'b> {
+    /// Sends CONNECT and waits for the broker to accept it.
+    pub async fn connect(
+        socket: &'a mut TcpSocket<'b>,
+        options: &Options<'_>,
+    ) -> Result<Self, Error> {
+        let mut client = Self {
+            socket,
+            next_packet_id: 1,
+        };
+        client.send(&mqtt::connect(options)).await?;
+        let (kind, body) = client.receive().await?;
+        match mqtt::connack(kind, &body)? {
+            0 => Ok(client),
+            code => Err(Error::Refused(code)),
+        }
+    }
+
+    pub async fn publish(
+        &mut self,
+        topic: &str,
+        payload: &[u8],
+        retain: bool,
+    ) -> Result<(), Error> {
+        self.send(&mqtt::publish(topic, payload, retain)).await
+    }
+
+    /// Subscribes to `topic` with QoS 0. The acknowledgment is skipped by
+    /// [`poll`](Self::poll) like any other packet that is not a message.
+    pub async fn subscribe(&mut self, topic: &str) -> Result<(), Error> {
+        let packet = mqtt::subscribe(self.next_packet_id, topic);
+        self.next_packet_id = self.next_packet_id.wrapping_add(1).max(1);
+        self.send(&packet).await
+    }
+
+    /// Keeps the connection alive. Call well within the keep alive period.
+    pub async fn ping(&mut self) -> Result<(), Error> {
+        self.send(&mqtt::ping()).await
+    }
+
+    /// Waits until the broker sent something, without reading it, so it can
+    /// be raced against other work.
+    pub async fn readable(&mut self) {
+        self.socket.wait_read_ready().await
+    }
+
+    /// Reads one packet, returning it if it is a message.
+    pub async fn poll(&mut self) -> Result<Option<Message>, Error> {
+        let (kind, body) = self.receive().await?;
+        Ok(mqtt::message(kind, &body)?)
+    }
+
+    async fn send(&mut self, packet: &[u8]) -> Result<(), Error> {
+        let mut data = packet;
+        while !data.is_empty() {
+            let written = self.socket.write(data).await?;
+            data = &data[written..];
+        }
+        Ok(())
+    }
+
+    async fn receive(&mut self) -> Result<(u8, Vec<u8>), Error> {
+        let mut byte = [0];
+        self.read_exact(&mut byte).await?;
+        let kind = byte[0];
+
+        let mut length = Length::default();
+        let len = loop {
+            self.read_exact(&mut byte).await?;
+            if let Some(len) = length.push(byte[0])? {
+                break len;
+            }
+        };
+        if len > MAX_PACKET {
+            return Err(Error::Protocol);
+        }
+
+        let mut body = vec![0; len];
+        self.read_exact(&mut body).await?;
+        Ok((kind, body))
+    }
+
+    async fn read_exact(&mut self, mut buffer: &mut [u8]) -> Result<(), Error> {
+        while !buffer.is_empty() {
+            match self.socket.read(buffer).await? {
+                0 => return Err(Error::Closed),
+                read => buffer = &mut buffer[read..],
+            }
+        }
+        Ok(())
+    }
+}
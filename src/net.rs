@@ -0,0 +1,148 @@
+use core::net::Ipv4Addr;
+
+use embassy_executor::Spawner;
+use embassy_net::dns::DnsQueryType;
+use embassy_net::udp::{PacketMetadata, UdpSocket};
+use embassy_net::{Config, Ipv4Cidr, Runner, Stack, StackResources, StaticConfigV4};
+use embassy_time::{Duration, Timer, with_timeout};
+use esp_radio::wifi::WifiDevice;
+use static_cell::StaticCell;
+
+use crate::clock;
+
+/// DHCP, DNS, two web server connections, MQTT, image downloads and SNTP
+const SOCKETS: usize = 7;
+/// DHCP and DNS servers and the setup page
+const ACCESS_POINT_SOCKETS: usize = 3;
+/// Address of the board on its own setup network
+pub const ACCESS_POINT_ADDRESS: Ipv4Addr = Ipv4Addr::new(192, 168, 4, 1);
+
+/// Host name of the SNTP server, set at build time
+const NTP_SERVER: &str = match option_env!("NTP_SERVER") {
+    Some(server) => server,
+    None => "pool.ntp.org",
+};
+const NTP_PORT: u16 = 123;
+const NTP_LOCAL_PORT: u16 = 50123;
+/// Mode 3 (client) of version 4, in the first byte of the packet
+const NTP_CLIENT: u8 = 0x23;
+const NTP_SERVER_MODE: u8 = 4;
+const NTP_PACKET_SIZE: usize = 48;
+/// From 1900, where NTP time starts, to 1970
+const NTP_UNIX_OFFSET: u32 = 2_208_988_800;
+const NTP_TIMEOUT: Duration = Duration::from_secs(5);
+/// The RTC of the ESP32 drifts a few seconds a day
+const SYNC_PERIOD: Duration = Duration::from_secs(6 * 3600);
+const RETRY_DELAY: Duration = Duration::from_secs(30);
+
+#[derive(defmt::Format)]
+enum SntpError {
+    /// The server name could not be resolved
+    Dns,
+    Bind,
+    Send,
+    /// No answer within [`NTP_TIMEOUT`]
+    Timeout,
+    /// Too short, not from a server, or a kiss-o'-death with stratum 0
+    BadResponse,
+}
+
+static RESOURCES: StaticCell<StackResources<SOCKETS>> = StaticCell::new();
+static ACCESS_POINT_RESOURCES: StaticCell<StackResources<ACCESS_POINT_SOCKETS>> = StaticCell::new();
+
+/// Starts the IP stack on the station interface. It gets an address over
+/// DHCP once the WiFi task joins a network.
+pub fn start(spawner: Spawner, device: WifiDevice<'static>, seed: u64) -> Stack<'static> {
+    let (stack, runner) = embassy_net::new(
+        device,
+        Config::dhcpv4(Default::default()),
+        RESOURCES.init(StackResources::new()),
+        seed,
+    );
+    spawner.spawn(run(runner).unwrap());
+    stack
+}
+
+/// Starts the IP stack on the access point interface, at
+/// [`ACCESS_POINT_ADDRESS`]. Clients get their address from `portal`.
+pub fn start_access_point(
+    spawner: Spawner,
+    device: WifiDevice<'static>,
+    seed: u64,
+) -> Stack<'static> {
+    let config = StaticConfigV4 {
+        address: Ipv4Cidr::new(ACCESS_POINT_ADDRESS, 24),
+        gateway: None,
+        dns_servers: Default::default(),
+    };
+    let (stack, runner) = embassy_net::new(
+        device,
+        Config::ipv4_static(config),
+        ACCESS_POINT_RESOURCES.init(StackResources::new()),
+        seed,
+    );
+    spawner.spawn(run(runner).unwrap());
+    stack
+}
+
+#[embassy_executor::task(pool_size = 2)]
+async fn run(mut runner: Runner<'static, WifiDevice<'static>>) -> ! {
+    runner.run().await
+}
+
+/// Sets the clock over SNTP once the station has an address, and again
+/// every [`SYNC_PERIOD`].
+#[embassy_executor::task]
+pub async fn sntp(stack: Stack<'static>) {
+    let mut rx_meta = [PacketMetadata::EMPTY; 1];
+    let mut rx = [0; NTP_PACKET_SIZE];
+    let mut tx_meta = [PacketMetadata::EMPTY; 1];
+    let mut tx = [0; NTP_PACKET_SIZE];
+    loop {
+        stack.wait_config_up().await;
+        let mut socket = UdpSocket::new(stack, &mut rx_meta, &mut rx, &mut tx_meta, &mut tx);
+        let delay = match query(stack, &mut socket).await {
+            Ok(secs) => {
+                defmt::info!("SNTP: {} seconds since 1970", secs);
+                clock::set_unix_time(secs);
+                SYNC_PERIOD
+            }
+            Err(error) => {
+                defmt::warn!("SNTP failed: {:?}", error);
+                RETRY_DELAY
+            }
+        };
+        drop(socket);
+        Timer::after(delay).await;
+    }
+}
+
+/// Asks [`NTP_SERVER`] for the time, as Unix seconds.
+async fn query(stack: Stack<'static>, socket: &mut UdpSocket<'_>) -> Result<u32, SntpError> {
+    let addresses = stack
+        .dns_query(NTP_SERVER, DnsQueryType::A)
+        .await
+        .map_err(|_| SntpError::Dns)?;
+    let address = *addresses.first().ok_or(SntpError::Dns)?;
+    socket.bind(NTP_LOCAL_PORT).map_err(|_| SntpError::Bind)?;
+
+    let mut request = [0; NTP_PACKET_SIZE];
+    request[0] = NTP_CLIENT;
+    socket
+        .send_to(&request, (address, NTP_PORT))
+        .await
+        .map_err(|_| SntpError::Send)?;
+
+    let mut response = [0; NTP_PACKET_SIZE];
+    let (len, _) = with_timeout(NTP_TIMEOUT, socket.recv_from(&mut response))
+        .await
+        .map_err(|_| SntpError::Timeout)?
+        .map_err(|_| SntpError::BadResponse)?;
+    let stratum = response[1];
+    if len < NTP_PACKET_SIZE || response[0] & 0x07 != NTP_SERVER_MODE || stratum == 0 {
+        return Err(SntpError::BadResponse);
+    }
+    // Seconds of the transmit timestamp
+    let secs = u32::from_be_bytes([response[40], response[41], response[42], response[43]]);
+    Ok(secs.wrapping_sub(NTP_UNIX_OFFSET))
+}
@@ -0,0 +1,33 @@
+use alloc::rc::Rc;
+use core::cell::RefCell;
+
+use embassy_time::Instant;
+use embedded_graphics::pixelcolor::Rgb565;
+use lv_bevy_ecs::display::{Display, DrawBuffer};
+
+use crate::cpu_frequency;
+use crate::metrics;
+use crate::mirror;
+
+pub use logic::panel::Panel;
+
+/// Makes `panel` LVGL's display, drawn through `buffer`. The display mirror
+/// gets a copy of every flush, and [`metrics`] its size and duration.
+pub fn attach<P: Panel + 'static, const N: usize>(
+    display: &mut Display,
+    buffer: DrawBuffer<N, Rgb565>,
+    panel: Rc<RefCell<P>>,
+) {
+    display.register(buffer, move |refresh| {
+        let area = refresh.rectangle;
+        let mut panel = panel.borrow_mut();
+        let start = Instant::now();
+        panel.wait();
+        if panel.flush(&area, refresh.colors.iter().cloned()).is_err() {
+            defmt::error!("Cannot fill display");
+        }
+        metrics::record_flush(area.size.width * area.size.height, start.elapsed());
+        mirror::push(&area, refresh.colors.iter().cloned());
+        cpu_frequency::mark_busy();
+    });
+}
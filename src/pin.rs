@@ -0,0 +1,47 @@
+use embassy_time::Duration;
+use esp_hal::rng::Rng;
+use logic::pin::{NONE, hash};
+
+use crate::settings::{Key, Settings};
+
+pub use logic::pin::{MAX_LEN, MIN_LEN};
+
+/// Whether the device locks at boot
+pub fn is_set(settings: &Settings) -> bool {
+    settings.get(Key::PinHash) != NONE
+}
+
+/// Locks the touch screen with `pin` from the next boot on.
+///
+/// It is a UI lock only. The flash is not encrypted, so whoever can read it
+/// over USB gets the settings, and the PIN too by trying every one against
+/// its hash.
+pub fn set(settings: &mut Settings, pin: &str) {
+    let salt = Rng::new().random();
+    settings.set(Key::PinSalt, salt);
+    settings.set(Key::PinHash, hash(salt, pin));
+    settings.set(Key::PinFailures, 0);
+}
+
+pub fn clear(settings: &mut Settings) {
+    settings.set(Key::PinHash, NONE);
+    settings.set(Key::PinFailures, 0);
+}
+
+/// Checks a guess and counts it if wrong. The count is kept across reboots,
+/// so restarting does not skip a [`lockout`].
+pub fn check(settings: &mut Settings, pin: &str) -> bool {
+    let correct = hash(settings.get(Key::PinSalt), pin) == settings.get(Key::PinHash);
+    let failures = if correct {
+        0
+    } else {
+        settings.get(Key::PinFailures).saturating_add(1)
+    };
+    settings.set(Key::PinFailures, failures);
+    correct
+}
+
+/// How long to wait before the next guess, once the free attempts are used up
+pub fn lockout(settings: &Settings) -> Option<Duration> {
+    logic::pin::lockout_secs(settings.get(Key::PinFailures)).map(Duration::from_secs)
+}
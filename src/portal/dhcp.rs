@@ -0,0 +1,44 @@
+use alloc::vec::Vec;
+use core::net::Ipv4Addr;
+
+use embassy_net::Stack;
+use embassy_net::udp::{PacketMetadata, UdpSocket};
+use logic::dhcp;
+
+use crate::net::ACCESS_POINT_ADDRESS;
+
+const SERVER_PORT: u16 = 67;
+const CLIENT_PORT: u16 = 68;
+
+/// Hands out addresses on the setup network, with the board as router and
+/// DNS server so every lookup ends at the setup page.
+#[embassy_executor::task]
+pub async fn serve(stack: Stack<'static>) {
+    let mut rx_meta = [PacketMetadata::EMPTY; 4];
+    let mut tx_meta = [PacketMetadata::EMPTY; 4];
+    let mut rx = [0; 1024];
+    let mut tx = [0; 1024];
+    let mut socket = UdpSocket::new(stack, &mut rx_meta, &mut rx, &mut tx_meta, &mut tx);
+    if let Err(error) = socket.bind(SERVER_PORT) {
+        defmt::error!("Could not start the DHCP server: {:?}", error);
+        return;
+    }
+
+    let mut clients: Vec<[u8; 6]> = Vec::new();
+    let mut buffer = [0; 576];
+    loop {
+        let Ok((len, _)) = socket.recv_from(&mut buffer).await else {
+            continue;
+        };
+        let Some(reply) = dhcp::reply(&buffer[..len], &mut clients, ACCESS_POINT_ADDRESS) else {
+            continue;
+        };
+        // Clients have no address yet, so replies are broadcast
+        if let Err(error) = socket
+            .send_to(&reply, (Ipv4Addr::BROADCAST, CLIENT_PORT))
+            .await
+        {
+            defmt::warn!("Could not send DHCP reply: {:?}", error);
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use embassy_net::Stack;
+use embassy_net::udp::{PacketMetadata, UdpSocket};
+use logic::dns;
+
+use crate::net::ACCESS_POINT_ADDRESS;
+
+const PORT: u16 = 53;
+
+/// Answers every address lookup with the board itself, which is what makes
+/// phones and laptops pop up the setup page.
+#[embassy_executor::task]
+pub async fn serve(stack: Stack<'static>) {
+    let mut rx_meta = [PacketMetadata::EMPTY; 4];
+    let mut tx_meta = [PacketMetadata::EMPTY; 4];
+    let mut rx = [0; 1024];
+    let mut tx = [0; 1024];
+    let mut socket = UdpSocket::new(stack, &mut rx_meta, &mut rx, &mut tx_meta, &mut tx);
+    if let Err(error) = socket.bind(PORT) {
+        defmt::error!("Could not start the DNS server: {:?}", error);
+        return;
+    }
+
+    let mut buffer = [0; 512];
+    loop {
+        let Ok((len, meta)) = socket.recv_from(&mut buffer).await else {
+            continue;
+        };
+        let Some(answer) = dns::answer(&buffer[..len], ACCESS_POINT_ADDRESS) else {
+            continue;
+        };
+        if let Err(error) = socket.send_to(&answer, meta.endpoint).await {
+            defmt::warn!("Could not send DNS answer: {:?}", error);
+        }
+    }
+}
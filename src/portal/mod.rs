@@ -0,0 +1,127 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use embassy_executor::Spawner;
+use embassy_net::Stack;
+use embassy_net::tcp::TcpSocket;
+use embassy_time::Duration;
+use esp_radio::wifi::WifiDevice;
+
+use crate::web::{self, Error};
+use crate::{net, wifi};
+
+mod dhcp;
+mod dns;
+
+const PORT: u16 = 80;
+const TIMEOUT: Duration = Duration::from_secs(10);
+const REQUEST_SIZE: usize = 1024;
+
+const PAGE: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>Setup</title>
+<style>
+body { font-family: sans-serif; max-width: 24em; margin: 2em auto; padding: 0 1em; }
+input, button { display: block; width: 100%; margin: 0.5em 0 1em; padding: 0.5em; box-sizing: border-box; }
+</style>
+</head>
+<body>
+<h1>WiFi setup</h1>
+<form action="/save">
+<label>Network <input name="ssid" maxlength="32" required></label>
+<label>Password <input name="password" type="password" maxlength="64"></label>
+<button>Connect</button>
+</form>
+</body>
+</html>
+"#;
+
+/// Name of the setup network, unique per board
+pub fn ssid(mac_address: [u8; 6]) -> String {
+    format!("CYD-Setup-{:02X}{:02X}", mac_address[4], mac_address[5])
+}
+
+/// Opens the setup network named `ssid` with a captive portal asking for the
+/// credentials of a real one.
+///
+/// Once they are submitted the WiFi task goes back to station mode to join
+/// that network, which closes the setup network.
+pub fn start(spawner: Spawner, device: WifiDevice<'static>, seed: u64, ssid: String) {
+    wifi::start_access_point(ssid);
+    let stack = net::start_access_point(spawner, device, seed);
+    spawner.spawn(dhcp::serve(stack).unwrap());
+    spawner.spawn(dns::serve(stack).unwrap());
+    spawner.spawn(serve(stack).unwrap());
+}
+
+#[embassy_executor::task]
+async fn serve(stack: Stack<'static>) {
+    let mut rx = [0; REQUEST_SIZE];
+    let mut tx = [0; 2048];
+    loop {
+        let mut socket = TcpSocket::new(stack, &mut rx, &mut tx);
+        socket.set_timeout(Some(TIMEOUT));
+        if let Err(error) = socket.accept(PORT).await {
+            defmt::warn!("Could not accept connection: {:?}", error);
+            continue;
+        }
+        if let Err(error) = handle(&mut socket).await {
+            defmt::warn!("Setup request failed: {:?}", error);
+        }
+        socket.close();
+        let _ = socket.flush().await;
+    }
+}
+
+async fn handle(socket: &mut TcpSocket<'_>) -> Result<(), Error> {
+    let mut buffer = [0; REQUEST_SIZE];
+    let request = web::read_request(socket, &mut buffer).await?;
+    let (path, query) = request.path.split_once('?').unwrap_or((request.path, ""));
+    if path == "/save" {
+        let ssid = field(query, "ssid").unwrap_or_default();
+        if !ssid.is_empty() {
+            let password = field(query, "password").unwrap_or_default();
+            let body = format!(
+                "<!DOCTYPE html><html><body><h1>Joining {}</h1><p>This network closes now. Check the display of the board.</p></body></html>",
+                escape(&ssid)
+            );
+            web::respond(socket, "200 OK", "text/html", body.as_bytes()).await?;
+            wifi::connect(ssid, password);
+            return Ok(());
+        }
+    }
+    // Every other path, including the connectivity checks of phones, gets
+    // the form so the captive portal shows up
+    web::respond(socket, "200 OK", "text/html", PAGE.as_bytes()).await
+}
+
+/// Decoded value of `name` in a form encoded query string
+fn field(query: &str, name: &str) -> Option<String> {
+    let value = query.split('&').find_map(|pair| {
+        let (key, value) = pair.split_once('=')?;
+        (key == name).then_some(value)
+    })?;
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut input = value.bytes();
+    while let Some(byte) = input.next() {
+        match byte {
+            b'+' => bytes.push(b' '),
+            b'%' => {
+                let hex = [input.next()?, input.next()?];
+                let hex = core::str::from_utf8(&hex).ok()?;
+                bytes.push(u8::from_str_radix(hex, 16).ok()?);
+            }
+            byte => bytes.push(byte),
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
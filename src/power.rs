@@ -0,0 +1,15 @@
+use core::sync::atomic::{AtomicBool, Ordering};
+
+static SAVER_ACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// Whether the battery saver is on, for the tasks and widgets that do less
+/// then.
+pub fn saver_active() -> bool {
+    SAVER_ACTIVE.load(Ordering::Relaxed)
+}
+
+/// Turns the flag read by [`saver_active`] on or off, returning what it was.
+/// The UI's battery saver does the rest of the switching.
+pub fn set_saver_active(active: bool) -> bool {
+    SAVER_ACTIVE.swap(active, Ordering::Relaxed)
+}
@@ -0,0 +1,48 @@
+use core::cell::Cell;
+
+use embassy_sync::blocking_mutex::Mutex;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::signal::Signal;
+
+use crate::mqtt::Message;
+use crate::smart_light::{self, Status};
+
+pub use logic::rooms::{Command, Pending, ROOMS, Report, command_message, state_filter};
+
+static REPORTS: Mutex<CriticalSectionRawMutex, Cell<[Report; ROOMS]>> =
+    Mutex::new(Cell::new([Report::NONE; ROOMS]));
+/// One per room, sent by the MQTT task of [`smart_light`], which owns the
+/// connection. A slider dragged faster than that only sends where it stops.
+pub static PENDING: [Signal<CriticalSectionRawMutex, Pending>; ROOMS] =
+    [const { Signal::new() }; ROOMS];
+
+pub fn report(room: usize) -> Report {
+    REPORTS.lock(|reports| reports.get().get(room).copied().unwrap_or_default())
+}
+
+/// Asks the devices of `room` for a change. Taken as done until they report
+/// otherwise, and dropped while the broker is offline, as there is nobody
+/// to tell.
+pub fn command(room: usize, command: Command) {
+    if room >= ROOMS || smart_light::status() != Status::Online {
+        return;
+    }
+    update(|reports| reports[room].apply(command));
+    let mut pending = PENDING[room].try_take().unwrap_or_default();
+    pending.push(command);
+    PENDING[room].signal(pending);
+}
+
+/// Takes in a message on one of the [`state_filter`] topics, ignoring
+/// anything else.
+pub fn receive(id: &str, message: &Message) {
+    update(|reports| logic::rooms::receive(id, message, reports));
+}
+
+fn update(change: impl FnOnce(&mut [Report; ROOMS])) {
+    REPORTS.lock(|reports| {
+        let mut next = reports.get();
+        change(&mut next);
+        reports.set(next);
+    });
+}
@@ -0,0 +1,13 @@
+use core::ffi::CStr;
+
+use crate::ui::i18n::Text;
+
+pub use logic::rotation::Rotation;
+
+/// The name of `rotation` in the settings.
+pub fn name(rotation: Rotation) -> &'static CStr {
+    match rotation {
+        Rotation::Normal => Text::Normal.get(),
+        Rotation::UpsideDown => Text::UpsideDown.get(),
+    }
+}
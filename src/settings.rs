@@ -0,0 +1,236 @@
+use alloc::string::String;
+
+use embedded_storage::{ReadStorage, Storage};
+use esp_storage::FlashStorage;
+
+/// Start of the `nvs` partition in `partitions.csv`.
+///
+/// The partition is not used as an ESP-IDF NVS store, just as a flat array of slots.
+const SETTINGS_OFFSET: u32 = 0x9000;
+const SLOTS: usize = 48;
+/// WiFi credentials get a flash sector of their own after the slots
+const CREDENTIALS_OFFSET: u32 = SETTINGS_OFFSET + 0x1000;
+const CREDENTIALS_MAGIC: u32 = 0x5749_4649;
+/// Longest SSID and WPA2 passphrase
+const MAX_SSID: usize = 32;
+const MAX_PASSWORD: usize = 64;
+const CREDENTIALS_SIZE: usize = 6 + MAX_SSID + MAX_PASSWORD;
+/// Erased flash reads back as all ones
+const UNSET: u32 = u32::MAX;
+
+/// Persisted values. Each key owns one `u32` slot, new keys go at the end.
+#[derive(Clone, Copy)]
+pub enum Key {
+    PomodoroCycles = 0,
+    AlarmEnabled = 1,
+    /// Minutes since midnight
+    AlarmMinute = 2,
+    /// Index into `Language::ALL`
+    Language = 3,
+    /// Index into `Mode::ALL` of the night mode
+    NightMode = 4,
+    /// See `calibration`
+    TouchCalibration = 5,
+    /// `f32` bits of the stored calibration, in `CalibrationData` field order
+    TouchAlphaX = 6,
+    TouchBetaX = 7,
+    TouchDeltaX = 8,
+    TouchAlphaY = 9,
+    TouchBetaY = 10,
+    TouchDeltaY = 11,
+    /// Backlight in percent
+    Brightness = 12,
+    /// Touch read period in milliseconds while nothing is pressed
+    TouchIdlePeriod = 13,
+    /// Index into `timezone::ZONES`
+    Timezone = 14,
+    /// Minutes without input before deep sleep, 0 is never
+    SleepTimeout = 15,
+    /// Audio volume in percent
+    Volume = 16,
+    /// Boots since the flash was erased, see `SystemInfo`
+    BootCount = 17,
+    /// `SocResetReason` of the last boot
+    LastResetReason = 18,
+    /// Index into `Rotation::ALL`
+    Rotation = 19,
+    /// Index into `accent::PALETTE`
+    Accent = 20,
+    /// Hash of the lock screen PIN, 0 is no PIN, see `pin`
+    PinHash = 21,
+    PinSalt = 22,
+    /// Wrong PIN guesses since the last right one
+    PinFailures = 23,
+    /// NEC codes of the remote keys, see `ir::Button`
+    IrUp = 24,
+    IrDown = 25,
+    IrLeft = 26,
+    IrRight = 27,
+    IrOk = 28,
+    IrBack = 29,
+    /// Distance in centimeters below which the HC-SR04 sounds the alarm
+    ProximityThreshold = 30,
+    /// Minutes without input before the screensaver, 0 is never
+    ScreensaverTimeout = 31,
+    /// Index into `Mode::ALL` of the battery saver
+    BatterySaver = 32,
+}
+
+impl Key {
+    const fn default_value(self) -> u32 {
+        match self {
+            Key::PomodoroCycles => 0,
+            Key::AlarmEnabled => 0,
+            Key::AlarmMinute => 7 * 60,
+            Key::Language => 0,
+            Key::NightMode => 0,
+            Key::TouchCalibration => 0,
+            Key::TouchAlphaX
+            | Key::TouchBetaX
+            | Key::TouchDeltaX
+            | Key::TouchAlphaY
+            | Key::TouchBetaY
+            | Key::TouchDeltaY => 0,
+            Key::Brightness => 100,
+            Key::TouchIdlePeriod => 80,
+            Key::Timezone => 0,
+            Key::SleepTimeout => 0,
+            Key::Volume => 70,
+            Key::BootCount => 0,
+            Key::LastResetReason => 0,
+            Key::Rotation => 0,
+            Key::Accent => 0,
+            Key::PinHash | Key::PinSalt | Key::PinFailures => 0,
+            // The 17 key remote that comes with most Arduino kits, with `*`
+            // for back
+            Key::IrUp => 0xE718_FF00,
+            Key::IrDown => 0xAD52_FF00,
+            Key::IrLeft => 0xF708_FF00,
+            Key::IrRight => 0xA55A_FF00,
+            Key::IrOk => 0xE31C_FF00,
+            Key::IrBack => 0xE916_FF00,
+            Key::ProximityThreshold => 20,
+            Key::ScreensaverTimeout => 0,
+            Key::BatterySaver => 0,
+        }
+    }
+}
+
+/// Small set of values kept in flash across reboots.
+pub struct Settings {
+    flash: FlashStorage<'static>,
+    values: [u32; SLOTS],
+}
+
+impl Settings {
+    pub fn load(mut flash: FlashStorage<'static>) -> Self {
+        let mut buffer = [0u8; SLOTS * 4];
+        if flash.read(SETTINGS_OFFSET, &mut buffer).is_err() {
+            defmt::error!("Could not read settings, using defaults");
+            buffer.fill(0xFF);
+        }
+
+        let mut values = [UNSET; SLOTS];
+        for (value, bytes) in values.iter_mut().zip(buffer.chunks_exact(4)) {
+            *value = u32::from_le_bytes(bytes.try_into().unwrap());
+        }
+
+        Self { flash, values }
+    }
+
+    /// Size of the whole flash chip in bytes
+    pub fn flash_capacity(&self) -> usize {
+        self.flash.capacity()
+    }
+
+    /// Reads from anywhere in flash, for the data partitions that have no
+    /// driver of their own, like `assets`.
+    pub fn read_flash(&mut self, offset: u32, bytes: &mut [u8]) -> bool {
+        self.flash.read(offset, bytes).is_ok()
+    }
+
+    pub fn get(&self, key: Key) -> u32 {
+        match self.values[key as usize] {
+            UNSET => key.default_value(),
+            value => value,
+        }
+    }
+
+    /// Stores `value` and writes the slots back to flash if it changed.
+    pub fn set(&mut self, key: Key, value: u32) {
+        if self.values[key as usize] == value {
+            return;
+        }
+        self.values[key as usize] = value;
+
+        let mut buffer = [0u8; SLOTS * 4];
+        for (value, bytes) in self.values.iter().zip(buffer.chunks_exact_mut(4)) {
+            bytes.copy_from_slice(&value.to_le_bytes());
+        }
+        if self.flash.write(SETTINGS_OFFSET, &buffer).is_err() {
+            defmt::error!("Could not save settings");
+        }
+    }
+
+    /// Goes back to the default value of `key`.
+    pub fn reset(&mut self, key: Key) {
+        self.set(key, UNSET);
+    }
+
+    /// Forgets every value and the WiFi credentials, as on a new board.
+    pub fn clear(&mut self) {
+        self.values = [UNSET; SLOTS];
+        let erased = self
+            .flash
+            .write(SETTINGS_OFFSET, &[0xFF; SLOTS * 4])
+            .is_ok()
+            && self
+                .flash
+                .write(CREDENTIALS_OFFSET, &[0xFF; CREDENTIALS_SIZE])
+                .is_ok();
+        if !erased {
+            defmt::error!("Could not clear settings");
+        }
+    }
+
+    /// Network joined at boot, `None` on first boot.
+    pub fn wifi_credentials(&mut self) -> Option<(String, String)> {
+        let mut buffer = [0u8; CREDENTIALS_SIZE];
+        self.flash.read(CREDENTIALS_OFFSET, &mut buffer).ok()?;
+        if u32::from_le_bytes(buffer[..4].try_into().unwrap()) != CREDENTIALS_MAGIC {
+            return None;
+        }
+        let (ssid_len, password_len) = (buffer[4] as usize, buffer[5] as usize);
+        if ssid_len > MAX_SSID || password_len > MAX_PASSWORD {
+            return None;
+        }
+        let ssid = core::str::from_utf8(&buffer[6..6 + ssid_len]).ok()?;
+        let password = &buffer[6 + MAX_SSID..6 + MAX_SSID + password_len];
+        let password = core::str::from_utf8(password).ok()?;
+        Some((String::from(ssid), String::from(password)))
+    }
+
+    pub fn set_wifi_credentials(&mut self, ssid: &str, password: &str) {
+        if ssid.len() > MAX_SSID || password.len() > MAX_PASSWORD {
+            defmt::error!("WiFi credentials too long to save");
+            return;
+        }
+        if self
+            .wifi_credentials()
+            .is_some_and(|(saved_ssid, saved_password)| {
+                saved_ssid == ssid && saved_password == password
+            })
+        {
+            return;
+        }
+        let mut buffer = [0u8; CREDENTIALS_SIZE];
+        buffer[..4].copy_from_slice(&CREDENTIALS_MAGIC.to_le_bytes());
+        buffer[4] = ssid.len() as u8;
+        buffer[5] = password.len() as u8;
+        buffer[6..6 + ssid.len()].copy_from_slice(ssid.as_bytes());
+        buffer[6 + MAX_SSID..6 + MAX_SSID + password.len()].copy_from_slice(password.as_bytes());
+        if self.flash.write(CREDENTIALS_OFFSET, &buffer).is_err() {
+            defmt::error!("Could not save WiFi credentials");
+        }
+    }
+}
@@ -0,0 +1,288 @@
+use alloc::format;
+use alloc::string::String;
+use core::cell::Cell;
+use core::net::Ipv4Addr;
+use core::sync::atomic::{AtomicU8, Ordering};
+
+use embassy_futures::select::{Either, Either4, select, select_array, select4};
+use embassy_net::Stack;
+use embassy_net::tcp::TcpSocket;
+use embassy_sync::blocking_mutex::Mutex;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::signal::Signal;
+use embassy_time::{Duration, Instant, Timer};
+
+use crate::journal;
+use crate::metrics;
+use crate::mqtt::{self, Client, Options};
+use crate::rooms;
+use crate::system::FIRMWARE_VERSION;
+use crate::ui::toast::{self, Severity};
+
+/// `host[:port]` of the MQTT broker, set at build time. Without it the light
+/// only works on screen.
+const BROKER: Option<&str> = option_env!("MQTT_BROKER");
+const USERNAME: Option<&str> = option_env!("MQTT_USERNAME");
+const PASSWORD: Option<&str> = option_env!("MQTT_PASSWORD");
+const DEFAULT_PORT: u16 = 1883;
+const KEEP_ALIVE_SECS: u16 = 60;
+const RETRY_DELAY: Duration = Duration::from_secs(10);
+/// Home Assistant listens for discovery messages under this prefix
+const DISCOVERY_PREFIX: &str = "homeassistant";
+/// Often enough to follow a soak test, rarely enough not to cost frames
+const METRICS_PERIOD: Duration = Duration::from_secs(10);
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Light {
+    pub on: bool,
+    /// 0 to 100
+    pub brightness: u8,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    /// No broker was set at build time
+    Disabled = 0,
+    Offline = 1,
+    Online = 2,
+}
+
+static LIGHT: Mutex<CriticalSectionRawMutex, Cell<Light>> = Mutex::new(Cell::new(Light {
+    on: false,
+    brightness: 100,
+}));
+/// Raised by [`set`] so the new state gets published
+static CHANGED: Signal<CriticalSectionRawMutex, ()> = Signal::new();
+static STATUS: AtomicU8 = AtomicU8::new(Status::Disabled as u8);
+
+pub fn get() -> Light {
+    LIGHT.lock(|light| light.get())
+}
+
+/// Changes the light from this device, like from the touch screen.
+pub fn set(light: Light) {
+    LIGHT.lock(|current| current.set(light));
+    CHANGED.signal(());
+}
+
+/// `host[:port]` it was built for
+pub fn broker() -> Option<&'static str> {
+    BROKER
+}
+
+pub fn status() -> Status {
+    match STATUS.load(Ordering::Relaxed) {
+        1 => Status::Offline,
+        2 => Status::Online,
+        _ => Status::Disabled,
+    }
+}
+
+/// Topics of this device, derived from its MAC address so several boards can
+/// share a broker
+struct Topics {
+    id: String,
+    config: String,
+    state: String,
+    command: String,
+    availability: String,
+    /// [`metrics::Snapshot::json`], not retained
+    metrics: String,
+}
+
+impl Topics {
+    fn new(mac_address: [u8; 6]) -> Self {
+        let id = format!(
+            "cyd_{:02x}{:02x}{:02x}",
+            mac_address[3], mac_address[4], mac_address[5]
+        );
+        Self {
+            config: format!("{DISCOVERY_PREFIX}/light/{id}/config"),
+            state: format!("{id}/light/state"),
+            command: format!("{id}/light/set"),
+            availability: format!("{id}/status"),
+            metrics: format!("{id}/metrics"),
+            id,
+        }
+    }
+
+    /// Home Assistant MQTT discovery payload for a dimmable light using the
+    /// JSON schema
+    fn discovery(&self) -> String {
+        format!(
+            concat!(
+                "{{\"name\":\"Light\",\"unique_id\":\"{id}_light\",\"schema\":\"json\",",
+                "\"brightness\":true,\"brightness_scale\":100,",
+                "\"state_topic\":\"{state}\",\"command_topic\":\"{command}\",",
+                "\"availability_topic\":\"{availability}\",",
+                "\"device\":{{\"identifiers\":[\"{id}\"],\"name\":\"CYD {id}\",",
+                "\"model\":\"ESP32-2432S028\",\"sw_version\":\"{version}\"}}}}"
+            ),
+            id = self.id,
+            state = self.state,
+            command = self.command,
+            availability = self.availability,
+            version = FIRMWARE_VERSION,
+        )
+    }
+}
+
+fn state_payload(light: Light) -> String {
+    format!(
+        "{{\"state\":\"{}\",\"brightness\":{}}}",
+        if light.on { "ON" } else { "OFF" },
+        light.brightness
+    )
+}
+
+/// Applies a JSON schema command such as `{"state":"ON","brightness":40}`.
+///
+/// Only the two fields of a dimmable light matter, so they are picked out of
+/// the text instead of parsing the JSON properly.
+fn apply_command(payload: &[u8], mut light: Light) -> Light {
+    let Ok(payload) = core::str::from_utf8(payload) else {
+        return light;
+    };
+    let payload: String = payload.chars().filter(|c| !c.is_whitespace()).collect();
+    if payload.contains("\"state\":\"ON\"") {
+        light.on = true;
+    } else if payload.contains("\"state\":\"OFF\"") {
+        light.on = false;
+    }
+    if let Some((_, rest)) = payload.split_once("\"brightness\":") {
+        let digits = rest
+            .split(|c: char| !c.is_ascii_digit())
+            .next()
+            .unwrap_or("");
+        if let Ok(brightness) = digits.parse::<u32>() {
+            light.brightness = brightness.min(100) as u8;
+        }
+    }
+    light
+}
+
+fn broker_address() -> Option<(Ipv4Addr, u16)> {
+    let broker = BROKER?;
+    let (host, port) = match broker.split_once(':') {
+        Some((host, port)) => (host, port.parse().ok()?),
+        None => (broker, DEFAULT_PORT),
+    };
+    Some((host.parse().ok()?, port))
+}
+
+/// Keeps the light on an MQTT broker, announced with Home Assistant
+/// discovery, and reconnects whenever the connection drops.
+///
+/// The same connection carries the performance counters, published every
+/// [`METRICS_PERIOD`], and the rooms of the dashboard, see [`rooms`].
+#[embassy_executor::task]
+pub async fn run(stack: Stack<'static>, mac_address: [u8; 6]) {
+    let Some((address, port)) = broker_address() else {
+        if BROKER.is_some() {
+            defmt::error!("MQTT_BROKER must look like 192.168.1.2:1883");
+        }
+        return;
+    };
+    STATUS.store(Status::Offline as u8, Ordering::Relaxed);
+    let topics = Topics::new(mac_address);
+
+    let mut rx = [0; 1024];
+    let mut tx = [0; 1024];
+    loop {
+        stack.wait_config_up().await;
+        let mut socket = TcpSocket::new(stack, &mut rx, &mut tx);
+        socket.set_timeout(Some(Duration::from_secs(KEEP_ALIVE_SECS as u64 * 2)));
+        match socket.connect((address, port)).await {
+            Ok(()) => {
+                if let Err(error) = session(&mut socket, &topics).await {
+                    defmt::warn!("MQTT connection lost: {:?}", error);
+                    journal::record(String::from("MQTT connection lost"));
+                    toast::show(Severity::Warning, "MQTT connection lost");
+                }
+            }
+            Err(error) => defmt::warn!("Could not reach the MQTT broker: {:?}", error),
+        }
+        STATUS.store(Status::Offline as u8, Ordering::Relaxed);
+        socket.abort();
+        let _ = socket.flush().await;
+        Timer::after(RETRY_DELAY).await;
+    }
+}
+
+/// Runs one connection until it fails.
+async fn session(socket: &mut TcpSocket<'_>, topics: &Topics) -> Result<(), mqtt::Error> {
+    let options = Options {
+        client_id: &topics.id,
+        keep_alive_secs: KEEP_ALIVE_SECS,
+        username: USERNAME,
+        password: PASSWORD,
+        will: Some((&topics.availability, b"offline")),
+    };
+    let mut client = Client::connect(socket, &options).await?;
+    client
+        .publish(&topics.config, topics.discovery().as_bytes(), true)
+        .await?;
+    client
+        .publish(&topics.availability, b"online", true)
+        .await?;
+    client.subscribe(&topics.command).await?;
+    client.subscribe(&rooms::state_filter(&topics.id)).await?;
+    STATUS.store(Status::Online as u8, Ordering::Relaxed);
+    journal::record(String::from("MQTT connected"));
+    toast::show(Severity::Success, "MQTT connected");
+
+    let ping_period = Duration::from_secs(KEEP_ALIVE_SECS as u64 / 2);
+    let mut ping_at = Instant::now() + ping_period;
+    let mut metrics_at = Instant::now();
+    let mut published = None;
+    loop {
+        let light = get();
+        if published != Some(light) {
+            client
+                .publish(&topics.state, state_payload(light).as_bytes(), true)
+                .await?;
+            published = Some(light);
+        }
+        match select4(
+            client.readable(),
+            select(
+                CHANGED.wait(),
+                select_array(rooms::PENDING.each_ref().map(|pending| pending.wait())),
+            ),
+            Timer::at(ping_at),
+            Timer::at(metrics_at),
+        )
+        .await
+        {
+            Either4::First(()) => {
+                let Some(message) = client.poll().await? else {
+                    continue;
+                };
+                if message.topic == topics.command.as_bytes() {
+                    LIGHT.lock(|light| light.set(apply_command(&message.payload, light.get())));
+                    toast::show(Severity::Info, "Light changed over MQTT");
+                } else {
+                    rooms::receive(&topics.id, &message);
+                }
+            }
+            Either4::Second(Either::First(())) => {}
+            Either4::Second(Either::Second((pending, room))) => {
+                for command in pending.commands() {
+                    let (topic, payload) = rooms::command_message(&topics.id, room, command);
+                    client.publish(&topic, payload.as_bytes(), false).await?;
+                }
+            }
+            Either4::Third(()) => {
+                client.ping().await?;
+                ping_at += ping_period;
+            }
+            Either4::Fourth(()) => {
+                let payload = metrics::snapshot().json();
+                client
+                    .publish(&topics.metrics, payload.as_bytes(), false)
+                    .await?;
+                metrics_at += METRICS_PERIOD;
+            }
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use esp_hal::clock::CpuClock;
+use esp_hal::efuse::Efuse;
+use esp_hal::rtc_cntl::{SocResetReason, reset_reason};
+use esp_hal::system::Cpu;
+
+use crate::settings::{Key, Settings};
+
+pub const FIRMWARE_VERSION: &str = env!("CARGO_PKG_VERSION");
+/// Set by the build script
+pub const BUILD_TIMESTAMP: &str = env!("BUILD_TIMESTAMP");
+
+/// Static facts about the chip and the firmware, collected once at boot.
+pub struct SystemInfo {
+    pub chip_revision: (u8, u8),
+    pub cpu_mhz: u32,
+    pub flash_size: usize,
+    pub mac_address: [u8; 6],
+    pub reset_reason: Option<SocResetReason>,
+    /// Boots so far, this one included
+    pub boot_count: u32,
+    /// Reset reason of the last counted boot before this one
+    pub previous_reset_reason: Option<SocResetReason>,
+}
+
+impl SystemInfo {
+    /// Also counts this boot in `settings`, unless it is a wake up from deep
+    /// sleep, which is neither a crash nor worth a flash write.
+    pub fn collect(cpu_clock: CpuClock, settings: &mut Settings) -> Self {
+        let reset_reason = reset_reason(Cpu::ProCpu);
+        let previous_reset_reason =
+            SocResetReason::from_repr(settings.get(Key::LastResetReason) as usize);
+        let mut boot_count = settings.get(Key::BootCount);
+        if reset_reason != Some(SocResetReason::CoreDeepSleep) {
+            boot_count = boot_count.wrapping_add(1);
+            settings.set(Key::BootCount, boot_count);
+            settings.set(
+                Key::LastResetReason,
+                reset_reason.map_or(0, |reason| reason as u32),
+            );
+        }
+        Self {
+            chip_revision: (Efuse::major_chip_version(), Efuse::minor_chip_version()),
+            cpu_mhz: cpu_clock.mhz(),
+            flash_size: settings.flash_capacity(),
+            mac_address: Efuse::read_base_mac_address(),
+            reset_reason,
+            boot_count,
+            previous_reset_reason,
+        }
+    }
+}
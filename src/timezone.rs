@@ -0,0 +1,36 @@
+use core::sync::atomic::{AtomicU8, Ordering};
+
+pub use logic::timezone::{ZONES, Zone};
+
+/// Index into [`ZONES`] of the zone in use
+static CURRENT: AtomicU8 = AtomicU8::new(0);
+
+pub fn set(index: u32) {
+    let index = if (index as usize) < ZONES.len() {
+        index
+    } else {
+        0
+    };
+    CURRENT.store(index as u8, Ordering::Relaxed);
+}
+
+pub fn current_index() -> u32 {
+    CURRENT.load(Ordering::Relaxed) as u32
+}
+
+pub fn current() -> &'static Zone {
+    &ZONES[current_index() as usize]
+}
+
+/// Converts Unix time to local time in the current zone. The clock keeps
+/// UTC, as SNTP and the DS3231 give it, so a new zone or a DST switch
+/// applies at once.
+pub fn to_local(utc: u32) -> i64 {
+    current().to_local(utc)
+}
+
+/// Converts local time in the current zone back to Unix time. Times in the
+/// hour skipped or repeated by DST resolve to one of the candidates.
+pub fn to_utc(local: i64) -> u32 {
+    current().to_utc(local)
+}
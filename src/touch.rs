@@ -0,0 +1,84 @@
+use core::cell::Cell;
+
+use embassy_sync::blocking_mutex::Mutex;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_time::Timer;
+use embedded_graphics::prelude::Point;
+use embedded_hal_bus::spi::ExclusiveDevice;
+use esp_hal::Blocking;
+use esp_hal::delay::Delay;
+use esp_hal::gpio::Output;
+use esp_hal::spi::master::Spi;
+use lv_bevy_ecs::input::{BufferStatus, InputEvent, InputState, Pointer};
+use xpt2046::{TouchEvent, TouchKind, TouchScreen, Xpt2046};
+
+use crate::power;
+use crate::touch_polling::{ACTIVE_PERIOD_MS, MAX_IDLE_PERIOD_MS};
+
+/// The XPT2046 on its own SPI bus, as the CYD wires it
+pub type Touch = Xpt2046<ExclusiveDevice<Spi<'static, Blocking>, Output<'static>, Delay>>;
+
+/// Pointer state as of the last sample, in display coordinates before
+/// rotation
+static LATEST: Mutex<CriticalSectionRawMutex, Cell<InputEvent<Pointer>>> =
+    Mutex::new(Cell::new(InputEvent::new(Point::zero())));
+
+/// What [`run`] last read. Cheap enough for the pointer read callback.
+pub fn latest() -> InputEvent<Pointer> {
+    LATEST.lock(|latest| latest.get())
+}
+
+/// Samples the touch screen off the LVGL thread.
+///
+/// A read is a few SPI transfers at 1 MHz, which used to stall rendering
+/// when done in the pointer read callback. Here it runs whenever the UI
+/// loop waits for its next timer. Samples are `ACTIVE_PERIOD_MS` apart
+/// while a finger is down and `idle_ms` apart otherwise, or the longest
+/// idle period with the battery saver on, like the reads of
+/// [`TouchPolling`](crate::touch_polling::TouchPolling).
+#[embassy_executor::task]
+pub async fn run(mut touch: Touch, idle_ms: u32) {
+    let mut pressed = false;
+    loop {
+        match touch.get_touch_event() {
+            Ok(Some(event)) => {
+                if let Some(input) = to_input(event, &mut pressed) {
+                    LATEST.lock(|latest| latest.set(input));
+                }
+            }
+            Ok(None) => {}
+            Err(_error) => defmt::error!("Error reading touch event"),
+        }
+        let period = if pressed {
+            ACTIVE_PERIOD_MS
+        } else if power::saver_active() {
+            MAX_IDLE_PERIOD_MS
+        } else {
+            idle_ms
+        };
+        Timer::after_millis(period.into()).await;
+    }
+}
+
+/// The pointer state `event` leads to, if it changes it. Moves are only
+/// followed after a start, `pressed` tracks which.
+fn to_input(event: TouchEvent, pressed: &mut bool) -> Option<InputEvent<Pointer>> {
+    match event.kind {
+        TouchKind::Start => *pressed = true,
+        TouchKind::Move if *pressed => {}
+        TouchKind::Move => return None,
+        TouchKind::End => {
+            *pressed = false;
+            return Some(InputEvent {
+                status: BufferStatus::Once,
+                state: InputState::Released,
+                data: Point::zero(),
+            });
+        }
+    }
+    Some(InputEvent {
+        status: BufferStatus::Once,
+        state: InputState::Pressed,
+        data: event.point,
+    })
+}
@@ -0,0 +1,64 @@
+use lv_bevy_ecs::sys::{lv_indev_active, lv_indev_get_read_timer, lv_timer_set_period};
+
+use crate::power;
+use crate::settings::{Key, Settings};
+
+/// Read period while a finger is down, fast enough for smooth drags
+pub const ACTIVE_PERIOD_MS: u32 = 5;
+/// Range allowed for [`Key::TouchIdlePeriod`]
+pub const MIN_IDLE_PERIOD_MS: u32 = 50;
+pub const MAX_IDLE_PERIOD_MS: u32 = 100;
+
+/// Reads the pointer often only while it is pressed.
+///
+/// Idle screens then only read it every [`Key::TouchIdlePeriod`]
+/// milliseconds, or [`MAX_IDLE_PERIOD_MS`] with the battery saver on,
+/// which lets the UI loop sleep longer. [`touch::run`]
+/// samples the XPT2046 at the same periods, saving SPI traffic. The price
+/// is latency on the first touch, up to one period for the sample and one
+/// for the read.
+///
+/// [`touch::run`]: crate::touch::run
+pub struct TouchPolling {
+    idle_ms: u32,
+    current_ms: u32,
+}
+
+/// [`Key::TouchIdlePeriod`], within the allowed range.
+pub fn idle_period_ms(settings: &Settings) -> u32 {
+    settings
+        .get(Key::TouchIdlePeriod)
+        .clamp(MIN_IDLE_PERIOD_MS, MAX_IDLE_PERIOD_MS)
+}
+
+impl TouchPolling {
+    pub fn new(settings: &Settings) -> Self {
+        Self {
+            idle_ms: idle_period_ms(settings),
+            current_ms: 0,
+        }
+    }
+
+    /// Adjusts the read period of the input device being read. Call from
+    /// its read callback with whether the pointer is pressed.
+    pub fn update(&mut self, pressed: bool) {
+        let period = if pressed {
+            ACTIVE_PERIOD_MS
+        } else if power::saver_active() {
+            MAX_IDLE_PERIOD_MS
+        } else {
+            self.idle_ms
+        };
+        if period == self.current_ms {
+            return;
+        }
+        unsafe {
+            let timer = lv_indev_get_read_timer(lv_indev_active());
+            if timer.is_null() {
+                return;
+            }
+            lv_timer_set_period(timer, period);
+        }
+        self.current_ms = period;
+    }
+}
@@ -0,0 +1,62 @@
+use alloc::boxed::Box;
+use alloc::vec;
+use core::ptr::NonNull;
+
+use embedded_storage::ReadStorage;
+use esp_storage::FlashStorage;
+use lv_bevy_ecs::sys::{lv_font_t, lv_tiny_ttf_create_data};
+
+/// Start of the `font` partition in `partitions.csv`.
+///
+/// `espflash write-bin 0x310000 font.ttf`
+pub const FONT_OFFSET: u32 = 0x310000;
+/// The font is copied to RAM, so larger files are rejected. Subset fonts to
+/// the glyphs you need to stay below this.
+const MAX_FONT_SIZE: usize = 96 * 1024;
+
+/// Reads the font flashed at [`FONT_OFFSET`], `None` if there is none.
+///
+/// The data is kept for the rest of the program, as fonts created from it
+/// refer to it.
+pub fn read_from_flash(flash: &mut FlashStorage<'static>) -> Option<&'static [u8]> {
+    let mut header = [0u8; 12];
+    flash.read(FONT_OFFSET, &mut header).ok()?;
+    let version = u32::from_be_bytes(header[0..4].try_into().unwrap());
+    if version != 0x0001_0000 && &header[0..4] != b"true" {
+        return None;
+    }
+
+    // The file ends with whichever table ends last
+    let tables = u16::from_be_bytes([header[4], header[5]]) as u32;
+    let mut size = 12 + tables as usize * 16;
+    for index in 0..tables {
+        let mut record = [0u8; 16];
+        flash
+            .read(FONT_OFFSET + 12 + index * 16, &mut record)
+            .ok()?;
+        let offset = u32::from_be_bytes(record[8..12].try_into().unwrap()) as usize;
+        let length = u32::from_be_bytes(record[12..16].try_into().unwrap()) as usize;
+        size = size.max(offset + length);
+    }
+    if size > MAX_FONT_SIZE {
+        defmt::error!(
+            "Font is {} bytes, at most {} fit in RAM",
+            size,
+            MAX_FONT_SIZE
+        );
+        return None;
+    }
+
+    let mut data = vec![0u8; size];
+    if flash.read(FONT_OFFSET, &mut data).is_err() {
+        defmt::error!("Could not read font");
+        return None;
+    }
+    defmt::info!("Loaded {} byte font from flash", size);
+    Some(Box::leak(data.into_boxed_slice()))
+}
+
+/// Renders `data` at `size` pixels with Tiny TTF.
+pub fn create_font(data: &'static [u8], size: i32) -> Option<NonNull<lv_font_t>> {
+    NonNull::new(unsafe { lv_tiny_ttf_create_data(data.as_ptr().cast(), data.len(), size) })
+}
@@ -0,0 +1,124 @@
+use alloc::ffi::CString;
+use alloc::format;
+use alloc::string::{String, ToString};
+use core::cell::Cell;
+
+use embassy_time::Instant;
+use esp_hal::rtc_cntl::SocResetReason;
+use lv_bevy_ecs::support::Align;
+use lv_bevy_ecs::sys::lv_label_set_text;
+use lv_bevy_ecs::widgets::{Button, Label};
+
+use super::i18n::Text;
+use super::module::{Resources, UiModule};
+use super::screen::Screen;
+use super::timer::Timer;
+use super::{back_button, builder};
+use crate::system::{BUILD_TIMESTAMP, FIRMWARE_VERSION, SystemInfo};
+use crate::{cpu_frequency, heap};
+
+/// Checks for a new CPU clock, see [`cpu_frequency`], and uptime minute
+const POLL_PERIOD_MS: u32 = 500;
+
+fn reason_name(reason: Option<SocResetReason>) -> String {
+    match reason {
+        Some(reason) => format!("{:?}", reason),
+        None => "Unknown".to_string(),
+    }
+}
+
+fn psram_description() -> String {
+    if !cfg!(feature = "psram") {
+        return "not enabled".to_string();
+    }
+    match heap::psram_size() {
+        0 => "none found".to_string(),
+        size => format!("{} KB", size / 1024),
+    }
+}
+
+/// Chip, memory and firmware details, with the uptime and boot count for
+/// telling how stable a build runs.
+pub struct About {
+    _timer: Timer,
+    _back: (Button, Label),
+    _title: Label,
+    _details: Label,
+}
+
+impl About {
+    /// Builds the about screen on the active screen. The back button loads `home`.
+    pub fn new(home: Screen, info: &SystemInfo) -> Self {
+        let back = back_button(home);
+
+        let title = builder::label()
+            .translated(Text::About)
+            .align(Align::TopMid)
+            .offset(0, 12)
+            .build();
+
+        let [m0, m1, m2, m3, m4, m5] = info.mac_address;
+        let chip = format!(
+            "Chip: ESP32 rev {}.{}",
+            info.chip_revision.0, info.chip_revision.1
+        );
+        let rest = format!(
+            "Flash: {} KB\n\
+             PSRAM: {}\n\
+             Firmware: {} (esp-hal)\n\
+             Built: {} UTC\n\
+             MAC: {:02X}:{:02X}:{:02X}:{:02X}:{:02X}:{:02X}\n\
+             Boots: {}\n\
+             Reset reason: {} (before: {})",
+            info.flash_size / 1024,
+            psram_description(),
+            FIRMWARE_VERSION,
+            BUILD_TIMESTAMP,
+            m0,
+            m1,
+            m2,
+            m3,
+            m4,
+            m5,
+            info.boot_count,
+            reason_name(info.reset_reason),
+            reason_name(info.previous_reset_reason),
+        );
+
+        let mut details = Label::new();
+        details.align(Align::TopLeft.into(), 10, 45);
+        let details_raw = details.raw();
+        // The clock drops while the screen is static, so redrawing every
+        // second would keep it up. The uptime goes in minutes and the label
+        // only changes along with them or the clock.
+        let max_mhz = info.cpu_mhz;
+        let shown = Cell::new((0, u64::MAX));
+        let refresh = move || {
+            let mhz = cpu_frequency::current_mhz();
+            let minutes = Instant::now().as_secs() / 60;
+            if shown.replace((mhz, minutes)) == (mhz, minutes) {
+                return;
+            }
+            let uptime = format!("{} h {:02} min", minutes / 60, minutes % 60);
+            let text = format!("{chip}\nCPU: {mhz} of {max_mhz} MHz\nUptime: {uptime}\n{rest}");
+            unsafe { lv_label_set_text(details_raw, CString::new(text).unwrap().as_ptr()) };
+        };
+        refresh();
+        let timer = Timer::new(POLL_PERIOD_MS, refresh);
+
+        Self {
+            _timer: timer,
+            _back: back,
+            _title: title,
+            _details: details,
+        }
+    }
+}
+
+impl UiModule for About {
+    const NAME: Text = Text::About;
+
+    fn build(home: Screen, resources: &mut Resources) -> Self {
+        Self::new(home, resources.get::<SystemInfo>())
+    }
+}
@@ -0,0 +1,50 @@
+use lv_bevy_ecs::sys::{
+    LV_PALETTE_AMBER, LV_PALETTE_BLUE, LV_PALETTE_CYAN, LV_PALETTE_DEEP_ORANGE,
+    LV_PALETTE_DEEP_PURPLE, LV_PALETTE_GREEN, LV_PALETTE_INDIGO, LV_PALETTE_PINK, LV_PALETTE_RED,
+    LV_PALETTE_TEAL, lv_color_t, lv_display_get_default, lv_display_set_theme, lv_palette_main,
+    lv_palette_t, lv_theme_default_init,
+};
+
+use super::focus;
+use super::fonts::Font;
+
+/// Colors the accent is picked from, the default theme's blue first
+pub const PALETTE: [lv_palette_t; 10] = [
+    LV_PALETTE_BLUE,
+    LV_PALETTE_INDIGO,
+    LV_PALETTE_DEEP_PURPLE,
+    LV_PALETTE_PINK,
+    LV_PALETTE_RED,
+    LV_PALETTE_DEEP_ORANGE,
+    LV_PALETTE_AMBER,
+    LV_PALETTE_GREEN,
+    LV_PALETTE_TEAL,
+    LV_PALETTE_CYAN,
+];
+/// Second color of the default theme, used for checked states and such
+const SECONDARY: lv_palette_t = LV_PALETTE_RED;
+
+pub fn color(index: u32) -> lv_color_t {
+    let palette = PALETTE.get(index as usize).copied().unwrap_or(PALETTE[0]);
+    unsafe { lv_palette_main(palette) }
+}
+
+/// Sets up the default theme again with `PALETTE[index]` as its primary
+/// color. The index is kept in `Key::Accent`.
+///
+/// The theme restyles every existing object, so arcs, sliders and buttons
+/// take the new color right away. So does the outline of the widget focused
+/// by a key, see [`focus::theme`].
+pub fn apply(index: u32) {
+    unsafe {
+        let display = lv_display_get_default();
+        let theme = lv_theme_default_init(
+            display,
+            color(index),
+            lv_palette_main(SECONDARY),
+            false,
+            Font::Montserrat14.raw(),
+        );
+        lv_display_set_theme(display, focus::theme(theme, color(index)));
+    }
+}
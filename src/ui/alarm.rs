@@ -0,0 +1,263 @@
+use alloc::ffi::CString;
+use alloc::format;
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+
+use lv_bevy_ecs::events::EventCode;
+use lv_bevy_ecs::support::Align;
+use lv_bevy_ecs::sys::{
+    LV_ANIM_OFF, LV_OPA_COVER, LV_ROLLER_MODE_NORMAL, LV_STATE_CHECKED, lv_color_hex, lv_layer_top,
+    lv_obj_add_state, lv_obj_has_state, lv_obj_set_parent, lv_obj_set_style_bg_color,
+    lv_obj_set_style_bg_opa, lv_roller_get_selected, lv_roller_set_options, lv_roller_set_selected,
+    lv_roller_set_visible_row_count,
+};
+use lv_bevy_ecs::widgets::{Button, Label, Obj, Roller, Switch};
+
+use super::animate::{Animate, Animation, Easing, Property};
+use super::i18n::Text;
+use super::module::{Resources, UiModule};
+use super::screen::Screen;
+use super::timer::Timer;
+use super::{back_button, builder, set_visible, translated_button};
+use crate::buzzer::{Beeper, Buzzer};
+use crate::clock;
+use crate::settings::{Key, Settings};
+
+const TICK_MS: u32 = 500;
+const SNOOZE_MINUTES: u32 = 5;
+const MINUTES_PER_DAY: u32 = 24 * 60;
+const DIALOG_COLOR: u32 = 0x1E88E5;
+const SLIDE_IN_MS: u32 = 400;
+
+struct State {
+    enabled: bool,
+    /// Minutes since midnight
+    alarm: u32,
+    /// Minute of day the snoozed alarm rings again
+    snooze: Option<u32>,
+    ringing: bool,
+}
+
+/// Daily alarm set with two rollers.
+///
+/// When the wall clock reaches the alarm time the buzzer beeps and a dialog
+/// covering the whole display offers dismiss and snooze, whichever screen is
+/// shown. The alarm time and whether it is enabled are kept in [`Settings`].
+///
+/// Off WiFi and without a DS3231 nothing else sets the clock, so "Set clock"
+/// takes the roller values as the current local time of day. SNTP replaces
+/// it once the board is on a network.
+pub struct Alarm {
+    _back: (Button, Label),
+    _title: Label,
+    _rollers: (Roller, Roller),
+    _enabled: Switch,
+    _set_clock: (Button, Label),
+    _dismiss: (Button, Label),
+    _snooze: (Button, Label),
+    /// Owns the dialog, which it shows and hides
+    _timer: Timer,
+}
+
+impl Alarm {
+    /// Builds the alarm screen on the active screen. The back button loads `home`.
+    pub fn new(home: Screen, settings: Rc<RefCell<Settings>>, buzzer: Rc<RefCell<Buzzer>>) -> Self {
+        let back = back_button(home);
+
+        let title = builder::label()
+            .translated(Text::Alarm)
+            .align(Align::TopMid)
+            .offset(0, 12)
+            .build();
+
+        let mut clock_label = Label::new();
+        clock_label.align(Align::TopRight.into(), -10, 12);
+
+        let state = Rc::new(RefCell::new(State {
+            enabled: settings.borrow().get(Key::AlarmEnabled) != 0,
+            alarm: settings.borrow().get(Key::AlarmMinute) % MINUTES_PER_DAY,
+            snooze: None,
+            ringing: false,
+        }));
+
+        let mut hours = number_roller(24);
+        hours.align(Align::Center.into(), -45, -10);
+        let mut minutes = number_roller(60);
+        minutes.align(Align::Center.into(), 45, -10);
+        let (hours_raw, minutes_raw) = (hours.raw(), minutes.raw());
+        unsafe {
+            let alarm = state.borrow().alarm;
+            lv_roller_set_selected(hours_raw, alarm / 60, LV_ANIM_OFF);
+            lv_roller_set_selected(minutes_raw, alarm % 60, LV_ANIM_OFF);
+        }
+        let selected = move || unsafe {
+            lv_roller_get_selected(hours_raw) * 60 + lv_roller_get_selected(minutes_raw)
+        };
+        for roller in [&mut hours, &mut minutes] {
+            roller.add_event_cb(EventCode::ValueChanged, {
+                let state = state.clone();
+                let settings = settings.clone();
+                move |_| {
+                    let mut state = state.borrow_mut();
+                    state.alarm = selected();
+                    state.snooze = None;
+                    settings.borrow_mut().set(Key::AlarmMinute, state.alarm);
+                }
+            });
+        }
+
+        let mut enabled = Switch::new();
+        enabled.align(Align::BottomMid.into(), -60, -14);
+        let enabled_raw = enabled.raw();
+        if state.borrow().enabled {
+            unsafe { lv_obj_add_state(enabled_raw, LV_STATE_CHECKED) };
+        }
+        enabled.add_event_cb(EventCode::ValueChanged, {
+            let state = state.clone();
+            let settings = settings.clone();
+            move |_| {
+                let on = unsafe { lv_obj_has_state(enabled_raw, LV_STATE_CHECKED) };
+                let mut state = state.borrow_mut();
+                state.enabled = on;
+                state.snooze = None;
+                settings.borrow_mut().set(Key::AlarmEnabled, on as u32);
+            }
+        });
+
+        let mut set_clock = translated_button(Text::SetClock);
+        set_clock.0.set_size(100, 36);
+        set_clock.0.align(Align::BottomMid.into(), 60, -8);
+        set_clock.0.add_event_cb(EventCode::Clicked, move |_| {
+            let minute = selected();
+            clock::set_time_of_day((minute / 60) as u8, (minute % 60) as u8);
+        });
+
+        let mut dialog = Obj::new();
+        unsafe {
+            lv_obj_set_parent(dialog.raw(), lv_layer_top());
+            lv_obj_set_style_bg_color(dialog.raw(), lv_color_hex(DIALOG_COLOR), 0);
+            lv_obj_set_style_bg_opa(dialog.raw(), LV_OPA_COVER as _, 0);
+        }
+        dialog.set_size(320, 240);
+        dialog.set_pos(0, 0);
+        set_visible(&mut dialog, false);
+
+        let mut dialog_label = Label::new();
+        dialog_label.set_parent(&mut dialog);
+        dialog_label.align(Align::Center.into(), 0, -40);
+
+        let mut dismiss = translated_button(Text::Dismiss);
+        dismiss.0.set_parent(&mut dialog);
+        dismiss.0.set_size(110, 44);
+        dismiss.0.align(Align::Center.into(), -65, 40);
+        dismiss.0.add_event_cb(EventCode::Clicked, {
+            let state = state.clone();
+            move |_| state.borrow_mut().ringing = false
+        });
+
+        let mut snooze = translated_button(Text::Snooze);
+        snooze.0.set_parent(&mut dialog);
+        snooze.0.set_size(110, 44);
+        snooze.0.align(Align::Center.into(), 65, 40);
+        snooze.0.add_event_cb(EventCode::Clicked, {
+            let state = state.clone();
+            move |_| {
+                let mut state = state.borrow_mut();
+                state.ringing = false;
+                state.snooze = clock::now()
+                    .map(|now| (now.minute_of_day() + SNOOZE_MINUTES) % MINUTES_PER_DAY);
+            }
+        });
+
+        let mut last_minute = None;
+        let mut beep = false;
+        let mut shown = false;
+        let mut slide_in: Option<Animation> = None;
+        let timer = Timer::new(TICK_MS, move || {
+            let now = clock::now();
+            let text = match now {
+                Some(now) => format!("{:02}:{:02}", now.hour, now.minute),
+                None => String::from("--:--"),
+            };
+            clock_label.set_text(CString::new(text).unwrap().as_c_str());
+
+            let mut state = state.borrow_mut();
+            let minute = now.map(|now| now.minute_of_day());
+            if minute.is_some() && minute != last_minute {
+                last_minute = minute;
+                let due = state.enabled && minute == Some(state.alarm);
+                if due || (state.snooze.is_some() && state.snooze == minute) {
+                    state.ringing = true;
+                    state.snooze = None;
+                    let alarm = minute.unwrap();
+                    let text = format!("Alarm {:02}:{:02}", alarm / 60, alarm % 60);
+                    dialog_label.set_text(CString::new(text).unwrap().as_c_str());
+                    set_visible(&mut dialog, true);
+                    slide_in = Some(
+                        Animate::new(Property::Y, -240, 0, SLIDE_IN_MS)
+                            .with_easing(Easing::Overshoot)
+                            .start(dialog.raw()),
+                    );
+                    shown = true;
+                }
+            }
+
+            if state.ringing {
+                beep = !beep;
+                buzzer.borrow_mut().set_beeping(Beeper::Alarm, beep);
+            } else if shown {
+                beep = false;
+                shown = false;
+                slide_in = None;
+                buzzer.borrow_mut().set_beeping(Beeper::Alarm, false);
+                set_visible(&mut dialog, false);
+            }
+        });
+
+        Self {
+            _back: back,
+            _title: title,
+            _rollers: (hours, minutes),
+            _enabled: enabled,
+            _set_clock: set_clock,
+            _dismiss: dismiss,
+            _snooze: snooze,
+            _timer: timer,
+        }
+    }
+}
+
+impl UiModule for Alarm {
+    const NAME: Text = Text::Alarm;
+    const RESIDENT: bool = true;
+
+    fn build(home: Screen, resources: &mut Resources) -> Self {
+        Self::new(
+            home,
+            resources.get::<Rc<RefCell<Settings>>>().clone(),
+            resources.get::<Rc<RefCell<Buzzer>>>().clone(),
+        )
+    }
+}
+
+/// Roller listing `00` up to `count - 1`.
+fn number_roller(count: u32) -> Roller {
+    let options = (0..count)
+        .map(|value| format!("{:02}", value))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let mut roller = Roller::new();
+    roller.set_width(70);
+    unsafe {
+        lv_roller_set_options(
+            roller.raw(),
+            CString::new(options).unwrap().as_ptr(),
+            LV_ROLLER_MODE_NORMAL,
+        );
+        lv_roller_set_visible_row_count(roller.raw(), 3);
+    }
+    roller
+}
@@ -0,0 +1,183 @@
+use alloc::rc::Rc;
+use core::cell::Cell;
+
+use embassy_time::Instant;
+use lv_bevy_ecs::sys::{
+    LV_ANIM_OFF, lv_arc_set_value, lv_bar_set_value, lv_obj_set_height, lv_obj_set_style_opa,
+    lv_obj_set_width, lv_obj_set_x, lv_obj_set_y, lv_obj_t,
+};
+
+use super::timer::Timer;
+use crate::power;
+
+/// Same as `LV_DEF_REFR_PERIOD`, so every rendered frame gets a new value
+const FRAME_MS: u32 = 33;
+
+/// What an [`Animate`] writes to its target.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Property {
+    X,
+    Y,
+    Width,
+    Height,
+    /// Whole object opacity, 0 to 255
+    Opacity,
+    /// Only for arcs
+    ArcValue,
+    /// Only for bars and sliders
+    BarValue,
+}
+
+impl Property {
+    fn write(self, target: *mut lv_obj_t, value: i32) {
+        unsafe {
+            match self {
+                Property::X => lv_obj_set_x(target, value),
+                Property::Y => lv_obj_set_y(target, value),
+                Property::Width => lv_obj_set_width(target, value),
+                Property::Height => lv_obj_set_height(target, value),
+                Property::Opacity => lv_obj_set_style_opa(target, value.clamp(0, 255) as _, 0),
+                Property::ArcValue => lv_arc_set_value(target, value),
+                Property::BarValue => lv_bar_set_value(target, value, LV_ANIM_OFF),
+            }
+        }
+    }
+}
+
+/// Maps linear progress to eased progress, both from 0 to 1.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Easing {
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+    /// Goes a little past the end and settles back
+    Overshoot,
+    /// Bounces off the end like a dropped ball
+    Bounce,
+}
+
+impl Easing {
+    pub fn apply(self, t: f32) -> f32 {
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t * t,
+            Easing::EaseOut => 1.0 - cube(1.0 - t),
+            Easing::EaseInOut if t < 0.5 => 4.0 * t * t * t,
+            Easing::EaseInOut => 1.0 - cube(2.0 - 2.0 * t) / 2.0,
+            Easing::Overshoot => {
+                const BACK: f32 = 1.70158;
+                let t = t - 1.0;
+                1.0 + (BACK + 1.0) * t * t * t + BACK * t * t
+            }
+            Easing::Bounce => {
+                const N: f32 = 7.5625;
+                const D: f32 = 2.75;
+                if t < 1.0 / D {
+                    N * t * t
+                } else if t < 2.0 / D {
+                    let t = t - 1.5 / D;
+                    N * t * t + 0.75
+                } else if t < 2.5 / D {
+                    let t = t - 2.25 / D;
+                    N * t * t + 0.9375
+                } else {
+                    let t = t - 2.625 / D;
+                    N * t * t + 0.984375
+                }
+            }
+        }
+    }
+}
+
+fn cube(x: f32) -> f32 {
+    x * x * x
+}
+
+/// Moves one property of a widget from `from` to `to` over `duration_ms`.
+#[derive(Clone, Copy)]
+pub struct Animate {
+    pub property: Property,
+    pub from: i32,
+    pub to: i32,
+    pub duration_ms: u32,
+    pub easing: Easing,
+}
+
+impl Animate {
+    pub fn new(property: Property, from: i32, to: i32, duration_ms: u32) -> Self {
+        Self {
+            property,
+            from,
+            to,
+            duration_ms,
+            easing: Easing::Linear,
+        }
+    }
+
+    pub fn with_easing(self, easing: Easing) -> Self {
+        Self { easing, ..self }
+    }
+
+    /// The value `elapsed_ms` into the animation, `to` once it is over.
+    pub fn value_at(&self, elapsed_ms: u32) -> i32 {
+        if elapsed_ms >= self.duration_ms {
+            return self.to;
+        }
+        let t = self
+            .easing
+            .apply(elapsed_ms as f32 / self.duration_ms as f32);
+        self.from + ((self.to - self.from) as f32 * t) as i32
+    }
+
+    /// Writes `from` to `target` right away and advances it every frame.
+    /// With the battery saver on it writes `to` instead and is done.
+    ///
+    /// The animation must be dropped before `target` is deleted, like a
+    /// [`Timer`] touching widgets.
+    pub fn start(self, target: *mut lv_obj_t) -> Animation {
+        let animate = if power::saver_active() {
+            Self {
+                from: self.to,
+                duration_ms: 0,
+                ..self
+            }
+        } else {
+            self
+        };
+        animate.begin(target)
+    }
+
+    fn begin(self, target: *mut lv_obj_t) -> Animation {
+        self.property.write(target, self.from);
+        let start = Instant::now();
+        let finished = Rc::new(Cell::new(false));
+        let timer = Timer::new(FRAME_MS, {
+            let finished = finished.clone();
+            move || {
+                if finished.get() {
+                    return;
+                }
+                let elapsed = start.elapsed().as_millis() as u32;
+                self.property.write(target, self.value_at(elapsed));
+                finished.set(elapsed >= self.duration_ms);
+            }
+        });
+        Animation {
+            finished,
+            _timer: timer,
+        }
+    }
+}
+
+/// A running [`Animate`]. Dropping it stops the animation where it is.
+pub struct Animation {
+    finished: Rc<Cell<bool>>,
+    _timer: Timer,
+}
+
+impl Animation {
+    pub fn is_finished(&self) -> bool {
+        self.finished.get()
+    }
+}
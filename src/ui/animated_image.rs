@@ -0,0 +1,148 @@
+use alloc::boxed::Box;
+use alloc::ffi::CString;
+use alloc::format;
+use core::ffi::c_void;
+
+use lv_bevy_ecs::events::EventCode;
+use lv_bevy_ecs::support::Align;
+use lv_bevy_ecs::sys::{
+    LV_ANIM_OFF, LV_ANIM_REPEAT_INFINITE, LV_COLOR_FORMAT_RGB565A8, lv_animimg_create,
+    lv_animimg_set_duration, lv_animimg_set_repeat_count, lv_animimg_set_src, lv_animimg_start,
+    lv_draw_buf_init, lv_draw_buf_t, lv_label_set_text, lv_obj_center, lv_obj_remove_style_all,
+    lv_obj_t, lv_slider_get_value, lv_slider_set_range, lv_slider_set_value,
+};
+use lv_bevy_ecs::widgets::{Button, Label, Obj, Slider};
+
+use super::i18n::Text;
+use super::module::{Resources, UiModule};
+use super::screen::Screen;
+use super::timer::Timer;
+use super::{Aligned, back_button, builder, fps_counter};
+
+const MIN_RATE: i32 = 1;
+const MAX_RATE: i32 = 30;
+const DEFAULT_RATE: i32 = 12;
+
+// `FRAME_SIZE` and `FRAMES`, the loading animation drawn by the build script
+include!(concat!(env!("OUT_DIR"), "/loading_frames.rs"));
+
+/// Plays each animation frame for `1 / rate` seconds.
+fn set_rate(player: *mut lv_obj_t, rate: i32) {
+    let duration = FRAMES.len() as u32 * 1000 / rate.clamp(MIN_RATE, MAX_RATE) as u32;
+    unsafe {
+        lv_animimg_set_duration(player, duration);
+        // Takes the new duration only when started again
+        lv_animimg_start(player);
+    }
+}
+
+fn show_rate(label: *mut lv_obj_t, rate: i32) {
+    let text = format!("{}: {rate} fps", Text::FrameRate.get().to_str().unwrap());
+    unsafe { lv_label_set_text(label, CString::new(text).unwrap().as_ptr()) };
+}
+
+/// A loading animation played by an `lv_animimg` from frames embedded in
+/// flash, with a slider for its frame rate and the rate the display
+/// actually refreshes at.
+///
+/// Every new frame redraws only the image area, so the two rates should
+/// match up to the display's own limit.
+pub struct AnimatedImage {
+    _back: (Button, Label),
+    _title: Label,
+    _rate_label: Label,
+    _rate: Slider,
+    _timer: Timer,
+    /// Holds the animimg, so it goes before the frames it plays
+    _player: Obj,
+    /// The animimg keeps a pointer to this array
+    _sources: Box<[*const c_void]>,
+    _frames: Box<[lv_draw_buf_t]>,
+}
+
+impl AnimatedImage {
+    /// Builds the demo on the active screen. The back button loads `home`.
+    pub fn new(home: Screen) -> Self {
+        let back = back_button(home);
+
+        let title = builder::label()
+            .translated(Text::AnimatedImage)
+            .align(Align::TopMid)
+            .offset(0, 12)
+            .build();
+
+        let frames = FRAMES
+            .iter()
+            .map(|data| unsafe {
+                let mut frame: lv_draw_buf_t = core::mem::zeroed();
+                lv_draw_buf_init(
+                    &mut frame,
+                    FRAME_SIZE,
+                    FRAME_SIZE,
+                    LV_COLOR_FORMAT_RGB565A8,
+                    0,
+                    data.as_ptr().cast_mut().cast(),
+                    data.len() as u32,
+                );
+                frame
+            })
+            .collect::<Box<[_]>>();
+        let mut sources = frames
+            .iter()
+            .map(|frame| (frame as *const lv_draw_buf_t).cast::<c_void>())
+            .collect::<Box<[_]>>();
+
+        let mut player = Obj::new();
+        unsafe { lv_obj_remove_style_all(player.raw()) };
+        player.set_size(FRAME_SIZE as i32, FRAME_SIZE as i32);
+        player.align(Align::Center.into(), 0, -10);
+        let animimg = unsafe {
+            let animimg = lv_animimg_create(player.raw());
+            lv_obj_center(animimg);
+            lv_animimg_set_src(animimg, sources.as_mut_ptr(), sources.len());
+            lv_animimg_set_repeat_count(animimg, LV_ANIM_REPEAT_INFINITE);
+            animimg
+        };
+        set_rate(animimg, DEFAULT_RATE);
+
+        let mut rate_label = Label::new();
+        rate_label.align(Align::BottomLeft.into(), 10, -45);
+        let rate_label_raw = rate_label.raw();
+        show_rate(rate_label_raw, DEFAULT_RATE);
+
+        let mut rate = Slider::new();
+        rate.set_width(280);
+        rate.align(Align::BottomMid.into(), 0, -20);
+        let rate_raw = rate.raw();
+        unsafe {
+            lv_slider_set_range(rate_raw, MIN_RATE, MAX_RATE);
+            lv_slider_set_value(rate_raw, DEFAULT_RATE, LV_ANIM_OFF);
+        }
+        rate.add_event_cb(EventCode::ValueChanged, move |_| {
+            let value = unsafe { lv_slider_get_value(rate_raw) };
+            set_rate(animimg, value);
+            show_rate(rate_label_raw, value);
+        });
+
+        let timer = fps_counter();
+
+        Self {
+            _back: back,
+            _title: title,
+            _rate_label: rate_label,
+            _rate: rate,
+            _timer: timer,
+            _player: player,
+            _sources: sources,
+            _frames: frames,
+        }
+    }
+}
+
+impl UiModule for AnimatedImage {
+    const NAME: Text = Text::AnimatedImage;
+
+    fn build(home: Screen, _resources: &mut Resources) -> Self {
+        Self::new(home)
+    }
+}
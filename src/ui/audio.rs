@@ -0,0 +1,145 @@
+use alloc::ffi::CString;
+use alloc::format;
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+
+use lv_bevy_ecs::events::EventCode;
+use lv_bevy_ecs::support::Align;
+use lv_bevy_ecs::sys::{
+    LV_OBJ_FLAG_HIDDEN, lv_arc_get_value, lv_dropdown_get_selected, lv_dropdown_set_options,
+    lv_label_set_text, lv_obj_add_flag, lv_obj_remove_flag,
+};
+use lv_bevy_ecs::widgets::{Arc, Button, Dropdown, Label};
+
+use super::builder;
+use super::i18n::Text;
+use super::module::{Resources, UiModule};
+use super::screen::Screen;
+use super::timer::Timer;
+use super::{back_button, translated_button};
+use crate::audio::{self, Clip};
+use crate::settings::{Key, Settings};
+
+/// Clips built into the firmware, 8 kHz 8 bit mono
+const CLIPS: [(Text, &[u8]); 2] = [
+    (Text::Chime, include_bytes!("../../assets/chime.wav")),
+    (Text::Sweep, include_bytes!("../../assets/sweep.wav")),
+];
+/// Shows the stop button only while something plays
+const POLL_PERIOD_MS: u32 = 100;
+
+/// Plays the built-in clips through [`audio`], with an arc for the volume.
+pub struct Audio {
+    _back: (Button, Label),
+    _title: Label,
+    _clips: Dropdown,
+    _play: (Button, Label),
+    _stop: (Button, Label),
+    _volume: (Arc, Label),
+    _volume_title: Label,
+    _timer: Timer,
+}
+
+impl Audio {
+    /// Builds the player on the active screen. The back button loads `home`.
+    pub fn new(home: Screen, settings: Rc<RefCell<Settings>>) -> Self {
+        let back = back_button(home);
+
+        let title = builder::label()
+            .translated(Text::Audio)
+            .align(Align::TopMid)
+            .offset(0, 12)
+            .build();
+
+        let options = CLIPS
+            .iter()
+            .map(|(name, _)| name.get().to_str().unwrap())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let mut clips = Dropdown::new();
+        clips.set_width(130);
+        clips.align(Align::TopLeft.into(), 10, 55);
+        let clips_raw = clips.raw();
+        unsafe { lv_dropdown_set_options(clips_raw, CString::new(options).unwrap().as_ptr()) };
+
+        let mut play = translated_button(Text::Play);
+        play.0.set_size(130, 40);
+        play.0.align(Align::TopLeft.into(), 10, 110);
+        play.0.add_event_cb(EventCode::Clicked, move |_| {
+            let index = unsafe { lv_dropdown_get_selected(clips_raw) } as usize;
+            let Some((name, wav)) = CLIPS.get(index) else {
+                return;
+            };
+            match Clip::from_wav(wav) {
+                Some(clip) => audio::play(clip),
+                None => defmt::error!("{=str} is not a PCM WAV file", name.get().to_str().unwrap()),
+            }
+        });
+
+        let mut stop = translated_button(Text::Stop);
+        stop.0.set_size(130, 40);
+        stop.0.align(Align::TopLeft.into(), 10, 165);
+        stop.0.add_event_cb(EventCode::Clicked, |_| audio::stop());
+        let stop_raw = stop.0.raw();
+
+        let volume_title = builder::label()
+            .translated(Text::Volume)
+            .align(Align::TopRight)
+            .offset(-50, 55)
+            .build();
+
+        let mut volume = builder::arc()
+            .size(130, 130)
+            .range(0, 100)
+            .value(audio::volume() as i32)
+            .align(Align::TopRight)
+            .offset(-10, 80)
+            .build();
+        let volume_raw = volume.raw();
+        let percent = builder::label().parent(&mut volume).center().build();
+        let percent_raw = percent.raw();
+        let show_volume = move || {
+            let text = CString::new(format!("{}%", audio::volume())).unwrap();
+            unsafe { lv_label_set_text(percent_raw, text.as_ptr()) };
+        };
+        show_volume();
+        volume.add_event_cb(EventCode::ValueChanged, move |_| {
+            audio::set_volume(unsafe { lv_arc_get_value(volume_raw) } as u8);
+            show_volume();
+        });
+        // Written once the finger lifts, not on every step of the drag
+        volume.add_event_cb(EventCode::Released, move |_| {
+            settings
+                .borrow_mut()
+                .set(Key::Volume, audio::volume() as u32);
+        });
+
+        let timer = Timer::new(POLL_PERIOD_MS, move || unsafe {
+            if audio::is_playing() {
+                lv_obj_remove_flag(stop_raw, LV_OBJ_FLAG_HIDDEN);
+            } else {
+                lv_obj_add_flag(stop_raw, LV_OBJ_FLAG_HIDDEN);
+            }
+        });
+
+        Self {
+            _back: back,
+            _title: title,
+            _clips: clips,
+            _play: play,
+            _stop: stop,
+            _volume: (volume, percent),
+            _volume_title: volume_title,
+            _timer: timer,
+        }
+    }
+}
+
+impl UiModule for Audio {
+    const NAME: Text = Text::Audio;
+
+    fn build(home: Screen, resources: &mut Resources) -> Self {
+        Self::new(home, resources.get::<Rc<RefCell<Settings>>>().clone())
+    }
+}
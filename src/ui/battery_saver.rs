@@ -0,0 +1,154 @@
+use alloc::format;
+use alloc::rc::Rc;
+use core::cell::RefCell;
+use core::ffi::CStr;
+
+use lv_bevy_ecs::sys::{lv_display_get_default, lv_display_get_refr_timer, lv_timer_set_period};
+
+use super::i18n::Text;
+use super::timer::Timer;
+use super::toast::{self, Severity};
+use crate::backlight::Backlight;
+use crate::battery::Battery;
+use crate::power;
+use crate::settings::{Key, Settings};
+
+const CHECK_PERIOD_MS: u32 = 10_000;
+/// Same as `LV_DEF_REFR_PERIOD`
+const REFRESH_MS: u32 = 33;
+/// Ten frames a second, still smooth enough for scrolling a list
+const SAVER_REFRESH_MS: u32 = 100;
+/// The backlight goes no brighter than this while saving
+const DIM_PERCENT: u8 = 30;
+/// Charge at which [`Mode::LowBattery`] turns the saver on, and the one it
+/// has to come back to for it to turn off again
+const LOW_PERCENT: u8 = 20;
+const RECOVERED_PERCENT: u8 = 25;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Off = 0,
+    On = 1,
+    /// On below [`LOW_PERCENT`] while running from the cell
+    LowBattery = 2,
+}
+
+impl Mode {
+    pub const ALL: [Mode; 3] = [Mode::Off, Mode::On, Mode::LowBattery];
+
+    pub fn name(self) -> &'static CStr {
+        match self {
+            Mode::Off => Text::Off.get(),
+            Mode::On => Text::On.get(),
+            Mode::LowBattery => Text::LowBattery.get(),
+        }
+    }
+
+    pub fn from_index(index: u32) -> Self {
+        Self::ALL.get(index as usize).copied().unwrap_or(Mode::Off)
+    }
+}
+
+struct State {
+    mode: Mode,
+    battery: Rc<RefCell<Battery>>,
+    backlight: Rc<RefCell<Backlight>>,
+    settings: Rc<RefCell<Settings>>,
+}
+
+impl State {
+    fn update(&mut self) {
+        let on = match self.mode {
+            Mode::Off => false,
+            Mode::On => true,
+            Mode::LowBattery => {
+                let mut battery = self.battery.borrow_mut();
+                let threshold = if power::saver_active() {
+                    RECOVERED_PERCENT
+                } else {
+                    LOW_PERCENT
+                };
+                !battery.external_power() && battery.percent().is_some_and(|p| p < threshold)
+            }
+        };
+        if power::set_saver_active(on) == on {
+            return;
+        }
+
+        let period = if on { SAVER_REFRESH_MS } else { REFRESH_MS };
+        unsafe {
+            let timer = lv_display_get_refr_timer(lv_display_get_default());
+            if !timer.is_null() {
+                lv_timer_set_period(timer, period);
+            }
+        }
+        let brightness = self.settings.borrow().get(Key::Brightness) as u8;
+        let percent = if on {
+            brightness.min(DIM_PERCENT)
+        } else {
+            brightness
+        };
+        self.backlight.borrow_mut().set_percent(percent);
+
+        if self.mode == Mode::LowBattery {
+            let state = if on { Text::On } else { Text::Off };
+            let text = format!(
+                "{}: {}",
+                Text::BatterySaver.get().to_str().unwrap(),
+                state.get().to_str().unwrap()
+            );
+            toast::show(Severity::Info, text);
+        }
+    }
+}
+
+/// Low power profile, switched on by hand or when the cell runs low.
+///
+/// While it is on the display refreshes at ten frames a second, the
+/// backlight is dimmed, the demo's own slide animations and screen
+/// transitions jump straight to their end, and WiFi scans, touch reads and
+/// distance measurements happen less often. Others can follow along with
+/// [`power::saver_active`].
+///
+/// The brightness in [`Settings`] stays as it was and comes back when the
+/// saver turns off. Setting one by hand meanwhile still takes effect.
+pub struct BatterySaver {
+    state: Rc<RefCell<State>>,
+    _timer: Timer,
+}
+
+impl BatterySaver {
+    pub fn new(
+        mode: Mode,
+        battery: Rc<RefCell<Battery>>,
+        backlight: Rc<RefCell<Backlight>>,
+        settings: Rc<RefCell<Settings>>,
+    ) -> Self {
+        let state = Rc::new(RefCell::new(State {
+            mode,
+            battery,
+            backlight,
+            settings,
+        }));
+        state.borrow_mut().update();
+        let timer = Timer::new(CHECK_PERIOD_MS, {
+            let state = state.clone();
+            move || state.borrow_mut().update()
+        });
+
+        Self {
+            state,
+            _timer: timer,
+        }
+    }
+
+    pub fn mode(&self) -> Mode {
+        self.state.borrow().mode
+    }
+
+    pub fn set_mode(&self, mode: Mode) {
+        let mut state = self.state.borrow_mut();
+        state.mode = mode;
+        state.update();
+    }
+}
@@ -0,0 +1,186 @@
+use core::ffi::CStr;
+
+use lv_bevy_ecs::support::{Align, LabelLongMode};
+use lv_bevy_ecs::sys::{
+    LV_OPA_COVER, lv_color_hex, lv_obj_set_style_bg_color, lv_obj_set_style_bg_opa,
+    lv_obj_set_style_text_color,
+};
+use lv_bevy_ecs::widgets::{Arc, Bar, Button, Label, Obj, Slider, Switch};
+
+use super::fonts::Font;
+use super::i18n::{Text, translate};
+use super::set_visible;
+
+/// Widgets a [`Builder`] can set up, all of which are an [`Obj`] underneath.
+pub trait Widget {
+    fn obj(&mut self) -> &mut Obj;
+}
+
+impl Widget for Obj {
+    fn obj(&mut self) -> &mut Obj {
+        self
+    }
+}
+
+macro_rules! widget {
+    ($($widget:ty),*) => {
+        $(impl Widget for $widget {
+            fn obj(&mut self) -> &mut Obj {
+                self
+            }
+        })*
+    };
+}
+
+widget!(Arc, Bar, Button, Label, Slider, Switch);
+
+/// Sets up a widget in one chained expression, for screens that would
+/// otherwise repeat its name on every line:
+///
+/// ```ignore
+/// let title = builder::label()
+///     .text(c"Hello")
+///     .font(Font::LARGEST)
+///     .align(Align::TopMid)
+///     .offset(0, 12)
+///     .build();
+/// ```
+///
+/// The widget is created on the active screen like any other, and
+/// [`Builder::build`] hands it back to be kept for as long as it should show.
+#[must_use = "the widget is deleted when the builder is dropped"]
+pub struct Builder<W> {
+    widget: W,
+}
+
+pub fn obj() -> Builder<Obj> {
+    Builder::new(Obj::new())
+}
+
+pub fn label() -> Builder<Label> {
+    Builder::new(Label::new())
+}
+
+pub fn button() -> Builder<Button> {
+    Builder::new(Button::new())
+}
+
+pub fn arc() -> Builder<Arc> {
+    Builder::new(Arc::new())
+}
+
+impl<W: Widget> Builder<W> {
+    pub fn new(widget: W) -> Self {
+        Self { widget }
+    }
+
+    pub fn parent(mut self, parent: &mut Obj) -> Self {
+        self.widget.obj().set_parent(parent);
+        self
+    }
+
+    pub fn size(mut self, width: i32, height: i32) -> Self {
+        self.widget.obj().set_size(width, height);
+        self
+    }
+
+    pub fn width(mut self, width: i32) -> Self {
+        self.widget.obj().set_width(width);
+        self
+    }
+
+    /// Where the widget sits in its parent. Any [`Builder::offset`] is from
+    /// there.
+    pub fn align(mut self, align: Align) -> Self {
+        self.widget.obj().set_align(align.into());
+        self
+    }
+
+    pub fn offset(mut self, x: i32, y: i32) -> Self {
+        self.widget.obj().set_pos(x, y);
+        self
+    }
+
+    pub fn center(mut self) -> Self {
+        self.widget.obj().center();
+        self
+    }
+
+    /// For the widget and its children.
+    pub fn font(mut self, font: Font) -> Self {
+        font.apply(self.widget.obj().raw());
+        self
+    }
+
+    pub fn text_color(mut self, color: u32) -> Self {
+        unsafe { lv_obj_set_style_text_color(self.widget.obj().raw(), lv_color_hex(color), 0) };
+        self
+    }
+
+    /// An opaque background in `color`.
+    pub fn background(mut self, color: u32) -> Self {
+        let raw = self.widget.obj().raw();
+        unsafe {
+            lv_obj_set_style_bg_color(raw, lv_color_hex(color), 0);
+            lv_obj_set_style_bg_opa(raw, LV_OPA_COVER as _, 0);
+        }
+        self
+    }
+
+    pub fn visible(mut self, visible: bool) -> Self {
+        set_visible(self.widget.obj(), visible);
+        self
+    }
+
+    pub fn build(self) -> W {
+        self.widget
+    }
+}
+
+impl Builder<Label> {
+    pub fn text(mut self, text: &CStr) -> Self {
+        self.widget.set_text(text);
+        self
+    }
+
+    /// Like [`Builder::text`] without a copy, for string literals.
+    pub fn text_static(mut self, text: &'static CStr) -> Self {
+        self.widget.set_text_static(text);
+        self
+    }
+
+    /// Kept in the current language, see [`translate`].
+    pub fn translated(mut self, text: Text) -> Self {
+        translate(&mut self.widget, text);
+        self
+    }
+
+    pub fn long_mode(mut self, mode: LabelLongMode) -> Self {
+        self.widget.set_long_mode(mode.into());
+        self
+    }
+}
+
+impl Builder<Arc> {
+    pub fn range(mut self, min: i32, max: i32) -> Self {
+        self.widget.set_range(min as _, max as _);
+        self
+    }
+
+    pub fn value(mut self, value: i32) -> Self {
+        self.widget.set_value(value);
+        self
+    }
+
+    /// Of the start of the background arc, clockwise from 3 o'clock.
+    pub fn rotation(mut self, degrees: i32) -> Self {
+        self.widget.set_rotation(degrees as _);
+        self
+    }
+
+    /// Span of the background arc, relative to the rotation.
+    pub fn background_angles(mut self, start: u32, end: u32) -> Self {
+        self.widget.set_bg_angles(start as _, end as _);
+        self
+    }
+}
@@ -0,0 +1,216 @@
+use alloc::ffi::CString;
+use alloc::format;
+use alloc::string::{String, ToString};
+use core::ffi::CStr;
+
+use lv_bevy_ecs::events::EventCode;
+use lv_bevy_ecs::support::Align;
+use lv_bevy_ecs::sys::{
+    LV_BUTTONMATRIX_BUTTON_NONE, lv_buttonmatrix_get_button_text,
+    lv_buttonmatrix_get_selected_button, lv_buttonmatrix_set_button_width, lv_buttonmatrix_set_map,
+};
+use lv_bevy_ecs::widgets::{Button, ButtonMatrix, Label};
+
+use super::i18n::Text;
+use super::module::{Resources, UiModule};
+use super::screen::Screen;
+use super::{ButtonMap, back_button};
+
+/// Keeps the right aligned expression clear of the back button
+const MAX_EXPRESSION_LEN: usize = 24;
+
+static BUTTON_MAP: ButtonMap<23> = ButtonMap([
+    c"C".as_ptr(),
+    c"DEL".as_ptr(),
+    c"/".as_ptr(),
+    c"*".as_ptr(),
+    c"\n".as_ptr(),
+    c"7".as_ptr(),
+    c"8".as_ptr(),
+    c"9".as_ptr(),
+    c"-".as_ptr(),
+    c"\n".as_ptr(),
+    c"4".as_ptr(),
+    c"5".as_ptr(),
+    c"6".as_ptr(),
+    c"+".as_ptr(),
+    c"\n".as_ptr(),
+    c"1".as_ptr(),
+    c"2".as_ptr(),
+    c"3".as_ptr(),
+    c"=".as_ptr(),
+    c"\n".as_ptr(),
+    c"0".as_ptr(),
+    c".".as_ptr(),
+    c"".as_ptr(),
+]);
+
+/// Index of the "0" button, not counting the line breaks
+const ZERO_BUTTON_ID: u32 = 16;
+
+/// Four function calculator built from a button matrix.
+pub struct Calculator {
+    _back: (Button, Label),
+    _matrix: ButtonMatrix,
+}
+
+impl Calculator {
+    /// Builds the calculator on the active screen. The back button loads `home`.
+    pub fn new(home: Screen) -> Self {
+        let back = back_button(home);
+
+        let mut expression_label = Label::new();
+        expression_label.set_text_static(c"0");
+        expression_label.align(Align::TopRight.into(), -10, 12);
+
+        let mut matrix = ButtonMatrix::new();
+        matrix.set_size(310, 180);
+        matrix.align(Align::BottomMid.into(), 0, -5);
+        let raw = matrix.raw();
+        unsafe {
+            lv_buttonmatrix_set_map(raw, BUTTON_MAP.0.as_ptr());
+            lv_buttonmatrix_set_button_width(raw, ZERO_BUTTON_ID, 3);
+        }
+
+        let mut expression = String::new();
+        matrix.add_event_cb(EventCode::ValueChanged, move |_| {
+            let text = unsafe {
+                let id = lv_buttonmatrix_get_selected_button(raw);
+                if id == LV_BUTTONMATRIX_BUTTON_NONE {
+                    return;
+                }
+                CStr::from_ptr(lv_buttonmatrix_get_button_text(raw, id))
+            };
+            let Ok(key) = text.to_str() else {
+                return;
+            };
+
+            press(&mut expression, key);
+
+            let shown = if expression.is_empty() {
+                "0"
+            } else {
+                &expression
+            };
+            expression_label.set_text(CString::new(shown).unwrap().as_c_str());
+        });
+
+        Self {
+            _back: back,
+            _matrix: matrix,
+        }
+    }
+}
+
+impl UiModule for Calculator {
+    const NAME: Text = Text::Calculator;
+
+    fn build(home: Screen, _resources: &mut Resources) -> Self {
+        Self::new(home)
+    }
+}
+
+fn press(expression: &mut String, key: &str) {
+    match key {
+        "C" => expression.clear(),
+        "DEL" => {
+            expression.pop();
+        }
+        "=" => {
+            *expression = match evaluate(expression) {
+                Some(value) => format_number(value),
+                None => "Error".to_string(),
+            };
+        }
+        _ => {
+            // Start over when typing after an error
+            if expression == "Error" {
+                expression.clear();
+            }
+            if expression.len() < MAX_EXPRESSION_LEN {
+                expression.push_str(key);
+            }
+        }
+    }
+}
+
+/// Evaluates `+ - * /` with the usual precedence and unary minus.
+fn evaluate(expression: &str) -> Option<f64> {
+    let mut parser = Parser {
+        input: expression.as_bytes(),
+        position: 0,
+    };
+    let value = parser.expression()?;
+    if parser.position != parser.input.len() || !value.is_finite() {
+        return None;
+    }
+    Some(value)
+}
+
+struct Parser<'a> {
+    input: &'a [u8],
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.input.get(self.position).copied()
+    }
+
+    fn expression(&mut self) -> Option<f64> {
+        let mut value = self.term()?;
+        while let Some(operator @ (b'+' | b'-')) = self.peek() {
+            self.position += 1;
+            let rhs = self.term()?;
+            value = if operator == b'+' {
+                value + rhs
+            } else {
+                value - rhs
+            };
+        }
+        Some(value)
+    }
+
+    fn term(&mut self) -> Option<f64> {
+        let mut value = self.factor()?;
+        while let Some(operator @ (b'*' | b'/')) = self.peek() {
+            self.position += 1;
+            let rhs = self.factor()?;
+            if operator == b'*' {
+                value *= rhs;
+            } else if rhs == 0.0 {
+                return None;
+            } else {
+                value /= rhs;
+            }
+        }
+        Some(value)
+    }
+
+    fn factor(&mut self) -> Option<f64> {
+        if self.peek() == Some(b'-') {
+            self.position += 1;
+            return self.factor().map(|value| -value);
+        }
+        self.number()
+    }
+
+    fn number(&mut self) -> Option<f64> {
+        let start = self.position;
+        while let Some(b'0'..=b'9' | b'.') = self.peek() {
+            self.position += 1;
+        }
+        let digits = core::str::from_utf8(&self.input[start..self.position]).ok()?;
+        digits.parse().ok()
+    }
+}
+
+/// Prints whole numbers without a fraction, everything else with at most 6 decimals.
+pub(super) fn format_number(value: f64) -> String {
+    let in_range = value >= i64::MIN as f64 && value <= i64::MAX as f64;
+    if in_range && (value as i64) as f64 == value {
+        return format!("{}", value as i64);
+    }
+    let text = format!("{:.6}", value);
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
@@ -0,0 +1,223 @@
+use alloc::ffi::CString;
+use alloc::format;
+use alloc::vec::Vec;
+
+use embassy_time::Duration;
+use lv_bevy_ecs::support::Align;
+use lv_bevy_ecs::sys::{
+    LV_ANIM_OFF, LV_FLEX_FLOW_ROW_WRAP, LV_OBJ_FLAG_CLICKABLE, LV_OPA_50, LV_OPA_COVER,
+    LV_PART_KNOB, lv_bar_set_range, lv_bar_set_value, lv_led_off, lv_led_on, lv_obj_remove_flag,
+    lv_obj_remove_style, lv_obj_set_flex_flow, lv_obj_set_style_opa, lv_obj_set_style_pad_all,
+    lv_obj_set_style_pad_gap,
+};
+use lv_bevy_ecs::widgets::{Arc, Bar, Button, Label, Led, Obj};
+
+use super::i18n::Text;
+use super::module::{Resources, UiModule};
+use super::screen::Screen;
+use super::timer::Timer;
+use super::{back_button, builder, json_error};
+use crate::assets::Assets;
+use crate::can::{self, Signal, Widget};
+
+const POLL_PERIOD_MS: u32 = 100;
+/// Signals without a frame for this long are shown dimmed
+const STALE: Duration = Duration::from_secs(1);
+/// Read from the assets partition when it is there, so the signals can be
+/// changed by flashing the archive again
+const FILE: &str = "can/signals.json";
+/// Used when the assets partition has no signal table
+const BUILT_IN: &str = include_str!("../../assets/can/signals.json");
+
+enum Indicator {
+    Gauge(Arc),
+    Bar(Bar),
+    Led(Led),
+}
+
+struct Card {
+    _name: Label,
+    value: Label,
+    indicator: Indicator,
+    /// Holds the widgets above, so it goes after them
+    card: Obj,
+    signal: Signal,
+    /// Value and freshness on screen, so unchanged cards are not redrawn
+    shown: Option<(Option<i32>, bool)>,
+}
+
+/// Frames from the CAN bus, decoded into gauges, bars and fault LEDs by a
+/// signal table in JSON, see [`can::signals`].
+///
+/// A minimal vehicle or machine display: the table picks which IDs and bits
+/// matter, and cards without a recent frame are dimmed.
+pub struct CanDashboard {
+    _back: (Button, Label),
+    _title: Label,
+    _error: Option<Label>,
+    /// Owns the cards, which are inside the grid
+    _timer: Timer,
+    _grid: Obj,
+}
+
+impl CanDashboard {
+    /// Builds the dashboard on the active screen. The back button loads `home`.
+    pub fn new(home: Screen, assets: Assets) -> Self {
+        let back = back_button(home);
+
+        let title = builder::label()
+            .translated(Text::Can)
+            .align(Align::TopMid)
+            .offset(0, 12)
+            .build();
+
+        let mut counters = Label::new();
+        counters.align(Align::TopRight.into(), -10, 12);
+
+        let mut grid = Obj::new();
+        grid.set_size(320, 200);
+        grid.align(Align::BottomMid.into(), 0, 0);
+        unsafe {
+            lv_obj_set_flex_flow(grid.raw(), LV_FLEX_FLOW_ROW_WRAP);
+            lv_obj_set_style_pad_all(grid.raw(), 6, 0);
+            lv_obj_set_style_pad_gap(grid.raw(), 6, 0);
+        }
+
+        let (signals, error) = match can::signals(&assets.read_text_or(FILE, BUILT_IN)) {
+            Ok(signals) => (signals, None),
+            Err(error) => {
+                defmt::error!(
+                    "Could not load the CAN signals, byte {}: {=str}",
+                    error.offset,
+                    error.message
+                );
+                (Vec::new(), Some(json_error(FILE, &error)))
+            }
+        };
+
+        let mut cards: Vec<Card> = signals
+            .into_iter()
+            .map(|signal| card(&mut grid, signal))
+            .collect();
+
+        let timer = Timer::new(POLL_PERIOD_MS, move || {
+            let (received, errors, dropped) = can::counters();
+            let text = if dropped > 0 {
+                format!("{} frames, {} bad, {} dropped", received, errors, dropped)
+            } else {
+                format!("{} frames, {} bad", received, errors)
+            };
+            counters.set_text(CString::new(text).unwrap().as_c_str());
+            for card in &mut cards {
+                show(card);
+            }
+        });
+
+        Self {
+            _back: back,
+            _title: title,
+            _error: error,
+            _timer: timer,
+            _grid: grid,
+        }
+    }
+}
+
+impl UiModule for CanDashboard {
+    const NAME: Text = Text::Can;
+
+    fn build(home: Screen, resources: &mut Resources) -> Self {
+        Self::new(home, *resources.get::<Assets>())
+    }
+}
+
+fn card(grid: &mut Obj, signal: Signal) -> Card {
+    let mut card = Obj::new();
+    card.set_parent(grid);
+    card.set_size(151, 90);
+
+    let mut name = Label::new();
+    name.set_parent(&mut card);
+    name.set_text(CString::new(signal.name.as_str()).unwrap().as_c_str());
+    name.align(Align::TopLeft.into(), 0, 0);
+
+    let mut value = Label::new();
+    value.set_parent(&mut card);
+    value.set_text_static(c"--");
+
+    let indicator = match signal.widget {
+        Widget::Gauge => {
+            let mut arc = Arc::new();
+            arc.set_parent(&mut card);
+            arc.set_size(50, 50);
+            arc.set_rotation(135);
+            arc.set_bg_angles(0, 270);
+            arc.set_range(signal.min as _, signal.max as _);
+            arc.set_value(signal.min);
+            arc.align(Align::BottomLeft.into(), 0, 0);
+            unsafe {
+                lv_obj_remove_style(arc.raw(), core::ptr::null_mut(), LV_PART_KNOB);
+                lv_obj_remove_flag(arc.raw(), LV_OBJ_FLAG_CLICKABLE);
+            }
+            value.align(Align::BottomRight.into(), 0, -10);
+            Indicator::Gauge(arc)
+        }
+        Widget::Bar => {
+            let mut bar = Bar::new();
+            bar.set_parent(&mut card);
+            bar.set_size(110, 10);
+            bar.align(Align::BottomMid.into(), 0, -2);
+            unsafe { lv_bar_set_range(bar.raw(), signal.min, signal.max) };
+            value.align(Align::LeftMid.into(), 0, 4);
+            Indicator::Bar(bar)
+        }
+        Widget::Led => {
+            let mut led = Led::new();
+            led.set_parent(&mut card);
+            led.set_size(24, 24);
+            led.align(Align::BottomLeft.into(), 4, -4);
+            unsafe { lv_led_off(led.raw()) };
+            value.align(Align::BottomRight.into(), 0, -6);
+            Indicator::Led(led)
+        }
+    };
+
+    Card {
+        _name: name,
+        value,
+        indicator,
+        card,
+        signal,
+        shown: None,
+    }
+}
+
+fn show(card: &mut Card) {
+    let frame = can::latest(card.signal.id);
+    let value = frame.and_then(|frame| card.signal.decode(frame.data()));
+    let fresh = frame.is_some_and(|frame| frame.received.elapsed() < STALE);
+    if card.shown == Some((value, fresh)) {
+        return;
+    }
+    card.shown = Some((value, fresh));
+
+    let text = match (value, card.signal.widget) {
+        (None, _) => CString::from(c"--"),
+        (Some(0), Widget::Led) => CString::from(Text::Ok.get()),
+        (Some(_), Widget::Led) => CString::from(Text::Fault.get()),
+        (Some(value), _) => CString::new(format!("{} {}", value, card.signal.unit)).unwrap(),
+    };
+    card.value.set_text(text.as_c_str());
+    let (min, max) = (card.signal.min, card.signal.max);
+    let level = value.unwrap_or(min).clamp(min, max);
+    unsafe {
+        match &mut card.indicator {
+            Indicator::Gauge(arc) => arc.set_value(level),
+            Indicator::Bar(bar) => lv_bar_set_value(bar.raw(), level, LV_ANIM_OFF),
+            Indicator::Led(led) if value.unwrap_or(0) != 0 => lv_led_on(led.raw()),
+            Indicator::Led(led) => lv_led_off(led.raw()),
+        }
+        let opacity = if fresh { LV_OPA_COVER } else { LV_OPA_50 };
+        lv_obj_set_style_opa(card.card.raw(), opacity as _, 0);
+    }
+}
@@ -0,0 +1,82 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use lv_bevy_ecs::sys::{LV_COLOR_FORMAT_RGB565, lv_canvas_set_buffer, lv_obj_invalidate, lv_obj_t};
+use lv_bevy_ecs::widgets::Canvas;
+
+/// Canvas backed by an RGB565 buffer that is drawn into directly from Rust.
+///
+/// Call [`PixelCanvas::invalidate`] after drawing to get the changes on screen.
+pub struct PixelCanvas {
+    canvas: Canvas,
+    /// Never reallocated, LVGL keeps a pointer to it
+    pixels: Vec<u16>,
+    width: i32,
+    height: i32,
+}
+
+impl PixelCanvas {
+    /// Creates the canvas on the active screen.
+    pub fn new(width: i32, height: i32, background: u16) -> Self {
+        let mut pixels = vec![background; (width * height) as usize];
+        let canvas = Canvas::new();
+        unsafe {
+            lv_canvas_set_buffer(
+                canvas.raw(),
+                pixels.as_mut_ptr().cast(),
+                width,
+                height,
+                LV_COLOR_FORMAT_RGB565,
+            );
+        }
+        Self {
+            canvas,
+            pixels,
+            width,
+            height,
+        }
+    }
+
+    pub fn canvas(&mut self) -> &mut Canvas {
+        &mut self.canvas
+    }
+
+    pub fn raw(&self) -> *mut lv_obj_t {
+        self.canvas.raw()
+    }
+
+    pub fn fill(&mut self, color: u16) {
+        self.pixels.fill(color);
+    }
+
+    pub fn fill_rect(&mut self, x: i32, y: i32, width: i32, height: i32, color: u16) {
+        for py in y.max(0)..(y + height).min(self.height) {
+            for px in x.max(0)..(x + width).min(self.width) {
+                self.pixels[(py * self.width + px) as usize] = color;
+            }
+        }
+    }
+
+    pub fn fill_circle(&mut self, cx: i32, cy: i32, radius: i32, color: u16) {
+        for y in (cy - radius).max(0)..=(cy + radius).min(self.height - 1) {
+            for x in (cx - radius).max(0)..=(cx + radius).min(self.width - 1) {
+                let (dx, dy) = (x - cx, y - cy);
+                if dx * dx + dy * dy <= radius * radius {
+                    self.pixels[(y * self.width + x) as usize] = color;
+                }
+            }
+        }
+    }
+
+    pub fn invalidate(&self) {
+        unsafe { lv_obj_invalidate(self.canvas.raw()) }
+    }
+}
+
+/// Converts a `0xRRGGBB` color to the canvas pixel format.
+pub const fn rgb565(color: u32) -> u16 {
+    let r = ((color >> 16) & 0xFF) as u16;
+    let g = ((color >> 8) & 0xFF) as u16;
+    let b = (color & 0xFF) as u16;
+    ((r >> 3) << 11) | ((g >> 2) << 5) | (b >> 3)
+}
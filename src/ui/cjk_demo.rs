@@ -0,0 +1,73 @@
+use alloc::ffi::CString;
+use alloc::format;
+use alloc::vec::Vec;
+
+use lv_bevy_ecs::support::Align;
+use lv_bevy_ecs::widgets::{Button, Label};
+
+use super::fonts::Font;
+use super::i18n::Text;
+use super::module::{Resources, UiModule};
+use super::screen::Screen;
+use super::{back_button, builder};
+
+/// The compiled subset is Simplified Chinese only, so there are no kana and
+/// no Japanese-only kanji here. Use a TrueType font for those.
+const SAMPLES: [&str; 4] = [
+    "你好, 世界!",
+    "中文显示测试",
+    "汉字 UTF-8 多字节",
+    "春眠不觉晓, 处处闻啼鸟",
+];
+
+/// Chinese sample strings with their character and byte counts, to check that
+/// multi-byte UTF-8 and wide glyphs make it through the bindings.
+pub struct CjkDemo {
+    _back: (Button, Label),
+    _title: Label,
+    _samples: Vec<(Label, Label)>,
+}
+
+impl CjkDemo {
+    /// Builds the demo on the active screen. The back button loads `home`.
+    pub fn new(home: Screen) -> Self {
+        let back = back_button(home);
+
+        let title = builder::label()
+            .translated(Text::Cjk)
+            .align(Align::TopMid)
+            .offset(0, 12)
+            .build();
+
+        let mut samples = Vec::new();
+        for (index, sample) in SAMPLES.into_iter().enumerate() {
+            let y = 45 + index as i32 * 40;
+
+            let mut text = Label::new();
+            text.set_text(CString::new(sample).unwrap().as_c_str());
+            Font::SourceHanSans16Cjk.apply(text.raw());
+            text.align(Align::TopLeft.into(), 10, y);
+
+            let mut counts = Label::new();
+            let info = format!("{} chars, {} bytes", sample.chars().count(), sample.len());
+            counts.set_text(CString::new(info).unwrap().as_c_str());
+            counts.align(Align::TopLeft.into(), 10, y + 19);
+
+            samples.push((text, counts));
+        }
+
+        Self {
+            _back: back,
+            _title: title,
+            _samples: samples,
+        }
+    }
+}
+
+impl UiModule for CjkDemo {
+    const NAME: Text = Text::Cjk;
+
+    fn build(home: Screen, _resources: &mut Resources) -> Self {
+        Self::new(home)
+    }
+}
@@ -0,0 +1,135 @@
+use alloc::collections::VecDeque;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+
+use embassy_sync::blocking_mutex::Mutex;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_time::Instant;
+use lv_bevy_ecs::sys::lv_display_get_inactive_time;
+
+use crate::{cpu_frequency, wifi};
+
+/// Commands not applied yet are dropped beyond this
+const MAX_PENDING: usize = 8;
+
+static PENDING: Mutex<CriticalSectionRawMutex, RefCell<VecDeque<Command>>> =
+    Mutex::new(RefCell::new(VecDeque::new()));
+
+/// Values `set` can change at runtime
+#[derive(Clone, Copy, defmt::Format)]
+pub enum Parameter {
+    /// Backlight in percent
+    Brightness,
+    /// 0 or 1
+    Mute,
+    /// Audio volume in percent
+    Volume,
+    /// Index into `Language::ALL`
+    Language,
+    /// Index into `timezone::ZONES`
+    Timezone,
+}
+
+impl Parameter {
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "brightness" => Parameter::Brightness,
+            "mute" => Parameter::Mute,
+            "volume" => Parameter::Volume,
+            "language" => Parameter::Language,
+            "timezone" => Parameter::Timezone,
+            _ => return None,
+        })
+    }
+}
+
+/// A line typed on the serial console, see [`submit`].
+pub enum Command {
+    Set(Parameter, u32),
+    /// Opens the module with this name in any language, or `launcher`
+    Goto(String),
+    /// Value of the demo arc on the launcher
+    Arc(i32),
+    Stats,
+    Help,
+}
+
+fn parse(line: &str) -> Option<Command> {
+    let mut words = line.split_whitespace();
+    let command = match words.next()? {
+        "set" => {
+            let parameter = Parameter::parse(words.next()?)?;
+            Command::Set(parameter, words.next()?.parse().ok()?)
+        }
+        "goto" => {
+            let name = words.collect::<Vec<_>>().join(" ");
+            if name.is_empty() {
+                return None;
+            }
+            return Some(Command::Goto(name));
+        }
+        "arc" => Command::Arc(words.next()?.parse().ok()?),
+        "stats" => Command::Stats,
+        "help" => Command::Help,
+        _ => return None,
+    };
+    // Trailing words are a typo rather than something to ignore
+    words.next().is_none().then_some(command)
+}
+
+/// Queues `line` if it is a console command and returns whether it was.
+///
+/// Commands are applied by the UI loop through [`take`], where every part of
+/// the UI can be reached safely:
+///
+/// ```text
+/// set brightness 50
+/// set mute 1
+/// set volume 40
+/// set language 1
+/// set timezone 2
+/// goto settings
+/// goto launcher
+/// arc 42
+/// stats
+/// help
+/// ```
+pub fn submit(line: &str) -> bool {
+    let Some(command) = parse(line) else {
+        return false;
+    };
+    PENDING.lock(|pending| {
+        let mut pending = pending.borrow_mut();
+        if pending.len() < MAX_PENDING {
+            pending.push_back(command);
+        } else {
+            defmt::warn!("cli: busy, dropped {=str}", line);
+        }
+    });
+    true
+}
+
+/// Next command to apply, oldest first
+pub fn take() -> Option<Command> {
+    PENDING.lock(|pending| pending.borrow_mut().pop_front())
+}
+
+/// Logs heap use, uptime and what else helps tuning.
+pub fn print_stats() {
+    defmt::info!("cli: {}", esp_alloc::HEAP.stats());
+    defmt::info!(
+        "cli: up {} s, CPU {} MHz, idle {} ms, RSSI {}",
+        Instant::now().as_secs(),
+        cpu_frequency::current_mhz(),
+        unsafe { lv_display_get_inactive_time(core::ptr::null_mut()) },
+        wifi::signal_strength()
+    );
+}
+
+/// Logs the commands [`submit`] takes.
+pub fn print_help() {
+    defmt::info!(
+        "cli: set brightness|mute|volume|language|timezone <value>, goto <module>|launcher, arc <value>, stats, help"
+    );
+}
@@ -0,0 +1,123 @@
+use alloc::ffi::CString;
+use alloc::format;
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::ffi::CStr;
+
+use lv_bevy_ecs::events::EventCode;
+use lv_bevy_ecs::support::Align;
+use lv_bevy_ecs::sys::{
+    LV_KEYBOARD_MODE_NUMBER, lv_dropdown_get_selected, lv_dropdown_set_options,
+    lv_keyboard_set_mode, lv_keyboard_set_textarea, lv_textarea_get_text,
+    lv_textarea_set_accepted_chars, lv_textarea_set_one_line, lv_textarea_set_text,
+};
+use lv_bevy_ecs::widgets::{Button, Dropdown, Keyboard, Label, Textarea};
+
+use super::calculator::format_number;
+use super::i18n::Text;
+use super::module::{Resources, UiModule};
+use super::screen::Screen;
+use super::{back_button, builder};
+
+/// Unit pair shown in the dropdown and how to convert between them
+const CONVERSIONS: [(&str, fn(f64) -> f64); 8] = [
+    ("°C to °F", |c| c * 9.0 / 5.0 + 32.0),
+    ("°F to °C", |f| (f - 32.0) * 5.0 / 9.0),
+    ("km to mi", |km| km / 1.609344),
+    ("mi to km", |mi| mi * 1.609344),
+    ("m to ft", |m| m / 0.3048),
+    ("ft to m", |ft| ft * 0.3048),
+    ("kg to lb", |kg| kg / 0.45359237),
+    ("lb to kg", |lb| lb * 0.45359237),
+];
+
+/// Temperature, length and weight converter.
+///
+/// The result follows every keystroke and every change of the unit pair.
+pub struct Converter {
+    _back: (Button, Label),
+    _title: Label,
+    _units: Dropdown,
+    _input: Textarea,
+    _keyboard: Keyboard,
+}
+
+impl Converter {
+    /// Builds the converter on the active screen. The back button loads `home`.
+    pub fn new(home: Screen) -> Self {
+        let back = back_button(home);
+
+        let title = builder::label()
+            .translated(Text::Converter)
+            .align(Align::TopMid)
+            .offset(0, 12)
+            .build();
+
+        let options = CONVERSIONS
+            .iter()
+            .map(|(name, _)| *name)
+            .collect::<Vec<_>>()
+            .join("\n");
+        let mut units = Dropdown::new();
+        units.set_width(110);
+        units.align(Align::TopRight.into(), -5, 5);
+        unsafe { lv_dropdown_set_options(units.raw(), CString::new(options).unwrap().as_ptr()) };
+
+        let mut input = Textarea::new();
+        input.set_width(140);
+        input.align(Align::TopLeft.into(), 5, 45);
+        unsafe {
+            lv_textarea_set_one_line(input.raw(), true);
+            lv_textarea_set_accepted_chars(input.raw(), c"0123456789.-".as_ptr());
+            lv_textarea_set_text(input.raw(), c"0".as_ptr());
+        }
+
+        let mut result = Label::new();
+        result.align(Align::TopLeft.into(), 160, 57);
+        let result = Rc::new(RefCell::new(result));
+
+        let (units_raw, input_raw) = (units.raw(), input.raw());
+        let update = move || {
+            let (_, convert) = CONVERSIONS[unsafe { lv_dropdown_get_selected(units_raw) } as usize];
+            let text = unsafe { CStr::from_ptr(lv_textarea_get_text(input_raw)) };
+            let text = match text.to_str().ok().and_then(|text| text.parse::<f64>().ok()) {
+                Some(value) => format!("= {}", format_number(convert(value))),
+                None => "= ?".into(),
+            };
+            result
+                .borrow_mut()
+                .set_text(CString::new(text).unwrap().as_c_str());
+        };
+        update();
+        units.add_event_cb(EventCode::ValueChanged, {
+            let update = update.clone();
+            move |_| update()
+        });
+        input.add_event_cb(EventCode::ValueChanged, move |_| update());
+
+        let mut keyboard = Keyboard::new();
+        keyboard.set_size(320, 130);
+        keyboard.align(Align::BottomMid.into(), 0, 0);
+        unsafe {
+            lv_keyboard_set_mode(keyboard.raw(), LV_KEYBOARD_MODE_NUMBER);
+            lv_keyboard_set_textarea(keyboard.raw(), input_raw);
+        }
+
+        Self {
+            _back: back,
+            _title: title,
+            _units: units,
+            _input: input,
+            _keyboard: keyboard,
+        }
+    }
+}
+
+impl UiModule for Converter {
+    const NAME: Text = Text::Converter;
+
+    fn build(home: Screen, _resources: &mut Resources) -> Self {
+        Self::new(home)
+    }
+}
@@ -0,0 +1,262 @@
+use alloc::boxed::Box;
+use alloc::ffi::CString;
+use alloc::format;
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+
+use embassy_time::Instant;
+use lv_bevy_ecs::events::EventCode;
+use lv_bevy_ecs::support::Align;
+use lv_bevy_ecs::sys::{
+    LV_ANIM_OFF, LV_FLEX_FLOW_ROW_WRAP, LV_STATE_CHECKED, lv_obj_add_state, lv_obj_has_state,
+    lv_obj_remove_state, lv_obj_set_flex_flow, lv_slider_get_value, lv_slider_set_value,
+};
+use lv_bevy_ecs::widgets::{Button, Label, Obj, Slider, Switch};
+
+use super::i18n::{Text, translate};
+use super::module::{Resources, UiModule};
+use super::screen::Screen;
+use super::timer::Timer;
+use super::{back_button, builder};
+use crate::rooms::{self, Command};
+use crate::smart_light;
+
+const POLL_PERIOD_MS: u32 = 2000;
+
+/// State of a single room as shown on its card.
+#[derive(Clone, Copy)]
+pub struct RoomState {
+    /// In tenths of a degree Celsius
+    pub temperature: i32,
+    pub light: bool,
+    /// 0 is open, 100 is closed
+    pub blinds: i32,
+}
+
+/// Where room states come from and where user changes go.
+pub trait Backend {
+    /// Updates `rooms` with the latest known values.
+    fn poll(&mut self, rooms: &mut [RoomState]);
+    fn set_light(&mut self, room: usize, on: bool);
+    fn set_blinds(&mut self, room: usize, percent: i32);
+}
+
+/// Backend without devices, temperatures drift randomly.
+pub struct Simulated {
+    rng: u32,
+}
+
+impl Simulated {
+    pub fn new() -> Self {
+        Self {
+            rng: Instant::now().as_ticks() as u32 | 1,
+        }
+    }
+}
+
+impl Default for Simulated {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Backend for Simulated {
+    fn poll(&mut self, rooms: &mut [RoomState]) {
+        for room in rooms {
+            self.rng ^= self.rng << 13;
+            self.rng ^= self.rng >> 17;
+            self.rng ^= self.rng << 5;
+            let drift = (self.rng % 5) as i32 - 2;
+            room.temperature = (room.temperature + drift).clamp(150, 280);
+        }
+    }
+
+    fn set_light(&mut self, room: usize, on: bool) {
+        defmt::info!("Room {} light {}", room, on);
+    }
+
+    fn set_blinds(&mut self, room: usize, percent: i32) {
+        defmt::info!("Room {} blinds {}%", room, percent);
+    }
+}
+
+/// Backend for the devices of each room, reached over the MQTT broker of
+/// the smart light, see [`rooms`]. Until a room reports, its card keeps the
+/// values it starts with.
+pub struct Mqtt;
+
+impl Backend for Mqtt {
+    fn poll(&mut self, states: &mut [RoomState]) {
+        for (room, state) in states.iter_mut().enumerate() {
+            let report = rooms::report(room);
+            state.temperature = report.temperature.unwrap_or(state.temperature);
+            state.light = report.light.unwrap_or(state.light);
+            state.blinds = report.blinds.unwrap_or(state.blinds);
+        }
+    }
+
+    fn set_light(&mut self, room: usize, on: bool) {
+        rooms::command(room, Command::Light(on));
+    }
+
+    fn set_blinds(&mut self, room: usize, percent: i32) {
+        rooms::command(room, Command::Blinds(percent));
+    }
+}
+
+struct Card {
+    _card: Obj,
+    _name: Label,
+    temperature: Label,
+    light: Switch,
+    blinds: Slider,
+}
+
+/// Grid of room cards with temperature, light toggle and blinds slider.
+pub struct Dashboard {
+    _back: (Button, Label),
+    _title: Label,
+    _grid: Obj,
+    _timer: Timer,
+}
+
+impl Dashboard {
+    /// Builds the dashboard on the active screen. The back button loads `home`.
+    pub fn new(home: Screen, backend: Box<dyn Backend>) -> Self {
+        const ROOMS: [(Text, RoomState); rooms::ROOMS] = [
+            (Text::LivingRoom, room(215, true, 0)),
+            (Text::Kitchen, room(228, false, 0)),
+            (Text::Bedroom, room(195, false, 80)),
+            (Text::Office, room(210, true, 30)),
+        ];
+
+        let back = back_button(home);
+
+        let title = builder::label()
+            .translated(Text::Home)
+            .align(Align::TopMid)
+            .offset(0, 12)
+            .build();
+
+        let mut grid = Obj::new();
+        grid.set_size(320, 185);
+        grid.align(Align::BottomMid.into(), 0, 0);
+        unsafe { lv_obj_set_flex_flow(grid.raw(), LV_FLEX_FLOW_ROW_WRAP) };
+
+        let backend = Rc::new(RefCell::new(backend));
+        let rooms = Rc::new(RefCell::new(Vec::new()));
+        let mut cards = Vec::new();
+
+        for (index, (name, state)) in ROOMS.into_iter().enumerate() {
+            let mut card = Obj::new();
+            card.set_parent(&mut grid);
+            card.set_size(140, 80);
+
+            let mut name_label = Label::new();
+            name_label.set_parent(&mut card);
+            translate(&mut name_label, name);
+            name_label.align(Align::TopLeft.into(), 0, 0);
+
+            let mut temperature = Label::new();
+            temperature.set_parent(&mut card);
+            temperature.align(Align::TopRight.into(), 0, 0);
+
+            let mut light = Switch::new();
+            light.set_parent(&mut card);
+            light.set_size(40, 20);
+            light.align(Align::BottomLeft.into(), 0, 0);
+            let light_raw = light.raw();
+            light.add_event_cb(EventCode::ValueChanged, {
+                let backend = backend.clone();
+                let rooms = rooms.clone();
+                move |_| {
+                    let on = unsafe { lv_obj_has_state(light_raw, LV_STATE_CHECKED) };
+                    rooms.borrow_mut()[index].light = on;
+                    backend.borrow_mut().set_light(index, on);
+                }
+            });
+
+            let mut blinds = Slider::new();
+            blinds.set_parent(&mut card);
+            blinds.set_size(60, 8);
+            blinds.align(Align::BottomRight.into(), -4, -6);
+            let blinds_raw = blinds.raw();
+            blinds.add_event_cb(EventCode::Released, {
+                let backend = backend.clone();
+                let rooms = rooms.clone();
+                move |_| {
+                    let percent = unsafe { lv_slider_get_value(blinds_raw) };
+                    rooms.borrow_mut()[index].blinds = percent;
+                    backend.borrow_mut().set_blinds(index, percent);
+                }
+            });
+
+            let mut card = Card {
+                _card: card,
+                _name: name_label,
+                temperature,
+                light,
+                blinds,
+            };
+            show(&mut card, &state);
+            cards.push(card);
+            rooms.borrow_mut().push(state);
+        }
+
+        let timer = Timer::new(POLL_PERIOD_MS, move || {
+            let mut rooms = rooms.borrow_mut();
+            backend.borrow_mut().poll(&mut rooms);
+            for (card, state) in cards.iter_mut().zip(rooms.iter()) {
+                show(card, state);
+            }
+        });
+
+        Self {
+            _back: back,
+            _title: title,
+            _grid: grid,
+            _timer: timer,
+        }
+    }
+}
+
+impl UiModule for Dashboard {
+    const NAME: Text = Text::Home;
+
+    fn build(home: Screen, _resources: &mut Resources) -> Self {
+        let backend: Box<dyn Backend> = match smart_light::broker() {
+            Some(_) => Box::new(Mqtt),
+            None => Box::new(Simulated::new()),
+        };
+        Self::new(home, backend)
+    }
+}
+
+const fn room(temperature: i32, light: bool, blinds: i32) -> RoomState {
+    RoomState {
+        temperature,
+        light,
+        blinds,
+    }
+}
+
+fn show(card: &mut Card, state: &RoomState) {
+    let text = format!(
+        "{}.{} °C",
+        state.temperature / 10,
+        state.temperature.abs() % 10
+    );
+    card.temperature
+        .set_text(CString::new(text).unwrap().as_c_str());
+    unsafe {
+        if state.light {
+            lv_obj_add_state(card.light.raw(), LV_STATE_CHECKED);
+        } else {
+            lv_obj_remove_state(card.light.raw(), LV_STATE_CHECKED);
+        }
+        if lv_slider_get_value(card.blinds.raw()) != state.blinds {
+            lv_slider_set_value(card.blinds.raw(), state.blinds, LV_ANIM_OFF);
+        }
+    }
+}
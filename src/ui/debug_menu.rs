@@ -0,0 +1,164 @@
+use alloc::boxed::Box;
+use alloc::ffi::CString;
+use alloc::format;
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cell::{Cell, RefCell};
+
+use embassy_time::{Duration, Instant};
+use lv_bevy_ecs::events::EventCode;
+use lv_bevy_ecs::support::{Align, LabelLongMode};
+use lv_bevy_ecs::sys::{
+    LV_OBJ_FLAG_HIDDEN, LV_OPA_COVER, LV_OPA_TRANSP, lv_color_hex, lv_label_set_text, lv_layer_top,
+    lv_obj_add_flag, lv_obj_invalidate, lv_obj_remove_flag, lv_obj_set_parent,
+    lv_obj_set_style_bg_color, lv_obj_set_style_bg_opa, lv_obj_set_style_border_width,
+    lv_obj_set_style_text_color, lv_refr_now, lv_screen_active,
+};
+use lv_bevy_ecs::widgets::{Button, Label, Obj};
+
+use super::i18n::{Text, translate};
+use super::{set_visible, status_bar, translated_button};
+use crate::settings::Settings;
+use crate::{calibration, journal};
+
+/// How long the corner has to be held
+const HOLD: Duration = Duration::from_secs(3);
+/// Top left corner of the status bar, away from every back button
+const CORNER_WIDTH: i32 = 40;
+const BENCHMARK_FRAMES: u32 = 20;
+const BACKGROUND: u32 = 0x263238;
+
+/// Maintenance menu opened by holding the top left corner for three seconds.
+///
+/// It has no launcher entry. Recalibration happens on the next boot, before
+/// the UI starts, as it draws straight to the display.
+pub struct DebugMenu {
+    _corner: Obj,
+    _panel: Obj,
+    _title: Label,
+    _buttons: Vec<(Button, Label)>,
+    _output: (Obj, Label),
+}
+
+impl DebugMenu {
+    /// Creates the hidden menu on the top layer. Create it after everything
+    /// else on the top layer so it covers it.
+    pub fn new(settings: Rc<RefCell<Settings>>) -> Self {
+        let mut panel = Obj::new();
+        unsafe {
+            lv_obj_set_parent(panel.raw(), lv_layer_top());
+            lv_obj_set_style_bg_color(panel.raw(), lv_color_hex(BACKGROUND), 0);
+            lv_obj_set_style_bg_opa(panel.raw(), LV_OPA_COVER as _, 0);
+            lv_obj_set_style_text_color(panel.raw(), lv_color_hex(0xFFFFFF), 0);
+        }
+        panel.set_size(320, 240);
+        panel.set_pos(0, 0);
+        set_visible(&mut panel, false);
+        let panel_raw = panel.raw();
+
+        let mut title = Label::new();
+        title.set_parent(&mut panel);
+        translate(&mut title, Text::Debug);
+        title.align(Align::TopLeft.into(), 0, 0);
+
+        let mut output = Obj::new();
+        output.set_parent(&mut panel);
+        output.set_size(170, 200);
+        output.align(Align::TopRight.into(), 0, 20);
+        let mut output_label = Label::new();
+        output_label.set_parent(&mut output);
+        output_label.set_width(145);
+        output_label.set_long_mode(LabelLongMode::Wrap.into());
+        output_label.set_text_static(c"");
+        let output_raw = output_label.raw();
+        // Journal lines can quote anything, NULs included
+        let show = move |text: String| unsafe {
+            let text = CString::new(text.replace('\0', "")).unwrap();
+            lv_label_set_text(output_raw, text.as_ptr());
+        };
+
+        let mut buttons = Vec::new();
+        let mut add = |text: Text, action: Box<dyn Fn()>| {
+            let mut button = translated_button(text);
+            button.0.set_parent(&mut panel);
+            button.0.set_size(116, 34);
+            button
+                .0
+                .align(Align::TopLeft.into(), 0, 20 + buttons.len() as i32 * 40);
+            button.0.add_event_cb(EventCode::Clicked, move |_| action());
+            buttons.push(button);
+        };
+
+        add(
+            Text::Recalibrate,
+            Box::new(move || {
+                calibration::request(&mut settings.borrow_mut());
+                reboot();
+            }),
+        );
+        add(Text::Benchmark, Box::new(move || show(benchmark())));
+        add(Text::Log, Box::new(move || show(journal::lines())));
+        add(Text::Reboot, Box::new(reboot));
+        add(
+            Text::Close,
+            Box::new(move || unsafe { lv_obj_add_flag(panel_raw, LV_OBJ_FLAG_HIDDEN) }),
+        );
+
+        let mut corner = Obj::new();
+        unsafe {
+            lv_obj_set_parent(corner.raw(), lv_layer_top());
+            lv_obj_set_style_bg_opa(corner.raw(), LV_OPA_TRANSP as _, 0);
+            lv_obj_set_style_border_width(corner.raw(), 0, 0);
+        }
+        corner.set_size(CORNER_WIDTH, status_bar::HEIGHT);
+        corner.set_pos(0, 0);
+        let pressed_at = Rc::new(Cell::new(None));
+        corner.add_event_cb(EventCode::Pressed, {
+            let pressed_at = pressed_at.clone();
+            move |_| pressed_at.set(Some(Instant::now()))
+        });
+        corner.add_event_cb(EventCode::Pressing, {
+            let pressed_at = pressed_at.clone();
+            move |_| {
+                if pressed_at.get().is_some_and(|at| at.elapsed() >= HOLD) {
+                    pressed_at.set(None);
+                    unsafe { lv_obj_remove_flag(panel_raw, LV_OBJ_FLAG_HIDDEN) };
+                }
+            }
+        });
+        corner.add_event_cb(EventCode::Released, move |_| pressed_at.set(None));
+
+        Self {
+            _corner: corner,
+            _panel: panel,
+            _title: title,
+            _buttons: buttons,
+            _output: (output, output_label),
+        }
+    }
+}
+
+fn reboot() {
+    esp_hal::system::software_reset()
+}
+
+/// Redraws the whole display a few times and reports how long it took.
+fn benchmark() -> String {
+    let start = Instant::now();
+    for _ in 0..BENCHMARK_FRAMES {
+        unsafe {
+            lv_obj_invalidate(lv_screen_active());
+            lv_obj_invalidate(lv_layer_top());
+            lv_refr_now(core::ptr::null_mut());
+        }
+    }
+    let micros = start.elapsed().as_micros() / BENCHMARK_FRAMES as u64;
+    format!(
+        "{} full redraws\n{}.{} ms per frame\n{} FPS",
+        BENCHMARK_FRAMES,
+        micros / 1000,
+        micros % 1000 / 100,
+        1_000_000 / micros.max(1)
+    )
+}
@@ -0,0 +1,162 @@
+use alloc::ffi::CString;
+use alloc::format;
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use embassy_time::{Duration, Instant};
+use esp_hal::gpio::{Input, InputConfig, Pull};
+use esp_hal::peripherals::GPIO0;
+use lv_bevy_ecs::support::Align;
+use lv_bevy_ecs::sys::{
+    LV_EVENT_ALL, LV_EVENT_CLICKED, LV_EVENT_GESTURE, LV_EVENT_LONG_PRESSED, LV_EVENT_PRESSED,
+    LV_EVENT_PRESSING, LV_EVENT_RELEASED, LV_EVENT_SCROLL, LV_OBJ_FLAG_CLICKABLE,
+    LV_OBJ_FLAG_HIDDEN, LV_OBJ_FLAG_SCROLLABLE, LV_OPA_50, lv_color_hex, lv_event_code_t,
+    lv_event_get_code, lv_event_t, lv_indev_add_event_cb, lv_indev_get_next, lv_indev_get_point,
+    lv_label_set_text, lv_layer_top, lv_obj_add_flag, lv_obj_remove_flag, lv_obj_set_parent,
+    lv_obj_set_style_bg_color, lv_obj_set_style_bg_opa, lv_obj_set_style_border_width,
+    lv_obj_set_style_pad_all, lv_obj_set_style_text_color, lv_point_t,
+};
+use lv_bevy_ecs::widgets::{Label, Obj};
+
+use super::timer::Timer;
+use super::{set_visible, status_bar};
+use crate::metrics;
+
+const POLL_PERIOD_MS: u32 = 50;
+const REFRESH_PERIOD_MS: u32 = 500;
+/// Held longer than this, a press of the button is not a toggle
+const SHORT_PRESS: Duration = Duration::from_millis(800);
+const BACKGROUND: u32 = 0x000000;
+
+/// Code of the last event the pointer sent
+static LAST_EVENT: AtomicU32 = AtomicU32::new(0);
+
+unsafe extern "C" fn record_event(event: *mut lv_event_t) {
+    LAST_EVENT.store(
+        unsafe { lv_event_get_code(event) } as u32,
+        Ordering::Relaxed,
+    );
+}
+
+fn event_name(code: lv_event_code_t) -> &'static str {
+    match code {
+        0 => "-",
+        LV_EVENT_PRESSED => "pressed",
+        LV_EVENT_PRESSING => "pressing",
+        LV_EVENT_RELEASED => "released",
+        LV_EVENT_CLICKED => "clicked",
+        LV_EVENT_LONG_PRESSED => "long pressed",
+        LV_EVENT_SCROLL => "scroll",
+        LV_EVENT_GESTURE => "gesture",
+        _ => "other",
+    }
+}
+
+/// Frame rate, heap use and the pointer in the top right corner, toggled by a
+/// short press of the BOOT button on GPIO0.
+///
+/// It sits on the top layer over every screen, but takes no input, so taps go
+/// through to whatever is below.
+pub struct DebugOverlay {
+    _panel: (Obj, Label),
+    _timer: Timer,
+}
+
+impl DebugOverlay {
+    /// Create it after the input device, whose events it shows, and after
+    /// everything else on the top layer so it covers it.
+    pub fn new(boot_button: GPIO0<'static>) -> Self {
+        let button = Input::new(boot_button, InputConfig::default().with_pull(Pull::Up));
+
+        let mut panel = Obj::new();
+        unsafe {
+            lv_obj_set_parent(panel.raw(), lv_layer_top());
+            lv_obj_set_style_bg_color(panel.raw(), lv_color_hex(BACKGROUND), 0);
+            lv_obj_set_style_bg_opa(panel.raw(), LV_OPA_50 as _, 0);
+            lv_obj_set_style_text_color(panel.raw(), lv_color_hex(0xFFFFFF), 0);
+            lv_obj_set_style_border_width(panel.raw(), 0, 0);
+            lv_obj_set_style_pad_all(panel.raw(), 4, 0);
+            lv_obj_remove_flag(panel.raw(), LV_OBJ_FLAG_CLICKABLE);
+            lv_obj_remove_flag(panel.raw(), LV_OBJ_FLAG_SCROLLABLE);
+        }
+        panel.set_size(150, 84);
+        panel.align(Align::TopRight.into(), -4, status_bar::HEIGHT + 4);
+        set_visible(&mut panel, false);
+
+        let mut label = Label::new();
+        label.set_parent(&mut panel);
+        label.set_text_static(c"");
+        let label_raw = label.raw();
+
+        let indev = unsafe { lv_indev_get_next(core::ptr::null_mut()) };
+        unsafe {
+            if !indev.is_null() {
+                lv_indev_add_event_cb(
+                    indev,
+                    Some(record_event),
+                    LV_EVENT_ALL,
+                    core::ptr::null_mut(),
+                );
+            }
+        }
+
+        let panel_raw = panel.raw();
+        let mut pressed_at: Option<Instant> = None;
+        let mut visible = false;
+        let mut since_refresh = 0;
+        let mut rate = metrics::FrameRate::new();
+        let timer = Timer::new(POLL_PERIOD_MS, move || {
+            // Low while pressed
+            match (button.is_low(), pressed_at) {
+                (true, None) => pressed_at = Some(Instant::now()),
+                (false, Some(at)) => {
+                    pressed_at = None;
+                    if at.elapsed() < SHORT_PRESS {
+                        visible = !visible;
+                        unsafe {
+                            if visible {
+                                lv_obj_remove_flag(panel_raw, LV_OBJ_FLAG_HIDDEN);
+                            } else {
+                                lv_obj_add_flag(panel_raw, LV_OBJ_FLAG_HIDDEN);
+                            }
+                        }
+                        since_refresh = 0;
+                        rate = metrics::FrameRate::new();
+                    }
+                }
+                _ => {}
+            }
+
+            if !visible {
+                return;
+            }
+            since_refresh += POLL_PERIOD_MS;
+            if since_refresh < REFRESH_PERIOD_MS {
+                return;
+            }
+            since_refresh = 0;
+
+            let fps = rate.per_second();
+            let heap = esp_alloc::HEAP.stats();
+            let mut point = lv_point_t { x: 0, y: 0 };
+            if !indev.is_null() {
+                unsafe { lv_indev_get_point(indev, &mut point) };
+            }
+            let event = event_name(LAST_EVENT.load(Ordering::Relaxed) as lv_event_code_t);
+            let text = format!(
+                "{} FPS\nHeap {} / {} KB\nTouch {}, {}\nEvent {}",
+                fps,
+                heap.current_usage / 1024,
+                heap.size / 1024,
+                point.x,
+                point.y,
+                event
+            );
+            unsafe { lv_label_set_text(label_raw, CString::new(text).unwrap().as_ptr()) };
+        });
+
+        Self {
+            _panel: (panel, label),
+            _timer: timer,
+        }
+    }
+}
@@ -0,0 +1,212 @@
+use alloc::ffi::CString;
+use alloc::format;
+use alloc::rc::Rc;
+use core::cell::{Cell, RefCell};
+
+use lv_bevy_ecs::events::EventCode;
+use lv_bevy_ecs::support::Align;
+use lv_bevy_ecs::sys::{
+    LV_ANIM_OFF, LV_CHART_AXIS_PRIMARY_Y, LV_CHART_TYPE_LINE, LV_CHART_UPDATE_MODE_SHIFT,
+    LV_OBJ_FLAG_CLICKABLE, LV_PART_INDICATOR, LV_PART_KNOB, lv_arc_set_value, lv_chart_add_series,
+    lv_chart_create, lv_chart_set_axis_range, lv_chart_set_next_value, lv_chart_set_point_count,
+    lv_chart_set_type, lv_chart_set_update_mode, lv_color_hex, lv_label_set_text,
+    lv_obj_remove_flag, lv_obj_remove_style, lv_obj_remove_style_all, lv_obj_set_size,
+    lv_obj_set_style_size, lv_slider_get_value, lv_slider_set_range, lv_slider_set_value,
+};
+use lv_bevy_ecs::widgets::{Arc, Button, Label, Obj, Slider};
+
+use super::i18n::Text;
+use super::module::{Resources, UiModule};
+use super::screen::Screen;
+use super::timer::Timer;
+use super::toast::{self, Severity};
+use super::{back_button, builder};
+use crate::buzzer::{Beeper, Buzzer};
+use crate::hc_sr04;
+use crate::settings::{Key, Settings};
+
+const TICK_MS: u32 = 200;
+/// Top of the gauge and the chart
+const MAX_CM: i32 = 200;
+/// Ten seconds of history
+const HISTORY: u32 = 50;
+const MIN_THRESHOLD_CM: i32 = 5;
+const MAX_THRESHOLD_CM: i32 = 100;
+/// The alarm stops this much past the threshold, so a target sitting right
+/// at it does not keep starting it again
+const HYSTERESIS_CM: i32 = 5;
+const LINE_COLOR: u32 = 0x1E88E5;
+
+/// Live distance from the HC-SR04 on a gauge, with the last ten seconds on
+/// a chart.
+///
+/// Anything closer than the threshold on the slider beeps the buzzer and
+/// shows a toast. The module stays built, so the alarm works on any screen.
+/// The threshold is kept in [`Settings`].
+pub struct Distance {
+    _back: (Button, Label),
+    _title: Label,
+    /// Inside the gauge, so it goes first
+    _value: Label,
+    _gauge: Arc,
+    /// Holds the chart
+    _chart: Obj,
+    _threshold: Slider,
+    _threshold_label: Label,
+    _timer: Timer,
+}
+
+impl Distance {
+    /// Builds the page on the active screen. The back button loads `home`.
+    pub fn new(home: Screen, settings: Rc<RefCell<Settings>>, buzzer: Rc<RefCell<Buzzer>>) -> Self {
+        let back = back_button(home);
+
+        let title = builder::label()
+            .translated(Text::Distance)
+            .align(Align::TopMid)
+            .offset(0, 12)
+            .build();
+
+        let mut gauge = Arc::new();
+        gauge.set_size(130, 130);
+        gauge.set_rotation(135);
+        gauge.set_bg_angles(0, 270);
+        gauge.set_range(0, MAX_CM as _);
+        gauge.set_value(0);
+        gauge.align(Align::TopLeft.into(), 10, 45);
+        unsafe {
+            lv_obj_remove_style(gauge.raw(), core::ptr::null_mut(), LV_PART_KNOB);
+            lv_obj_remove_flag(gauge.raw(), LV_OBJ_FLAG_CLICKABLE);
+        }
+
+        let mut value = Label::new();
+        value.set_parent(&mut gauge);
+        value.center();
+        value.set_text_static(c"--");
+
+        let mut chart = Obj::new();
+        unsafe { lv_obj_remove_style_all(chart.raw()) };
+        chart.set_size(160, 110);
+        chart.align(Align::TopRight.into(), -10, 50);
+        let (chart_raw, series) = unsafe {
+            let chart_raw = lv_chart_create(chart.raw());
+            lv_obj_set_size(chart_raw, 160, 110);
+            lv_chart_set_type(chart_raw, LV_CHART_TYPE_LINE);
+            lv_chart_set_point_count(chart_raw, HISTORY);
+            lv_chart_set_update_mode(chart_raw, LV_CHART_UPDATE_MODE_SHIFT);
+            lv_chart_set_axis_range(chart_raw, LV_CHART_AXIS_PRIMARY_Y, 0, MAX_CM);
+            // Points would hide a line this dense
+            lv_obj_set_style_size(chart_raw, 0, 0, LV_PART_INDICATOR);
+            let series =
+                lv_chart_add_series(chart_raw, lv_color_hex(LINE_COLOR), LV_CHART_AXIS_PRIMARY_Y);
+            (chart_raw, series)
+        };
+
+        let threshold = Rc::new(Cell::new(
+            (settings.borrow().get(Key::ProximityThreshold) as i32)
+                .clamp(MIN_THRESHOLD_CM, MAX_THRESHOLD_CM),
+        ));
+
+        let mut threshold_label = Label::new();
+        threshold_label.align(Align::BottomLeft.into(), 20, -45);
+        let threshold_label_raw = threshold_label.raw();
+        let set_threshold_text = move |cm: i32| {
+            let text = CString::new(format!(
+                "{} {} cm",
+                Text::AlarmCloserThan.get().to_str().unwrap(),
+                cm
+            ))
+            .unwrap();
+            unsafe { lv_label_set_text(threshold_label_raw, text.as_ptr()) };
+        };
+        set_threshold_text(threshold.get());
+
+        let mut slider = Slider::new();
+        slider.set_width(280);
+        slider.align(Align::BottomMid.into(), 0, -20);
+        let slider_raw = slider.raw();
+        unsafe {
+            lv_slider_set_range(slider_raw, MIN_THRESHOLD_CM, MAX_THRESHOLD_CM);
+            lv_slider_set_value(slider_raw, threshold.get(), LV_ANIM_OFF);
+        }
+        slider.add_event_cb(EventCode::ValueChanged, {
+            let threshold = threshold.clone();
+            move |_| {
+                threshold.set(unsafe { lv_slider_get_value(slider_raw) });
+                set_threshold_text(threshold.get());
+            }
+        });
+        // Saved once let go, rather than on every step of the drag
+        slider.add_event_cb(EventCode::Released, {
+            let threshold = threshold.clone();
+            move |_| {
+                settings
+                    .borrow_mut()
+                    .set(Key::ProximityThreshold, threshold.get() as u32);
+            }
+        });
+
+        let (gauge_raw, value_raw) = (gauge.raw(), value.raw());
+        let mut alarm = false;
+        let mut beep = false;
+        let timer = Timer::new(TICK_MS, move || {
+            let distance = hc_sr04::distance_mm().map(|mm| (mm / 10) as i32);
+            let text = match distance {
+                Some(cm) => CString::new(format!("{} cm", cm)).unwrap(),
+                None => CString::new("--").unwrap(),
+            };
+            // Nothing in range shows as far away
+            let shown = distance.unwrap_or(MAX_CM).min(MAX_CM);
+            unsafe {
+                lv_label_set_text(value_raw, text.as_ptr());
+                lv_arc_set_value(gauge_raw, shown);
+                lv_chart_set_next_value(chart_raw, series, shown);
+            }
+
+            match distance {
+                Some(cm) if !alarm && cm < threshold.get() => {
+                    alarm = true;
+                    toast::show(
+                        Severity::Warning,
+                        format!("{} {} cm", Text::SomethingAt.get().to_str().unwrap(), cm),
+                    );
+                }
+                Some(cm) if alarm && cm < threshold.get() + HYSTERESIS_CM => {}
+                _ if alarm => {
+                    alarm = false;
+                    beep = false;
+                    buzzer.borrow_mut().set_beeping(Beeper::Proximity, false);
+                }
+                _ => {}
+            }
+            if alarm {
+                beep = !beep;
+                buzzer.borrow_mut().set_beeping(Beeper::Proximity, beep);
+            }
+        });
+
+        Self {
+            _back: back,
+            _title: title,
+            _value: value,
+            _gauge: gauge,
+            _chart: chart,
+            _threshold: slider,
+            _threshold_label: threshold_label,
+            _timer: timer,
+        }
+    }
+}
+
+impl UiModule for Distance {
+    const NAME: Text = Text::Distance;
+    const RESIDENT: bool = true;
+
+    fn build(home: Screen, resources: &mut Resources) -> Self {
+        Self::new(
+            home,
+            resources.get::<Rc<RefCell<Settings>>>().clone(),
+            resources.get::<Rc<RefCell<Buzzer>>>().clone(),
+        )
+    }
+}
@@ -0,0 +1,168 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::ptr::null_mut;
+use core::sync::atomic::{AtomicPtr, Ordering};
+
+use lv_bevy_ecs::sys::{
+    LV_OPA_COVER, LV_STATE_FOCUS_KEY, lv_color_t, lv_group_add_obj, lv_group_create,
+    lv_group_focus_obj, lv_group_get_focused, lv_group_remove_all_objs, lv_group_t, lv_layer_top,
+    lv_obj_add_style, lv_obj_get_child, lv_obj_get_child_count, lv_obj_get_height,
+    lv_obj_get_width, lv_obj_is_group_def, lv_obj_is_valid, lv_obj_is_visible, lv_obj_t,
+    lv_screen_active, lv_style_init, lv_style_set_outline_color, lv_style_set_outline_opa,
+    lv_style_set_outline_pad, lv_style_set_outline_width, lv_style_t, lv_theme_copy,
+    lv_theme_create, lv_theme_set_apply_cb, lv_theme_set_parent, lv_theme_t,
+};
+
+use super::timer::Timer;
+
+const POLL_PERIOD_MS: u32 = 200;
+/// Thicker than the default theme's outline, to be seen from a couch
+const OUTLINE_WIDTH: i32 = 3;
+const OUTLINE_PAD: i32 = 2;
+
+static GROUP: AtomicPtr<lv_group_t> = AtomicPtr::new(null_mut());
+static THEME: AtomicPtr<lv_theme_t> = AtomicPtr::new(null_mut());
+static STYLE: AtomicPtr<lv_style_t> = AtomicPtr::new(null_mut());
+
+/// The group keypads and encoders move through. Attach them with
+/// `lv_indev_set_group(indev, focus::group())`.
+///
+/// It is not the default group: [`Focus`] decides what is in it.
+pub fn group() -> *mut lv_group_t {
+    let mut group = GROUP.load(Ordering::Relaxed);
+    if group.is_null() {
+        group = unsafe { lv_group_create() };
+        GROUP.store(group, Ordering::Relaxed);
+    }
+    group
+}
+
+/// Extends `base` with an opaque outline in `color` around the widget
+/// focused by a key, see [`crate::ui::accent::apply`]. Touch focus looks as
+/// before.
+pub fn theme(base: *mut lv_theme_t, color: lv_color_t) -> *mut lv_theme_t {
+    let mut theme = THEME.load(Ordering::Relaxed);
+    let mut style = STYLE.load(Ordering::Relaxed);
+    unsafe {
+        if theme.is_null() {
+            theme = lv_theme_create();
+            style = Box::leak(Box::new(core::mem::zeroed()));
+            lv_style_init(style);
+            lv_style_set_outline_width(style, OUTLINE_WIDTH);
+            lv_style_set_outline_pad(style, OUTLINE_PAD);
+            lv_style_set_outline_opa(style, LV_OPA_COVER as _);
+            THEME.store(theme, Ordering::Relaxed);
+            STYLE.store(style, Ordering::Relaxed);
+        }
+        lv_style_set_outline_color(style, color);
+        // For the fonts and colors widgets ask the theme for
+        lv_theme_copy(theme, base);
+        lv_theme_set_parent(theme, base);
+        lv_theme_set_apply_cb(theme, Some(apply));
+    }
+    theme
+}
+
+unsafe extern "C" fn apply(_theme: *mut lv_theme_t, obj: *mut lv_obj_t) {
+    unsafe {
+        if lv_obj_is_group_def(obj) {
+            lv_obj_add_style(obj, STYLE.load(Ordering::Relaxed), LV_STATE_FOCUS_KEY as _);
+        }
+    }
+}
+
+/// Keeps [`group`] to the widgets that can be seen and used, so keys never
+/// move the focus to another screen or under an overlay.
+///
+/// That is the active screen, unless something on the top layer takes over:
+/// a panel with widgets of its own, like the open quick settings or the lock
+/// screen, or one covering the whole display, like the screensaver. Each
+/// remembers what had the focus when it was left.
+pub struct Focus {
+    _timer: Timer,
+}
+
+impl Focus {
+    /// Create it once every screen and overlay exists.
+    pub fn new() -> Self {
+        let mut scope: *mut lv_obj_t = null_mut();
+        let mut members: Vec<*mut lv_obj_t> = Vec::new();
+        let mut remembered: Vec<(*mut lv_obj_t, *mut lv_obj_t)> = Vec::new();
+        let timer = Timer::new(POLL_PERIOD_MS, move || {
+            let (next_scope, found) = unsafe { in_scope() };
+            if next_scope == scope && found == members {
+                return;
+            }
+            let group = group();
+            unsafe {
+                let focused = lv_group_get_focused(group);
+                // Overlays and the widgets of torn down modules come and go
+                remembered.retain(|&(root, obj)| {
+                    root != scope && lv_obj_is_valid(root) && lv_obj_is_valid(obj)
+                });
+                if !focused.is_null() {
+                    remembered.push((scope, focused));
+                }
+                lv_group_remove_all_objs(group);
+                for &obj in &found {
+                    lv_group_add_obj(group, obj);
+                }
+                // The first one added has the focus otherwise
+                let restored = remembered
+                    .iter()
+                    .find(|&&(root, obj)| root == next_scope && found.contains(&obj));
+                if let Some(&(_, obj)) = restored {
+                    lv_group_focus_obj(obj);
+                }
+            }
+            scope = next_scope;
+            members = found;
+        });
+        Self { _timer: timer }
+    }
+}
+
+impl Default for Focus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The object keys should stay within, and its focusable widgets in
+/// creation order.
+unsafe fn in_scope() -> (*mut lv_obj_t, Vec<*mut lv_obj_t>) {
+    let mut found = Vec::new();
+    unsafe {
+        let top = lv_layer_top();
+        // Later children are drawn over earlier ones
+        for index in (0..lv_obj_get_child_count(top) as i32).rev() {
+            let child = lv_obj_get_child(top, index);
+            if !lv_obj_is_visible(child) {
+                continue;
+            }
+            collect(child, &mut found);
+            let covers = lv_obj_get_width(child) >= lv_obj_get_width(top)
+                && lv_obj_get_height(child) >= lv_obj_get_height(top);
+            if covers || !found.is_empty() {
+                return (child, found);
+            }
+        }
+        let screen = lv_screen_active();
+        collect(screen, &mut found);
+        (screen, found)
+    }
+}
+
+/// Widgets LVGL would put in a default group, like buttons, sliders and
+/// text areas. Hidden ones stay in, LVGL skips them.
+unsafe fn collect(parent: *mut lv_obj_t, found: &mut Vec<*mut lv_obj_t>) {
+    unsafe {
+        for index in 0..lv_obj_get_child_count(parent) as i32 {
+            let child = lv_obj_get_child(parent, index);
+            if lv_obj_is_group_def(child) {
+                found.push(child);
+            }
+            collect(child, found);
+        }
+    }
+}
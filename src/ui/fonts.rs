@@ -0,0 +1,61 @@
+use lv_bevy_ecs::sys::{self, lv_font_t, lv_obj_set_style_text_font, lv_obj_t};
+
+/// Built-in fonts.
+///
+/// The `font-*` features decide which ones there are, and the matching
+/// defines in `CFLAGS` whether LVGL compiles them, see the README.
+/// Montserrat 14 is the default font and always available.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Font {
+    #[cfg(feature = "font-12")]
+    Montserrat12,
+    Montserrat14,
+    #[cfg(feature = "font-16")]
+    Montserrat16,
+    #[cfg(feature = "font-24")]
+    Montserrat24,
+    #[cfg(feature = "font-32")]
+    Montserrat32,
+    /// 16 px Source Han Sans SC with the 1338 most common CJK characters
+    #[cfg(feature = "font-cjk")]
+    SourceHanSans16Cjk,
+}
+
+impl Font {
+    /// Biggest enabled size, for readouts meant to be seen from afar
+    pub const LARGEST: Font = {
+        #[cfg(feature = "font-32")]
+        let font = Font::Montserrat32;
+        #[cfg(all(feature = "font-24", not(feature = "font-32")))]
+        let font = Font::Montserrat24;
+        #[cfg(all(
+            feature = "font-16",
+            not(any(feature = "font-24", feature = "font-32"))
+        ))]
+        let font = Font::Montserrat16;
+        #[cfg(not(any(feature = "font-16", feature = "font-24", feature = "font-32")))]
+        let font = Font::Montserrat14;
+        font
+    };
+
+    pub fn raw(self) -> *const lv_font_t {
+        match self {
+            #[cfg(feature = "font-12")]
+            Font::Montserrat12 => &raw const sys::lv_font_montserrat_12,
+            Font::Montserrat14 => &raw const sys::lv_font_montserrat_14,
+            #[cfg(feature = "font-16")]
+            Font::Montserrat16 => &raw const sys::lv_font_montserrat_16,
+            #[cfg(feature = "font-24")]
+            Font::Montserrat24 => &raw const sys::lv_font_montserrat_24,
+            #[cfg(feature = "font-32")]
+            Font::Montserrat32 => &raw const sys::lv_font_montserrat_32,
+            #[cfg(feature = "font-cjk")]
+            Font::SourceHanSans16Cjk => &raw const sys::lv_font_source_han_sans_sc_16_cjk,
+        }
+    }
+
+    /// Uses this font for the main part of `obj` and its children.
+    pub fn apply(self, obj: *mut lv_obj_t) {
+        unsafe { lv_obj_set_style_text_font(obj, self.raw(), 0) };
+    }
+}
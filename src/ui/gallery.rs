@@ -0,0 +1,283 @@
+use alloc::boxed::Box;
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use core::any::Any;
+use core::cell::{Cell, RefCell};
+use core::ffi::CStr;
+
+use embassy_sync::blocking_mutex::Mutex;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use lv_bevy_ecs::events::EventCode;
+use lv_bevy_ecs::support::Align;
+use lv_bevy_ecs::sys::{
+    LV_ANIM_OFF, LV_ROLLER_MODE_INFINITE, LV_STATE_CHECKED, lv_bar_set_value,
+    lv_buttonmatrix_set_map, lv_checkbox_set_text, lv_dropdown_set_options_static, lv_led_on,
+    lv_obj_add_state, lv_roller_set_options, lv_textarea_set_placeholder_text,
+};
+use lv_bevy_ecs::widgets::{
+    Arc, Bar, Button, ButtonMatrix, Checkbox, Dropdown, Label, Led, List, Roller, Slider, Spinner,
+    Switch, Textarea,
+};
+
+use super::i18n::{Text, translate};
+use super::module::{Resources, UiModule};
+use super::screen::Screen;
+use super::state;
+use super::{ButtonMap, back_button, text_button, translated_button};
+
+/// Builds an example on the active screen. The returned widgets are kept
+/// alive while the example is shown.
+type Example = fn() -> Box<dyn Any>;
+
+static BUTTON_MAP: ButtonMap<8> = ButtonMap([
+    c"1".as_ptr(),
+    c"2".as_ptr(),
+    c"3".as_ptr(),
+    c"\n".as_ptr(),
+    c"4".as_ptr(),
+    c"5".as_ptr(),
+    c"6".as_ptr(),
+    c"".as_ptr(),
+]);
+
+const EXAMPLES: [(&CStr, Example); 14] = [
+    (c"Arc", arc),
+    (c"Bar", bar),
+    (c"Button", button),
+    (c"Button matrix", button_matrix),
+    (c"Checkbox", checkbox),
+    (c"Dropdown", dropdown),
+    (c"Label", label),
+    (c"LED", led),
+    (c"List", list),
+    (c"Roller", roller),
+    (c"Slider", slider),
+    (c"Spinner", spinner),
+    (c"Switch", switch),
+    (c"Text area", textarea),
+];
+
+struct Shared(Cell<Option<Screen>>);
+
+// Screens are only created and loaded from the UI task
+unsafe impl Send for Shared {}
+
+static EXAMPLE_SCREEN: Mutex<CriticalSectionRawMutex, Shared> = Mutex::new(Shared(Cell::new(None)));
+
+/// The screen examples are shown on, created on the first visit and reused
+/// by the later ones.
+fn example_screen() -> Screen {
+    if let Some(screen) = EXAMPLE_SCREEN.lock(|screen| screen.0.get()) {
+        return screen;
+    }
+    let screen = Screen::new();
+    EXAMPLE_SCREEN.lock(|example| example.0.set(Some(screen)));
+    screen
+}
+
+/// List of the widgets supported by the bindings, each opening a small example.
+///
+/// Examples are created when opened and deleted when leaving them, so every
+/// visit also exercises widget teardown.
+pub struct Gallery {
+    gallery: Screen,
+    example_screen: Screen,
+    current: Rc<RefCell<Option<Box<dyn Any>>>>,
+    _back: (Button, Label),
+    _list: List,
+    _entries: Vec<(Button, Label)>,
+    _example_back: (Button, Label),
+}
+
+impl Gallery {
+    /// Builds the gallery on the active screen. The back button loads `home`.
+    pub fn new(home: Screen) -> Self {
+        let gallery = Screen::active();
+        let back = back_button(home);
+
+        let mut list = List::new();
+        list.set_size(300, 180);
+        list.align(Align::BottomMid.into(), 0, -5);
+
+        let example_screen = example_screen();
+        let current: Rc<RefCell<Option<Box<dyn Any>>>> = Rc::new(RefCell::new(None));
+        let (example_back, title) = example_screen.build(|| {
+            let back = back_button(gallery);
+            let mut title = Label::new();
+            title.align(Align::TopMid.into(), 0, 12);
+            (back, title)
+        });
+        let title = Rc::new(RefCell::new(title));
+
+        let mut entries = Vec::new();
+        for (name, build) in EXAMPLES {
+            let mut entry = text_button(name);
+            entry.0.set_parent(&mut list);
+            entry.0.set_width(280);
+            entry.0.add_event_cb(EventCode::Clicked, {
+                let current = current.clone();
+                let title = title.clone();
+                move |_| {
+                    title.borrow_mut().set_text_static(name);
+                    *current.borrow_mut() = Some(example_screen.build(build));
+                    state::set_next(example_screen);
+                }
+            });
+            entries.push(entry);
+        }
+
+        Self {
+            gallery,
+            example_screen,
+            current,
+            _back: back,
+            _list: list,
+            _entries: entries,
+            _example_back: example_back,
+        }
+    }
+}
+
+impl UiModule for Gallery {
+    const NAME: Text = Text::Gallery;
+
+    fn build(home: Screen, _resources: &mut Resources) -> Self {
+        Self::new(home)
+    }
+
+    fn owns(&self, screen: Screen) -> bool {
+        screen == self.example_screen
+    }
+
+    /// Deletes the example once the gallery is back on display.
+    fn update(&mut self) {
+        if Screen::active() == self.gallery {
+            self.current.borrow_mut().take();
+        }
+    }
+}
+
+fn arc() -> Box<dyn Any> {
+    let mut arc = Arc::new();
+    arc.set_size(140, 140);
+    arc.set_value(40);
+    arc.center();
+    Box::new(arc)
+}
+
+fn bar() -> Box<dyn Any> {
+    let mut bar = Bar::new();
+    bar.set_size(200, 20);
+    bar.center();
+    unsafe { lv_bar_set_value(bar.raw(), 70, LV_ANIM_OFF) };
+    Box::new(bar)
+}
+
+fn button() -> Box<dyn Any> {
+    let mut button = translated_button(Text::PressMe);
+    button.0.center();
+    Box::new(button)
+}
+
+fn button_matrix() -> Box<dyn Any> {
+    let mut matrix = ButtonMatrix::new();
+    matrix.set_size(280, 150);
+    matrix.align(Align::BottomMid.into(), 0, -10);
+    unsafe { lv_buttonmatrix_set_map(matrix.raw(), BUTTON_MAP.0.as_ptr()) };
+    Box::new(matrix)
+}
+
+fn checkbox() -> Box<dyn Any> {
+    let mut checkbox = Checkbox::new();
+    checkbox.center();
+    unsafe {
+        lv_checkbox_set_text(checkbox.raw(), Text::EnableFeature.get().as_ptr());
+        lv_obj_add_state(checkbox.raw(), LV_STATE_CHECKED);
+    }
+    Box::new(checkbox)
+}
+
+fn dropdown() -> Box<dyn Any> {
+    let mut dropdown = Dropdown::new();
+    dropdown.align(Align::TopMid.into(), 0, 60);
+    unsafe {
+        lv_dropdown_set_options_static(dropdown.raw(), Text::Fruits.get().as_ptr());
+    }
+    Box::new(dropdown)
+}
+
+fn label() -> Box<dyn Any> {
+    let mut label = Label::new();
+    translate(&mut label, Text::PlainLabel);
+    label.center();
+    Box::new(label)
+}
+
+fn led() -> Box<dyn Any> {
+    let mut led = Led::new();
+    led.set_size(40, 40);
+    led.center();
+    unsafe { lv_led_on(led.raw()) };
+    Box::new(led)
+}
+
+fn list() -> Box<dyn Any> {
+    let mut list = List::new();
+    list.set_size(200, 160);
+    list.align(Align::BottomMid.into(), 0, -10);
+    let mut items = Vec::new();
+    for text in [
+        Text::First,
+        Text::Second,
+        Text::Third,
+        Text::Fourth,
+        Text::Fifth,
+    ] {
+        let mut item = Label::new();
+        item.set_parent(&mut list);
+        translate(&mut item, text);
+        items.push(item);
+    }
+    Box::new((list, items))
+}
+
+fn roller() -> Box<dyn Any> {
+    let mut roller = Roller::new();
+    roller.center();
+    unsafe {
+        lv_roller_set_options(
+            roller.raw(),
+            Text::Weekdays.get().as_ptr(),
+            LV_ROLLER_MODE_INFINITE,
+        );
+    }
+    Box::new(roller)
+}
+
+fn slider() -> Box<dyn Any> {
+    let mut slider = Slider::new();
+    slider.set_width(200);
+    slider.center();
+    Box::new(slider)
+}
+
+fn spinner() -> Box<dyn Any> {
+    let mut spinner = Spinner::new();
+    spinner.set_size(80, 80);
+    spinner.center();
+    Box::new(spinner)
+}
+
+fn switch() -> Box<dyn Any> {
+    let mut switch = Switch::new();
+    switch.center();
+    Box::new(switch)
+}
+
+fn textarea() -> Box<dyn Any> {
+    let mut textarea = Textarea::new();
+    textarea.set_size(240, 80);
+    textarea.center();
+    unsafe { lv_textarea_set_placeholder_text(textarea.raw(), Text::TypeHere.get().as_ptr()) };
+    Box::new(textarea)
+}
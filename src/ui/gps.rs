@@ -0,0 +1,197 @@
+use alloc::ffi::CString;
+use alloc::format;
+use alloc::string::String;
+
+use embassy_time::Duration;
+use lv_bevy_ecs::support::Align;
+use lv_bevy_ecs::sys::{
+    LV_OBJ_FLAG_HIDDEN, lv_label_set_text, lv_obj_add_flag, lv_obj_remove_flag,
+    lv_obj_remove_style_all, lv_qrcode_create, lv_qrcode_set_size, lv_qrcode_update,
+};
+use lv_bevy_ecs::widgets::{Button, Label, Obj};
+
+use super::i18n::Text;
+use super::module::{Resources, UiModule};
+use super::screen::Screen;
+use super::timer::Timer;
+use super::{back_button, builder, set_visible};
+use crate::gps::{self, Fix, Position};
+
+const POLL_PERIOD_MS: u32 = 500;
+const QR_SIZE: i32 = 110;
+/// Without a sentence for this long the module counts as disconnected
+const SILENT: Duration = Duration::from_secs(3);
+
+/// Fix, coordinates, speed and satellites from the GPS module.
+///
+/// With a fix the position is also shown as a `geo:` QR code, which most
+/// phones open in their maps app.
+pub struct Gps {
+    _back: (Button, Label),
+    _title: Label,
+    _details: Label,
+    /// Holds the QR code
+    _qr: Obj,
+    _timer: Timer,
+}
+
+impl Gps {
+    /// Builds the page on the active screen. The back button loads `home`.
+    pub fn new(home: Screen) -> Self {
+        let back = back_button(home);
+
+        let title = builder::label()
+            .translated(Text::Gps)
+            .align(Align::TopMid)
+            .offset(0, 12)
+            .build();
+
+        let mut details = Label::new();
+        details.set_width(180);
+        details.align(Align::TopLeft.into(), 10, 45);
+
+        let mut qr = Obj::new();
+        unsafe { lv_obj_remove_style_all(qr.raw()) };
+        qr.set_size(QR_SIZE, QR_SIZE);
+        qr.align(Align::RightMid.into(), -10, 10);
+        set_visible(&mut qr, false);
+        // A child of the holder, so LVGL deletes it along with it
+        let qr_raw = unsafe {
+            let code = lv_qrcode_create(qr.raw());
+            lv_qrcode_set_size(code, QR_SIZE);
+            code
+        };
+
+        let details_raw = details.raw();
+        let qr_holder_raw = qr.raw();
+        let mut shown = String::new();
+        let mut update = move || {
+            let position = gps::position();
+            let text = CString::new(describe(&position)).unwrap();
+            unsafe { lv_label_set_text(details_raw, text.as_ptr()) };
+
+            let located = position.fix != Fix::None && !silent();
+            unsafe {
+                if located {
+                    lv_obj_remove_flag(qr_holder_raw, LV_OBJ_FLAG_HIDDEN);
+                } else {
+                    lv_obj_add_flag(qr_holder_raw, LV_OBJ_FLAG_HIDDEN);
+                }
+            }
+            if !located {
+                return;
+            }
+            let uri = format!(
+                "geo:{},{}",
+                signed_degrees(position.latitude),
+                signed_degrees(position.longitude)
+            );
+            // Encoding takes a while, so only when the position moved
+            if uri != shown {
+                unsafe { lv_qrcode_update(qr_raw, uri.as_ptr().cast(), uri.len() as u32) };
+                shown = uri;
+            }
+        };
+        update();
+        let timer = Timer::new(POLL_PERIOD_MS, update);
+
+        Self {
+            _back: back,
+            _title: title,
+            _details: details,
+            _qr: qr,
+            _timer: timer,
+        }
+    }
+}
+
+impl UiModule for Gps {
+    const NAME: Text = Text::Gps;
+
+    fn build(home: Screen, _resources: &mut Resources) -> Self {
+        Self::new(home)
+    }
+}
+
+fn silent() -> bool {
+    gps::updated().is_none_or(|updated| updated.elapsed() > SILENT)
+}
+
+fn fix_name(fix: Fix) -> Text {
+    match fix {
+        Fix::None => Text::NoFix,
+        Fix::Gps => Text::GpsFix,
+        Fix::Differential => Text::DgpsFix,
+        Fix::Rtk => Text::RtkFix,
+        Fix::Estimated => Text::Estimated,
+    }
+}
+
+fn describe(position: &Position) -> String {
+    let text = |text: Text| text.get().to_str().unwrap();
+    let (sentences, bad) = gps::counters();
+    let counters = format!(
+        "{} {}, {} {}",
+        sentences,
+        text(Text::Sentences),
+        bad,
+        text(Text::Bad)
+    );
+    if silent() {
+        return format!("{}\n\n{}", text(Text::NoGpsData), counters);
+    }
+    let mut description = format!(
+        "{}, {} {}\n",
+        text(fix_name(position.fix)),
+        position.satellites,
+        text(Text::Satellites)
+    );
+    if position.fix == Fix::None {
+        description.push_str(text(Text::WaitingForFix));
+        description.push('\n');
+    } else {
+        let altitude = position.altitude;
+        let speed = position.speed;
+        description.push_str(&format!(
+            "{}\n{}\n{} {}{}.{} m\n{} {}.{} km/h\n",
+            hemisphere_degrees(position.latitude, 'N', 'S'),
+            hemisphere_degrees(position.longitude, 'E', 'W'),
+            text(Text::Altitude),
+            if altitude < 0 { "-" } else { "" },
+            altitude.unsigned_abs() / 10,
+            altitude.unsigned_abs() % 10,
+            text(Text::Speed),
+            speed / 10,
+            speed % 10
+        ));
+    }
+    if let Some((hours, minutes, seconds)) = position.time {
+        description.push_str(&format!("{:02}:{:02}:{:02} UTC\n", hours, minutes, seconds));
+    }
+    description.push('\n');
+    description.push_str(&counters);
+    description
+}
+
+/// Six decimals, about 10 cm, with the hemisphere after it.
+fn hemisphere_degrees(value: i32, positive: char, negative: char) -> String {
+    let magnitude = value.unsigned_abs();
+    let hemisphere = if value < 0 { negative } else { positive };
+    format!(
+        "{}.{:06}° {}",
+        magnitude / 10_000_000,
+        magnitude % 10_000_000 / 10,
+        hemisphere
+    )
+}
+
+fn signed_degrees(value: i32) -> String {
+    let magnitude = value.unsigned_abs();
+    let sign = if value < 0 { "-" } else { "" };
+    format!(
+        "{}{}.{:06}",
+        sign,
+        magnitude / 10_000_000,
+        magnitude % 10_000_000 / 10
+    )
+}
@@ -0,0 +1,328 @@
+use alloc::collections::VecDeque;
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cell::{Cell, RefCell};
+use core::ffi::CStr;
+
+use embassy_sync::blocking_mutex::Mutex;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embedded_graphics::prelude::Point;
+use esp_hal::Blocking;
+use esp_hal::uart::UartRx;
+use lv_bevy_ecs::input::{BufferStatus, InputEvent, InputState, Pointer};
+use lv_bevy_ecs::sys::{
+    LV_OBJ_FLAG_HIDDEN, LV_STATE_CHECKED, lv_area_t, lv_label_class, lv_label_get_text,
+    lv_layer_top, lv_obj_check_type, lv_obj_get_child, lv_obj_get_child_count, lv_obj_get_coords,
+    lv_obj_has_flag, lv_obj_has_state, lv_obj_t, lv_screen_active,
+};
+
+use super::console;
+use super::timer::Timer;
+
+/// A little longer than the LVGL input read period while a script runs, so
+/// every pointer state is read at least once
+const TICK_MS: u32 = 40;
+const DEFAULT_DRAG_STEPS: i32 = 10;
+const MAX_LINE: usize = 128;
+
+/// Synthetic pointer state, `None` while the touch screen is in control
+static POINTER: Mutex<CriticalSectionRawMutex, Cell<Option<(bool, i32, i32)>>> =
+    Mutex::new(Cell::new(None));
+
+/// Pointer state to report instead of the touch screen while a script runs.
+/// Call from the pointer read callback.
+pub fn pointer() -> Option<InputEvent<Pointer>> {
+    let (pressed, x, y) = POINTER.lock(|pointer| pointer.get())?;
+    Some(InputEvent {
+        status: BufferStatus::Once,
+        state: if pressed {
+            InputState::Pressed
+        } else {
+            InputState::Released
+        },
+        data: Point::new(x, y),
+    })
+}
+
+enum Action {
+    Pointer(bool, i32, i32),
+    /// Release where the pointer last was
+    Release,
+    Wait(u32),
+    ExpectText {
+        x: i32,
+        y: i32,
+        text: String,
+    },
+    ExpectChecked {
+        x: i32,
+        y: i32,
+        checked: bool,
+    },
+    Report,
+}
+
+fn parse(line: &str) -> Option<Vec<Action>> {
+    let mut words = line.split_whitespace();
+    let command = words.next()?;
+    let mut number = || words.next()?.parse::<i32>().ok();
+    let actions = match command {
+        "press" => vec![Action::Pointer(true, number()?, number()?)],
+        "release" => vec![Action::Release],
+        "tap" => {
+            let (x, y) = (number()?, number()?);
+            vec![
+                Action::Pointer(true, x, y),
+                Action::Pointer(true, x, y),
+                Action::Pointer(false, x, y),
+            ]
+        }
+        "drag" => {
+            let (x1, y1, x2, y2) = (number()?, number()?, number()?, number()?);
+            let steps = number().unwrap_or(DEFAULT_DRAG_STEPS).max(1);
+            let mut actions: Vec<_> = (0..=steps)
+                .map(|i| {
+                    Action::Pointer(true, x1 + (x2 - x1) * i / steps, y1 + (y2 - y1) * i / steps)
+                })
+                .collect();
+            actions.push(Action::Pointer(false, x2, y2));
+            actions
+        }
+        "wait" => vec![Action::Wait(number()?.max(0) as u32)],
+        "expect-text" => {
+            let (x, y) = (number()?, number()?);
+            let text = line
+                .split_whitespace()
+                .skip(3)
+                .collect::<Vec<_>>()
+                .join(" ");
+            if text.is_empty() {
+                return None;
+            }
+            vec![Action::ExpectText { x, y, text }]
+        }
+        "expect-checked" => vec![Action::ExpectChecked {
+            x: number()?,
+            y: number()?,
+            checked: true,
+        }],
+        "expect-unchecked" => vec![Action::ExpectChecked {
+            x: number()?,
+            y: number()?,
+            checked: false,
+        }],
+        "report" => vec![Action::Report],
+        _ => return None,
+    };
+    Some(actions)
+}
+
+struct Queued {
+    action: Action,
+    line: u32,
+}
+
+#[derive(Default)]
+struct State {
+    queue: VecDeque<Queued>,
+    waiting: u32,
+    lines: u32,
+    passed: u32,
+    failed: u32,
+}
+
+impl State {
+    /// Queues the actions for one script line, reporting ones it cannot parse.
+    fn feed(&mut self, line: &str) {
+        self.lines += 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || console::submit(line) {
+            return;
+        }
+        match parse(line) {
+            Some(actions) => {
+                for action in actions {
+                    self.queue.push_back(Queued {
+                        action,
+                        line: self.lines,
+                    });
+                }
+            }
+            None => defmt::error!("ui-test: cannot parse line {}: {=str}", self.lines, line),
+        }
+    }
+
+    /// Runs the next action. Pointer states stay in effect until the queue
+    /// moves on, and the touch screen takes over when it is empty.
+    fn tick(&mut self) {
+        if self.waiting > 0 {
+            self.waiting = self.waiting.saturating_sub(TICK_MS);
+            return;
+        }
+        let Some(Queued { action, line }) = self.queue.pop_front() else {
+            POINTER.lock(|pointer| pointer.set(None));
+            return;
+        };
+        match action {
+            Action::Pointer(pressed, x, y) => {
+                POINTER.lock(|pointer| pointer.set(Some((pressed, x, y))))
+            }
+            Action::Release => POINTER.lock(|pointer| {
+                let (_, x, y) = pointer.get().unwrap_or_default();
+                pointer.set(Some((false, x, y)))
+            }),
+            Action::Wait(ms) => self.waiting = ms,
+            Action::ExpectText { x, y, text } => {
+                let found = object_at(x, y).and_then(|obj| unsafe { text_of(obj) });
+                let ok = found.is_some_and(|found| found.to_bytes() == text.as_bytes());
+                self.check(ok, line, || match found {
+                    Some(found) => defmt::error!(
+                        "ui-test: line {}: expected {=str}, found {=str}",
+                        line,
+                        text.as_str(),
+                        found.to_str().unwrap_or("<invalid>")
+                    ),
+                    None => defmt::error!("ui-test: line {}: no text at {}, {}", line, x, y),
+                });
+            }
+            Action::ExpectChecked { x, y, checked } => {
+                let found =
+                    object_at(x, y).map(|obj| unsafe { lv_obj_has_state(obj, LV_STATE_CHECKED) });
+                self.check(found == Some(checked), line, || {
+                    defmt::error!(
+                        "ui-test: line {}: expected checked {} at {}, {}",
+                        line,
+                        checked,
+                        x,
+                        y
+                    )
+                });
+            }
+            Action::Report => {
+                defmt::info!("ui-test: {} passed, {} failed", self.passed, self.failed);
+                self.passed = 0;
+                self.failed = 0;
+            }
+        }
+    }
+
+    fn check(&mut self, ok: bool, line: u32, report_failure: impl FnOnce()) {
+        if ok {
+            self.passed += 1;
+            defmt::info!("ui-test: line {} ok", line);
+        } else {
+            self.failed += 1;
+            report_failure();
+        }
+    }
+}
+
+/// Deepest visible object under the point, on the top layer first
+fn object_at(x: i32, y: i32) -> Option<*mut lv_obj_t> {
+    unsafe { find(lv_layer_top(), x, y, false).or_else(|| find(lv_screen_active(), x, y, true)) }
+}
+
+/// Layers always cover the whole display, so they only count as a hit
+/// through their children unless `accept_self` is set.
+unsafe fn find(obj: *mut lv_obj_t, x: i32, y: i32, accept_self: bool) -> Option<*mut lv_obj_t> {
+    if unsafe { lv_obj_has_flag(obj, LV_OBJ_FLAG_HIDDEN) } {
+        return None;
+    }
+    let mut area: lv_area_t = unsafe { core::mem::zeroed() };
+    unsafe { lv_obj_get_coords(obj, &mut area) };
+    if x < area.x1 || x > area.x2 || y < area.y1 || y > area.y2 {
+        return None;
+    }
+    let count = unsafe { lv_obj_get_child_count(obj) } as i32;
+    // Later children are drawn on top
+    for index in (0..count).rev() {
+        let child = unsafe { lv_obj_get_child(obj, index) };
+        if let Some(found) = unsafe { find(child, x, y, true) } {
+            return Some(found);
+        }
+    }
+    accept_self.then_some(obj)
+}
+
+/// Text of a label, or of the first label inside `obj`, like a button caption
+unsafe fn text_of(obj: *mut lv_obj_t) -> Option<&'static CStr> {
+    if unsafe { lv_obj_check_type(obj, &lv_label_class) } {
+        return Some(unsafe { CStr::from_ptr(lv_label_get_text(obj)) });
+    }
+    let count = unsafe { lv_obj_get_child_count(obj) } as i32;
+    (0..count).find_map(|index| unsafe { text_of(lv_obj_get_child(obj, index)) })
+}
+
+/// Drives the UI from a script of synthetic touches and checks the result,
+/// for hardware in the loop tests.
+///
+/// Scripts come line by line from the serial console and from [`run_script`],
+/// one command per line:
+///
+/// ```text
+/// tap 45 215
+/// drag 20 100 200 100
+/// press 10 10
+/// release
+/// wait 500
+/// expect-text 160 38 Stopwatch
+/// expect-checked 270 30
+/// expect-unchecked 270 30
+/// report
+/// ```
+///
+/// Results are logged with a `ui-test:` prefix. Coordinates are in display
+/// pixels, and the touch screen is ignored while commands are pending. Lines
+/// for the [`console`] are passed on to it.
+///
+/// [`run_script`]: Harness::run_script
+pub struct Harness {
+    state: Rc<RefCell<State>>,
+    _timer: Timer,
+}
+
+impl Harness {
+    pub fn new(mut serial: UartRx<'static, Blocking>) -> Self {
+        let state = Rc::new(RefCell::new(State::default()));
+        let mut line = String::new();
+        let mut buffer = [0u8; 64];
+        let timer = Timer::new(TICK_MS, {
+            let state = state.clone();
+            move || {
+                let mut state = state.borrow_mut();
+                while serial.read_ready() {
+                    let Ok(count) = serial.read_buffered(&mut buffer) else {
+                        break;
+                    };
+                    for &byte in &buffer[..count] {
+                        match byte {
+                            b'\n' | b'\r' => {
+                                if !line.is_empty() {
+                                    state.feed(&line);
+                                    line.clear();
+                                }
+                            }
+                            b' '..=b'~' if line.len() < MAX_LINE => line.push(byte as char),
+                            _ => {}
+                        }
+                    }
+                }
+                state.tick();
+            }
+        });
+        Self {
+            state,
+            _timer: timer,
+        }
+    }
+
+    /// Queues every line of `script`, after whatever is already pending.
+    pub fn run_script(&self, script: &str) {
+        let mut state = self.state.borrow_mut();
+        for line in script.lines() {
+            state.feed(line);
+        }
+    }
+}
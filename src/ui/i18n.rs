@@ -0,0 +1,509 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::ffi::CStr;
+use core::ptr::NonNull;
+use core::sync::atomic::{AtomicU8, Ordering};
+
+use embassy_sync::blocking_mutex::Mutex;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use lv_bevy_ecs::sys::{
+    LV_EVENT_DELETE, lv_event_get_target, lv_event_t, lv_label_set_text_static,
+    lv_obj_add_event_cb, lv_obj_t,
+};
+use lv_bevy_ecs::widgets::Label;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    English = 0,
+    Dutch = 1,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::English, Language::Dutch];
+
+    /// Name of the language in itself
+    pub fn name(self) -> &'static CStr {
+        match self {
+            Language::English => c"English",
+            Language::Dutch => c"Nederlands",
+        }
+    }
+
+    /// Falls back to English for unknown indices.
+    pub fn from_index(index: u32) -> Self {
+        Self::ALL
+            .get(index as usize)
+            .copied()
+            .unwrap_or(Language::English)
+    }
+}
+
+/// Identifiers of the translated strings.
+#[derive(Clone, Copy)]
+pub enum Text {
+    Back,
+    Settings,
+    Language,
+    Stopwatch,
+    Calculator,
+    Pomodoro,
+    Paint,
+    Snake,
+    Home,
+    About,
+    Gallery,
+    Alarm,
+    Converter,
+    Terminal,
+    Wifi,
+    Start,
+    Stop,
+    Pause,
+    Lap,
+    Reset,
+    Skip,
+    Undo,
+    Clear,
+    Restart,
+    SetClock,
+    Dismiss,
+    Snooze,
+    Cancel,
+    Fonts,
+    Cjk,
+    Stress,
+    NightMode,
+    Off,
+    On,
+    Scheduled,
+    Brightness,
+    Mute,
+    Light,
+    Timezone,
+    Sleep,
+    Memory,
+    Audio,
+    Play,
+    Volume,
+    RichText,
+    Display,
+    Network,
+    System,
+    Rotation,
+    Normal,
+    UpsideDown,
+    ResetPrompt,
+    Mqtt,
+    Accent,
+    PinLock,
+    EnterPin,
+    WrongPin,
+    TryAgainIn,
+    NewPin,
+    Remove,
+    AnimatedImage,
+    FrameRate,
+    Notifications,
+    ClearAll,
+    NothingNew,
+    Storage,
+    Calibration,
+    Peripherals,
+    TimeSync,
+    Images,
+    Downloading,
+    Lottie,
+    Layout,
+    Remote,
+    Distance,
+    Can,
+    Modbus,
+    Gps,
+    Screensaver,
+    BatterySaver,
+    LowBattery,
+    PressMe,
+    EnableFeature,
+    Fruits,
+    PlainLabel,
+    First,
+    Second,
+    Third,
+    Fourth,
+    Fifth,
+    Weekdays,
+    TypeHere,
+    Button,
+    Chime,
+    Sweep,
+    ArcIsAt,
+    WhichCountsAs,
+    DragArc,
+    Low,
+    Moderate,
+    High,
+    Scanning,
+    LearnPrompt,
+    Learn,
+    Defaults,
+    SwipeToStart,
+    Focus,
+    Break,
+    LivingRoom,
+    Kitchen,
+    Bedroom,
+    Office,
+    MqttDisabled,
+    BrokerOffline,
+    SharedWithHomeAssistant,
+    Debug,
+    Recalibrate,
+    Benchmark,
+    Log,
+    Reboot,
+    Close,
+    Ok,
+    Fault,
+    NoGpsData,
+    Sentences,
+    Bad,
+    Satellites,
+    WaitingForFix,
+    Altitude,
+    Speed,
+    NoFix,
+    GpsFix,
+    DgpsFix,
+    RtkFix,
+    Estimated,
+    Up,
+    Down,
+    Left,
+    Right,
+    Press,
+    OnTheRemote,
+    BackToDefaults,
+    ThatIs,
+    AlreadyPress,
+    Learned,
+    AlarmCloserThan,
+    SomethingAt,
+    Byte,
+    NewValueFor,
+    NotAValueFor,
+    RegisterWritten,
+    WriteFailed,
+    ModbusQueueFull,
+    NoReply,
+    BadCrc,
+    BadReply,
+    Exception,
+    UartError,
+    NoImagesInFlash,
+    FlashImagesWith,
+    CouldNotDownload,
+    LayoutReloaded,
+    Step,
+    WifiSetup,
+    JoinTheNetwork,
+    ThenSignIn,
+    BatteryLow,
+}
+
+/// One row per [`Text`], one column per [`Language`].
+///
+/// The built-in Montserrat font only covers ASCII, so translations must not
+/// need other glyphs.
+const STRINGS: [[&CStr; 2]; 167] = [
+    [c"Back", c"Terug"],
+    [c"Settings", c"Instellingen"],
+    [c"Language", c"Taal"],
+    [c"Stopwatch", c"Stopwatch"],
+    [c"Calculator", c"Rekenmachine"],
+    [c"Pomodoro", c"Pomodoro"],
+    [c"Paint", c"Tekenen"],
+    [c"Snake", c"Slang"],
+    [c"Home", c"Thuis"],
+    [c"About", c"Over"],
+    [c"Gallery", c"Galerij"],
+    [c"Alarm", c"Wekker"],
+    [c"Converter", c"Omrekenen"],
+    [c"Terminal", c"Terminal"],
+    [c"WiFi", c"WiFi"],
+    [c"Start", c"Start"],
+    [c"Stop", c"Stop"],
+    [c"Pause", c"Pauze"],
+    [c"Lap", c"Ronde"],
+    [c"Reset", c"Wissen"],
+    [c"Skip", c"Overslaan"],
+    [c"Undo", c"Herstel"],
+    [c"Clear", c"Leeg"],
+    [c"Restart", c"Opnieuw"],
+    [c"Set clock", c"Klok zetten"],
+    [c"Dismiss", c"Uit"],
+    [c"Snooze", c"Sluimer"],
+    [c"Cancel", c"Annuleren"],
+    [c"Fonts", c"Lettertypen"],
+    [c"CJK", c"CJK"],
+    [c"Stress", c"Stress"],
+    [c"Night mode", c"Nachtmodus"],
+    [c"Off", c"Uit"],
+    [c"On", c"Aan"],
+    [c"Scheduled", c"Gepland"],
+    [c"Brightness", c"Helderheid"],
+    [c"Mute", c"Dempen"],
+    [c"Light", c"Lamp"],
+    [c"Time zone", c"Tijdzone"],
+    [c"Sleep", c"Slaapstand"],
+    [c"Memory", c"Geheugen"],
+    [c"Audio", c"Geluid"],
+    [c"Play", c"Afspelen"],
+    [c"Volume", c"Volume"],
+    [c"Rich text", c"Opmaak"],
+    [c"Display", c"Scherm"],
+    [c"Network", c"Netwerk"],
+    [c"System", c"Systeem"],
+    [c"Rotation", c"Draaiing"],
+    [c"Normal", c"Normaal"],
+    [c"Upside down", c"Ondersteboven"],
+    [
+        c"Erase all settings and the WiFi network, then restart?",
+        c"Alle instellingen en het WiFi-netwerk wissen en opnieuw starten?",
+    ],
+    [c"MQTT", c"MQTT"],
+    [c"Accent color", c"Accentkleur"],
+    [c"PIN lock", c"Pincodeslot"],
+    [c"Enter PIN", c"Voer pincode in"],
+    [c"Wrong PIN", c"Verkeerde pincode"],
+    [c"Try again in", c"Opnieuw over"],
+    [
+        c"New PIN, 4 to 8 digits",
+        c"Nieuwe pincode, 4 tot 8 cijfers",
+    ],
+    [c"Remove", c"Verwijderen"],
+    [c"Animation", c"Animatie"],
+    [c"Frame rate", c"Beeldsnelheid"],
+    [c"Notifications", c"Meldingen"],
+    [c"Clear all", c"Alles wissen"],
+    [c"Nothing new", c"Niets nieuws"],
+    [c"Storage", c"Opslag"],
+    [c"Touch calibration", c"Aanraakkalibratie"],
+    [c"Peripherals", c"Randapparatuur"],
+    [c"Time sync", c"Tijdsynchronisatie"],
+    [c"Images", c"Afbeeldingen"],
+    [c"Downloading...", c"Downloaden..."],
+    [c"Lottie", c"Lottie"],
+    [c"Layout", c"Indeling"],
+    [c"Remote", c"Afstandsbediening"],
+    [c"Distance", c"Afstand"],
+    [c"CAN bus", c"CAN-bus"],
+    [c"Modbus", c"Modbus"],
+    [c"GPS", c"GPS"],
+    [c"Screensaver", c"Schermbeveiliging"],
+    [c"Battery saver", c"Batterijbesparing"],
+    [c"Low battery", c"Batterij bijna leeg"],
+    [c"Press me", c"Druk op mij"],
+    [c"Enable feature", c"Functie aanzetten"],
+    [c"Apple\nBanana\nCherry", c"Appel\nBanaan\nKers"],
+    [
+        c"Plain text label\nwith a second line",
+        c"Gewoon tekstlabel\nmet een tweede regel",
+    ],
+    [c"First", c"Eerste"],
+    [c"Second", c"Tweede"],
+    [c"Third", c"Derde"],
+    [c"Fourth", c"Vierde"],
+    [c"Fifth", c"Vijfde"],
+    [
+        c"Monday\nTuesday\nWednesday\nThursday\nFriday\nSaturday\nSunday",
+        c"Maandag\nDinsdag\nWoensdag\nDonderdag\nVrijdag\nZaterdag\nZondag",
+    ],
+    [c"Type here", c"Typ hier"],
+    [c"Button", c"Knop"],
+    [c"Chime", c"Gong"],
+    [c"Sweep", c"Glijtoon"],
+    [c"The arc below is at ", c"De boog hieronder staat op "],
+    [c", which counts as ", c", wat telt als "],
+    [
+        c". Drag it and this sentence follows.",
+        c". Versleep hem en deze zin volgt mee.",
+    ],
+    [c"low", c"laag"],
+    [c"moderate", c"gemiddeld"],
+    [c"high", c"hoog"],
+    [c"Scanning...", c"Zoeken..."],
+    [
+        c"Press Learn, then the keys it asks for",
+        c"Druk op Leren en dan op de gevraagde toetsen",
+    ],
+    [c"Learn", c"Leren"],
+    [c"Defaults", c"Standaard"],
+    [c"Swipe to start", c"Veeg om te starten"],
+    [c"Focus", c"Focus"],
+    [c"Break", c"Rust"],
+    [c"Living room", c"Woonkamer"],
+    [c"Kitchen", c"Keuken"],
+    [c"Bedroom", c"Slaapkamer"],
+    [c"Office", c"Kantoor"],
+    [
+        c"Build with MQTT_BROKER to share this light",
+        c"Bouw met MQTT_BROKER om deze lamp te delen",
+    ],
+    [c"MQTT broker offline", c"MQTT-broker offline"],
+    [c"Shared with Home Assistant", c"Gedeeld met Home Assistant"],
+    [c"Debug", c"Debug"],
+    [c"Recalibrate", c"Herkalibreren"],
+    [c"Benchmark", c"Benchmark"],
+    [c"Log", c"Logboek"],
+    [c"Reboot", c"Herstarten"],
+    [c"Close", c"Sluiten"],
+    [c"OK", c"OK"],
+    [c"Fault", c"Storing"],
+    [
+        c"No data from the GPS module",
+        c"Geen gegevens van de GPS-module",
+    ],
+    [c"sentences", c"zinnen"],
+    [c"bad", c"fout"],
+    [c"satellites", c"satellieten"],
+    [c"Waiting for a fix", c"Wachten op een fix"],
+    [c"Altitude", c"Hoogte"],
+    [c"Speed", c"Snelheid"],
+    [c"No fix", c"Geen fix"],
+    [c"GPS fix", c"GPS-fix"],
+    [c"DGPS fix", c"DGPS-fix"],
+    [c"RTK fix", c"RTK-fix"],
+    [c"Estimated", c"Geschat"],
+    [c"Up", c"Omhoog"],
+    [c"Down", c"Omlaag"],
+    [c"Left", c"Links"],
+    [c"Right", c"Rechts"],
+    [c"Press", c"Druk op"],
+    [c"on the remote", c"op de afstandsbediening"],
+    [
+        c"Back to the default codes",
+        c"Terug naar de standaardcodes",
+    ],
+    [c"That is", c"Dat is"],
+    [c"already, press", c"al, druk op"],
+    [
+        c"Learned, the remote works as a keypad now",
+        c"Geleerd, de afstandsbediening werkt nu als toetsenbord",
+    ],
+    [c"Alarm closer than", c"Alarm dichterbij dan"],
+    [c"Something at", c"Iets op"],
+    [c"byte", c"byte"],
+    [c"New value for", c"Nieuwe waarde voor"],
+    [c"Not a value for", c"Geen waarde voor"],
+    [c"Register written", c"Register geschreven"],
+    [c"Write failed", c"Schrijven mislukt"],
+    [c"The Modbus queue is full", c"De Modbus-wachtrij is vol"],
+    [c"No reply", c"Geen antwoord"],
+    [c"Bad CRC", c"Foute CRC"],
+    [c"Bad reply", c"Fout antwoord"],
+    [c"Exception", c"Uitzondering"],
+    [c"UART error", c"UART-fout"],
+    [c"No images in flash.", c"Geen afbeeldingen in flash."],
+    [
+        c"Flash a tar archive of them with",
+        c"Flash er een tar-archief van met",
+    ],
+    [
+        c"Could not download the image",
+        c"Kon de afbeelding niet downloaden",
+    ],
+    [c"Layout reloaded", c"Indeling herladen"],
+    [c"Step", c"Stap"],
+    [c"WiFi setup", c"WiFi instellen"],
+    [c"Join the network", c"Verbind met het netwerk"],
+    [
+        c"then follow the sign in prompt, or open",
+        c"volg dan de aanmeldmelding, of open",
+    ],
+    [c"Battery low", c"Batterij bijna leeg"],
+];
+
+impl Text {
+    /// The string in the current language.
+    pub fn get(self) -> &'static CStr {
+        STRINGS[self as usize][language() as usize]
+    }
+
+    /// Whether `name` is the string in any language, ignoring ASCII case.
+    pub fn matches(self, name: &str) -> bool {
+        STRINGS[self as usize]
+            .iter()
+            .any(|text| text.to_bytes().eq_ignore_ascii_case(name.as_bytes()))
+    }
+}
+
+struct Bound {
+    obj: NonNull<lv_obj_t>,
+    binding: Binding,
+}
+
+enum Binding {
+    /// The object is a label showing the text
+    Text(Text),
+    /// Sets whatever texts the object shows
+    Refresh(Box<dyn FnMut()>),
+}
+
+// Bindings are only made and run from the UI task
+unsafe impl Send for Bound {}
+
+static LANGUAGE: AtomicU8 = AtomicU8::new(Language::English as u8);
+static BOUND: Mutex<CriticalSectionRawMutex, RefCell<Vec<Bound>>> =
+    Mutex::new(RefCell::new(Vec::new()));
+
+pub fn language() -> Language {
+    Language::from_index(LANGUAGE.load(Ordering::Relaxed) as u32)
+}
+
+/// Switches the language and retranslates every label bound with
+/// [`translate`], then runs the [`on_language_change`] callbacks.
+pub fn set_language(language: Language) {
+    LANGUAGE.store(language as u8, Ordering::Relaxed);
+    BOUND.lock(|bound| {
+        for bound in bound.borrow_mut().iter_mut() {
+            match &mut bound.binding {
+                Binding::Text(text) => unsafe {
+                    lv_label_set_text_static(bound.obj.as_ptr(), text.get().as_ptr())
+                },
+                Binding::Refresh(refresh) => refresh(),
+            }
+        }
+    });
+}
+
+/// Shows `text` on `label` and keeps it translated when the language changes.
+///
+/// The binding goes away with the label. Labels that change their text at
+/// runtime should use [`Text::get`] instead, with [`on_language_change`] to
+/// build it again.
+pub fn translate(label: &mut Label, text: Text) {
+    label.set_text_static(text.get());
+    bind(label.raw(), Binding::Text(text));
+}
+
+/// Calls `refresh` after every language change until LVGL deletes `obj`, for
+/// texts built at runtime, such as dropdown options or a changing prompt.
+///
+/// `refresh` should only set texts. Creating or deleting widgets from it
+/// could bind or unbind while [`set_language`] goes through the bindings.
+pub fn on_language_change(obj: *mut lv_obj_t, refresh: impl FnMut() + 'static) {
+    bind(obj, Binding::Refresh(Box::new(refresh)));
+}
+
+fn bind(obj: *mut lv_obj_t, binding: Binding) {
+    unsafe { lv_obj_add_event_cb(obj, Some(unbind), LV_EVENT_DELETE, core::ptr::null_mut()) };
+    let obj = NonNull::new(obj).unwrap();
+    BOUND.lock(|bound| bound.borrow_mut().push(Bound { obj, binding }));
+}
+
+/// Forgets the bindings of an object as LVGL deletes it.
+unsafe extern "C" fn unbind(event: *mut lv_event_t) {
+    let obj = unsafe { lv_event_get_target(event) }.cast::<lv_obj_t>();
+    BOUND.lock(|bound| bound.borrow_mut().retain(|bound| bound.obj.as_ptr() != obj));
+}
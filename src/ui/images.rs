@@ -0,0 +1,257 @@
+use alloc::boxed::Box;
+use alloc::ffi::CString;
+use alloc::format;
+use alloc::rc::Rc;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+
+use lv_bevy_ecs::events::EventCode;
+use lv_bevy_ecs::support::Align;
+use lv_bevy_ecs::sys::{
+    LV_COLOR_FORMAT_RAW, LV_IMAGE_HEADER_MAGIC, lv_dropdown_get_selected, lv_dropdown_set_options,
+    lv_image_cache_drop, lv_image_create, lv_image_dsc_t, lv_image_header_cache_drop,
+    lv_image_set_src, lv_label_set_text, lv_obj_center, lv_obj_t,
+};
+use lv_bevy_ecs::widgets::{Button, Dropdown, Label, Obj};
+
+use super::i18n::Text;
+use super::module::{Resources, UiModule};
+use super::screen::Screen;
+use super::timer::Timer;
+use super::{back_button, builder};
+use crate::assets::{ASSETS_OFFSET, Assets};
+use crate::download;
+
+/// Extensions of the files LVGL has a decoder for
+const EXTENSIONS: [&str; 3] = [".png", ".jpg", ".jpeg"];
+const POLL_PERIOD_MS: u32 = 250;
+
+fn is_image(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    EXTENSIONS.iter().any(|extension| name.ends_with(extension))
+}
+
+#[derive(Clone, Copy)]
+enum Source {
+    /// In the assets partition
+    File(&'static str),
+    /// One of [`download::urls`]
+    Url(&'static str),
+}
+
+/// A downloaded image, which LVGL decodes from RAM.
+struct Download {
+    /// The image widget points at this
+    dsc: Box<lv_image_dsc_t>,
+    _data: Arc<[u8]>,
+}
+
+impl Download {
+    fn new(data: Arc<[u8]>) -> Self {
+        let mut dsc: Box<lv_image_dsc_t> = Box::new(unsafe { core::mem::zeroed() });
+        dsc.header.set_magic(LV_IMAGE_HEADER_MAGIC);
+        // Left to the PNG and JPEG decoders, which read the size from the data
+        dsc.header.set_cf(LV_COLOR_FORMAT_RAW as _);
+        dsc.data_size = data.len() as u32;
+        dsc.data = data.as_ptr();
+        Self { dsc, _data: data }
+    }
+}
+
+impl Drop for Download {
+    fn drop(&mut self) {
+        // LVGL caches the decoded image by the address of its source, which
+        // some later download could reuse
+        let src = (&*self.dsc as *const lv_image_dsc_t).cast();
+        unsafe {
+            lv_image_cache_drop(src);
+            lv_image_header_cache_drop(src);
+        }
+    }
+}
+
+/// What is on display, changed by the list and the download poll.
+struct Viewer {
+    image: *mut lv_obj_t,
+    status: *mut lv_obj_t,
+    /// URL being downloaded, with the download revision when it was asked for
+    waiting: Option<(&'static str, u32)>,
+    shown: Option<Download>,
+}
+
+impl Viewer {
+    fn select(&mut self, source: Source) {
+        self.waiting = None;
+        self.set_status(None);
+        match source {
+            Source::File(name) => {
+                let path = Assets::path(name);
+                unsafe { lv_image_set_src(self.image, path.as_ptr().cast()) };
+                self.shown = None;
+            }
+            Source::Url(url) => match download::cached(url) {
+                Some(data) => self.show(data),
+                None => {
+                    unsafe { lv_image_set_src(self.image, core::ptr::null()) };
+                    self.shown = None;
+                    self.waiting = Some((url, download::revision()));
+                    self.set_status(Some(Text::Downloading));
+                    download::fetch(url);
+                }
+            },
+        }
+        unsafe { lv_obj_center(self.image) };
+    }
+
+    fn show(&mut self, data: Arc<[u8]>) {
+        let download = Download::new(data);
+        unsafe { lv_image_set_src(self.image, (&*download.dsc as *const lv_image_dsc_t).cast()) };
+        // Only dropped once the image points elsewhere
+        self.shown = Some(download);
+    }
+
+    /// Picks up the download waited for, once the task is done with it.
+    fn poll(&mut self) {
+        let Some((url, revision)) = self.waiting else {
+            return;
+        };
+        if download::revision() == revision {
+            return;
+        }
+        if let Some(data) = download::cached(url) {
+            self.waiting = None;
+            self.set_status(None);
+            self.show(data);
+            unsafe { lv_obj_center(self.image) };
+        } else if download::finished() == Some(url) {
+            // The task already showed why in a toast
+            self.waiting = None;
+            self.set_status(None);
+        } else {
+            // Some earlier download ended, this one is up next
+            self.waiting = Some((url, download::revision()));
+        }
+    }
+
+    fn set_status(&self, text: Option<Text>) {
+        let text = text.map_or(c"", Text::get);
+        unsafe { lv_label_set_text(self.status, text.as_ptr()) };
+    }
+}
+
+/// Images from the assets partition, and from the URLs it was built with,
+/// decoded when picked from the list.
+///
+/// Downloads stay cached in RAM, so showing one again does not fetch it again.
+pub struct Images {
+    _back: (Button, Label),
+    _title: Label,
+    _files: Option<Dropdown>,
+    _timer: Option<Timer>,
+    _message: Label,
+    /// Holds the image, which scrolls in it when larger
+    _frame: Obj,
+    /// Goes after the frame, as the image may show from its download
+    _viewer: Rc<RefCell<Viewer>>,
+}
+
+impl Images {
+    /// Builds the viewer on the active screen. The back button loads `home`.
+    pub fn new(home: Screen, assets: Assets) -> Self {
+        let back = back_button(home);
+
+        let title = builder::label()
+            .translated(Text::Images)
+            .align(Align::TopMid)
+            .offset(0, 12)
+            .build();
+
+        let mut frame = Obj::new();
+        frame.set_size(300, 140);
+        frame.align(Align::BottomMid.into(), 0, -10);
+        let image = unsafe { lv_image_create(frame.raw()) };
+
+        let mut message = Label::new();
+        message.set_parent(&mut frame);
+        message.center();
+        let viewer = Rc::new(RefCell::new(Viewer {
+            image,
+            status: message.raw(),
+            waiting: None,
+            shown: None,
+        }));
+
+        let sources = assets
+            .names()
+            .filter(|&name| is_image(name))
+            .map(Source::File)
+            .chain(download::urls().map(Source::Url))
+            .collect::<Vec<_>>();
+        let Some(&first) = sources.first() else {
+            let text = format!(
+                "{}\n{}\nespflash write-bin {:#x} assets.tar",
+                Text::NoImagesInFlash.get().to_str().unwrap(),
+                Text::FlashImagesWith.get().to_str().unwrap(),
+                ASSETS_OFFSET
+            );
+            message.set_text(CString::new(text).unwrap().as_c_str());
+            return Self {
+                _back: back,
+                _title: title,
+                _files: None,
+                _timer: None,
+                _message: message,
+                _frame: frame,
+                _viewer: viewer,
+            };
+        };
+
+        let options = sources
+            .iter()
+            .map(|source| match source {
+                Source::File(name) | Source::Url(name) => *name,
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let mut files = Dropdown::new();
+        files.set_width(200);
+        files.align(Align::TopMid.into(), 0, 45);
+        let files_raw = files.raw();
+        let options = CString::new(options).unwrap_or_default();
+        unsafe { lv_dropdown_set_options(files_raw, options.as_ptr()) };
+        files.add_event_cb(EventCode::ValueChanged, {
+            let viewer = viewer.clone();
+            move |_| {
+                let index = unsafe { lv_dropdown_get_selected(files_raw) } as usize;
+                if let Some(&source) = sources.get(index) {
+                    viewer.borrow_mut().select(source);
+                }
+            }
+        });
+        viewer.borrow_mut().select(first);
+
+        let timer = Timer::new(POLL_PERIOD_MS, {
+            let viewer = viewer.clone();
+            move || viewer.borrow_mut().poll()
+        });
+
+        Self {
+            _back: back,
+            _title: title,
+            _files: Some(files),
+            _timer: Some(timer),
+            _message: message,
+            _frame: frame,
+            _viewer: viewer,
+        }
+    }
+}
+
+impl UiModule for Images {
+    const NAME: Text = Text::Images;
+
+    fn build(home: Screen, resources: &mut Resources) -> Self {
+        Self::new(home, *resources.get::<Assets>())
+    }
+}
@@ -0,0 +1,138 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::ffi::c_void;
+
+use lv_bevy_ecs::events::EventCode;
+use lv_bevy_ecs::support::Align;
+use lv_bevy_ecs::sys::{
+    LV_COLOR_FORMAT_RGB565A8, LV_FLEX_FLOW_ROW, LV_IMAGEBUTTON_STATE_DISABLED,
+    LV_IMAGEBUTTON_STATE_PRESSED, LV_IMAGEBUTTON_STATE_RELEASED, LV_OBJ_FLAG_EVENT_BUBBLE,
+    lv_color_hex, lv_draw_buf_init, lv_draw_buf_t, lv_imagebutton_create, lv_imagebutton_set_src,
+    lv_imagebutton_state_t, lv_obj_add_flag, lv_obj_remove_style_all, lv_obj_set_flex_flow,
+    lv_obj_set_parent, lv_obj_set_style_pad_bottom, lv_obj_set_style_pad_column,
+    lv_obj_set_style_pad_left, lv_obj_set_style_pad_right, lv_obj_set_style_pad_top,
+    lv_obj_set_style_text_color, lv_obj_set_width,
+};
+use lv_bevy_ecs::widgets::{Label, Obj};
+
+use super::Aligned;
+use super::i18n::{Text, translate};
+use super::screen::Screen;
+use super::state;
+
+/// The width of the display
+const ROW_WIDTH: i32 = 320;
+/// Tiles wholly in view, with half of the next one peeking in to show that
+/// the row scrolls
+const COLUMNS: i32 = 3;
+/// Before the first tile and between tiles
+const TILE_GAP: i32 = 8;
+const TILE_WIDTH: i32 = (ROW_WIDTH - TILE_GAP * (COLUMNS + 1)) * 2 / (COLUMNS * 2 + 1);
+/// Height of the tile art
+const TILE_HEIGHT: i32 = 40;
+const ROW_PAD: i32 = 5;
+/// Tile art for each state, in the build script's `TILE_STATES` order
+const STATES: [lv_imagebutton_state_t; 3] = [
+    LV_IMAGEBUTTON_STATE_RELEASED,
+    LV_IMAGEBUTTON_STATE_PRESSED,
+    LV_IMAGEBUTTON_STATE_DISABLED,
+];
+
+/// Part of a tile image, RGB565A8 as written by the build script
+struct Slice {
+    width: u32,
+    height: u32,
+    data: &'static [u8],
+}
+
+// `SLICES`, the left end, middle and right end of each state
+include!(concat!(env!("OUT_DIR"), "/launcher_tiles.rs"));
+
+/// Horizontally scrolling row of image button tiles at the bottom of the
+/// home screen, one for each app screen.
+///
+/// The tiles come from `assets/launcher`, see `build.rs`. Their middle is
+/// repeated to the tile width, so one image per state fits every label.
+pub struct Launcher {
+    row: Obj,
+    /// What the image buttons draw, in `SLICES` order
+    images: Box<[lv_draw_buf_t]>,
+    entries: Vec<(Obj, Label)>,
+}
+
+impl Launcher {
+    pub fn new() -> Self {
+        let mut row = Obj::new();
+        row.set_size(ROW_WIDTH, 50);
+        row.align(Align::BottomMid.into(), 0, 0);
+        unsafe {
+            lv_obj_set_flex_flow(row.raw(), LV_FLEX_FLOW_ROW);
+            // Leaves the tile art its full height
+            lv_obj_set_style_pad_top(row.raw(), ROW_PAD, 0);
+            lv_obj_set_style_pad_bottom(row.raw(), ROW_PAD, 0);
+            lv_obj_set_style_pad_left(row.raw(), TILE_GAP, 0);
+            lv_obj_set_style_pad_right(row.raw(), TILE_GAP, 0);
+            lv_obj_set_style_pad_column(row.raw(), TILE_GAP, 0);
+        }
+
+        let images = SLICES
+            .iter()
+            .flatten()
+            .map(|slice| unsafe {
+                let mut image: lv_draw_buf_t = core::mem::zeroed();
+                lv_draw_buf_init(
+                    &mut image,
+                    slice.width,
+                    slice.height,
+                    LV_COLOR_FORMAT_RGB565A8,
+                    0,
+                    slice.data.as_ptr().cast_mut().cast(),
+                    slice.data.len() as u32,
+                );
+                image
+            })
+            .collect();
+
+        Self {
+            row,
+            images,
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn add(&mut self, name: Text, screen: Screen) {
+        // Takes the clicks the image button passes up, as the image button
+        // itself has no wrapper to add a closure to
+        let mut tile = Obj::new();
+        tile.set_parent(&mut self.row);
+        unsafe { lv_obj_remove_style_all(tile.raw()) };
+        tile.set_size(TILE_WIDTH, TILE_HEIGHT);
+        let button = unsafe { lv_imagebutton_create(tile.raw()) };
+        unsafe {
+            lv_obj_set_width(button, TILE_WIDTH);
+            lv_obj_add_flag(button, LV_OBJ_FLAG_EVENT_BUBBLE);
+            for (&state, images) in STATES.iter().zip(self.images.chunks_exact(3)) {
+                let [left, middle, right] = [0, 1, 2]
+                    .map(|index| (&images[index] as *const lv_draw_buf_t).cast::<c_void>());
+                lv_imagebutton_set_src(button, state, left, middle, right);
+            }
+        }
+
+        let mut label = Label::new();
+        translate(&mut label, name);
+        unsafe {
+            lv_obj_set_parent(label.raw(), button);
+            lv_obj_set_style_text_color(label.raw(), lv_color_hex(0xFFFFFF), 0);
+        }
+        label.center();
+
+        tile.add_event_cb(EventCode::Clicked, move |_| state::set_next(screen));
+        self.entries.push((tile, label));
+    }
+}
+
+impl Default for Launcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
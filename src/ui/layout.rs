@@ -0,0 +1,237 @@
+use alloc::boxed::Box;
+use alloc::ffi::CString;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use embassy_sync::blocking_mutex::Mutex;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use lv_bevy_ecs::support::Align;
+use lv_bevy_ecs::sys::{
+    LV_ANIM_OFF, LV_OPA_COVER, lv_arc_set_range, lv_arc_set_value, lv_bar_set_range,
+    lv_bar_set_value, lv_color_hex, lv_label_set_text, lv_obj_set_style_bg_color,
+    lv_obj_set_style_bg_opa, lv_obj_set_style_text_color, lv_obj_t, lv_slider_set_range,
+    lv_slider_set_value,
+};
+use lv_bevy_ecs::widgets::{Arc, Bar, Button, Label, Obj, Slider, Switch};
+
+use super::builder::Widget;
+use crate::json::{self, Value};
+
+static UPLOADED: Mutex<CriticalSectionRawMutex, RefCell<Option<String>>> =
+    Mutex::new(RefCell::new(None));
+/// Bumped by every [`upload`], so screens know to load the layout again
+static UPLOADS: AtomicU32 = AtomicU32::new(0);
+
+/// Replaces the layout from flash until the next restart. Safe to call from
+/// any task.
+pub fn upload(text: String) {
+    UPLOADED.lock(|uploaded| *uploaded.borrow_mut() = Some(text));
+    UPLOADS.fetch_add(1, Ordering::Relaxed);
+}
+
+pub fn uploads() -> u32 {
+    UPLOADS.load(Ordering::Relaxed)
+}
+
+/// The layout last given to [`upload`].
+pub fn uploaded() -> Option<String> {
+    UPLOADED.lock(|uploaded| uploaded.borrow().clone())
+}
+
+/// Screen content described in JSON, so the layout can change without
+/// building the firmware again.
+///
+/// The document is an object with a `widgets` array. Each widget is an
+/// object with a `type` out of `obj`, `label`, `button`, `arc`, `bar`,
+/// `slider` and `switch`, and optionally:
+///
+/// - `id`, to find it with [`Layout::obj`] and bind handlers to it
+/// - `align`, like `top_mid`, and `x` and `y` offsets from there
+/// - `width` and `height`
+/// - `text`, for labels and buttons
+/// - `min`, `max` and `value`, for arcs, bars and sliders
+/// - `background` and `text_color`, as `#RRGGBB`
+/// - `children`, more widgets inside this one
+///
+/// ```text
+/// {"widgets": [
+///   {"type": "label", "id": "count", "text": "0", "align": "center"},
+///   {"type": "button", "id": "add", "text": "+1", "align": "bottom_mid", "y": -10}
+/// ]}
+/// ```
+///
+/// Unknown types and values are skipped with a warning, so a typo leaves
+/// the rest of the screen in place.
+pub struct Layout {
+    /// Parents before their children, so they are deleted the other way round
+    widgets: Vec<(Option<String>, Box<dyn Widget>)>,
+}
+
+impl Layout {
+    /// Creates the widgets described by `text` on the active screen.
+    pub fn load(text: &str) -> Result<Self, json::Error> {
+        let document = json::parse(text)?;
+        let mut layout = Self {
+            widgets: Vec::new(),
+        };
+        let widgets = document.get("widgets").map_or(&[][..], Value::as_array);
+        for widget in widgets {
+            layout.create(widget, None);
+        }
+        Ok(layout)
+    }
+
+    /// The widget with `id`, for binding event handlers to. Logs a warning
+    /// when the layout has none, which usually means the file and the code
+    /// went out of step.
+    pub fn obj(&mut self, id: &str) -> Option<&mut Obj> {
+        let found = self
+            .widgets
+            .iter_mut()
+            .find(|(widget_id, _)| widget_id.as_deref() == Some(id));
+        if found.is_none() {
+            defmt::warn!("Layout has no widget {=str}", id);
+        }
+        found.map(|(_, widget)| widget.obj())
+    }
+
+    /// Like [`Layout::obj`], for the raw calls in handlers.
+    pub fn raw(&mut self, id: &str) -> Option<*mut lv_obj_t> {
+        self.obj(id).map(|obj| obj.raw())
+    }
+
+    fn create(&mut self, description: &Value, parent: Option<usize>) {
+        let kind = description.get("type").and_then(Value::as_str);
+        let text = description.get("text").and_then(Value::as_str);
+        let mut widget: Box<dyn Widget> = match kind {
+            Some("obj") => Box::new(Obj::new()),
+            Some("label") => Box::new(Label::new()),
+            Some("button") => Box::new(Button::new()),
+            Some("arc") => Box::new(Arc::new()),
+            Some("bar") => Box::new(Bar::new()),
+            Some("slider") => Box::new(Slider::new()),
+            Some("switch") => Box::new(Switch::new()),
+            _ => {
+                defmt::warn!("Skipped widget of unknown type {}", kind);
+                return;
+            }
+        };
+        if let Some(parent) = parent {
+            widget.obj().set_parent(self.widgets[parent].1.obj());
+        }
+        let raw = widget.obj().raw();
+
+        let number = |key| description.get(key).and_then(Value::as_i32);
+        match (number("width"), number("height")) {
+            (Some(width), Some(height)) => widget.obj().set_size(width, height),
+            (Some(width), None) => widget.obj().set_width(width),
+            (None, Some(height)) => widget.obj().set_height(height),
+            (None, None) => {}
+        }
+        if let Some(name) = description.get("align").and_then(Value::as_str) {
+            match align(name) {
+                Some(align) => widget.obj().set_align(align.into()),
+                None => defmt::warn!("Skipped unknown alignment {=str}", name),
+            }
+        }
+        widget
+            .obj()
+            .set_pos(number("x").unwrap_or(0), number("y").unwrap_or(0));
+
+        let color = |key| {
+            let text = description.get(key).and_then(Value::as_str)?;
+            let color = hex_color(text);
+            if color.is_none() {
+                defmt::warn!("Skipped bad color {=str}", text);
+            }
+            color
+        };
+        unsafe {
+            if let Some(background) = color("background") {
+                lv_obj_set_style_bg_color(raw, lv_color_hex(background), 0);
+                lv_obj_set_style_bg_opa(raw, LV_OPA_COVER as _, 0);
+            }
+            if let Some(text_color) = color("text_color") {
+                lv_obj_set_style_text_color(raw, lv_color_hex(text_color), 0);
+            }
+        }
+
+        if let Some(value) = number("value") {
+            let (min, max) = (number("min").unwrap_or(0), number("max").unwrap_or(100));
+            unsafe {
+                match kind {
+                    Some("arc") => {
+                        lv_arc_set_range(raw, min, max);
+                        lv_arc_set_value(raw, value);
+                    }
+                    Some("bar") => {
+                        lv_bar_set_range(raw, min, max);
+                        lv_bar_set_value(raw, value, LV_ANIM_OFF);
+                    }
+                    Some("slider") => {
+                        lv_slider_set_range(raw, min, max);
+                        lv_slider_set_value(raw, value, LV_ANIM_OFF);
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let id = description
+            .get("id")
+            .and_then(Value::as_str)
+            .map(String::from);
+        self.widgets.push((id, widget));
+        let index = self.widgets.len() - 1;
+
+        if let Some(text) = text {
+            let text = CString::new(text).unwrap_or_default();
+            match kind {
+                Some("label") => unsafe { lv_label_set_text(raw, text.as_ptr()) },
+                Some("button") => {
+                    let mut label = Label::new();
+                    label.set_parent(self.widgets[index].1.obj());
+                    label.set_text(text.as_c_str());
+                    label.center();
+                    self.widgets.push((None, Box::new(label)));
+                }
+                _ => {}
+            }
+        }
+
+        for child in description.get("children").map_or(&[][..], Value::as_array) {
+            self.create(child, Some(index));
+        }
+    }
+}
+
+impl Drop for Layout {
+    fn drop(&mut self) {
+        // Children first, as LVGL would delete them along with their parent
+        // before their own wrappers get to it
+        while self.widgets.pop().is_some() {}
+    }
+}
+
+fn align(name: &str) -> Option<Align> {
+    Some(match name {
+        "center" => Align::Center,
+        "top_left" => Align::TopLeft,
+        "top_mid" => Align::TopMid,
+        "top_right" => Align::TopRight,
+        "bottom_left" => Align::BottomLeft,
+        "bottom_mid" => Align::BottomMid,
+        "bottom_right" => Align::BottomRight,
+        "left_mid" => Align::LeftMid,
+        "right_mid" => Align::RightMid,
+        _ => return None,
+    })
+}
+
+/// Parses `#RRGGBB`.
+fn hex_color(text: &str) -> Option<u32> {
+    let digits = text.strip_prefix('#').filter(|digits| digits.len() == 6)?;
+    u32::from_str_radix(digits, 16).ok()
+}
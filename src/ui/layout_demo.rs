@@ -0,0 +1,139 @@
+use alloc::borrow::Cow;
+use alloc::ffi::CString;
+use alloc::format;
+use alloc::rc::Rc;
+use alloc::string::String;
+use core::cell::Cell;
+
+use lv_bevy_ecs::events::EventCode;
+use lv_bevy_ecs::sys::{lv_label_set_text, lv_obj_t, lv_slider_get_value};
+use lv_bevy_ecs::widgets::{Button, Label};
+
+use super::i18n::Text;
+use super::layout::{self, Layout};
+use super::module::{Resources, UiModule};
+use super::screen::Screen;
+use super::toast::{self, Severity};
+use super::{back_button, json_error};
+use crate::assets::Assets;
+
+/// Read from the assets partition when it is there, so the layout can be
+/// changed by flashing the archive again
+const FILE: &str = "layouts/counter.json";
+/// Used when the assets partition has no layout
+const BUILT_IN: &str = include_str!("../../assets/layouts/counter.json");
+
+fn set_text(label: Option<*mut lv_obj_t>, text: String) {
+    if let Some(label) = label {
+        let text = CString::new(text).unwrap_or_default();
+        unsafe { lv_label_set_text(label, text.as_ptr()) };
+    }
+}
+
+/// A counter whose screen is described in JSON, with its buttons and slider
+/// bound to Rust handlers by their IDs.
+///
+/// A layout uploaded over WiFi replaces the screen right away. The count
+/// and step live here rather than in the widgets, so they survive that.
+pub struct LayoutDemo {
+    _back: (Button, Label),
+    error: Option<Label>,
+    layout: Option<Layout>,
+    /// [`layout::uploads`] as of the layout on screen
+    shown: u32,
+    count: Rc<Cell<i32>>,
+    step: Rc<Cell<i32>>,
+}
+
+impl LayoutDemo {
+    /// Builds the demo on the active screen. The back button loads `home`.
+    pub fn new(home: Screen, assets: Assets) -> Self {
+        let back = back_button(home);
+        let mut demo = Self {
+            _back: back,
+            error: None,
+            layout: None,
+            shown: layout::uploads(),
+            count: Rc::new(Cell::new(0)),
+            step: Rc::new(Cell::new(1)),
+        };
+        let text = layout::uploaded()
+            .map(Cow::Owned)
+            .unwrap_or_else(|| assets.read_text_or(FILE, BUILT_IN));
+        demo.load(&text);
+        demo
+    }
+
+    /// Replaces whatever the last layout built with `text`.
+    fn load(&mut self, text: &str) {
+        // Gone before the new widgets are created, so they do not overlap
+        self.layout = None;
+        self.error = None;
+        match Layout::load(text) {
+            Ok(layout) => self.layout = Some(self.bind(layout)),
+            Err(error) => {
+                defmt::error!(
+                    "Could not load the layout, byte {}: {=str}",
+                    error.offset,
+                    error.message
+                );
+                self.error = Some(json_error(Text::Layout.get().to_str().unwrap(), &error));
+            }
+        }
+    }
+
+    fn bind(&self, mut layout: Layout) -> Layout {
+        let (count, step) = (self.count.clone(), self.step.clone());
+        let count_label = layout.raw("count");
+        let step_label = layout.raw("step_label");
+        set_text(count_label, format!("{}", count.get()));
+        set_text(
+            step_label,
+            format!("{}: {}", Text::Step.get().to_str().unwrap(), step.get()),
+        );
+        if let Some(add) = layout.obj("add") {
+            let (count, step) = (count.clone(), step.clone());
+            add.add_event_cb(EventCode::Clicked, move |_| {
+                count.set(count.get() + step.get());
+                set_text(count_label, format!("{}", count.get()));
+            });
+        }
+        if let Some(reset) = layout.obj("reset") {
+            reset.add_event_cb(EventCode::Clicked, move |_| {
+                count.set(0);
+                set_text(count_label, String::from("0"));
+            });
+        }
+        if let Some(slider) = layout.obj("step") {
+            let slider_raw = slider.raw();
+            slider.add_event_cb(EventCode::ValueChanged, move |_| {
+                step.set(unsafe { lv_slider_get_value(slider_raw) });
+                set_text(
+                    step_label,
+                    format!("{}: {}", Text::Step.get().to_str().unwrap(), step.get()),
+                );
+            });
+        }
+        layout
+    }
+}
+
+impl UiModule for LayoutDemo {
+    const NAME: Text = Text::Layout;
+
+    fn build(home: Screen, resources: &mut Resources) -> Self {
+        Self::new(home, *resources.get::<Assets>())
+    }
+
+    fn update(&mut self) {
+        let uploads = layout::uploads();
+        if uploads == self.shown {
+            return;
+        }
+        self.shown = uploads;
+        if let Some(text) = layout::uploaded() {
+            self.load(&text);
+            toast::show(Severity::Info, Text::LayoutReloaded.get().to_str().unwrap());
+        }
+    }
+}
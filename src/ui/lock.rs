@@ -0,0 +1,231 @@
+use alloc::ffi::CString;
+use alloc::format;
+use alloc::rc::Rc;
+use alloc::string::String;
+use core::cell::{Cell, RefCell};
+use core::ffi::CStr;
+
+use embassy_time::Instant;
+use lv_bevy_ecs::events::EventCode;
+use lv_bevy_ecs::support::Align;
+use lv_bevy_ecs::sys::{
+    LV_ALIGN_BOTTOM_MID, LV_BUTTONMATRIX_BUTTON_NONE, LV_OBJ_FLAG_HIDDEN, LV_OPA_COVER,
+    lv_buttonmatrix_get_button_text, lv_buttonmatrix_get_selected_button, lv_buttonmatrix_set_map,
+    lv_color_hex, lv_label_set_text, lv_layer_top, lv_obj_add_flag, lv_obj_align,
+    lv_obj_remove_flag, lv_obj_remove_style_all, lv_obj_set_parent, lv_obj_set_style_bg_color,
+    lv_obj_set_style_bg_opa, lv_obj_set_style_text_color, lv_obj_t,
+};
+use lv_bevy_ecs::widgets::{ButtonMatrix, Label, Obj};
+
+use super::ButtonMap;
+use super::i18n::{self, Text};
+use super::timer::Timer;
+use crate::pin;
+use crate::settings::Settings;
+
+const POLL_PERIOD_MS: u32 = 500;
+const BACKGROUND: u32 = 0x102027;
+const ENTRY_HEIGHT: i32 = 24;
+const PAD_WIDTH: i32 = 220;
+const PAD_HEIGHT: i32 = 150;
+/// Shown for every digit entered
+const MASK: &str = "*";
+/// `LV_SYMBOL_BACKSPACE`
+const BACKSPACE: &CStr = c"\u{F55A}";
+/// `LV_SYMBOL_OK`
+const OK: &CStr = c"\u{F00C}";
+
+static KEYPAD_MAP: ButtonMap<16> = ButtonMap([
+    c"1".as_ptr(),
+    c"2".as_ptr(),
+    c"3".as_ptr(),
+    c"\n".as_ptr(),
+    c"4".as_ptr(),
+    c"5".as_ptr(),
+    c"6".as_ptr(),
+    c"\n".as_ptr(),
+    c"7".as_ptr(),
+    c"8".as_ptr(),
+    c"9".as_ptr(),
+    c"\n".as_ptr(),
+    BACKSPACE.as_ptr(),
+    c"0".as_ptr(),
+    OK.as_ptr(),
+    c"".as_ptr(),
+]);
+
+/// Numeric keypad with the digits entered above it, masked.
+pub struct PinPad {
+    _entry: Label,
+    _keypad: ButtonMatrix,
+    /// Holds the entry and the keypad, so it goes after them
+    container: Obj,
+}
+
+impl PinPad {
+    /// Builds a `width` wide pad on the active screen. OK passes the digits
+    /// entered, at most [`pin::MAX_LEN`], to `submit` and clears them.
+    pub fn new(width: i32, keypad_height: i32, mut submit: impl FnMut(&str) + 'static) -> Self {
+        let mut container = Obj::new();
+        unsafe { lv_obj_remove_style_all(container.raw()) };
+        container.set_size(width, ENTRY_HEIGHT + keypad_height);
+
+        let mut entry = Label::new();
+        entry.set_parent(&mut container);
+        entry.set_text_static(c"");
+        entry.align(Align::TopMid.into(), 0, 0);
+        let entry_raw = entry.raw();
+
+        let mut keypad = ButtonMatrix::new();
+        keypad.set_parent(&mut container);
+        keypad.set_size(width, keypad_height);
+        keypad.align(Align::BottomMid.into(), 0, 0);
+        let raw = keypad.raw();
+        unsafe { lv_buttonmatrix_set_map(raw, KEYPAD_MAP.0.as_ptr()) };
+
+        let mut digits = String::new();
+        keypad.add_event_cb(EventCode::ValueChanged, move |_| {
+            let key = unsafe {
+                let id = lv_buttonmatrix_get_selected_button(raw);
+                if id == LV_BUTTONMATRIX_BUTTON_NONE {
+                    return;
+                }
+                CStr::from_ptr(lv_buttonmatrix_get_button_text(raw, id))
+            };
+            if key == OK {
+                submit(&digits);
+                digits.clear();
+            } else if key == BACKSPACE {
+                digits.pop();
+            } else if digits.len() < pin::MAX_LEN {
+                digits.push_str(key.to_str().unwrap_or_default());
+            }
+            set_text(entry_raw, MASK.repeat(digits.len()));
+        });
+
+        Self {
+            _entry: entry,
+            _keypad: keypad,
+            container,
+        }
+    }
+
+    pub fn raw(&self) -> *mut lv_obj_t {
+        self.container.raw()
+    }
+}
+
+/// Covers the display at boot, and so after waking from deep sleep, until
+/// the PIN kept by [`pin`] is entered.
+///
+/// Past a few wrong guesses the keypad is hidden for [`pin::lockout`]. The
+/// guesses are counted in flash, so a restart does not end the lock-out.
+pub struct Lock {
+    _timer: Timer,
+    _message: Label,
+    _pad: PinPad,
+    _overlay: Obj,
+}
+
+impl Lock {
+    /// Shows the lock screen if a PIN is set. Create it after the debug menu,
+    /// so holding its corner does not get past the lock.
+    pub fn new(settings: Rc<RefCell<Settings>>) -> Option<Self> {
+        if !pin::is_set(&settings.borrow()) {
+            return None;
+        }
+
+        let mut overlay = Obj::new();
+        unsafe {
+            lv_obj_set_parent(overlay.raw(), lv_layer_top());
+            lv_obj_set_style_bg_color(overlay.raw(), lv_color_hex(BACKGROUND), 0);
+            lv_obj_set_style_bg_opa(overlay.raw(), LV_OPA_COVER as _, 0);
+            lv_obj_set_style_text_color(overlay.raw(), lv_color_hex(0xFFFFFF), 0);
+        }
+        overlay.set_size(320, 240);
+        overlay.set_pos(0, 0);
+        let overlay_raw = overlay.raw();
+
+        let mut message = Label::new();
+        message.set_parent(&mut overlay);
+        message.set_text_static(Text::EnterPin.get());
+        message.align(Align::TopMid.into(), 0, 0);
+        let message_raw = message.raw();
+
+        let deadline = Rc::new(Cell::new(lockout_deadline(&settings.borrow())));
+        // Shown while not locked out, the countdown is redrawn by the timer
+        let prompt = Rc::new(Cell::new(Text::EnterPin));
+        i18n::on_language_change(message_raw, {
+            let (deadline, prompt) = (deadline.clone(), prompt.clone());
+            move || {
+                if deadline.get().is_none() {
+                    set_text(
+                        message_raw,
+                        String::from(prompt.get().get().to_str().unwrap()),
+                    );
+                }
+            }
+        });
+        let pad = PinPad::new(PAD_WIDTH, PAD_HEIGHT, {
+            let (deadline, prompt) = (deadline.clone(), prompt.clone());
+            move |digits| {
+                if deadline.get().is_some() {
+                    return;
+                }
+                if pin::check(&mut settings.borrow_mut(), digits) {
+                    unsafe { lv_obj_add_flag(overlay_raw, LV_OBJ_FLAG_HIDDEN) };
+                    return;
+                }
+                prompt.set(Text::WrongPin);
+                set_text(
+                    message_raw,
+                    String::from(Text::WrongPin.get().to_str().unwrap()),
+                );
+                deadline.set(lockout_deadline(&settings.borrow()));
+            }
+        });
+        let pad_raw = pad.raw();
+        unsafe {
+            lv_obj_set_parent(pad_raw, overlay_raw);
+            lv_obj_align(pad_raw, LV_ALIGN_BOTTOM_MID, 0, 0);
+        }
+
+        let refresh = move || {
+            let Some(until) = deadline.get() else {
+                return;
+            };
+            let now = Instant::now();
+            if now >= until {
+                deadline.set(None);
+                prompt.set(Text::EnterPin);
+                set_text(
+                    message_raw,
+                    String::from(Text::EnterPin.get().to_str().unwrap()),
+                );
+                unsafe { lv_obj_remove_flag(pad_raw, LV_OBJ_FLAG_HIDDEN) };
+            } else {
+                let seconds = (until - now).as_secs() + 1;
+                let prompt = Text::TryAgainIn.get().to_str().unwrap();
+                set_text(message_raw, format!("{prompt} {seconds} s"));
+                unsafe { lv_obj_add_flag(pad_raw, LV_OBJ_FLAG_HIDDEN) };
+            }
+        };
+        refresh();
+        let timer = Timer::new(POLL_PERIOD_MS, refresh);
+
+        Some(Self {
+            _timer: timer,
+            _message: message,
+            _pad: pad,
+            _overlay: overlay,
+        })
+    }
+}
+
+fn lockout_deadline(settings: &Settings) -> Option<Instant> {
+    pin::lockout(settings).map(|lockout| Instant::now() + lockout)
+}
+
+fn set_text(label: *mut lv_obj_t, text: String) {
+    unsafe { lv_label_set_text(label, CString::new(text).unwrap().as_ptr()) };
+}
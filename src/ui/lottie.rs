@@ -0,0 +1,91 @@
+use alloc::boxed::Box;
+use alloc::ffi::CString;
+use alloc::format;
+use alloc::vec;
+
+use lv_bevy_ecs::support::Align;
+use lv_bevy_ecs::sys::{
+    lv_lottie_create, lv_lottie_set_buffer, lv_lottie_set_src_data, lv_obj_center,
+    lv_obj_remove_style_all,
+};
+use lv_bevy_ecs::widgets::{Button, Label, Obj};
+
+use super::i18n::Text;
+use super::module::{Resources, UiModule};
+use super::screen::Screen;
+use super::timer::Timer;
+use super::{back_button, builder, fps_counter};
+
+/// Width and height the animation is rendered at, as set in its JSON
+const SIZE: u32 = 96;
+/// A spinner drawn as Lottie shapes, a dot running around a ring
+const ANIMATION: &[u8] = include_bytes!("../../assets/lottie/spinner.json");
+
+/// A small Lottie animation, rendered by ThorVG into an ARGB8888 buffer on
+/// every frame, with the rate the display refreshes at.
+///
+/// There is no vector hardware, so this shows what software rendering of
+/// vector shapes costs next to the prerendered frames of the animated image
+/// demo.
+pub struct Lottie {
+    _back: (Button, Label),
+    _title: Label,
+    _cost: Label,
+    _timer: Timer,
+    /// Holds the lottie widget, so it goes before the buffer it renders to
+    _player: Obj,
+    _buffer: Box<[u32]>,
+}
+
+impl Lottie {
+    /// Builds the demo on the active screen. The back button loads `home`.
+    pub fn new(home: Screen) -> Self {
+        let back = back_button(home);
+
+        let title = builder::label()
+            .translated(Text::Lottie)
+            .align(Align::TopMid)
+            .offset(0, 12)
+            .build();
+
+        let mut buffer = vec![0u32; (SIZE * SIZE) as usize].into_boxed_slice();
+        let mut player = Obj::new();
+        unsafe { lv_obj_remove_style_all(player.raw()) };
+        player.set_size(SIZE as i32, SIZE as i32);
+        player.align(Align::Center.into(), 0, -5);
+        unsafe {
+            let lottie = lv_lottie_create(player.raw());
+            lv_obj_center(lottie);
+            lv_lottie_set_buffer(lottie, SIZE as i32, SIZE as i32, buffer.as_mut_ptr().cast());
+            lv_lottie_set_src_data(lottie, ANIMATION.as_ptr().cast(), ANIMATION.len());
+        }
+
+        let mut cost = Label::new();
+        let text = format!(
+            "{} B of JSON, {} KB render buffer",
+            ANIMATION.len(),
+            buffer.len() * 4 / 1024
+        );
+        cost.set_text(CString::new(text).unwrap().as_c_str());
+        cost.align(Align::BottomMid.into(), 0, -20);
+
+        let timer = fps_counter();
+
+        Self {
+            _back: back,
+            _title: title,
+            _cost: cost,
+            _timer: timer,
+            _player: player,
+            _buffer: buffer,
+        }
+    }
+}
+
+impl UiModule for Lottie {
+    const NAME: Text = Text::Lottie;
+
+    fn build(home: Screen, _resources: &mut Resources) -> Self {
+        Self::new(home)
+    }
+}
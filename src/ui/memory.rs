@@ -0,0 +1,97 @@
+use alloc::ffi::CString;
+use alloc::format;
+use alloc::string::String;
+
+use lv_bevy_ecs::support::Align;
+use lv_bevy_ecs::sys::{lv_label_set_text, lv_mem_monitor, lv_mem_monitor_t};
+use lv_bevy_ecs::widgets::{Button, Label};
+
+use super::i18n::Text;
+use super::module::{Resources, UiModule};
+use super::screen::Screen;
+use super::timer::Timer;
+use super::{back_button, builder};
+use crate::heap;
+
+const REFRESH_PERIOD_MS: u32 = 500;
+
+/// What `lv_mem_monitor` reports next to the ESP heap, for sizing `LV_MEM_SIZE`.
+///
+/// This build allocates LVGL memory from the ESP heap, so LVGL sees the same
+/// numbers. The share since `lv_init` is what a builtin pool would have to
+/// hold, plus whatever else the firmware allocated since.
+pub struct Memory {
+    _timer: Timer,
+    _back: (Button, Label),
+    _title: Label,
+    _details: Label,
+}
+
+impl Memory {
+    /// Builds the memory screen on the active screen. The back button loads `home`.
+    pub fn new(home: Screen) -> Self {
+        let back = back_button(home);
+
+        let title = builder::label()
+            .translated(Text::Memory)
+            .align(Align::TopMid)
+            .offset(0, 12)
+            .build();
+
+        let mut details = Label::new();
+        details.align(Align::TopLeft.into(), 10, 45);
+        let details_raw = details.raw();
+        let refresh = move || {
+            let mut monitor: lv_mem_monitor_t = unsafe { core::mem::zeroed() };
+            unsafe { lv_mem_monitor(&mut monitor) };
+            let heap = esp_alloc::HEAP.stats();
+            let baseline = heap::lvgl_baseline();
+            let used = monitor.total_size.saturating_sub(monitor.free_size);
+            let fragmentation = match monitor.frag_pct {
+                0 => String::from("n/a"),
+                pct => format!("{pct}%"),
+            };
+            let text = format!(
+                "LVGL\n\
+                 Used: {} KB of {} KB ({}%)\n\
+                 Free: {} KB\n\
+                 Max used: {} KB\n\
+                 Fragmentation: {}\n\
+                 Since lv_init: {} KB, max {} KB\n\
+                 \n\
+                 ESP heap\n\
+                 Used: {} KB of {} KB\n\
+                 Before lv_init: {} KB",
+                used / 1024,
+                monitor.total_size / 1024,
+                monitor.used_pct,
+                monitor.free_size / 1024,
+                monitor.max_used / 1024,
+                fragmentation,
+                used.saturating_sub(baseline) / 1024,
+                monitor.max_used.saturating_sub(baseline) / 1024,
+                heap.current_usage / 1024,
+                heap.size / 1024,
+                baseline / 1024,
+            );
+            unsafe { lv_label_set_text(details_raw, CString::new(text).unwrap().as_ptr()) };
+        };
+        refresh();
+        let timer = Timer::new(REFRESH_PERIOD_MS, refresh);
+
+        Self {
+            _timer: timer,
+            _back: back,
+            _title: title,
+            _details: details,
+        }
+    }
+}
+
+impl UiModule for Memory {
+    const NAME: Text = Text::Memory;
+
+    fn build(home: Screen, _resources: &mut Resources) -> Self {
+        Self::new(home)
+    }
+}
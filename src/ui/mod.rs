@@ -0,0 +1,158 @@
+use alloc::ffi::CString;
+use alloc::format;
+use core::ffi::{CStr, c_char};
+
+use lv_bevy_ecs::events::EventCode;
+use lv_bevy_ecs::support::{Align, LabelLongMode};
+use lv_bevy_ecs::sys::{LV_OBJ_FLAG_HIDDEN, lv_obj_add_flag, lv_obj_remove_flag};
+use lv_bevy_ecs::widgets::{Button, Label, Obj};
+
+pub mod about;
+pub mod accent;
+pub mod alarm;
+pub mod animate;
+pub mod animated_image;
+pub mod audio;
+pub mod battery_saver;
+pub mod builder;
+pub mod calculator;
+#[cfg(feature = "can")]
+pub mod can_dashboard;
+pub mod canvas;
+#[cfg(feature = "font-cjk")]
+pub mod cjk_demo;
+pub mod console;
+pub mod converter;
+pub mod dashboard;
+pub mod debug_menu;
+pub mod debug_overlay;
+#[cfg(feature = "hc-sr04")]
+pub mod distance;
+pub mod focus;
+pub mod fonts;
+pub mod gallery;
+#[cfg(feature = "gps")]
+pub mod gps;
+pub mod harness;
+pub mod i18n;
+pub mod images;
+pub mod launcher;
+pub mod layout;
+pub mod layout_demo;
+pub mod lock;
+#[cfg(feature = "lottie")]
+pub mod lottie;
+pub mod memory;
+#[cfg(feature = "modbus")]
+pub mod modbus;
+pub mod module;
+pub mod night_mode;
+pub mod notifications;
+pub mod paint;
+pub mod pomodoro;
+pub mod preferences;
+pub mod quick_settings;
+pub mod remote;
+pub mod rich_text;
+pub mod screen;
+pub mod screensaver;
+pub mod setup;
+pub mod smart_light;
+pub mod snake;
+pub mod splash;
+pub mod state;
+pub mod status_bar;
+pub mod stopwatch;
+pub mod stress;
+pub mod symbols;
+pub mod terminal;
+pub mod timer;
+pub mod toast;
+pub mod transition;
+pub mod ttf_demo;
+pub mod wifi;
+
+use i18n::{Text, translate};
+use screen::Screen;
+use timer::Timer;
+
+use crate::{json, metrics};
+
+const FPS_PERIOD_MS: u32 = 1000;
+
+/// Button labels terminated by an empty string, as expected by `lv_buttonmatrix_set_map`.
+pub struct ButtonMap<const N: usize>(pub [*const c_char; N]);
+
+// Maps only point to static string literals
+unsafe impl<const N: usize> Sync for ButtonMap<N> {}
+
+/// Image data with the alignment LVGL wants for draw buffers, as embedded
+/// by the build script
+#[repr(C, align(4))]
+pub struct Aligned<const N: usize>(pub [u8; N]);
+
+/// Creates a button on the active screen with a centered text label.
+pub fn text_button(text: &'static CStr) -> (Button, Label) {
+    let mut button = Button::new();
+    let mut label = Label::new();
+    label.set_parent(&mut button);
+    label.set_text_static(text);
+    label.center();
+    (button, label)
+}
+
+/// Like [`text_button`], with the label kept in the current language.
+pub fn translated_button(text: Text) -> (Button, Label) {
+    let (button, mut label) = text_button(text.get());
+    translate(&mut label, text);
+    (button, label)
+}
+
+/// Creates a small button in the top left corner that switches to `target` when clicked.
+pub fn back_button(target: Screen) -> (Button, Label) {
+    let (mut button, label) = translated_button(Text::Back);
+    button.set_size(60, 30);
+    button.align(Align::TopLeft.into(), 5, 5);
+    button.add_event_cb(EventCode::Clicked, move |_| state::set_next(target));
+    (button, label)
+}
+
+/// Creates a wrapped label in the middle of the active screen telling where
+/// `source` failed to parse.
+pub fn json_error(source: &str, error: &json::Error) -> Label {
+    let text = format!(
+        "{source}, {} {}:\n{}",
+        Text::Byte.get().to_str().unwrap(),
+        error.offset,
+        error.message
+    );
+    builder::label()
+        .text(CString::new(text).unwrap().as_c_str())
+        .long_mode(LabelLongMode::Wrap)
+        .width(280)
+        .align(Align::Center)
+        .build()
+}
+
+/// Creates a label in the top right corner of the active screen with the
+/// frame rate, updated every second by the returned timer.
+pub fn fps_counter() -> Timer {
+    let mut label = Label::new();
+    label.align(Align::TopRight.into(), -10, 12);
+    let mut rate = metrics::FrameRate::new();
+    Timer::new(FPS_PERIOD_MS, move || {
+        let text = format!("{} FPS", rate.per_second());
+        label.set_text(CString::new(text).unwrap().as_c_str());
+    })
+}
+
+/// Shows or hides `obj` by toggling its hidden flag.
+pub fn set_visible(obj: &mut Obj, visible: bool) {
+    unsafe {
+        if visible {
+            lv_obj_remove_flag(obj.raw(), LV_OBJ_FLAG_HIDDEN);
+        } else {
+            lv_obj_add_flag(obj.raw(), LV_OBJ_FLAG_HIDDEN);
+        }
+    }
+}
@@ -0,0 +1,315 @@
+use alloc::ffi::CString;
+use alloc::format;
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::ffi::CStr;
+
+use lv_bevy_ecs::events::EventCode;
+use lv_bevy_ecs::support::Align;
+use lv_bevy_ecs::sys::{
+    LV_KEYBOARD_MODE_NUMBER, lv_keyboard_set_mode, lv_keyboard_set_textarea, lv_textarea_get_text,
+    lv_textarea_set_accepted_chars, lv_textarea_set_one_line, lv_textarea_set_text,
+};
+use lv_bevy_ecs::widgets::{Button, Keyboard, Label, List, Obj, Textarea};
+
+use super::i18n::Text;
+use super::module::{Resources, UiModule};
+use super::screen::Screen;
+use super::status_bar;
+use super::timer::Timer;
+use super::toast::{self, Severity};
+use super::{back_button, builder, json_error, set_visible, symbols, translated_button};
+use crate::assets::Assets;
+use crate::modbus::{self, Error, Register};
+
+const POLL_PERIOD_MS: u32 = 100;
+/// Read from the assets partition when it is there, so the registers can be
+/// changed by flashing the archive again
+const FILE: &str = "modbus/registers.json";
+/// Used when the assets partition has no register table
+const BUILT_IN: &str = include_str!("../../assets/modbus/registers.json");
+
+struct Row {
+    _button: Button,
+    _name: Label,
+    value: Label,
+}
+
+/// Number entry shown over the table for writable registers
+struct Editor {
+    panel: Obj,
+    title: Label,
+    input: Textarea,
+    _keyboard: Keyboard,
+    cancel: (Button, Label),
+    register: usize,
+}
+
+impl Editor {
+    fn new() -> Self {
+        let mut panel = Obj::new();
+        panel.set_size(320, 240 - status_bar::HEIGHT);
+        panel.set_pos(0, 0);
+        set_visible(&mut panel, false);
+
+        let mut title = Label::new();
+        title.set_parent(&mut panel);
+        title.align(Align::TopLeft.into(), 0, 0);
+
+        let mut input = Textarea::new();
+        input.set_parent(&mut panel);
+        input.set_width(280);
+        input.align(Align::TopMid.into(), 0, 30);
+        unsafe {
+            lv_textarea_set_one_line(input.raw(), true);
+            lv_textarea_set_accepted_chars(input.raw(), c"0123456789.-".as_ptr());
+        }
+
+        let mut keyboard = Keyboard::new();
+        keyboard.set_parent(&mut panel);
+        keyboard.set_size(300, 120);
+        keyboard.align(Align::BottomMid.into(), 0, 0);
+        unsafe {
+            lv_keyboard_set_mode(keyboard.raw(), LV_KEYBOARD_MODE_NUMBER);
+            lv_keyboard_set_textarea(keyboard.raw(), input.raw());
+        }
+
+        let mut cancel = translated_button(Text::Cancel);
+        cancel.0.set_parent(&mut panel);
+        cancel.0.align(Align::TopRight.into(), 0, -8);
+
+        Self {
+            panel,
+            title,
+            input,
+            _keyboard: keyboard,
+            cancel,
+            register: 0,
+        }
+    }
+
+    fn open(&mut self, index: usize, register: &Register) {
+        let text = format!(
+            "{} {}",
+            Text::NewValueFor.get().to_str().unwrap(),
+            register.name
+        );
+        self.title.set_text(CString::new(text).unwrap().as_c_str());
+        let current = match modbus::value(register.slave, register.address) {
+            Some(Ok(raw)) => register.number(raw),
+            _ => String::new(),
+        };
+        unsafe { lv_textarea_set_text(self.input.raw(), CString::new(current).unwrap().as_ptr()) };
+        self.register = index;
+        set_visible(&mut self.panel, true);
+    }
+
+    /// Hides the editor when cancelled and writes the register when the
+    /// keyboard's OK key is pressed.
+    fn connect_events(editor: &Rc<RefCell<Self>>, registers: &Rc<Vec<Register>>) {
+        let mut this = editor.borrow_mut();
+        let input_raw = this.input.raw();
+        this.input.add_event_cb(EventCode::Ready, {
+            let editor = editor.clone();
+            let registers = registers.clone();
+            move |_| {
+                let mut editor = editor.borrow_mut();
+                let register = &registers[editor.register];
+                let text = unsafe { CStr::from_ptr(lv_textarea_get_text(input_raw)) };
+                let Some(raw) = text.to_str().ok().and_then(|text| register.parse(text)) else {
+                    let text = format!(
+                        "{} {}",
+                        Text::NotAValueFor.get().to_str().unwrap(),
+                        register.name
+                    );
+                    toast::show(Severity::Warning, text);
+                    return;
+                };
+                if !modbus::write(register.slave, register.address, raw) {
+                    toast::show(
+                        Severity::Error,
+                        Text::ModbusQueueFull.get().to_str().unwrap(),
+                    );
+                }
+                set_visible(&mut editor.panel, false);
+            }
+        });
+        let hide = || {
+            let editor = editor.clone();
+            move |_| set_visible(&mut editor.borrow_mut().panel, false)
+        };
+        this.input.add_event_cb(EventCode::Cancel, hide());
+        this.cancel.0.add_event_cb(EventCode::Clicked, hide());
+    }
+}
+
+/// Holding registers of Modbus RTU slaves, listed by a register table in
+/// JSON, see [`modbus::registers`].
+///
+/// One register is read per tick, so a table of ten refreshes each of them
+/// about once a second. Tapping one marked writable asks for a new value.
+/// The module stays built and keeps polling, like a small HMI panel would.
+pub struct Modbus {
+    _back: (Button, Label),
+    _title: Label,
+    _error: Option<Label>,
+    /// Owns the rows, which are inside the list
+    _timer: Timer,
+    _list: List,
+    _editor: Rc<RefCell<Editor>>,
+}
+
+impl Modbus {
+    /// Builds the table on the active screen. The back button loads `home`.
+    pub fn new(home: Screen, assets: Assets) -> Self {
+        let back = back_button(home);
+
+        let title = builder::label()
+            .translated(Text::Modbus)
+            .align(Align::TopMid)
+            .offset(0, 12)
+            .build();
+
+        let mut list = List::new();
+        list.set_size(300, 180);
+        list.align(Align::BottomMid.into(), 0, -5);
+
+        let (registers, error) = match modbus::registers(&assets.read_text_or(FILE, BUILT_IN)) {
+            Ok(registers) => (registers, None),
+            Err(error) => {
+                defmt::error!(
+                    "Could not load the Modbus registers, byte {}: {=str}",
+                    error.offset,
+                    error.message
+                );
+                (Vec::new(), Some(json_error(FILE, &error)))
+            }
+        };
+        let registers = Rc::new(registers);
+
+        let editor = Rc::new(RefCell::new(Editor::new()));
+        Editor::connect_events(&editor, &registers);
+
+        let rows: Vec<Row> = registers
+            .iter()
+            .enumerate()
+            .map(|(index, register)| row(&mut list, index, register, &registers, &editor))
+            .collect();
+
+        let mut next = 0;
+        let mut shown = vec![None; rows.len()];
+        let timer = Timer::new(POLL_PERIOD_MS, move || {
+            // Queued one at a time, so a write gets through right away
+            if let Some(register) = registers.get(next) {
+                if !modbus::busy() && modbus::read(register.slave, register.address) {
+                    next = (next + 1) % registers.len();
+                }
+            }
+
+            for ((row, register), shown) in rows.iter().zip(registers.iter()).zip(&mut shown) {
+                let value = modbus::value(register.slave, register.address);
+                if *shown == value {
+                    continue;
+                }
+                *shown = value;
+                let text = match value {
+                    None => String::from("--"),
+                    Some(Ok(raw)) => register.format(raw),
+                    Some(Err(error)) => describe(error),
+                };
+                row.value.set_text(CString::new(text).unwrap().as_c_str());
+            }
+
+            match modbus::take_written() {
+                Some(Ok(())) => toast::show(
+                    Severity::Success,
+                    Text::RegisterWritten.get().to_str().unwrap(),
+                ),
+                Some(Err(error)) => toast::show(
+                    Severity::Error,
+                    format!(
+                        "{}: {}",
+                        Text::WriteFailed.get().to_str().unwrap(),
+                        describe(error)
+                    ),
+                ),
+                None => {}
+            }
+        });
+
+        Self {
+            _back: back,
+            _title: title,
+            _error: error,
+            _timer: timer,
+            _list: list,
+            _editor: editor,
+        }
+    }
+}
+
+impl UiModule for Modbus {
+    const NAME: Text = Text::Modbus;
+    const RESIDENT: bool = true;
+
+    fn build(home: Screen, resources: &mut Resources) -> Self {
+        Self::new(home, *resources.get::<Assets>())
+    }
+}
+
+fn row(
+    list: &mut List,
+    index: usize,
+    register: &Register,
+    registers: &Rc<Vec<Register>>,
+    editor: &Rc<RefCell<Editor>>,
+) -> Row {
+    let mut button = Button::new();
+    button.set_parent(list);
+    button.set_size(280, 36);
+
+    let mut name = Label::new();
+    name.set_parent(&mut button);
+    let text = if register.writable {
+        format!("{} {}", register.name, symbols::EDIT)
+    } else {
+        register.name.clone()
+    };
+    name.set_text(CString::new(text).unwrap().as_c_str());
+    name.align(Align::LeftMid.into(), 0, 0);
+
+    let mut value = Label::new();
+    value.set_parent(&mut button);
+    value.set_text_static(c"--");
+    value.align(Align::RightMid.into(), 0, 0);
+
+    if register.writable {
+        let registers = registers.clone();
+        let editor = editor.clone();
+        button.add_event_cb(EventCode::Clicked, move |_| {
+            editor.borrow_mut().open(index, &registers[index]);
+        });
+    }
+
+    Row {
+        _button: button,
+        _name: name,
+        value,
+    }
+}
+
+fn describe(error: Error) -> String {
+    let text = match error {
+        Error::Timeout => Text::NoReply,
+        Error::Crc => Text::BadCrc,
+        Error::Invalid => Text::BadReply,
+        Error::Exception(code) => {
+            return format!("{} {}", Text::Exception.get().to_str().unwrap(), code);
+        }
+        Error::Bus => Text::UartError,
+    };
+    String::from(text.get().to_str().unwrap())
+}
@@ -0,0 +1,256 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::any::{Any, type_name};
+
+use super::i18n::Text;
+use super::launcher::Launcher;
+use super::screen::Screen;
+use super::state;
+use super::transition::{Direction, Snapshot};
+
+/// Shared state modules pick from while they are built, looked up by type
+/// like Bevy resources.
+#[derive(Default)]
+pub struct Resources {
+    entries: Vec<Box<dyn Any>>,
+}
+
+impl Resources {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `value`, replacing any earlier resource of the same type.
+    pub fn insert<T: 'static>(&mut self, value: T) {
+        self.entries.retain(|entry| !entry.is::<T>());
+        self.entries.push(Box::new(value));
+    }
+
+    /// Panics if no `T` was inserted, which is a wiring mistake in `main`.
+    pub fn get<T: 'static>(&self) -> &T {
+        self.entries
+            .iter()
+            .find_map(|entry| entry.downcast_ref())
+            .unwrap_or_else(|| panic!("Missing resource {}", type_name::<T>()))
+    }
+
+    /// Removes and returns a resource only one module can own, such as a peripheral.
+    pub fn take<T: 'static>(&mut self) -> T {
+        let index = self
+            .entries
+            .iter()
+            .position(|entry| entry.is::<T>())
+            .unwrap_or_else(|| panic!("Missing resource {}", type_name::<T>()));
+        *self.entries.swap_remove(index).downcast().unwrap()
+    }
+}
+
+/// Self-contained app with its own screen and launcher entry.
+///
+/// Entering the screen builds the module and leaving it tears it down again,
+/// so only the app on screen holds widgets.
+pub trait UiModule: 'static {
+    /// Launcher button label
+    const NAME: Text;
+
+    /// Built at startup and never torn down, for modules that keep working
+    /// off screen, like an alarm.
+    const RESIDENT: bool = false;
+
+    /// Builds the widgets on the active screen. The back button should load `home`.
+    fn build(home: Screen, resources: &mut Resources) -> Self;
+
+    /// Runs once per UI loop while the module is built.
+    fn update(&mut self) {}
+
+    /// Runs before the module is dropped.
+    fn teardown(&mut self) {}
+
+    /// Whether `screen` is another screen of the module, which it can switch
+    /// to with [`state::set_next`] without being torn down.
+    fn owns(&self, _screen: Screen) -> bool {
+        false
+    }
+}
+
+/// Object safe part of [`UiModule`]
+trait Loaded {
+    fn update(&mut self);
+    fn teardown(&mut self);
+    fn owns(&self, screen: Screen) -> bool;
+}
+
+impl<M: UiModule> Loaded for M {
+    fn update(&mut self) {
+        UiModule::update(self)
+    }
+
+    fn owns(&self, screen: Screen) -> bool {
+        UiModule::owns(self, screen)
+    }
+
+    fn teardown(&mut self) {
+        UiModule::teardown(self)
+    }
+}
+
+struct Entry {
+    name: Text,
+    screen: Screen,
+    build: fn(Screen, &mut Resources) -> Box<dyn Loaded>,
+    resident: bool,
+    module: Option<Box<dyn Loaded>>,
+}
+
+impl Entry {
+    /// Whether `screen` is the module's own or, while it is built, one of its
+    /// other screens
+    fn shows(&self, screen: Screen) -> bool {
+        self.screen == screen
+            || self
+                .module
+                .as_ref()
+                .is_some_and(|module| module.owns(screen))
+    }
+
+    fn enter(&mut self, home: Screen, resources: &mut Resources) {
+        if self.module.is_none() {
+            self.module = Some(self.screen.build(|| (self.build)(home, resources)));
+        }
+    }
+
+    fn exit(&mut self) {
+        if self.resident {
+            return;
+        }
+        if let Some(mut module) = self.module.take() {
+            module.teardown();
+        }
+    }
+}
+
+/// Gives registered modules screens of their own, lists them in the launcher
+/// on `home` and switches between them.
+///
+/// The shown screen works like a Bevy `States` value: entering a module's
+/// screen builds it and leaving tears it down. Switches are requested with
+/// [`state::set_next`].
+pub struct Registry {
+    home: Screen,
+    current: Screen,
+    resources: Resources,
+    launcher: Launcher,
+    entries: Vec<Entry>,
+}
+
+impl Registry {
+    /// Creates the launcher on the active screen, which should be `home`.
+    pub fn new(home: Screen, resources: Resources) -> Self {
+        Self {
+            home,
+            current: home,
+            resources,
+            launcher: Launcher::new(),
+            entries: Vec::new(),
+        }
+    }
+
+    /// Adds `M` to the launcher, in registration order.
+    pub fn register<M: UiModule>(&mut self) {
+        let mut entry = Entry {
+            name: M::NAME,
+            screen: Screen::new(),
+            build: |home, resources| Box::new(M::build(home, resources)),
+            resident: M::RESIDENT,
+            module: None,
+        };
+        if M::RESIDENT {
+            entry.enter(self.home, &mut self.resources);
+        }
+        self.launcher.add(M::NAME, entry.screen);
+        self.entries.push(entry);
+    }
+
+    /// The screen on display
+    pub fn state(&self) -> Screen {
+        self.current
+    }
+
+    /// Registration index of the module on display, `None` on `home`
+    pub fn current_module(&self) -> Option<usize> {
+        self.entries
+            .iter()
+            .position(|entry| entry.shows(self.current))
+    }
+
+    /// Registration index of the module called `name` in any language
+    pub fn find(&self, name: &str) -> Option<usize> {
+        self.entries
+            .iter()
+            .position(|entry| entry.name.matches(name))
+    }
+
+    /// Asks to switch to the module registered as `index`, see
+    /// [`Registry::current_module`].
+    pub fn open(&self, index: usize) {
+        if let Some(entry) = self.entries.get(index) {
+            state::set_next(entry.screen);
+        }
+    }
+
+    /// Applies a pending screen switch, then updates the built modules.
+    ///
+    /// The new screen slides in over a [`Snapshot`] of the old one, or fades
+    /// in between two modules, when there is memory for it.
+    pub fn update(&mut self) {
+        if let Some(next) = state::take_next().filter(|&next| next != self.current) {
+            // Back to the module's own screen or on to another of its screens
+            let within = self
+                .entries
+                .iter()
+                .find(|entry| entry.shows(self.current) && entry.shows(next));
+            let direction = match within {
+                Some(entry) if entry.screen == next => Direction::Back,
+                Some(_) => Direction::Forward,
+                None if next == self.home => Direction::Back,
+                None if self.current == self.home => Direction::Forward,
+                None => Direction::Across,
+            };
+            // Before the screen being left is torn down
+            let snapshot = Snapshot::take();
+            for entry in &mut self.entries {
+                let (leaving, entering) = (entry.shows(self.current), entry.shows(next));
+                if leaving && !entering {
+                    entry.exit();
+                } else if entering && !leaving {
+                    entry.enter(self.home, &mut self.resources);
+                }
+            }
+            match snapshot {
+                Some(snapshot) => snapshot.reveal(next, direction),
+                None => next.load(),
+            }
+            self.current = next;
+        }
+
+        for module in self
+            .entries
+            .iter_mut()
+            .filter_map(|entry| entry.module.as_mut())
+        {
+            module.update();
+        }
+    }
+}
+
+impl Drop for Registry {
+    fn drop(&mut self) {
+        for module in self
+            .entries
+            .iter_mut()
+            .filter_map(|entry| entry.module.as_mut())
+        {
+            module.teardown();
+        }
+    }
+}
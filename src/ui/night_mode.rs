@@ -0,0 +1,114 @@
+use alloc::rc::Rc;
+use core::cell::RefCell;
+use core::ffi::CStr;
+
+use lv_bevy_ecs::sys::{
+    LV_OBJ_FLAG_CLICKABLE, LV_OBJ_FLAG_SCROLLABLE, LV_OPA_60, lv_color_hex, lv_layer_sys,
+    lv_obj_remove_flag, lv_obj_set_parent, lv_obj_set_style_bg_color, lv_obj_set_style_bg_opa,
+    lv_obj_set_style_border_width, lv_obj_set_style_radius,
+};
+use lv_bevy_ecs::widgets::Obj;
+
+use super::i18n::Text;
+use super::set_visible;
+use super::timer::Timer;
+use crate::clock;
+
+/// Dark amber, dims white to a warm grey at 60% opacity
+const FILTER_COLOR: u32 = 0x331A00;
+const CHECK_PERIOD_MS: u32 = 30_000;
+/// Night according to the schedule, in minutes since midnight
+const NIGHT_START: u32 = 21 * 60;
+const NIGHT_END: u32 = 7 * 60;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Off = 0,
+    On = 1,
+    /// On between [`NIGHT_START`] and [`NIGHT_END`], off while the clock is unset
+    Scheduled = 2,
+}
+
+impl Mode {
+    pub const ALL: [Mode; 3] = [Mode::Off, Mode::On, Mode::Scheduled];
+
+    pub fn name(self) -> &'static CStr {
+        match self {
+            Mode::Off => Text::Off.get(),
+            Mode::On => Text::On.get(),
+            Mode::Scheduled => Text::Scheduled.get(),
+        }
+    }
+
+    pub fn from_index(index: u32) -> Self {
+        Self::ALL.get(index as usize).copied().unwrap_or(Mode::Off)
+    }
+}
+
+struct State {
+    filter: Obj,
+    mode: Mode,
+}
+
+impl State {
+    fn update(&mut self) {
+        let night = match self.mode {
+            Mode::Off => false,
+            Mode::On => true,
+            Mode::Scheduled => clock::now().is_some_and(|now| {
+                let minute = now.minute_of_day();
+                minute >= NIGHT_START || minute < NIGHT_END
+            }),
+        };
+        set_visible(&mut self.filter, night);
+    }
+}
+
+/// Warm, dimmed palette for dark rooms.
+///
+/// A translucent amber layer on the system layer tints everything below it,
+/// including the status bar and alerts, without touching any styles. It does
+/// not take input.
+pub struct NightMode {
+    state: Rc<RefCell<State>>,
+    _timer: Timer,
+}
+
+impl NightMode {
+    pub fn new(mode: Mode) -> Self {
+        let mut filter = Obj::new();
+        unsafe {
+            lv_obj_set_parent(filter.raw(), lv_layer_sys());
+            lv_obj_set_style_bg_color(filter.raw(), lv_color_hex(FILTER_COLOR), 0);
+            lv_obj_set_style_bg_opa(filter.raw(), LV_OPA_60 as _, 0);
+            lv_obj_set_style_border_width(filter.raw(), 0, 0);
+            lv_obj_set_style_radius(filter.raw(), 0, 0);
+            lv_obj_remove_flag(filter.raw(), LV_OBJ_FLAG_CLICKABLE);
+            lv_obj_remove_flag(filter.raw(), LV_OBJ_FLAG_SCROLLABLE);
+        }
+        filter.set_size(320, 240);
+        filter.set_pos(0, 0);
+
+        let state = Rc::new(RefCell::new(State { filter, mode }));
+        state.borrow_mut().update();
+        let timer = Timer::new(CHECK_PERIOD_MS, {
+            let state = state.clone();
+            move || state.borrow_mut().update()
+        });
+
+        Self {
+            state,
+            _timer: timer,
+        }
+    }
+
+    pub fn mode(&self) -> Mode {
+        self.state.borrow().mode
+    }
+
+    pub fn set_mode(&self, mode: Mode) {
+        let mut state = self.state.borrow_mut();
+        state.mode = mode;
+        state.update();
+    }
+}
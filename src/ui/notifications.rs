@@ -0,0 +1,281 @@
+use alloc::collections::VecDeque;
+use alloc::ffi::CString;
+use alloc::format;
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use embassy_sync::blocking_mutex::Mutex;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_time::Instant;
+use lv_bevy_ecs::events::EventCode;
+use lv_bevy_ecs::support::{Align, LabelLongMode};
+use lv_bevy_ecs::sys::{
+    LV_BORDER_SIDE_LEFT, LV_DIR_BOTTOM, LV_DIR_TOP, LV_OBJ_FLAG_CLICKABLE, LV_OBJ_FLAG_SCROLLABLE,
+    LV_OPA_COVER, LV_OPA_TRANSP, lv_color_hex, lv_indev_active, lv_indev_get_gesture_dir,
+    lv_layer_top, lv_obj_get_y, lv_obj_remove_flag, lv_obj_set_parent, lv_obj_set_style_bg_color,
+    lv_obj_set_style_bg_opa, lv_obj_set_style_border_color, lv_obj_set_style_border_side,
+    lv_obj_set_style_border_width, lv_obj_set_style_pad_left, lv_obj_set_style_radius,
+    lv_obj_set_style_text_color, lv_obj_t,
+};
+use lv_bevy_ecs::widgets::{Button, Label, List, Obj};
+
+use super::animate::{Animate, Animation, Easing, Property};
+use super::i18n::{Text, translate};
+use super::timer::Timer;
+use super::toast::Severity;
+use super::translated_button;
+use crate::clock;
+
+const PANEL_HEIGHT: i32 = 190;
+/// Handle in the middle of the bottom edge that takes the swipe up or a tap,
+/// small enough to stay clear of the widgets screens put at the bottom
+const EDGE_WIDTH: i32 = 60;
+const EDGE_HEIGHT: i32 = 12;
+const GRIP_COLOR: u32 = 0x9E9E9E;
+const SLIDE_MS: u32 = 250;
+const POLL_PERIOD_MS: u32 = 500;
+const BACKGROUND: u32 = 0x303030;
+const ENTRY_WIDTH: i32 = 270;
+const MARK_WIDTH: i32 = 3;
+/// Older notifications are dropped past this
+const MAX_ENTRIES: usize = 20;
+
+struct Entry {
+    severity: Severity,
+    /// Local time, or uptime while the clock is unset
+    stamp: String,
+    text: String,
+}
+
+static LOG: Mutex<CriticalSectionRawMutex, RefCell<VecDeque<Entry>>> =
+    Mutex::new(RefCell::new(VecDeque::new()));
+/// Bumped on every change of [`LOG`], so the list is only rebuilt then
+static REVISION: AtomicU32 = AtomicU32::new(0);
+
+/// Keeps `text` for the [`NotificationCenter`]. Every toast ends up here,
+/// so most callers want [`toast::show`](super::toast::show) instead. Safe to
+/// call from any task.
+pub fn record(severity: Severity, text: &str) {
+    let stamp = match clock::now() {
+        Some(now) => format!("{:02}:{:02}", now.hour, now.minute),
+        None => {
+            let secs = Instant::now().as_secs();
+            format!("+{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+        }
+    };
+    let entry = Entry {
+        severity,
+        stamp,
+        text: String::from(text),
+    };
+    LOG.lock(|log| {
+        let mut log = log.borrow_mut();
+        if log.len() == MAX_ENTRIES {
+            log.pop_front();
+        }
+        log.push_back(entry);
+    });
+    REVISION.fetch_add(1, Ordering::Relaxed);
+}
+
+pub fn clear() {
+    LOG.lock(|log| log.borrow_mut().clear());
+    REVISION.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Panel pulled up from a small handle at the bottom of any screen, listing
+/// the recent notifications newest first with the time they came in.
+///
+/// It slides back down with a swipe down on its header or a tap on the
+/// handle.
+pub struct NotificationCenter {
+    // Stopped before the panel it moves is deleted
+    _animation: Rc<RefCell<Option<Animation>>>,
+    _timer: Timer,
+    _edge: (Obj, Obj),
+    _title: Label,
+    _clear: (Button, Label),
+    _list: List,
+    _handle: (Obj, Obj),
+    _panel: Obj,
+}
+
+impl NotificationCenter {
+    /// Creates the closed panel on the top layer. Create it before the debug
+    /// menu so the debug corner stays reachable.
+    pub fn new() -> Self {
+        let mut edge = Obj::new();
+        unsafe {
+            lv_obj_set_parent(edge.raw(), lv_layer_top());
+            lv_obj_set_style_bg_opa(edge.raw(), LV_OPA_TRANSP as _, 0);
+            lv_obj_set_style_border_width(edge.raw(), 0, 0);
+        }
+        edge.set_size(EDGE_WIDTH, EDGE_HEIGHT);
+        edge.align(Align::BottomMid.into(), 0, 0);
+        let mut edge_grip = Obj::new();
+        edge_grip.set_parent(&mut edge);
+        edge_grip.set_size(40, 4);
+        edge_grip.align(Align::BottomMid.into(), 0, -2);
+        unsafe {
+            lv_obj_set_style_bg_color(edge_grip.raw(), lv_color_hex(GRIP_COLOR), 0);
+            lv_obj_set_style_border_width(edge_grip.raw(), 0, 0);
+            lv_obj_remove_flag(edge_grip.raw(), LV_OBJ_FLAG_CLICKABLE);
+        }
+
+        // Created after the edge handle so it covers it while open
+        let mut panel = Obj::new();
+        unsafe {
+            lv_obj_set_parent(panel.raw(), lv_layer_top());
+            lv_obj_set_style_bg_color(panel.raw(), lv_color_hex(BACKGROUND), 0);
+            lv_obj_set_style_bg_opa(panel.raw(), LV_OPA_COVER as _, 0);
+            lv_obj_set_style_text_color(panel.raw(), lv_color_hex(0xFFFFFF), 0);
+            lv_obj_set_style_radius(panel.raw(), 0, 0);
+            lv_obj_remove_flag(panel.raw(), LV_OBJ_FLAG_SCROLLABLE);
+        }
+        panel.set_size(320, PANEL_HEIGHT);
+        panel.set_pos(0, 240);
+        let panel_raw = panel.raw();
+
+        let animation = Rc::new(RefCell::new(None));
+        let slide = {
+            let animation = animation.clone();
+            move |open: bool| {
+                let from = unsafe { lv_obj_get_y(panel_raw) };
+                let to = if open { 240 - PANEL_HEIGHT } else { 240 };
+                *animation.borrow_mut() = Some(
+                    Animate::new(Property::Y, from, to, SLIDE_MS)
+                        .with_easing(Easing::EaseOut)
+                        .start(panel_raw),
+                );
+            }
+        };
+
+        // A wide, easy to hit strip around a small grip
+        let mut handle = Obj::new();
+        handle.set_parent(&mut panel);
+        handle.set_size(120, 20);
+        handle.align(Align::TopMid.into(), 0, -10);
+        let mut grip = Obj::new();
+        grip.set_parent(&mut handle);
+        grip.set_size(40, 6);
+        grip.center();
+        unsafe {
+            lv_obj_set_style_bg_opa(handle.raw(), LV_OPA_TRANSP as _, 0);
+            lv_obj_set_style_border_width(handle.raw(), 0, 0);
+            lv_obj_set_style_bg_color(grip.raw(), lv_color_hex(GRIP_COLOR), 0);
+            lv_obj_set_style_border_width(grip.raw(), 0, 0);
+            lv_obj_remove_flag(grip.raw(), LV_OBJ_FLAG_CLICKABLE);
+        }
+
+        let mut title = Label::new();
+        title.set_parent(&mut panel);
+        translate(&mut title, Text::Notifications);
+        title.align(Align::TopLeft.into(), 0, 12);
+
+        let mut clear_button = translated_button(Text::ClearAll);
+        clear_button.0.set_parent(&mut panel);
+        clear_button.0.align(Align::TopRight.into(), 0, 4);
+        clear_button.0.add_event_cb(EventCode::Clicked, |_| clear());
+
+        let mut list = List::new();
+        list.set_parent(&mut panel);
+        list.set_size(300, PANEL_HEIGHT - 70);
+        list.align(Align::BottomMid.into(), 0, 0);
+        unsafe {
+            lv_obj_set_style_bg_opa(list.raw(), LV_OPA_TRANSP as _, 0);
+            lv_obj_set_style_border_width(list.raw(), 0, 0);
+        }
+
+        handle.add_event_cb(EventCode::Clicked, {
+            let slide = slide.clone();
+            move |_| slide(false)
+        });
+        panel.add_event_cb(EventCode::Gesture, {
+            let slide = slide.clone();
+            move |_| {
+                if unsafe { lv_indev_get_gesture_dir(lv_indev_active()) } == LV_DIR_BOTTOM {
+                    slide(false);
+                }
+            }
+        });
+        edge.add_event_cb(EventCode::Gesture, {
+            let slide = slide.clone();
+            move |_| {
+                if unsafe { lv_indev_get_gesture_dir(lv_indev_active()) } == LV_DIR_TOP {
+                    slide(true);
+                }
+            }
+        });
+        edge.add_event_cb(EventCode::Clicked, move |_| slide(true));
+
+        let mut entries = Vec::new();
+        // Differs from any revision, so the first poll fills the list
+        let mut shown = u32::MAX;
+        let list_raw = list.raw();
+        let mut refresh = move || {
+            let revision = REVISION.load(Ordering::Relaxed);
+            if revision == shown {
+                return;
+            }
+            shown = revision;
+            entries.clear();
+            // Copied out first, so other tasks are not held up while LVGL builds the labels
+            let lines = LOG.lock(|log| {
+                log.borrow()
+                    .iter()
+                    .rev()
+                    .map(|entry| (entry.severity, format!("{}  {}", entry.stamp, entry.text)))
+                    .collect::<Vec<_>>()
+            });
+            for (severity, text) in lines {
+                entries.push(entry_label(list_raw, &text, Some(severity)));
+            }
+            if entries.is_empty() {
+                let mut empty = entry_label(list_raw, "", None);
+                translate(&mut empty, Text::NothingNew);
+                entries.push(empty);
+            }
+        };
+        refresh();
+        let timer = Timer::new(POLL_PERIOD_MS, refresh);
+
+        Self {
+            _animation: animation,
+            _timer: timer,
+            _edge: (edge, edge_grip),
+            _title: title,
+            _clear: clear_button,
+            _list: list,
+            _handle: (handle, grip),
+            _panel: panel,
+        }
+    }
+}
+
+impl Default for NotificationCenter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Adds a wrapping line to `list`, marked on the left in the color of
+/// `severity`.
+fn entry_label(list: *mut lv_obj_t, text: &str, severity: Option<Severity>) -> Label {
+    let mut label = Label::new();
+    unsafe { lv_obj_set_parent(label.raw(), list) };
+    label.set_width(ENTRY_WIDTH);
+    label.set_long_mode(LabelLongMode::Wrap.into());
+    label.set_text(CString::new(text).unwrap_or_default().as_c_str());
+    if let Some(severity) = severity {
+        unsafe {
+            lv_obj_set_style_border_side(label.raw(), LV_BORDER_SIDE_LEFT as _, 0);
+            lv_obj_set_style_border_width(label.raw(), MARK_WIDTH, 0);
+            lv_obj_set_style_border_color(label.raw(), lv_color_hex(severity.color()), 0);
+            lv_obj_set_style_pad_left(label.raw(), 2 * MARK_WIDTH, 0);
+        }
+    }
+    label
+}
@@ -0,0 +1,225 @@
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+
+use lv_bevy_ecs::events::EventCode;
+use lv_bevy_ecs::support::Align;
+use lv_bevy_ecs::sys::{
+    LV_ANIM_OFF, LV_OBJ_FLAG_CLICKABLE, lv_area_t, lv_color_hex, lv_indev_active,
+    lv_indev_get_point, lv_obj_add_flag, lv_obj_get_coords, lv_obj_set_style_bg_color, lv_obj_t,
+    lv_point_t, lv_slider_get_value, lv_slider_set_range, lv_slider_set_value,
+};
+use lv_bevy_ecs::widgets::{Button, Label, Slider};
+
+use super::canvas::{PixelCanvas, rgb565};
+use super::i18n::Text;
+use super::module::{Resources, UiModule};
+use super::screen::Screen;
+use super::{back_button, translated_button};
+
+const CANVAS_WIDTH: i32 = 230;
+const CANVAS_HEIGHT: i32 = 170;
+const BACKGROUND: u32 = 0xFFFFFF;
+const PALETTE: [u32; 6] = [0x000000, 0xE53935, 0xFDD835, 0x43A047, 0x1E88E5, 0xFFFFFF];
+const MIN_BRUSH: i32 = 1;
+const MAX_BRUSH: i32 = 12;
+const DEFAULT_BRUSH: i32 = 3;
+
+struct Stroke {
+    color: u16,
+    radius: i32,
+    points: Vec<(i32, i32)>,
+}
+
+struct State {
+    canvas: PixelCanvas,
+    strokes: Vec<Stroke>,
+    color: u16,
+    radius: i32,
+}
+
+impl State {
+    fn begin(&mut self, point: (i32, i32)) {
+        self.strokes.push(Stroke {
+            color: self.color,
+            radius: self.radius,
+            points: Vec::new(),
+        });
+        self.extend(point);
+    }
+
+    fn extend(&mut self, point: (i32, i32)) {
+        let Some(stroke) = self.strokes.last_mut() else {
+            return;
+        };
+        let from = stroke.points.last().copied().unwrap_or(point);
+        stroke.points.push(point);
+        let (color, radius) = (stroke.color, stroke.radius);
+        draw_segment(&mut self.canvas, from, point, radius, color);
+        self.canvas.invalidate();
+    }
+
+    fn undo(&mut self) {
+        self.strokes.pop();
+        self.redraw();
+    }
+
+    fn clear(&mut self) {
+        self.strokes.clear();
+        self.redraw();
+    }
+
+    /// Repaints the remaining strokes, which is much cheaper in RAM than
+    /// keeping a copy of the canvas for undo.
+    fn redraw(&mut self) {
+        self.canvas.fill(rgb565(BACKGROUND));
+        for stroke in &self.strokes {
+            let mut from = stroke.points[0];
+            for &to in &stroke.points {
+                draw_segment(&mut self.canvas, from, to, stroke.radius, stroke.color);
+                from = to;
+            }
+        }
+        self.canvas.invalidate();
+    }
+}
+
+/// Finger painting on a canvas with a color palette, brush size slider and undo.
+pub struct Paint {
+    _back: (Button, Label),
+    _palette: Vec<Button>,
+    _slider: Slider,
+    _undo: (Button, Label),
+    _clear: (Button, Label),
+    /// Owns the canvas, so it goes after the buttons sharing it
+    _state: Rc<RefCell<State>>,
+}
+
+impl Paint {
+    /// Builds the paint app on the active screen. The back button loads `home`.
+    pub fn new(home: Screen) -> Self {
+        let back = back_button(home);
+
+        let mut canvas = PixelCanvas::new(CANVAS_WIDTH, CANVAS_HEIGHT, rgb565(BACKGROUND));
+        canvas.canvas().set_pos(5, 45);
+        let canvas_raw = canvas.raw();
+        unsafe { lv_obj_add_flag(canvas_raw, LV_OBJ_FLAG_CLICKABLE) };
+
+        let state = Rc::new(RefCell::new(State {
+            canvas,
+            strokes: Vec::new(),
+            color: rgb565(PALETTE[0]),
+            radius: DEFAULT_BRUSH,
+        }));
+
+        for (code, starts_stroke) in [(EventCode::Pressed, true), (EventCode::Pressing, false)] {
+            // Weak, since the canvas holding the callback is part of the state
+            let callback_state = Rc::downgrade(&state);
+            let mut state = state.borrow_mut();
+            state.canvas.canvas().add_event_cb(code, move |_| {
+                let Some(callback_state) = callback_state.upgrade() else {
+                    return;
+                };
+                let point = touch_point(canvas_raw);
+                let mut state = callback_state.borrow_mut();
+                if starts_stroke {
+                    state.begin(point);
+                } else {
+                    state.extend(point);
+                }
+            });
+        }
+
+        let mut palette = Vec::new();
+        for (i, color) in PALETTE.into_iter().enumerate() {
+            let mut swatch = Button::new();
+            swatch.set_size(30, 30);
+            swatch.set_pos(75 + i as i32 * 36, 5);
+            unsafe { lv_obj_set_style_bg_color(swatch.raw(), lv_color_hex(color), 0) };
+            let state = state.clone();
+            swatch.add_event_cb(EventCode::Clicked, move |_| {
+                state.borrow_mut().color = rgb565(color);
+            });
+            palette.push(swatch);
+        }
+
+        let mut slider = Slider::new();
+        slider.set_size(20, 100);
+        slider.set_pos(268, 45);
+        let slider_raw = slider.raw();
+        unsafe {
+            lv_slider_set_range(slider_raw, MIN_BRUSH, MAX_BRUSH);
+            lv_slider_set_value(slider_raw, DEFAULT_BRUSH, LV_ANIM_OFF);
+        }
+        slider.add_event_cb(EventCode::ValueChanged, {
+            let state = state.clone();
+            move |_| state.borrow_mut().radius = unsafe { lv_slider_get_value(slider_raw) }
+        });
+
+        let mut undo = translated_button(Text::Undo);
+        undo.0.set_size(66, 30);
+        undo.0.align(Align::BottomRight.into(), -5, -45);
+        undo.0.add_event_cb(EventCode::Clicked, {
+            let state = state.clone();
+            move |_| state.borrow_mut().undo()
+        });
+
+        let mut clear = translated_button(Text::Clear);
+        clear.0.set_size(66, 30);
+        clear.0.align(Align::BottomRight.into(), -5, -8);
+        clear.0.add_event_cb(EventCode::Clicked, {
+            let state = state.clone();
+            move |_| state.borrow_mut().clear()
+        });
+
+        Self {
+            _back: back,
+            _palette: palette,
+            _slider: slider,
+            _undo: undo,
+            _clear: clear,
+            _state: state,
+        }
+    }
+}
+
+impl UiModule for Paint {
+    const NAME: Text = Text::Paint;
+
+    fn build(home: Screen, _resources: &mut Resources) -> Self {
+        Self::new(home)
+    }
+}
+
+/// Position of the active pointer relative to the canvas
+fn touch_point(canvas: *mut lv_obj_t) -> (i32, i32) {
+    let mut point = lv_point_t { x: 0, y: 0 };
+    let mut coords = lv_area_t {
+        x1: 0,
+        y1: 0,
+        x2: 0,
+        y2: 0,
+    };
+    unsafe {
+        lv_indev_get_point(lv_indev_active(), &mut point);
+        lv_obj_get_coords(canvas, &mut coords);
+    }
+    (point.x - coords.x1, point.y - coords.y1)
+}
+
+/// Stamps circles along the line so fast movements don't leave gaps.
+fn draw_segment(
+    canvas: &mut PixelCanvas,
+    from: (i32, i32),
+    to: (i32, i32),
+    radius: i32,
+    color: u16,
+) {
+    let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+    let steps = (dx.abs().max(dy.abs()) / (radius / 2).max(1)).max(1);
+    for step in 0..=steps {
+        let x = from.0 + dx * step / steps;
+        let y = from.1 + dy * step / steps;
+        canvas.fill_circle(x, y, radius, color);
+    }
+}
@@ -0,0 +1,241 @@
+use alloc::ffi::CString;
+use alloc::format;
+use alloc::rc::Rc;
+use core::cell::{Cell, RefCell};
+
+use embassy_time::{Duration, Instant};
+use lv_bevy_ecs::events::EventCode;
+use lv_bevy_ecs::support::Align;
+use lv_bevy_ecs::sys::{
+    LV_OBJ_FLAG_CLICKABLE, LV_OPA_COVER, LV_PART_KNOB, lv_color_hex, lv_layer_top,
+    lv_obj_remove_flag, lv_obj_remove_style, lv_obj_set_parent, lv_obj_set_style_bg_color,
+    lv_obj_set_style_bg_opa,
+};
+use lv_bevy_ecs::widgets::{Arc, Button, Label, Obj};
+
+use super::i18n::Text;
+use super::module::{Resources, UiModule};
+use super::screen::Screen;
+use super::timer::Timer;
+use super::{back_button, set_visible, text_button, translated_button};
+use crate::buzzer::{Beeper, Buzzer};
+use crate::settings::{Key, Settings};
+
+const FOCUS: Duration = Duration::from_secs(25 * 60);
+const BREAK: Duration = Duration::from_secs(5 * 60);
+const TICK_MS: u32 = 250;
+/// Number of ticks the overlay and the buzzer toggle for after a phase ends
+const ALERT_TICKS: u32 = 12;
+const ALERT_COLOR: u32 = 0xE53935;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Phase {
+    Focus,
+    Break,
+}
+
+impl Phase {
+    fn duration(self) -> Duration {
+        match self {
+            Phase::Focus => FOCUS,
+            Phase::Break => BREAK,
+        }
+    }
+
+    fn next(self) -> Self {
+        match self {
+            Phase::Focus => Phase::Break,
+            Phase::Break => Phase::Focus,
+        }
+    }
+
+    fn name(self) -> Text {
+        match self {
+            Phase::Focus => Text::Focus,
+            Phase::Break => Text::Break,
+        }
+    }
+}
+
+struct State {
+    phase: Phase,
+    /// Set while the countdown is running
+    deadline: Option<Instant>,
+    /// Time left while paused
+    remaining: Duration,
+}
+
+impl State {
+    fn remaining(&self) -> Duration {
+        match self.deadline {
+            Some(deadline) => deadline.saturating_duration_since(Instant::now()),
+            None => self.remaining,
+        }
+    }
+}
+
+/// 25/5 minute focus timer using an arc as the countdown indicator.
+///
+/// When a phase ends the buzzer beeps and the whole screen flashes for a few
+/// seconds or until tapped.
+/// The number of completed focus phases is kept in [`Settings`].
+pub struct Pomodoro {
+    _back: (Button, Label),
+    _start: Button,
+    _skip: (Button, Label),
+    _timer: Timer,
+}
+
+impl Pomodoro {
+    /// Builds the pomodoro timer on the active screen. The back button loads `home`.
+    pub fn new(home: Screen, settings: Rc<RefCell<Settings>>, buzzer: Rc<RefCell<Buzzer>>) -> Self {
+        let back = back_button(home);
+
+        let mut cycles_label = Label::new();
+        cycles_label.align(Align::TopRight.into(), -10, 12);
+        let set_cycles_text = |label: &mut Label, cycles: u32| {
+            let text = CString::new(format!("Cycles: {}", cycles)).unwrap();
+            label.set_text(text.as_c_str());
+        };
+        set_cycles_text(
+            &mut cycles_label,
+            settings.borrow().get(Key::PomodoroCycles),
+        );
+
+        let mut arc = Arc::new();
+        arc.set_size(140, 140);
+        arc.set_rotation(270);
+        arc.set_bg_angles(0, 360);
+        arc.set_range(0, FOCUS.as_secs() as i32);
+        arc.set_value(FOCUS.as_secs() as i32);
+        arc.align(Align::Center.into(), 0, -5);
+        unsafe {
+            lv_obj_remove_style(arc.raw(), core::ptr::null_mut(), LV_PART_KNOB);
+            lv_obj_remove_flag(arc.raw(), LV_OBJ_FLAG_CLICKABLE);
+        }
+
+        let mut time_label = Label::new();
+        time_label.set_parent(&mut arc);
+        time_label.center();
+
+        let mut phase_label = Label::new();
+        phase_label.set_parent(&mut arc);
+        phase_label.align(Align::Center.into(), 0, 24);
+        phase_label.set_text_static(Phase::Focus.name().get());
+
+        let state = Rc::new(RefCell::new(State {
+            phase: Phase::Focus,
+            deadline: None,
+            remaining: FOCUS,
+        }));
+
+        let (mut start, start_label) = text_button(Text::Start.get());
+        let start_label = Rc::new(RefCell::new(start_label));
+        start.set_size(100, 36);
+        start.align(Align::BottomMid.into(), -60, -8);
+        start.add_event_cb(EventCode::Clicked, {
+            let state = state.clone();
+            let start_label = start_label.clone();
+            move |_| {
+                let mut state = state.borrow_mut();
+                match state.deadline.take() {
+                    Some(deadline) => {
+                        state.remaining = deadline.saturating_duration_since(Instant::now());
+                        start_label.borrow_mut().set_text_static(Text::Start.get());
+                    }
+                    None => {
+                        state.deadline = Some(Instant::now() + state.remaining);
+                        start_label.borrow_mut().set_text_static(Text::Pause.get());
+                    }
+                }
+            }
+        });
+
+        let mut skip = translated_button(Text::Skip);
+        skip.0.set_size(100, 36);
+        skip.0.align(Align::BottomMid.into(), 60, -8);
+        let skipped = Rc::new(Cell::new(false));
+        skip.0.add_event_cb(EventCode::Clicked, {
+            let skipped = skipped.clone();
+            move |_| skipped.set(true)
+        });
+
+        let mut overlay = Obj::new();
+        unsafe {
+            lv_obj_set_parent(overlay.raw(), lv_layer_top());
+            lv_obj_set_style_bg_color(overlay.raw(), lv_color_hex(ALERT_COLOR), 0);
+            lv_obj_set_style_bg_opa(overlay.raw(), LV_OPA_COVER as _, 0);
+        }
+        set_visible(&mut overlay, false);
+        overlay.set_size(320, 240);
+        overlay.set_pos(0, 0);
+        let alert_ticks = Rc::new(Cell::new(0));
+        overlay.add_event_cb(EventCode::Clicked, {
+            let alert_ticks = alert_ticks.clone();
+            move |_| alert_ticks.set(0)
+        });
+
+        let mut alerting = false;
+        let timer = Timer::new(TICK_MS, move || {
+            let mut state = state.borrow_mut();
+            let remaining = state.remaining();
+            let finished = state.deadline.is_some() && remaining == Duration::from_ticks(0);
+
+            if finished || skipped.replace(false) {
+                if state.phase == Phase::Focus && finished {
+                    let mut settings = settings.borrow_mut();
+                    let cycles = settings.get(Key::PomodoroCycles) + 1;
+                    settings.set(Key::PomodoroCycles, cycles);
+                    set_cycles_text(&mut cycles_label, cycles);
+                }
+                if finished {
+                    alert_ticks.set(ALERT_TICKS);
+                }
+                state.phase = state.phase.next();
+                state.deadline = None;
+                state.remaining = state.phase.duration();
+                phase_label.set_text_static(state.phase.name().get());
+                start_label.borrow_mut().set_text_static(Text::Start.get());
+                arc.set_range(0, state.phase.duration().as_secs() as i32);
+            }
+
+            let secs = state.remaining().as_secs();
+            arc.set_value(secs as i32);
+            let text = CString::new(format!("{:02}:{:02}", secs / 60, secs % 60)).unwrap();
+            time_label.set_text(text.as_c_str());
+
+            let ticks = alert_ticks.get();
+            if ticks > 0 {
+                alert_ticks.set(ticks - 1);
+                alerting = true;
+                let on = ticks % 2 == 0;
+                set_visible(&mut overlay, on);
+                buzzer.borrow_mut().set_beeping(Beeper::Pomodoro, on);
+            } else if alerting {
+                alerting = false;
+                set_visible(&mut overlay, false);
+                buzzer.borrow_mut().set_beeping(Beeper::Pomodoro, false);
+            }
+        });
+
+        Self {
+            _back: back,
+            _start: start,
+            _skip: skip,
+            _timer: timer,
+        }
+    }
+}
+
+impl UiModule for Pomodoro {
+    const NAME: Text = Text::Pomodoro;
+    const RESIDENT: bool = true;
+
+    fn build(home: Screen, resources: &mut Resources) -> Self {
+        Self::new(
+            home,
+            resources.get::<Rc<RefCell<Settings>>>().clone(),
+            resources.get::<Rc<RefCell<Buzzer>>>().clone(),
+        )
+    }
+}
@@ -0,0 +1,593 @@
+use alloc::ffi::CString;
+use alloc::format;
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::ffi::CStr;
+
+use lv_bevy_ecs::events::EventCode;
+use lv_bevy_ecs::support::{Align, LabelLongMode};
+use lv_bevy_ecs::sys::{
+    LV_ANIM_OFF, LV_FLEX_FLOW_ROW_WRAP, LV_MENU_HEADER_TOP_FIXED, LV_OBJ_FLAG_SCROLLABLE,
+    LV_RADIUS_CIRCLE, LV_ROLLER_MODE_NORMAL, LV_STATE_CHECKED, lv_color_hex,
+    lv_dropdown_get_selected, lv_dropdown_set_options, lv_dropdown_set_selected, lv_label_set_text,
+    lv_label_set_text_static, lv_menu_cont_create, lv_menu_create, lv_menu_page_create,
+    lv_menu_set_load_page_event, lv_menu_set_mode_header, lv_menu_set_page, lv_obj_add_state,
+    lv_obj_has_state, lv_obj_remove_flag, lv_obj_remove_style_all, lv_obj_set_flex_flow,
+    lv_obj_set_flex_grow, lv_obj_set_parent, lv_obj_set_size, lv_obj_set_style_bg_color,
+    lv_obj_set_style_border_color, lv_obj_set_style_border_width, lv_obj_set_style_radius,
+    lv_obj_t, lv_roller_get_selected, lv_roller_set_options, lv_roller_set_selected,
+    lv_roller_set_visible_row_count, lv_slider_get_value, lv_slider_set_range, lv_slider_set_value,
+};
+use lv_bevy_ecs::widgets::{Button, Dropdown, Label, Obj, Roller, Slider, Switch};
+
+use super::accent;
+use super::battery_saver::{self, BatterySaver};
+use super::i18n::{self, Language, Text, translate};
+use super::lock::PinPad;
+use super::module::{Resources, UiModule};
+use super::night_mode::{Mode, NightMode};
+use super::screen::Screen;
+use super::screensaver;
+use super::timer::Timer;
+use super::{back_button, builder, status_bar, translated_button};
+use crate::backlight::{self, Backlight};
+use crate::deep_sleep::TIMEOUTS_MINUTES;
+use crate::pin;
+use crate::rotation::{self, Rotation};
+use crate::settings::{Key, Settings};
+use crate::smart_light::{self, Status};
+use crate::system::{BUILD_TIMESTAMP, FIRMWARE_VERSION, SystemInfo};
+use crate::timezone::{self, ZONES};
+use crate::wifi;
+
+/// Below the back button and the title
+const MENU_Y: i32 = 40;
+const MENU_HEIGHT: i32 = 240 - status_bar::HEIGHT - MENU_Y;
+/// Picks up a change of the WiFi or MQTT connection
+const STATUS_PERIOD_MS: u32 = 1000;
+const DANGER: u32 = 0xD32F2F;
+const SWATCH_SIZE: i32 = 44;
+const MARK_WIDTH: i32 = 4;
+const MARK_COLOR: u32 = 0x212121;
+const PIN_PAD_WIDTH: i32 = 200;
+const PIN_PAD_HEIGHT: i32 = 120;
+
+/// Builds the pages of an `lv_menu`. Pages hold rows, rows hold a label and
+/// a control.
+struct Pages {
+    menu: *mut lv_obj_t,
+    labels: Vec<Label>,
+}
+
+impl Pages {
+    /// A page shown with a back arrow and `title` in the header, or the
+    /// root page for `None`.
+    fn page(&self, title: Option<Text>) -> *mut lv_obj_t {
+        let title = title.map_or(core::ptr::null(), |title| title.get().as_ptr());
+        unsafe { lv_menu_page_create(self.menu, title) }
+    }
+
+    /// Adds a row with `text` on the left to `page`. Place the control with [`place`].
+    fn row(&mut self, page: *mut lv_obj_t, text: Text) -> *mut lv_obj_t {
+        let row = unsafe { lv_menu_cont_create(page) };
+        let mut label = Label::new();
+        translate(&mut label, text);
+        unsafe {
+            lv_obj_set_parent(label.raw(), row);
+            lv_obj_set_flex_grow(label.raw(), 1);
+        }
+        self.labels.push(label);
+        row
+    }
+
+    /// Adds a row to `page` that opens `target` when tapped.
+    fn link(&mut self, page: *mut lv_obj_t, text: Text, target: *mut lv_obj_t) {
+        let row = self.row(page, text);
+        unsafe { lv_menu_set_load_page_event(self.menu, row, target) };
+    }
+}
+
+/// Moves `control` to the right of `row`.
+fn place(row: *mut lv_obj_t, control: *mut lv_obj_t) {
+    unsafe { lv_obj_set_parent(control, row) };
+}
+
+/// Row on `page` with a dropdown of `choices` in minutes, 0 being off, for
+/// the timeout kept under `key`.
+fn timeout_row(
+    pages: &mut Pages,
+    page: *mut lv_obj_t,
+    text: Text,
+    key: Key,
+    choices: &'static [u32],
+    settings: &Rc<RefCell<Settings>>,
+) -> Dropdown {
+    let mut dropdown = Dropdown::new();
+    dropdown.set_width(140);
+    let dropdown_raw = dropdown.raw();
+    place(pages.row(page, text), dropdown_raw);
+    let timeout = settings.borrow().get(key);
+    let selected = choices
+        .iter()
+        .position(|&minutes| minutes == timeout)
+        .unwrap_or(0);
+    translate_options(dropdown_raw, move || {
+        choices
+            .iter()
+            .map(|&minutes| match minutes {
+                0 => String::from(Text::Off.get().to_str().unwrap()),
+                minutes => format!("{minutes} min"),
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    });
+    unsafe { lv_dropdown_set_selected(dropdown_raw, selected as u32) };
+    dropdown.add_event_cb(EventCode::ValueChanged, {
+        let settings = settings.clone();
+        move |_| {
+            let index = unsafe { lv_dropdown_get_selected(dropdown_raw) };
+            let minutes = choices.get(index as usize).copied().unwrap_or(0);
+            settings.borrow_mut().set(key, minutes);
+        }
+    });
+    dropdown
+}
+
+/// Sets the options of `dropdown` to what `options` builds, and again in the
+/// new language after each change, keeping the selection.
+fn translate_options(dropdown: *mut lv_obj_t, options: impl Fn() -> String + 'static) {
+    let set = move || unsafe {
+        let selected = lv_dropdown_get_selected(dropdown);
+        lv_dropdown_set_options(dropdown, CString::new(options()).unwrap().as_ptr());
+        lv_dropdown_set_selected(dropdown, selected);
+    };
+    set();
+    i18n::on_language_change(dropdown, set);
+}
+
+/// Dropdown options naming each of `items`.
+fn names<T>(items: &[T], name: impl Fn(&T) -> &'static CStr) -> String {
+    items
+        .iter()
+        .map(|item| name(item).to_str().unwrap())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Label filling a row of its own on `page`, for text that changes.
+fn text_row(page: *mut lv_obj_t) -> Label {
+    let row = unsafe { lv_menu_cont_create(page) };
+    let mut label = Label::new();
+    label.set_long_mode(LabelLongMode::Wrap.into());
+    unsafe {
+        lv_obj_set_parent(label.raw(), row);
+        lv_obj_set_flex_grow(label.raw(), 1);
+    }
+    label.set_text_static(c"");
+    label
+}
+
+/// Rings the swatch of the current accent.
+fn mark_swatch(swatches: &[*mut lv_obj_t], selected: usize) {
+    for (index, &swatch) in swatches.iter().enumerate() {
+        let width = if index == selected { MARK_WIDTH } else { 0 };
+        unsafe { lv_obj_set_style_border_width(swatch, width, 0) };
+    }
+}
+
+fn show_pin_status(label: *mut lv_obj_t, set: bool) {
+    let text = if set { Text::On } else { Text::Off };
+    unsafe { lv_label_set_text_static(label, text.get().as_ptr()) };
+}
+
+fn set_text(label: *mut lv_obj_t, text: String) {
+    unsafe { lv_label_set_text(label, CString::new(text).unwrap().as_ptr()) };
+}
+
+/// Device settings on nested `lv_menu` pages: Display, Network and System.
+///
+/// The accent color is picked from [`accent::PALETTE`] on a page of its own,
+/// as is the PIN for the [`Lock`](super::lock::Lock) screen.
+///
+/// Changes are applied right away and kept in [`Settings`]. Only the
+/// rotation, which the panel is set up with at boot, restarts the device.
+pub struct Preferences {
+    _timer: Timer,
+    _back: (Button, Label),
+    _title: Label,
+    _labels: Vec<Label>,
+    _brightness: Slider,
+    _night: Dropdown,
+    _rotation: Dropdown,
+    _wifi: Switch,
+    _wifi_status: Label,
+    _mqtt_status: Label,
+    _language: Dropdown,
+    _timezone: Roller,
+    _sleep: Dropdown,
+    _screensaver: Dropdown,
+    _battery_saver: Dropdown,
+    _about: Label,
+    _reset_prompt: Label,
+    _reset: (Button, Label),
+    _pin_status: Label,
+    _pin_prompt: Label,
+    _pin_pad: PinPad,
+    _pin_remove: (Button, Label),
+    _swatches: Vec<Obj>,
+    /// Holds the menu, so it goes after everything placed on its pages
+    _menu: Obj,
+}
+
+impl Preferences {
+    /// Builds the settings screen on the active screen. The back button loads `home`.
+    pub fn new(
+        home: Screen,
+        settings: Rc<RefCell<Settings>>,
+        night_mode: Rc<NightMode>,
+        battery_saver: Rc<BatterySaver>,
+        backlight: Rc<RefCell<Backlight>>,
+        info: &SystemInfo,
+    ) -> Self {
+        let back = back_button(home);
+
+        let title = builder::label()
+            .translated(Text::Settings)
+            .align(Align::TopMid)
+            .offset(0, 12)
+            .build();
+
+        let mut container = Obj::new();
+        unsafe { lv_obj_remove_style_all(container.raw()) };
+        container.set_size(320, MENU_HEIGHT);
+        container.align(Align::TopMid.into(), 0, MENU_Y);
+        let menu = unsafe { lv_menu_create(container.raw()) };
+        unsafe {
+            lv_obj_set_size(menu, 320, MENU_HEIGHT);
+            lv_menu_set_mode_header(menu, LV_MENU_HEADER_TOP_FIXED);
+        }
+
+        let mut pages = Pages {
+            menu,
+            labels: Vec::new(),
+        };
+        let root = pages.page(None);
+        let display = pages.page(Some(Text::Display));
+        let network = pages.page(Some(Text::Network));
+        let system = pages.page(Some(Text::System));
+        let about = pages.page(Some(Text::About));
+        let reset = pages.page(Some(Text::Reset));
+        let accent_page = pages.page(Some(Text::Accent));
+        let pin_page = pages.page(Some(Text::PinLock));
+        pages.link(root, Text::Display, display);
+        pages.link(root, Text::Network, network);
+        pages.link(root, Text::System, system);
+
+        // Display
+        let mut brightness = Slider::new();
+        brightness.set_width(140);
+        let brightness_raw = brightness.raw();
+        place(pages.row(display, Text::Brightness), brightness_raw);
+        unsafe {
+            lv_slider_set_range(brightness_raw, backlight::MIN_PERCENT as i32, 100);
+            lv_slider_set_value(
+                brightness_raw,
+                backlight.borrow().percent() as i32,
+                LV_ANIM_OFF,
+            );
+        }
+        brightness.add_event_cb(EventCode::ValueChanged, {
+            let backlight = backlight.clone();
+            move |_| {
+                let percent = unsafe { lv_slider_get_value(brightness_raw) };
+                backlight.borrow_mut().set_percent(percent as u8);
+            }
+        });
+        // Written once the finger lifts, not on every step of the drag
+        brightness.add_event_cb(EventCode::Released, {
+            let settings = settings.clone();
+            move |_| {
+                let percent = backlight.borrow().percent();
+                settings.borrow_mut().set(Key::Brightness, percent as u32);
+            }
+        });
+
+        let mut night = Dropdown::new();
+        night.set_width(140);
+        let night_raw = night.raw();
+        place(pages.row(display, Text::NightMode), night_raw);
+        translate_options(night_raw, || names(&Mode::ALL, |mode| mode.name()));
+        unsafe { lv_dropdown_set_selected(night_raw, night_mode.mode() as u32) };
+        night.add_event_cb(EventCode::ValueChanged, {
+            let settings = settings.clone();
+            move |_| {
+                let index = unsafe { lv_dropdown_get_selected(night_raw) };
+                night_mode.set_mode(Mode::from_index(index));
+                settings.borrow_mut().set(Key::NightMode, index);
+            }
+        });
+
+        let mut rotation = Dropdown::new();
+        rotation.set_width(140);
+        let rotation_raw = rotation.raw();
+        place(pages.row(display, Text::Rotation), rotation_raw);
+        translate_options(rotation_raw, || {
+            names(&Rotation::ALL, |&item| rotation::name(item))
+        });
+        unsafe { lv_dropdown_set_selected(rotation_raw, settings.borrow().get(Key::Rotation)) };
+        rotation.add_event_cb(EventCode::ValueChanged, {
+            let settings = settings.clone();
+            move |_| {
+                let index = unsafe { lv_dropdown_get_selected(rotation_raw) };
+                settings.borrow_mut().set(Key::Rotation, index);
+                esp_hal::system::software_reset();
+            }
+        });
+
+        pages.link(display, Text::Accent, accent_page);
+
+        // Display, Accent
+        let swatch_row = unsafe { lv_menu_cont_create(accent_page) };
+        unsafe { lv_obj_set_flex_flow(swatch_row, LV_FLEX_FLOW_ROW_WRAP) };
+        let mut swatches = (0..accent::PALETTE.len())
+            .map(|index| {
+                let mut swatch = Obj::new();
+                swatch.set_size(SWATCH_SIZE, SWATCH_SIZE);
+                unsafe {
+                    lv_obj_set_parent(swatch.raw(), swatch_row);
+                    lv_obj_set_style_bg_color(swatch.raw(), accent::color(index as u32), 0);
+                    lv_obj_set_style_radius(swatch.raw(), LV_RADIUS_CIRCLE as _, 0);
+                    lv_obj_set_style_border_color(swatch.raw(), lv_color_hex(MARK_COLOR), 0);
+                    lv_obj_remove_flag(swatch.raw(), LV_OBJ_FLAG_SCROLLABLE);
+                }
+                swatch
+            })
+            .collect::<Vec<_>>();
+        let swatch_raws: Rc<[*mut lv_obj_t]> = swatches.iter().map(Obj::raw).collect();
+        mark_swatch(&swatch_raws, settings.borrow().get(Key::Accent) as usize);
+        for (index, swatch) in swatches.iter_mut().enumerate() {
+            let settings = settings.clone();
+            let swatch_raws = swatch_raws.clone();
+            swatch.add_event_cb(EventCode::Clicked, move |_| {
+                accent::apply(index as u32);
+                mark_swatch(&swatch_raws, index);
+                settings.borrow_mut().set(Key::Accent, index as u32);
+            });
+        }
+
+        // Network
+        let mut wifi_switch = Switch::new();
+        let wifi_raw = wifi_switch.raw();
+        place(pages.row(network, Text::Wifi), wifi_raw);
+        if wifi::is_enabled() {
+            unsafe { lv_obj_add_state(wifi_raw, LV_STATE_CHECKED) };
+        }
+        wifi_switch.add_event_cb(EventCode::ValueChanged, move |_| {
+            wifi::set_enabled(unsafe { lv_obj_has_state(wifi_raw, LV_STATE_CHECKED) });
+        });
+        let wifi_status = text_row(network);
+        let wifi_status_raw = wifi_status.raw();
+
+        pages.row(network, Text::Mqtt);
+        let mqtt_status = text_row(network);
+        let mqtt_status_raw = mqtt_status.raw();
+
+        let ssid = settings
+            .borrow_mut()
+            .wifi_credentials()
+            .map(|(ssid, _)| ssid);
+        let refresh = move || {
+            let wifi = match (&ssid, wifi::signal_strength()) {
+                (None, _) => String::from("No network saved"),
+                (Some(ssid), None) => format!("{ssid}, not connected"),
+                (Some(ssid), Some(rssi)) => format!("{ssid}, {rssi} dBm"),
+            };
+            set_text(wifi_status_raw, wifi);
+            let mqtt = match (smart_light::broker(), smart_light::status()) {
+                (Some(broker), Status::Online) => format!("{broker}, online"),
+                (Some(broker), _) => format!("{broker}, offline"),
+                (None, _) => String::from("Build with MQTT_BROKER to use a broker"),
+            };
+            set_text(mqtt_status_raw, mqtt);
+        };
+        refresh();
+        let timer = Timer::new(STATUS_PERIOD_MS, refresh);
+
+        // System
+        let options = Language::ALL
+            .iter()
+            .map(|language| language.name().to_str().unwrap())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let mut language = Dropdown::new();
+        language.set_width(140);
+        let language_raw = language.raw();
+        place(pages.row(system, Text::Language), language_raw);
+        unsafe {
+            lv_dropdown_set_options(language_raw, CString::new(options).unwrap().as_ptr());
+            lv_dropdown_set_selected(language_raw, i18n::language() as u32);
+        }
+        language.add_event_cb(EventCode::ValueChanged, {
+            let settings = settings.clone();
+            move |_| {
+                let index = unsafe { lv_dropdown_get_selected(language_raw) };
+                i18n::set_language(Language::from_index(index));
+                settings.borrow_mut().set(Key::Language, index);
+            }
+        });
+
+        let options = ZONES
+            .iter()
+            .map(|zone| zone.name.to_str().unwrap())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let mut timezone_roller = Roller::new();
+        timezone_roller.set_width(170);
+        let timezone_raw = timezone_roller.raw();
+        place(pages.row(system, Text::Timezone), timezone_raw);
+        unsafe {
+            lv_roller_set_options(
+                timezone_raw,
+                CString::new(options).unwrap().as_ptr(),
+                LV_ROLLER_MODE_NORMAL,
+            );
+            lv_roller_set_visible_row_count(timezone_raw, 2);
+            lv_roller_set_selected(timezone_raw, timezone::current_index(), LV_ANIM_OFF);
+        }
+        timezone_roller.add_event_cb(EventCode::ValueChanged, {
+            let settings = settings.clone();
+            move |_| {
+                let index = unsafe { lv_roller_get_selected(timezone_raw) };
+                timezone::set(index);
+                settings.borrow_mut().set(Key::Timezone, index);
+            }
+        });
+
+        let sleep = timeout_row(
+            &mut pages,
+            system,
+            Text::Sleep,
+            Key::SleepTimeout,
+            &TIMEOUTS_MINUTES,
+            &settings,
+        );
+        let screensaver = timeout_row(
+            &mut pages,
+            system,
+            Text::Screensaver,
+            Key::ScreensaverTimeout,
+            &screensaver::TIMEOUTS_MINUTES,
+            &settings,
+        );
+
+        let mut saver = Dropdown::new();
+        saver.set_width(140);
+        let saver_raw = saver.raw();
+        place(pages.row(system, Text::BatterySaver), saver_raw);
+        translate_options(saver_raw, || {
+            names(&battery_saver::Mode::ALL, |mode| mode.name())
+        });
+        unsafe { lv_dropdown_set_selected(saver_raw, battery_saver.mode() as u32) };
+        saver.add_event_cb(EventCode::ValueChanged, {
+            let settings = settings.clone();
+            move |_| {
+                let index = unsafe { lv_dropdown_get_selected(saver_raw) };
+                battery_saver.set_mode(battery_saver::Mode::from_index(index));
+                settings.borrow_mut().set(Key::BatterySaver, index);
+            }
+        });
+
+        pages.link(system, Text::PinLock, pin_page);
+        pages.link(system, Text::About, about);
+        pages.link(system, Text::Reset, reset);
+
+        // System, PIN lock
+        let pin_status = Label::new();
+        let pin_status_raw = pin_status.raw();
+        place(pages.row(pin_page, Text::PinLock), pin_status_raw);
+        show_pin_status(pin_status_raw, pin::is_set(&settings.borrow()));
+        let mut pin_prompt = text_row(pin_page);
+        translate(&mut pin_prompt, Text::NewPin);
+        let pin_pad = PinPad::new(PIN_PAD_WIDTH, PIN_PAD_HEIGHT, {
+            let settings = settings.clone();
+            move |digits| {
+                if digits.len() < pin::MIN_LEN {
+                    return;
+                }
+                pin::set(&mut settings.borrow_mut(), digits);
+                show_pin_status(pin_status_raw, true);
+            }
+        });
+        let pin_pad_row = unsafe { lv_menu_cont_create(pin_page) };
+        place(pin_pad_row, pin_pad.raw());
+        let mut pin_remove = translated_button(Text::Remove);
+        let pin_remove_row = unsafe { lv_menu_cont_create(pin_page) };
+        place(pin_remove_row, pin_remove.0.raw());
+        unsafe {
+            lv_obj_remove_flag(pin_pad_row, LV_OBJ_FLAG_SCROLLABLE);
+            lv_obj_remove_flag(pin_remove_row, LV_OBJ_FLAG_SCROLLABLE);
+        }
+        pin_remove.0.add_event_cb(EventCode::Clicked, {
+            let settings = settings.clone();
+            move |_| {
+                pin::clear(&mut settings.borrow_mut());
+                show_pin_status(pin_status_raw, false);
+            }
+        });
+
+        // System, About
+        let about_label = text_row(about);
+        let [m0, m1, m2, m3, m4, m5] = info.mac_address;
+        set_text(
+            about_label.raw(),
+            format!(
+                "Firmware: {}\n\
+                 Built: {} UTC\n\
+                 MAC: {:02X}:{:02X}:{:02X}:{:02X}:{:02X}:{:02X}\n\
+                 Boots: {}",
+                FIRMWARE_VERSION, BUILD_TIMESTAMP, m0, m1, m2, m3, m4, m5, info.boot_count
+            ),
+        );
+
+        // System, Reset
+        let mut reset_prompt = text_row(reset);
+        translate(&mut reset_prompt, Text::ResetPrompt);
+        let mut reset_button = translated_button(Text::Reset);
+        let reset_row = unsafe { lv_menu_cont_create(reset) };
+        place(reset_row, reset_button.0.raw());
+        unsafe {
+            lv_obj_set_style_bg_color(reset_button.0.raw(), lv_color_hex(DANGER), 0);
+            lv_obj_remove_flag(reset_row, LV_OBJ_FLAG_SCROLLABLE);
+        }
+        reset_button.0.add_event_cb(EventCode::Clicked, move |_| {
+            settings.borrow_mut().clear();
+            esp_hal::system::software_reset();
+        });
+
+        unsafe { lv_menu_set_page(menu, root) };
+
+        Self {
+            _timer: timer,
+            _back: back,
+            _title: title,
+            _labels: pages.labels,
+            _brightness: brightness,
+            _night: night,
+            _rotation: rotation,
+            _wifi: wifi_switch,
+            _wifi_status: wifi_status,
+            _mqtt_status: mqtt_status,
+            _language: language,
+            _timezone: timezone_roller,
+            _sleep: sleep,
+            _screensaver: screensaver,
+            _battery_saver: saver,
+            _about: about_label,
+            _reset_prompt: reset_prompt,
+            _reset: reset_button,
+            _pin_status: pin_status,
+            _pin_prompt: pin_prompt,
+            _pin_pad: pin_pad,
+            _pin_remove: pin_remove,
+            _swatches: swatches,
+            _menu: container,
+        }
+    }
+}
+
+impl UiModule for Preferences {
+    const NAME: Text = Text::Settings;
+
+    fn build(home: Screen, resources: &mut Resources) -> Self {
+        Self::new(
+            home,
+            resources.get::<Rc<RefCell<Settings>>>().clone(),
+            resources.get::<Rc<NightMode>>().clone(),
+            resources.get::<Rc<BatterySaver>>().clone(),
+            resources.get::<Rc<RefCell<Backlight>>>().clone(),
+            resources.get::<SystemInfo>(),
+        )
+    }
+}
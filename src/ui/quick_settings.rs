@@ -0,0 +1,196 @@
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+
+use lv_bevy_ecs::events::EventCode;
+use lv_bevy_ecs::support::Align;
+use lv_bevy_ecs::sys::{
+    LV_ANIM_OFF, LV_DIR_BOTTOM, LV_DIR_TOP, LV_OBJ_FLAG_CLICKABLE, LV_OBJ_FLAG_SCROLLABLE,
+    LV_OPA_COVER, LV_OPA_TRANSP, LV_STATE_CHECKED, lv_color_hex, lv_indev_active,
+    lv_indev_get_gesture_dir, lv_layer_top, lv_obj_add_state, lv_obj_get_y, lv_obj_has_state,
+    lv_obj_remove_flag, lv_obj_set_parent, lv_obj_set_style_bg_color, lv_obj_set_style_bg_opa,
+    lv_obj_set_style_border_width, lv_obj_set_style_radius, lv_obj_set_style_text_color, lv_obj_t,
+    lv_slider_get_value, lv_slider_set_range, lv_slider_set_value,
+};
+use lv_bevy_ecs::widgets::{Label, Obj, Slider, Switch};
+
+use super::animate::{Animate, Animation, Easing, Property};
+use super::i18n::{Text, translate};
+use super::status_bar;
+use crate::backlight::{self, Backlight};
+use crate::buzzer::Buzzer;
+use crate::settings::{Key, Settings};
+use crate::wifi;
+
+const PANEL_HEIGHT: i32 = 140;
+const SLIDE_MS: u32 = 250;
+const BACKGROUND: u32 = 0x303030;
+const ROW_HEIGHT: i32 = 38;
+
+/// Panel pulled down from the top edge of any screen, with the backlight
+/// brightness, mute and WiFi.
+///
+/// It sits above the current screen on the top layer and slides back up
+/// with a swipe up or a tap on the handle.
+pub struct QuickSettings {
+    // Stopped before the panel it moves is deleted
+    _animation: Rc<RefCell<Option<Animation>>>,
+    _edge: Obj,
+    _panel: Obj,
+    _labels: Vec<Label>,
+    _brightness: Slider,
+    _mute: Switch,
+    _wifi: Switch,
+    _handle: (Obj, Obj),
+}
+
+impl QuickSettings {
+    /// Creates the closed panel on the top layer. Create it before the debug
+    /// menu so the debug corner stays reachable.
+    pub fn new(
+        buzzer: Rc<RefCell<Buzzer>>,
+        backlight: Rc<RefCell<Backlight>>,
+        settings: Rc<RefCell<Settings>>,
+    ) -> Self {
+        let mut edge = Obj::new();
+        unsafe {
+            lv_obj_set_parent(edge.raw(), lv_layer_top());
+            lv_obj_set_style_bg_opa(edge.raw(), LV_OPA_TRANSP as _, 0);
+            lv_obj_set_style_border_width(edge.raw(), 0, 0);
+        }
+        edge.set_size(320, status_bar::HEIGHT);
+        edge.set_pos(0, 0);
+
+        // Created after the edge strip so it covers it while open
+        let mut panel = Obj::new();
+        unsafe {
+            lv_obj_set_parent(panel.raw(), lv_layer_top());
+            lv_obj_set_style_bg_color(panel.raw(), lv_color_hex(BACKGROUND), 0);
+            lv_obj_set_style_bg_opa(panel.raw(), LV_OPA_COVER as _, 0);
+            lv_obj_set_style_text_color(panel.raw(), lv_color_hex(0xFFFFFF), 0);
+            lv_obj_set_style_radius(panel.raw(), 0, 0);
+            lv_obj_remove_flag(panel.raw(), LV_OBJ_FLAG_SCROLLABLE);
+        }
+        panel.set_size(320, PANEL_HEIGHT);
+        panel.set_pos(0, -PANEL_HEIGHT);
+        let panel_raw = panel.raw();
+
+        let animation = Rc::new(RefCell::new(None));
+        let slide = {
+            let animation = animation.clone();
+            move |open: bool| {
+                let from = unsafe { lv_obj_get_y(panel_raw) };
+                let to = if open { 0 } else { -PANEL_HEIGHT };
+                *animation.borrow_mut() = Some(
+                    Animate::new(Property::Y, from, to, SLIDE_MS)
+                        .with_easing(Easing::EaseOut)
+                        .start(panel_raw),
+                );
+            }
+        };
+
+        let mut labels = Vec::new();
+        let mut row = |text: Text, control: *mut lv_obj_t, index: i32| {
+            let mut label = Label::new();
+            label.set_parent(&mut panel);
+            label.align(Align::TopLeft.into(), 0, index * ROW_HEIGHT + 6);
+            translate(&mut label, text);
+            labels.push(label);
+            unsafe { lv_obj_set_parent(control, panel_raw) };
+        };
+
+        let mut brightness = Slider::new();
+        brightness.set_width(160);
+        let brightness_raw = brightness.raw();
+        row(Text::Brightness, brightness_raw, 0);
+        brightness.align(Align::TopRight.into(), -10, 8);
+        unsafe {
+            lv_slider_set_range(brightness_raw, backlight::MIN_PERCENT as i32, 100);
+            lv_slider_set_value(
+                brightness_raw,
+                backlight.borrow().percent() as i32,
+                LV_ANIM_OFF,
+            );
+        }
+        brightness.add_event_cb(EventCode::ValueChanged, {
+            let backlight = backlight.clone();
+            move |_| {
+                let percent = unsafe { lv_slider_get_value(brightness_raw) };
+                backlight.borrow_mut().set_percent(percent as u8);
+            }
+        });
+        brightness.add_event_cb(EventCode::Released, move |_| {
+            let percent = backlight.borrow().percent();
+            settings.borrow_mut().set(Key::Brightness, percent as u32);
+        });
+
+        let mut mute = Switch::new();
+        let mute_raw = mute.raw();
+        row(Text::Mute, mute_raw, 1);
+        mute.align(Align::TopRight.into(), 0, ROW_HEIGHT);
+        if buzzer.borrow().is_muted() {
+            unsafe { lv_obj_add_state(mute_raw, LV_STATE_CHECKED) };
+        }
+        mute.add_event_cb(EventCode::ValueChanged, move |_| {
+            let on = unsafe { lv_obj_has_state(mute_raw, LV_STATE_CHECKED) };
+            buzzer.borrow_mut().set_muted(on);
+        });
+
+        let mut wifi_switch = Switch::new();
+        let wifi_raw = wifi_switch.raw();
+        row(Text::Wifi, wifi_raw, 2);
+        wifi_switch.align(Align::TopRight.into(), 0, 2 * ROW_HEIGHT);
+        if wifi::is_enabled() {
+            unsafe { lv_obj_add_state(wifi_raw, LV_STATE_CHECKED) };
+        }
+        wifi_switch.add_event_cb(EventCode::ValueChanged, move |_| {
+            wifi::set_enabled(unsafe { lv_obj_has_state(wifi_raw, LV_STATE_CHECKED) });
+        });
+
+        // A wide, easy to hit strip around a small grip
+        let mut handle = Obj::new();
+        handle.set_parent(&mut panel);
+        handle.set_size(120, 20);
+        handle.align(Align::BottomMid.into(), 0, 10);
+        let mut grip = Obj::new();
+        grip.set_parent(&mut handle);
+        grip.set_size(40, 6);
+        grip.center();
+        unsafe {
+            lv_obj_set_style_bg_opa(handle.raw(), LV_OPA_TRANSP as _, 0);
+            lv_obj_set_style_border_width(handle.raw(), 0, 0);
+            lv_obj_set_style_bg_color(grip.raw(), lv_color_hex(0x9E9E9E), 0);
+            lv_obj_set_style_border_width(grip.raw(), 0, 0);
+            lv_obj_remove_flag(grip.raw(), LV_OBJ_FLAG_CLICKABLE);
+        }
+        handle.add_event_cb(EventCode::Clicked, {
+            let slide = slide.clone();
+            move |_| slide(false)
+        });
+        panel.add_event_cb(EventCode::Gesture, {
+            let slide = slide.clone();
+            move |_| {
+                if unsafe { lv_indev_get_gesture_dir(lv_indev_active()) } == LV_DIR_TOP {
+                    slide(false);
+                }
+            }
+        });
+
+        edge.add_event_cb(EventCode::Gesture, move |_| {
+            if unsafe { lv_indev_get_gesture_dir(lv_indev_active()) } == LV_DIR_BOTTOM {
+                slide(true);
+            }
+        });
+
+        Self {
+            _animation: animation,
+            _edge: edge,
+            _panel: panel,
+            _labels: labels,
+            _brightness: brightness,
+            _mute: mute,
+            _wifi: wifi_switch,
+            _handle: (handle, grip),
+        }
+    }
+}
@@ -0,0 +1,206 @@
+use alloc::ffi::CString;
+use alloc::format;
+use alloc::rc::Rc;
+use alloc::string::String;
+use core::cell::{Cell, RefCell};
+
+use lv_bevy_ecs::events::EventCode;
+use lv_bevy_ecs::support::Align;
+use lv_bevy_ecs::sys::lv_label_set_text;
+use lv_bevy_ecs::widgets::{Button, Label};
+
+use super::i18n::Text;
+use super::module::{Resources, UiModule};
+use super::screen::Screen;
+use super::timer::Timer;
+use super::translated_button;
+use super::{back_button, builder};
+use crate::ir::{self, Button as RemoteButton};
+use crate::settings::Settings;
+
+const POLL_PERIOD_MS: u32 = 50;
+
+/// Teaches the keypad input the codes of an unknown remote.
+///
+/// "Learn" asks for each key in turn and stores the whole set in
+/// [`Settings`] once the last one arrives, "Defaults" goes back to the codes
+/// of the common Arduino kit remote. The keypad ignores the remote while
+/// learning, and a set left unfinished is dropped.
+pub struct Remote {
+    _back: (Button, Label),
+    _title: Label,
+    _codes: Label,
+    _status: Label,
+    _learn: (Button, Label),
+    _defaults: (Button, Label),
+    _timer: Timer,
+}
+
+/// Codes in [`RemoteButton::ALL`] order
+type Codes = [u32; RemoteButton::ALL.len()];
+
+fn stored_codes(settings: &Settings) -> Codes {
+    RemoteButton::ALL.map(|button| settings.get(button.key()))
+}
+
+fn name(button: RemoteButton) -> &'static str {
+    let text = match button {
+        RemoteButton::Up => Text::Up,
+        RemoteButton::Down => Text::Down,
+        RemoteButton::Left => Text::Left,
+        RemoteButton::Right => Text::Right,
+        RemoteButton::Ok => Text::Ok,
+        RemoteButton::Back => Text::Back,
+    };
+    text.get().to_str().unwrap()
+}
+
+fn press_prompt(button: RemoteButton) -> String {
+    format!(
+        "{} {} {}",
+        Text::Press.get().to_str().unwrap(),
+        name(button),
+        Text::OnTheRemote.get().to_str().unwrap()
+    )
+}
+
+fn codes_text(codes: &Codes) -> CString {
+    let mut text = String::new();
+    for (button, code) in RemoteButton::ALL.into_iter().zip(codes) {
+        text.push_str(&format!("{:<8}{:#010X}\n", name(button), code));
+    }
+    CString::new(text.trim_end()).unwrap()
+}
+
+impl Remote {
+    /// Builds the screen on the active screen. The back button loads `home`.
+    pub fn new(home: Screen, settings: Rc<RefCell<Settings>>) -> Self {
+        let back = back_button(home);
+
+        let title = builder::label()
+            .translated(Text::Remote)
+            .align(Align::TopMid)
+            .offset(0, 12)
+            .build();
+
+        let mut codes = Label::new();
+        codes.set_text(codes_text(&stored_codes(&settings.borrow())).as_c_str());
+        codes.align(Align::TopLeft.into(), 20, 50);
+
+        let mut status = Label::new();
+        status.set_text_static(Text::LearnPrompt.get());
+        status.set_width(290);
+        status.align(Align::BottomMid.into(), 0, -60);
+
+        let mut learn = translated_button(Text::Learn);
+        learn.0.set_size(110, 36);
+        learn.0.align(Align::TopRight.into(), -20, 60);
+
+        let mut defaults = translated_button(Text::Defaults);
+        defaults.0.set_size(110, 36);
+        defaults.0.align(Align::TopRight.into(), -20, 110);
+
+        // Index into `RemoteButton::ALL` of the key asked for, and the
+        // codes so far
+        let learning: Rc<Cell<Option<usize>>> = Rc::new(Cell::new(None));
+        let learned = Rc::new(Cell::new(stored_codes(&settings.borrow())));
+        let (codes_raw, status_raw) = (codes.raw(), status.raw());
+        let set_status = move |text: String| {
+            let text = CString::new(text).unwrap();
+            unsafe { lv_label_set_text(status_raw, text.as_ptr()) };
+        };
+
+        learn.0.add_event_cb(EventCode::Clicked, {
+            let (learning, learned, settings) =
+                (learning.clone(), learned.clone(), settings.clone());
+            move |_| {
+                ir::set_learning(true);
+                learning.set(Some(0));
+                learned.set(stored_codes(&settings.borrow()));
+                set_status(press_prompt(RemoteButton::ALL[0]));
+            }
+        });
+
+        defaults.0.add_event_cb(EventCode::Clicked, {
+            let (learning, settings) = (learning.clone(), settings.clone());
+            move |_| {
+                ir::set_learning(false);
+                learning.set(None);
+                let mut settings = settings.borrow_mut();
+                for button in RemoteButton::ALL {
+                    settings.reset(button.key());
+                }
+                ir::reload(&settings);
+                let text = codes_text(&stored_codes(&settings));
+                unsafe { lv_label_set_text(codes_raw, text.as_ptr()) };
+                set_status(String::from(Text::BackToDefaults.get().to_str().unwrap()));
+            }
+        });
+
+        let timer = Timer::new(POLL_PERIOD_MS, move || {
+            let Some(index) = learning.get() else {
+                return;
+            };
+            let Some(code) = ir::take_received() else {
+                return;
+            };
+            let button = RemoteButton::ALL[index];
+            let mut codes = learned.get();
+            // One key for two buttons would make the second unreachable
+            let taken = codes[..index].iter().position(|&earlier| earlier == code);
+            if let Some(earlier) = taken.map(|earlier| RemoteButton::ALL[earlier]) {
+                set_status(format!(
+                    "{} {} {} {}",
+                    Text::ThatIs.get().to_str().unwrap(),
+                    name(earlier),
+                    Text::AlreadyPress.get().to_str().unwrap(),
+                    name(button)
+                ));
+                return;
+            }
+            codes[index] = code;
+            learned.set(codes);
+            let text = codes_text(&codes);
+            unsafe { lv_label_set_text(codes_raw, text.as_ptr()) };
+
+            match RemoteButton::ALL.get(index + 1) {
+                Some(next) => {
+                    learning.set(Some(index + 1));
+                    set_status(press_prompt(*next));
+                }
+                None => {
+                    learning.set(None);
+                    ir::set_learning(false);
+                    let mut settings = settings.borrow_mut();
+                    for (button, code) in RemoteButton::ALL.into_iter().zip(codes) {
+                        settings.set(button.key(), code);
+                    }
+                    ir::reload(&settings);
+                    set_status(String::from(Text::Learned.get().to_str().unwrap()));
+                }
+            }
+        });
+
+        Self {
+            _back: back,
+            _title: title,
+            _codes: codes,
+            _status: status,
+            _learn: learn,
+            _defaults: defaults,
+            _timer: timer,
+        }
+    }
+}
+
+impl UiModule for Remote {
+    const NAME: Text = Text::Remote;
+
+    fn build(home: Screen, resources: &mut Resources) -> Self {
+        Self::new(home, resources.get::<Rc<RefCell<Settings>>>().clone())
+    }
+
+    fn teardown(&mut self) {
+        ir::set_learning(false);
+    }
+}
@@ -0,0 +1,121 @@
+use alloc::ffi::CString;
+use alloc::format;
+
+use lv_bevy_ecs::events::EventCode;
+use lv_bevy_ecs::support::Align;
+use lv_bevy_ecs::sys::{
+    LV_TEXT_DECOR_UNDERLINE, lv_arc_get_value, lv_color_hex, lv_obj_remove_style_all,
+    lv_obj_set_size, lv_obj_t, lv_span_get_style, lv_span_t, lv_spangroup_add_span,
+    lv_spangroup_create, lv_spangroup_refresh, lv_spangroup_set_span_text, lv_style_set_text_color,
+    lv_style_set_text_decor, lv_style_set_text_font,
+};
+use lv_bevy_ecs::widgets::{Arc, Button, Label, Obj};
+
+use super::fonts::Font;
+use super::i18n::Text;
+use super::module::{Resources, UiModule};
+use super::screen::Screen;
+use super::{back_button, builder};
+
+const WIDTH: i32 = 300;
+const HEIGHT: i32 = 100;
+const VALUE_COLOR: u32 = 0x2196F3;
+/// Level names with their colors, by arc value
+const LEVELS: [(i32, Text, u32); 3] = [
+    (33, Text::Low, 0x4CAF50),
+    (66, Text::Moderate, 0xFF9800),
+    (100, Text::High, 0xF44336),
+];
+
+/// Adds a span with `text`, which LVGL copies.
+unsafe fn add_span(group: *mut lv_obj_t, text: &str) -> *mut lv_span_t {
+    let span = unsafe { lv_spangroup_add_span(group) };
+    let text = CString::new(text).unwrap();
+    unsafe { lv_spangroup_set_span_text(group, span, text.as_ptr()) };
+    span
+}
+
+/// A sentence in a spangroup, with the arc value and its level in their own
+/// colors and fonts, which a single label cannot mix.
+///
+/// Dragging the arc rewrites the spans in place, so the paragraph wraps
+/// again around the new text.
+pub struct RichText {
+    _back: (Button, Label),
+    _title: Label,
+    /// Holds the spangroup, which LVGL deletes with it
+    _paragraph: Obj,
+    _arc: Arc,
+}
+
+impl RichText {
+    /// Builds the demo on the active screen. The back button loads `home`.
+    pub fn new(home: Screen) -> Self {
+        let back = back_button(home);
+
+        let title = builder::label()
+            .translated(Text::RichText)
+            .align(Align::TopMid)
+            .offset(0, 12)
+            .build();
+
+        let mut paragraph = Obj::new();
+        unsafe { lv_obj_remove_style_all(paragraph.raw()) };
+        paragraph.set_size(WIDTH, HEIGHT);
+        paragraph.align(Align::TopMid.into(), 0, 45);
+
+        let (group, value, level) = unsafe {
+            let group = lv_spangroup_create(paragraph.raw());
+            lv_obj_set_size(group, WIDTH, HEIGHT);
+            add_span(group, Text::ArcIsAt.get().to_str().unwrap());
+            let value = add_span(group, "");
+            let style = lv_span_get_style(value);
+            lv_style_set_text_color(style, lv_color_hex(VALUE_COLOR));
+            lv_style_set_text_font(style, Font::LARGEST.raw());
+            add_span(group, Text::WhichCountsAs.get().to_str().unwrap());
+            let level = add_span(group, "");
+            lv_style_set_text_decor(lv_span_get_style(level), LV_TEXT_DECOR_UNDERLINE as _);
+            add_span(group, Text::DragArc.get().to_str().unwrap());
+            (group, value, level)
+        };
+
+        let mut arc = Arc::new();
+        arc.set_size(110, 110);
+        arc.set_range(0, 100);
+        arc.set_value(42);
+        arc.align(Align::BottomMid.into(), 0, -5);
+        let arc_raw = arc.raw();
+        let show = move || {
+            let percent = unsafe { lv_arc_get_value(arc_raw) };
+            let (_, name, color) = LEVELS
+                .iter()
+                .find(|(limit, _, _)| percent <= *limit)
+                .unwrap_or(&LEVELS[LEVELS.len() - 1]);
+            let percent = CString::new(format!("{percent}%")).unwrap();
+            unsafe {
+                lv_spangroup_set_span_text(group, value, percent.as_ptr());
+                lv_spangroup_set_span_text(group, level, name.get().as_ptr());
+                lv_style_set_text_color(lv_span_get_style(level), lv_color_hex(*color));
+                // Style changes are not picked up on their own
+                lv_spangroup_refresh(group);
+            }
+        };
+        show();
+        arc.add_event_cb(EventCode::ValueChanged, move |_| show());
+
+        Self {
+            _back: back,
+            _title: title,
+            _paragraph: paragraph,
+            _arc: arc,
+        }
+    }
+}
+
+impl UiModule for RichText {
+    const NAME: Text = Text::RichText;
+
+    fn build(home: Screen, _resources: &mut Resources) -> Self {
+        Self::new(home)
+    }
+}
@@ -0,0 +1,64 @@
+use core::ptr::NonNull;
+
+use lv_bevy_ecs::sys::{
+    lv_obj_create, lv_obj_set_style_pad_top, lv_obj_t, lv_screen_active, lv_screen_load,
+};
+
+use super::status_bar;
+
+/// Handle to an LVGL screen.
+///
+/// Screens are created once at startup and live for the rest of the program,
+/// so the handle is `Copy` and never deletes the underlying object.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Screen {
+    raw: NonNull<lv_obj_t>,
+}
+
+impl Screen {
+    pub fn new() -> Self {
+        let raw = unsafe { lv_obj_create(core::ptr::null_mut()) };
+        let screen = Self {
+            raw: NonNull::new(raw).expect("Could not create screen"),
+        };
+        screen.reserve_status_bar();
+        screen
+    }
+
+    pub fn active() -> Self {
+        let raw = unsafe { lv_screen_active() };
+        Self {
+            raw: NonNull::new(raw).expect("No active screen"),
+        }
+    }
+
+    pub fn load(&self) {
+        unsafe { lv_screen_load(self.raw.as_ptr()) }
+    }
+
+    /// Widgets are always created on the active screen, so this temporarily
+    /// loads `self` while `build` runs and switches back afterwards.
+    pub fn build<R>(&self, build: impl FnOnce() -> R) -> R {
+        let previous = Self::active();
+        self.load();
+        let result = build();
+        previous.load();
+        result
+    }
+
+    /// Pads the top of the screen so aligned content starts below the status bar.
+    /// Screens made with [`Screen::new`] already do this.
+    pub fn reserve_status_bar(&self) {
+        unsafe { lv_obj_set_style_pad_top(self.raw.as_ptr(), status_bar::HEIGHT, 0) };
+    }
+
+    pub fn raw(&self) -> *mut lv_obj_t {
+        self.raw.as_ptr()
+    }
+}
+
+impl Default for Screen {
+    fn default() -> Self {
+        Self::new()
+    }
+}
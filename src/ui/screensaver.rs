@@ -0,0 +1,148 @@
+use alloc::ffi::CString;
+use alloc::format;
+use alloc::rc::Rc;
+use alloc::string::String;
+use core::cell::RefCell;
+
+use esp_hal::rng::Rng;
+use lv_bevy_ecs::events::EventCode;
+use lv_bevy_ecs::sys::{
+    LV_OBJ_FLAG_HIDDEN, LV_OPA_COVER, lv_color_hex, lv_display_get_inactive_time,
+    lv_label_set_text, lv_layer_top, lv_obj_add_flag, lv_obj_get_height, lv_obj_get_width,
+    lv_obj_get_x, lv_obj_get_y, lv_obj_has_flag, lv_obj_remove_flag, lv_obj_remove_style_all,
+    lv_obj_set_parent, lv_obj_set_style_bg_color, lv_obj_set_style_bg_opa,
+    lv_obj_set_style_text_color, lv_obj_t, lv_obj_update_layout,
+};
+use lv_bevy_ecs::widgets::{Label, Obj};
+
+use super::animate::{Animate, Animation, Easing, Property};
+use super::fonts::Font;
+use super::timer::Timer;
+use crate::clock;
+use crate::power;
+use crate::settings::{Key, Settings};
+
+/// Choices offered for [`Key::ScreensaverTimeout`], 0 is never
+pub const TIMEOUTS_MINUTES: [u32; 6] = [0, 1, 2, 5, 10, 30];
+const POLL_PERIOD_MS: u32 = 250;
+/// Each drift to a new spot takes this long, slow enough to read the time
+const DRIFT_MS: u32 = 20_000;
+/// Grey rather than white, which would wear the panel as well
+const TEXT_COLOR: u32 = 0x9E9E9E;
+/// Shown while the clock is not set
+const LOGO: &str = "lvgl-bevy-demo";
+
+/// Covers the display with the time, or the demo's name while the clock is
+/// not set, drifting over black after [`Key::ScreensaverTimeout`] minutes
+/// without input.
+///
+/// Nothing stays on the same pixels for long, so a page left open does not
+/// burn in. With the battery saver on it jumps to a new spot every
+/// `DRIFT_MS` instead of drifting. Touching the screen or pressing a key
+/// ends it, and that touch or key goes no further than the screensaver.
+pub struct Screensaver {
+    _timer: Timer,
+    _text: Label,
+    _overlay: Obj,
+}
+
+impl Screensaver {
+    /// Create it after every other top layer widget, the lock screen
+    /// included, so it covers them all.
+    pub fn new(settings: Rc<RefCell<Settings>>) -> Self {
+        let mut overlay = Obj::new();
+        unsafe {
+            lv_obj_set_parent(overlay.raw(), lv_layer_top());
+            lv_obj_remove_style_all(overlay.raw());
+            lv_obj_set_style_bg_color(overlay.raw(), lv_color_hex(0x000000), 0);
+            lv_obj_set_style_bg_opa(overlay.raw(), LV_OPA_COVER as _, 0);
+            lv_obj_set_style_text_color(overlay.raw(), lv_color_hex(TEXT_COLOR), 0);
+            lv_obj_add_flag(overlay.raw(), LV_OBJ_FLAG_HIDDEN);
+        }
+        overlay.set_size(320, 240);
+        overlay.set_pos(0, 0);
+        let overlay_raw = overlay.raw();
+        // Ended on press, before the release could count as a click
+        // anywhere else
+        overlay.add_event_cb(EventCode::Pressed, move |_| unsafe {
+            lv_obj_add_flag(overlay_raw, LV_OBJ_FLAG_HIDDEN)
+        });
+
+        let mut text = Label::new();
+        text.set_parent(&mut overlay);
+        Font::LARGEST.apply(text.raw());
+        let text_raw = text.raw();
+
+        let mut shown = String::new();
+        let mut drift: Option<(Animation, Animation)> = None;
+        let mut still_ms = 0;
+        let timer = Timer::new(POLL_PERIOD_MS, move || {
+            let inactive_ms = unsafe { lv_display_get_inactive_time(core::ptr::null_mut()) };
+            if unsafe { lv_obj_has_flag(overlay_raw, LV_OBJ_FLAG_HIDDEN) } {
+                drift = None;
+                let minutes = settings.borrow().get(Key::ScreensaverTimeout);
+                if minutes == 0 || inactive_ms / 60_000 < minutes {
+                    return;
+                }
+                unsafe { lv_obj_remove_flag(overlay_raw, LV_OBJ_FLAG_HIDDEN) };
+            } else if inactive_ms < POLL_PERIOD_MS {
+                // A key was pressed, touches end it on their own
+                unsafe { lv_obj_add_flag(overlay_raw, LV_OBJ_FLAG_HIDDEN) };
+                return;
+            }
+
+            let text = match clock::now() {
+                Some(now) => format!("{:02}:{:02}", now.hour, now.minute),
+                None => String::from(LOGO),
+            };
+            if text != shown {
+                unsafe {
+                    lv_label_set_text(text_raw, CString::new(text.as_str()).unwrap().as_ptr())
+                };
+                shown = text;
+            }
+
+            still_ms += POLL_PERIOD_MS;
+            let drifting = drift
+                .as_ref()
+                .is_some_and(|(x, y)| !x.is_finished() || !y.is_finished());
+            // Animations end at once with the battery saver, so wait as long
+            // as a drift would have taken
+            let waiting = power::saver_active() && drift.is_some() && still_ms < DRIFT_MS;
+            if !drifting && !waiting {
+                drift = Some(drift_to_random_spot(text_raw));
+                still_ms = 0;
+            }
+        });
+
+        Self {
+            _timer: timer,
+            _text: text,
+            _overlay: overlay,
+        }
+    }
+}
+
+/// Starts moving `text` from where it is to anywhere it fits on screen.
+fn drift_to_random_spot(text: *mut lv_obj_t) -> (Animation, Animation) {
+    let (x, y, width, height) = unsafe {
+        lv_obj_update_layout(text);
+        (
+            lv_obj_get_x(text),
+            lv_obj_get_y(text),
+            lv_obj_get_width(text),
+            lv_obj_get_height(text),
+        )
+    };
+    let mut rng = Rng::new();
+    let to_x = (rng.random() % (320 - width).max(1) as u32) as i32;
+    let to_y = (rng.random() % (240 - height).max(1) as u32) as i32;
+    (
+        Animate::new(Property::X, x, to_x, DRIFT_MS)
+            .with_easing(Easing::EaseInOut)
+            .start(text),
+        Animate::new(Property::Y, y, to_y, DRIFT_MS)
+            .with_easing(Easing::EaseInOut)
+            .start(text),
+    )
+}
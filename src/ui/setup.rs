@@ -0,0 +1,101 @@
+use alloc::ffi::CString;
+use alloc::format;
+use alloc::rc::Rc;
+use alloc::string::String;
+use core::cell::RefCell;
+
+use lv_bevy_ecs::support::{Align, LabelLongMode};
+use lv_bevy_ecs::sys::{
+    LV_ALIGN_RIGHT_MID, LV_OBJ_FLAG_HIDDEN, LV_OPA_COVER, lv_color_hex, lv_label_set_text,
+    lv_layer_top, lv_obj_add_flag, lv_obj_align, lv_obj_set_parent, lv_obj_set_style_bg_color,
+    lv_obj_set_style_bg_opa, lv_obj_set_style_text_color, lv_qrcode_create, lv_qrcode_set_size,
+    lv_qrcode_update,
+};
+use lv_bevy_ecs::widgets::{Label, Obj};
+
+use super::i18n::{self, Text};
+use super::timer::Timer;
+use crate::net::ACCESS_POINT_ADDRESS;
+use crate::settings::Settings;
+use crate::wifi;
+
+const POLL_PERIOD_MS: u32 = 500;
+const QR_SIZE: i32 = 110;
+const BACKGROUND: u32 = 0x102027;
+
+/// Saves the credentials of every network joined, and on first boot covers
+/// the display with instructions for the setup network until one is.
+pub struct Setup {
+    _overlay: Option<(Obj, Label)>,
+    _timer: Timer,
+}
+
+impl Setup {
+    /// Shows the setup instructions while `access_point` is the SSID of the
+    /// open setup network. Create it after the other top layer widgets but the
+    /// debug menu.
+    pub fn new(settings: Rc<RefCell<Settings>>, access_point: Option<String>) -> Self {
+        let overlay = access_point.map(|ssid| overlay(&ssid));
+        let overlay_raw = overlay.as_ref().map(|(obj, _)| obj.raw());
+
+        let timer = Timer::new(POLL_PERIOD_MS, move || {
+            let Some((ssid, password)) = wifi::take_joined() else {
+                return;
+            };
+            settings.borrow_mut().set_wifi_credentials(&ssid, &password);
+            if let Some(overlay_raw) = overlay_raw {
+                unsafe { lv_obj_add_flag(overlay_raw, LV_OBJ_FLAG_HIDDEN) };
+            }
+        });
+
+        Self {
+            _overlay: overlay,
+            _timer: timer,
+        }
+    }
+}
+
+fn overlay(ssid: &str) -> (Obj, Label) {
+    let mut overlay = Obj::new();
+    unsafe {
+        lv_obj_set_parent(overlay.raw(), lv_layer_top());
+        lv_obj_set_style_bg_color(overlay.raw(), lv_color_hex(BACKGROUND), 0);
+        lv_obj_set_style_bg_opa(overlay.raw(), LV_OPA_COVER as _, 0);
+        lv_obj_set_style_text_color(overlay.raw(), lv_color_hex(0xFFFFFF), 0);
+    }
+    overlay.set_size(320, 240);
+    overlay.set_pos(0, 0);
+
+    let mut label = Label::new();
+    label.set_parent(&mut overlay);
+    label.set_width(160);
+    label.set_long_mode(LabelLongMode::Wrap.into());
+    label.align(Align::LeftMid.into(), 0, 0);
+    let label_raw = label.raw();
+    let instructions = {
+        let ssid = String::from(ssid);
+        move || {
+            let [a, b, c, d] = ACCESS_POINT_ADDRESS.octets();
+            let text = format!(
+                "{}\n\n{}\n{ssid}\n\n{} http://{a}.{b}.{c}.{d}/",
+                Text::WifiSetup.get().to_str().unwrap(),
+                Text::JoinTheNetwork.get().to_str().unwrap(),
+                Text::ThenSignIn.get().to_str().unwrap(),
+            );
+            unsafe { lv_label_set_text(label_raw, CString::new(text).unwrap().as_ptr()) };
+        }
+    };
+    instructions();
+    i18n::on_language_change(label_raw, instructions);
+
+    // Scanning joins the open setup network on most phones. The QR code is a
+    // child of the overlay, so LVGL deletes it along with it.
+    let contents = format!("WIFI:T:nopass;S:{ssid};;");
+    unsafe {
+        let qr = lv_qrcode_create(overlay.raw());
+        lv_qrcode_set_size(qr, QR_SIZE);
+        lv_qrcode_update(qr, contents.as_ptr().cast(), contents.len() as u32);
+        lv_obj_align(qr, LV_ALIGN_RIGHT_MID as _, 0, 0);
+    }
+    (overlay, label)
+}
@@ -0,0 +1,149 @@
+use alloc::ffi::CString;
+use alloc::format;
+use alloc::rc::Rc;
+use core::cell::Cell;
+
+use lv_bevy_ecs::events::EventCode;
+use lv_bevy_ecs::support::Align;
+use lv_bevy_ecs::sys::{
+    LV_OPA_30, LV_OPA_COVER, LV_PART_INDICATOR, LV_STATE_CHECKED, lv_arc_get_value,
+    lv_arc_set_value, lv_label_set_text, lv_label_set_text_static, lv_obj_add_state,
+    lv_obj_has_state, lv_obj_remove_state, lv_obj_set_style_arc_opa,
+};
+use lv_bevy_ecs::widgets::{Arc, Button, Label, Switch};
+
+use super::back_button;
+use super::i18n::{Text, translate};
+use super::module::{Resources, UiModule};
+use super::screen::Screen;
+use super::timer::Timer;
+use crate::smart_light::{self, Light, Status};
+
+/// Picks up changes made from the broker
+const POLL_PERIOD_MS: u32 = 250;
+
+/// Dimmable light shared over MQTT, see [`smart_light`].
+///
+/// The arc sets the brightness and the switch turns it on and off. Changes
+/// made from Home Assistant show up here too.
+pub struct SmartLight {
+    _back: (Button, Label),
+    _title: Label,
+    _arc: Arc,
+    _percent: Label,
+    _power: Switch,
+    _status: Label,
+    _timer: Timer,
+}
+
+impl SmartLight {
+    /// Builds the light controls on the active screen. The back button loads `home`.
+    pub fn new(home: Screen) -> Self {
+        let back = back_button(home);
+
+        let mut title = Label::new();
+        title.align(Align::TopMid.into(), 0, 12);
+        translate(&mut title, Text::Light);
+
+        let mut arc = Arc::new();
+        arc.set_size(140, 140);
+        arc.set_range(0, 100);
+        arc.align(Align::Center.into(), -40, 10);
+        let arc_raw = arc.raw();
+
+        let mut percent = Label::new();
+        percent.set_parent(&mut arc);
+        percent.center();
+        let percent_raw = percent.raw();
+
+        let mut power = Switch::new();
+        power.align(Align::Center.into(), 100, 10);
+        let power_raw = power.raw();
+
+        let mut status = Label::new();
+        status.align(Align::BottomMid.into(), 0, -8);
+        let status_raw = status.raw();
+
+        // Last state written to the widgets, so polling only redraws changes
+        let shown = Rc::new(Cell::new(None));
+        let show = move |light: Light| {
+            if shown.get() == Some(light) {
+                return;
+            }
+            shown.set(Some(light));
+            let text = CString::new(format!("{}%", light.brightness)).unwrap();
+            unsafe {
+                lv_label_set_text(percent_raw, text.as_ptr());
+                if lv_arc_get_value(arc_raw) != light.brightness as i32 {
+                    lv_arc_set_value(arc_raw, light.brightness as i32);
+                }
+                let opa = if light.on { LV_OPA_COVER } else { LV_OPA_30 };
+                lv_obj_set_style_arc_opa(arc_raw, opa as _, LV_PART_INDICATOR);
+                if light.on {
+                    lv_obj_add_state(power_raw, LV_STATE_CHECKED);
+                } else {
+                    lv_obj_remove_state(power_raw, LV_STATE_CHECKED);
+                }
+            }
+        };
+        show(smart_light::get());
+
+        arc.add_event_cb(EventCode::ValueChanged, {
+            let show = show.clone();
+            move |_| {
+                // Dimming a light that is off turns it on, like in Home Assistant
+                let light = Light {
+                    on: true,
+                    brightness: unsafe { lv_arc_get_value(arc_raw) } as u8,
+                };
+                smart_light::set(light);
+                show(light);
+            }
+        });
+        power.add_event_cb(EventCode::ValueChanged, {
+            let show = show.clone();
+            move |_| {
+                let light = Light {
+                    on: unsafe { lv_obj_has_state(power_raw, LV_STATE_CHECKED) },
+                    ..smart_light::get()
+                };
+                smart_light::set(light);
+                show(light);
+            }
+        });
+
+        let mut last_status = None;
+        let timer = Timer::new(POLL_PERIOD_MS, move || {
+            show(smart_light::get());
+            let status = smart_light::status();
+            if last_status == Some(status) {
+                return;
+            }
+            last_status = Some(status);
+            let text = match status {
+                Status::Disabled => Text::MqttDisabled,
+                Status::Offline => Text::BrokerOffline,
+                Status::Online => Text::SharedWithHomeAssistant,
+            };
+            unsafe { lv_label_set_text_static(status_raw, text.get().as_ptr()) };
+        });
+
+        Self {
+            _back: back,
+            _title: title,
+            _arc: arc,
+            _percent: percent,
+            _power: power,
+            _status: status,
+            _timer: timer,
+        }
+    }
+}
+
+impl UiModule for SmartLight {
+    const NAME: Text = Text::Light;
+
+    fn build(home: Screen, _resources: &mut Resources) -> Self {
+        Self::new(home)
+    }
+}
@@ -0,0 +1,293 @@
+use alloc::collections::VecDeque;
+use alloc::ffi::CString;
+use alloc::format;
+use alloc::rc::Rc;
+use core::cell::{Cell, RefCell};
+
+use embassy_time::Instant;
+use lv_bevy_ecs::events::EventCode;
+use lv_bevy_ecs::support::Align;
+use lv_bevy_ecs::sys::{
+    LV_DIR_BOTTOM, LV_DIR_LEFT, LV_DIR_RIGHT, LV_DIR_TOP, LV_OBJ_FLAG_CLICKABLE, lv_dir_t,
+    lv_indev_active, lv_indev_get_gesture_dir, lv_obj_add_flag,
+};
+use lv_bevy_ecs::widgets::{Button, Label, Obj};
+
+use super::canvas::{PixelCanvas, rgb565};
+use super::i18n::Text;
+use super::module::{Resources, UiModule};
+use super::screen::Screen;
+use super::timer::Timer;
+use super::{back_button, set_visible, translated_button};
+
+const CELL: i32 = 12;
+const COLUMNS: i32 = 20;
+const ROWS: i32 = 13;
+const STEP_MS: u32 = 150;
+const START_LENGTH: i32 = 3;
+
+const BACKGROUND: u16 = rgb565(0x1B1B1B);
+const BODY: u16 = rgb565(0x43A047);
+const FOOD: u16 = rgb565(0xE53935);
+
+#[derive(Clone, Copy, PartialEq)]
+enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    fn from_gesture(dir: lv_dir_t) -> Option<Self> {
+        match dir {
+            LV_DIR_TOP => Some(Direction::Up),
+            LV_DIR_BOTTOM => Some(Direction::Down),
+            LV_DIR_LEFT => Some(Direction::Left),
+            LV_DIR_RIGHT => Some(Direction::Right),
+            _ => None,
+        }
+    }
+
+    fn offset(self) -> (i32, i32) {
+        match self {
+            Direction::Up => (0, -1),
+            Direction::Down => (0, 1),
+            Direction::Left => (-1, 0),
+            Direction::Right => (1, 0),
+        }
+    }
+
+    fn opposite(self) -> Self {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Status {
+    Ready,
+    Running,
+    Over,
+}
+
+struct Game {
+    canvas: PixelCanvas,
+    /// Head first
+    body: VecDeque<(i32, i32)>,
+    direction: Direction,
+    /// Applied on the next step, so two quick swipes can't reverse the snake
+    next_direction: Direction,
+    food: (i32, i32),
+    score: u32,
+    status: Status,
+    rng: u32,
+}
+
+impl Game {
+    fn reset(&mut self) {
+        self.body.clear();
+        for x in (0..START_LENGTH).rev() {
+            self.body.push_back((x + 2, ROWS / 2));
+        }
+        self.direction = Direction::Right;
+        self.next_direction = Direction::Right;
+        self.score = 0;
+        self.status = Status::Ready;
+
+        self.canvas.fill(BACKGROUND);
+        for i in 0..self.body.len() {
+            self.draw_cell(self.body[i], BODY);
+        }
+        self.place_food();
+        self.canvas.invalidate();
+    }
+
+    fn steer(&mut self, direction: Direction) {
+        if direction != self.direction.opposite() {
+            self.next_direction = direction;
+        }
+        if self.status == Status::Ready {
+            self.status = Status::Running;
+        }
+    }
+
+    /// Moves the snake by one cell and ends the game when it hits a wall or itself.
+    fn step(&mut self) {
+        self.direction = self.next_direction;
+        let (dx, dy) = self.direction.offset();
+        let (x, y) = self.body[0];
+        let head = (x + dx, y + dy);
+
+        if !(0..COLUMNS).contains(&head.0) || !(0..ROWS).contains(&head.1) {
+            self.status = Status::Over;
+            return;
+        }
+
+        if head == self.food {
+            self.score += 1;
+        } else if let Some(tail) = self.body.pop_back() {
+            self.draw_cell(tail, BACKGROUND);
+        }
+
+        if self.body.contains(&head) {
+            self.status = Status::Over;
+            return;
+        }
+
+        self.body.push_front(head);
+        self.draw_cell(head, BODY);
+        if head == self.food {
+            self.place_food();
+        }
+        self.canvas.invalidate();
+    }
+
+    fn place_food(&mut self) {
+        loop {
+            let cell = (
+                (self.next_random() % COLUMNS as u32) as i32,
+                (self.next_random() % ROWS as u32) as i32,
+            );
+            if !self.body.contains(&cell) {
+                self.food = cell;
+                break;
+            }
+        }
+        self.draw_cell(self.food, FOOD);
+    }
+
+    fn draw_cell(&mut self, (x, y): (i32, i32), color: u16) {
+        self.canvas
+            .fill_rect(x * CELL + 1, y * CELL + 1, CELL - 2, CELL - 2, color);
+    }
+
+    /// xorshift32, good enough for placing food
+    fn next_random(&mut self) -> u32 {
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 17;
+        self.rng ^= self.rng << 5;
+        self.rng
+    }
+}
+
+/// Snake game steered with swipes on the playing field.
+pub struct Snake {
+    _back: (Button, Label),
+    _restart: (Button, Label),
+    _timer: Timer,
+}
+
+impl Snake {
+    /// Builds the game on the active screen. The back button loads `home`.
+    pub fn new(home: Screen) -> Self {
+        let screen = Screen::active();
+        let back = back_button(home);
+
+        let mut score_label = Label::new();
+        score_label.align(Align::TopMid.into(), 0, 12);
+        score_label.set_text_static(Text::SwipeToStart.get());
+
+        let mut canvas = PixelCanvas::new(COLUMNS * CELL, ROWS * CELL, BACKGROUND);
+        canvas.canvas().align(Align::BottomMid.into(), 0, -10);
+        unsafe { lv_obj_add_flag(canvas.raw(), LV_OBJ_FLAG_CLICKABLE) };
+
+        let game = Rc::new(RefCell::new(Game {
+            canvas,
+            body: VecDeque::new(),
+            direction: Direction::Right,
+            next_direction: Direction::Right,
+            food: (0, 0),
+            score: 0,
+            status: Status::Ready,
+            rng: Instant::now().as_ticks() as u32 | 1,
+        }));
+        game.borrow_mut().reset();
+
+        {
+            // Weak, since the canvas holding the callback is part of the game
+            let callback_game = Rc::downgrade(&game);
+            let mut game = game.borrow_mut();
+            game.canvas
+                .canvas()
+                .add_event_cb(EventCode::Gesture, move |_| {
+                    let Some(callback_game) = callback_game.upgrade() else {
+                        return;
+                    };
+                    let dir = unsafe { lv_indev_get_gesture_dir(lv_indev_active()) };
+                    let mut game = callback_game.borrow_mut();
+                    if let (Some(direction), Status::Ready | Status::Running) =
+                        (Direction::from_gesture(dir), game.status)
+                    {
+                        game.steer(direction);
+                    }
+                });
+        }
+
+        let mut dialog = Obj::new();
+        dialog.set_size(200, 110);
+        dialog.center();
+        set_visible(&mut dialog, false);
+
+        let mut result_label = Label::new();
+        result_label.set_parent(&mut dialog);
+        result_label.align(Align::TopMid.into(), 0, 0);
+
+        let mut restart = translated_button(Text::Restart);
+        restart.0.set_parent(&mut dialog);
+        restart.0.align(Align::BottomMid.into(), 0, 0);
+        let restart_requested = Rc::new(Cell::new(false));
+        restart.0.add_event_cb(EventCode::Clicked, {
+            let restart_requested = restart_requested.clone();
+            move |_| restart_requested.set(true)
+        });
+
+        let mut shown_score = u32::MAX;
+        let timer = Timer::new(STEP_MS, move || {
+            let mut game = game.borrow_mut();
+
+            if restart_requested.replace(false) {
+                game.reset();
+                set_visible(&mut dialog, false);
+                score_label.set_text_static(Text::SwipeToStart.get());
+                shown_score = u32::MAX;
+                return;
+            }
+
+            // Pause while another screen is shown
+            if game.status != Status::Running || Screen::active() != screen {
+                return;
+            }
+
+            game.step();
+
+            if game.status == Status::Over {
+                let text = CString::new(format!("Game over\nScore: {}", game.score)).unwrap();
+                result_label.set_text(text.as_c_str());
+                set_visible(&mut dialog, true);
+            } else if game.score != shown_score {
+                shown_score = game.score;
+                let text = CString::new(format!("Score: {}", game.score)).unwrap();
+                score_label.set_text(text.as_c_str());
+            }
+        });
+
+        Self {
+            _back: back,
+            _restart: restart,
+            _timer: timer,
+        }
+    }
+}
+
+impl UiModule for Snake {
+    const NAME: Text = Text::Snake;
+
+    fn build(home: Screen, _resources: &mut Resources) -> Self {
+        Self::new(home)
+    }
+}
@@ -0,0 +1,133 @@
+use alloc::ffi::CString;
+use alloc::format;
+use alloc::rc::Rc;
+use core::cell::Cell;
+
+use embassy_time::{Duration, Instant};
+use lv_bevy_ecs::events::EventCode;
+use lv_bevy_ecs::support::Align;
+use lv_bevy_ecs::sys::{
+    LV_ANIM_ON, LV_OBJ_FLAG_HIDDEN, LV_OPA_COVER, lv_bar_set_range, lv_bar_set_value, lv_color_hex,
+    lv_label_set_text, lv_layer_top, lv_obj_add_flag, lv_obj_has_flag, lv_obj_move_foreground,
+    lv_obj_set_parent, lv_obj_set_style_bg_color, lv_obj_set_style_bg_opa,
+    lv_obj_set_style_text_color, lv_obj_t, lv_timer_pause, lv_timer_t,
+};
+use lv_bevy_ecs::widgets::{Bar, Label, Obj};
+
+use super::fonts::Font;
+use super::timer::Timer;
+use crate::boot::{self, Stage};
+use crate::system::FIRMWARE_VERSION;
+
+const POLL_PERIOD_MS: u32 = 100;
+const BACKGROUND: u32 = 0x102027;
+/// Gives up on the stages left, most likely a network that is out of reach
+const GIVE_UP_AFTER: Duration = Duration::from_secs(15);
+
+/// Covers the display from the moment LVGL is up until every [`Stage`] is
+/// complete, with a bar that fills up as they are.
+///
+/// `main` runs the LVGL timers between the stages it waits for, so the bar
+/// moves while the rest of the device is set up. A tap, or waiting
+/// [`GIVE_UP_AFTER`], skips the stages left. Once hidden it stops polling.
+pub struct Splash {
+    _timer: Timer,
+    _title: Label,
+    _bar: Bar,
+    _stage: Label,
+    overlay: Obj,
+}
+
+impl Splash {
+    /// Creates the splash on the top layer. Call [`Splash::raise`] once the
+    /// rest of the top layer is built.
+    pub fn new() -> Self {
+        let started = Instant::now();
+
+        let mut overlay = Obj::new();
+        unsafe {
+            lv_obj_set_parent(overlay.raw(), lv_layer_top());
+            lv_obj_set_style_bg_color(overlay.raw(), lv_color_hex(BACKGROUND), 0);
+            lv_obj_set_style_bg_opa(overlay.raw(), LV_OPA_COVER as _, 0);
+            lv_obj_set_style_text_color(overlay.raw(), lv_color_hex(0xFFFFFF), 0);
+        }
+        overlay.set_size(320, 240);
+        overlay.set_pos(0, 0);
+        let overlay_raw = overlay.raw();
+        overlay.add_event_cb(EventCode::Clicked, move |_| hide(overlay_raw));
+
+        let mut title = Label::new();
+        title.set_parent(&mut overlay);
+        let text = format!("lvgl-bevy-demo {FIRMWARE_VERSION}");
+        title.set_text(CString::new(text).unwrap().as_c_str());
+        Font::LARGEST.apply(title.raw());
+        title.align(Align::Center.into(), 0, -40);
+
+        let mut bar = Bar::new();
+        bar.set_parent(&mut overlay);
+        bar.set_width(240);
+        bar.align(Align::Center.into(), 0, 10);
+        let bar_raw = bar.raw();
+        unsafe { lv_bar_set_range(bar_raw, 0, Stage::ALL.len() as i32) };
+
+        let mut stage = Label::new();
+        stage.set_parent(&mut overlay);
+        stage.align(Align::Center.into(), 0, 40);
+        let stage_raw = stage.raw();
+
+        let running: Rc<Cell<*mut lv_timer_t>> = Rc::new(Cell::new(core::ptr::null_mut()));
+        let refresh = {
+            let running = running.clone();
+            move || {
+                if unsafe { lv_obj_has_flag(overlay_raw, LV_OBJ_FLAG_HIDDEN) } {
+                    if !running.get().is_null() {
+                        unsafe { lv_timer_pause(running.get()) };
+                    }
+                    return;
+                }
+                let done = Stage::ALL
+                    .iter()
+                    .filter(|&&stage| boot::is_complete(stage))
+                    .count();
+                unsafe { lv_bar_set_value(bar_raw, done as i32, LV_ANIM_ON) };
+                let waiting = Stage::ALL.iter().find(|&&stage| !boot::is_complete(stage));
+                match waiting {
+                    Some(stage) if Instant::now() - started < GIVE_UP_AFTER => {
+                        let text = format!("{}...", stage.name().get().to_str().unwrap());
+                        unsafe {
+                            lv_label_set_text(stage_raw, CString::new(text).unwrap().as_ptr())
+                        };
+                    }
+                    _ => hide(overlay_raw),
+                }
+            }
+        };
+        refresh();
+        let timer = Timer::new(POLL_PERIOD_MS, refresh);
+        running.set(timer.raw());
+
+        Self {
+            _timer: timer,
+            _title: title,
+            _bar: bar,
+            _stage: stage,
+            overlay,
+        }
+    }
+
+    /// Brings the splash back in front of the top layer widgets created
+    /// after it.
+    pub fn raise(&self) {
+        unsafe { lv_obj_move_foreground(self.overlay.raw()) };
+    }
+}
+
+impl Default for Splash {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn hide(overlay: *mut lv_obj_t) {
+    unsafe { lv_obj_add_flag(overlay, LV_OBJ_FLAG_HIDDEN) };
+}
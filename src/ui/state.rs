@@ -0,0 +1,26 @@
+use core::cell::Cell;
+
+use embassy_sync::blocking_mutex::Mutex;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+
+use super::screen::Screen;
+
+struct Pending(Cell<Option<Screen>>);
+
+// Screens are only created and loaded from the UI task
+unsafe impl Send for Pending {}
+
+static NEXT: Mutex<CriticalSectionRawMutex, Pending> = Mutex::new(Pending(Cell::new(None)));
+
+/// Asks to switch to `screen`, like setting a Bevy `NextState`.
+///
+/// The switch happens in [`Registry::update`](super::module::Registry::update)
+/// once the event that asked for it has returned, so the screen being left can
+/// be torn down safely.
+pub fn set_next(screen: Screen) {
+    NEXT.lock(|next| next.0.set(Some(screen)));
+}
+
+pub(super) fn take_next() -> Option<Screen> {
+    NEXT.lock(|next| next.0.take())
+}
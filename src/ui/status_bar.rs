@@ -0,0 +1,136 @@
+use alloc::ffi::CString;
+use alloc::format;
+use alloc::rc::Rc;
+use alloc::string::String;
+use core::cell::RefCell;
+
+use lv_bevy_ecs::support::Align;
+use lv_bevy_ecs::sys::{
+    LV_OPA_40, LV_OPA_70, LV_OPA_COVER, lv_color_hex, lv_layer_top, lv_obj_set_parent,
+    lv_obj_set_style_bg_color, lv_obj_set_style_bg_opa, lv_obj_set_style_border_width,
+    lv_obj_set_style_pad_all, lv_obj_set_style_radius, lv_obj_set_style_text_color,
+    lv_obj_set_style_text_opa,
+};
+use lv_bevy_ecs::widgets::{Label, Obj};
+
+use super::i18n::Text;
+use super::timer::Timer;
+use super::toast::{self, Severity};
+use super::{set_visible, symbols};
+use crate::battery::Battery;
+use crate::buzzer::Buzzer;
+use crate::{clock, wifi};
+
+/// Rows at the top of every screen covered by the status bar
+pub const HEIGHT: i32 = 18;
+const REFRESH_PERIOD_MS: u32 = 1000;
+const BACKGROUND: u32 = 0x202020;
+/// Warns once when the battery drops to this
+const LOW_PERCENT: u8 = 15;
+/// And again only after it charged past this
+const LOW_CLEARED_PERCENT: u8 = 25;
+
+/// Time, WiFi, mute and battery indicators on the top layer, above every screen.
+///
+/// [`Screen`](super::screen::Screen) keeps the top [`HEIGHT`] rows free so
+/// the bar never covers screen content.
+pub struct StatusBar {
+    _bar: Obj,
+    _timer: Timer,
+}
+
+impl StatusBar {
+    pub fn new(buzzer: Rc<RefCell<Buzzer>>, battery: Rc<RefCell<Battery>>) -> Self {
+        let mut bar = Obj::new();
+        unsafe {
+            lv_obj_set_parent(bar.raw(), lv_layer_top());
+            lv_obj_set_style_bg_color(bar.raw(), lv_color_hex(BACKGROUND), 0);
+            lv_obj_set_style_bg_opa(bar.raw(), LV_OPA_COVER as _, 0);
+            lv_obj_set_style_text_color(bar.raw(), lv_color_hex(0xFFFFFF), 0);
+            lv_obj_set_style_border_width(bar.raw(), 0, 0);
+            lv_obj_set_style_radius(bar.raw(), 0, 0);
+            lv_obj_set_style_pad_all(bar.raw(), 0, 0);
+        }
+        bar.set_size(320, HEIGHT);
+        bar.set_pos(0, 0);
+
+        let mut time = Label::new();
+        time.set_parent(&mut bar);
+        time.align(Align::LeftMid.into(), 6, 0);
+
+        let mut battery_label = Label::new();
+        battery_label.set_parent(&mut bar);
+        battery_label.align(Align::RightMid.into(), -6, 0);
+
+        let mut mute = Label::new();
+        mute.set_parent(&mut bar);
+        mute.set_text(CString::new(symbols::MUTE).unwrap().as_c_str());
+        mute.align(Align::RightMid.into(), -82, 0);
+
+        let mut wifi_label = Label::new();
+        wifi_label.set_parent(&mut bar);
+        wifi_label.set_text(CString::new(symbols::WIFI).unwrap().as_c_str());
+        wifi_label.align(Align::RightMid.into(), -107, 0);
+
+        let mut warned_low = false;
+        let mut refresh = move || {
+            let text = match clock::now() {
+                Some(now) => format!("{:02}:{:02}", now.hour, now.minute),
+                None => String::from("--:--"),
+            };
+            time.set_text(CString::new(text).unwrap().as_c_str());
+
+            match wifi::signal_strength() {
+                Some(rssi) => {
+                    let opa = match rssi {
+                        -60.. => LV_OPA_COVER,
+                        -75..=-61 => LV_OPA_70,
+                        _ => LV_OPA_40,
+                    };
+                    unsafe { lv_obj_set_style_text_opa(wifi_label.raw(), opa as _, 0) };
+                    set_visible(&mut wifi_label, true);
+                }
+                None => set_visible(&mut wifi_label, false),
+            }
+
+            set_visible(&mut mute, buzzer.borrow().is_muted());
+
+            let mut battery = battery.borrow_mut();
+            let text = match battery.percent() {
+                Some(percent) => {
+                    let charging = battery.charging();
+                    if !warned_low && !charging && percent <= LOW_PERCENT {
+                        let low = Text::BatteryLow.get().to_str().unwrap();
+                        toast::show(Severity::Warning, format!("{low}, {percent}%"));
+                        warned_low = true;
+                    } else if percent >= LOW_CLEARED_PERCENT {
+                        warned_low = false;
+                    }
+                    let bolt = if charging { symbols::CHARGE } else { "" };
+                    Some(format!(
+                        "{}{} {}%",
+                        bolt,
+                        symbols::battery(percent),
+                        percent
+                    ))
+                }
+                None if battery.external_power() => Some(String::from(symbols::USB)),
+                None => None,
+            };
+            match text {
+                Some(text) => {
+                    battery_label.set_text(CString::new(text).unwrap().as_c_str());
+                    set_visible(&mut battery_label, true);
+                }
+                None => set_visible(&mut battery_label, false),
+            }
+        };
+        refresh();
+        let timer = Timer::new(REFRESH_PERIOD_MS, refresh);
+
+        Self {
+            _bar: bar,
+            _timer: timer,
+        }
+    }
+}
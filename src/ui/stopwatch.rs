@@ -0,0 +1,168 @@
+use alloc::ffi::CString;
+use alloc::format;
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+
+use embassy_time::{Duration, Instant};
+use lv_bevy_ecs::events::EventCode;
+use lv_bevy_ecs::support::Align;
+use lv_bevy_ecs::widgets::{Button, Label, List};
+
+use super::i18n::Text;
+use super::module::{Resources, UiModule};
+use super::screen::Screen;
+use super::timer::Timer;
+use super::{back_button, text_button};
+
+const REFRESH_PERIOD_MS: u32 = 100;
+
+struct State {
+    started_at: Option<Instant>,
+    accumulated: Duration,
+    last_lap: Duration,
+    list: List,
+    laps: Vec<Label>,
+}
+
+impl State {
+    fn elapsed(&self) -> Duration {
+        match self.started_at {
+            Some(started_at) => self.accumulated + started_at.elapsed(),
+            None => self.accumulated,
+        }
+    }
+
+    fn add_lap(&mut self) {
+        let elapsed = self.elapsed();
+        let split = elapsed - self.last_lap;
+        self.last_lap = elapsed;
+
+        let text = format!(
+            "Lap {}    {}    +{}",
+            self.laps.len() + 1,
+            format_tenths(elapsed),
+            format_tenths(split)
+        );
+        let mut label = Label::new();
+        label.set_parent(&mut self.list);
+        label.set_text(CString::new(text).unwrap().as_c_str());
+        // Newest lap on top
+        label.move_to_index(0);
+        self.laps.push(label);
+    }
+
+    fn reset(&mut self) {
+        self.accumulated = Duration::from_ticks(0);
+        self.last_lap = Duration::from_ticks(0);
+        self.laps.clear();
+    }
+}
+
+/// Stopwatch with start/stop and lap/reset buttons and a list of lap times.
+pub struct Stopwatch {
+    _back: (Button, Label),
+    _start: Button,
+    _lap: Button,
+    _timer: Timer,
+}
+
+impl Stopwatch {
+    /// Builds the stopwatch on the active screen. The back button loads `home`.
+    pub fn new(home: Screen) -> Self {
+        let back = back_button(home);
+
+        let mut time_label = Label::new();
+        time_label.set_text_static(c"00:00.0");
+        time_label.align(Align::TopMid.into(), 0, 12);
+
+        let mut list = List::new();
+        list.set_size(300, 130);
+        list.align(Align::BottomMid.into(), 0, -5);
+
+        let state = Rc::new(RefCell::new(State {
+            started_at: None,
+            accumulated: Duration::from_ticks(0),
+            last_lap: Duration::from_ticks(0),
+            list,
+            laps: Vec::new(),
+        }));
+
+        let (mut start, mut start_label) = text_button(Text::Start.get());
+        start.set_size(100, 36);
+        start.align(Align::TopMid.into(), -60, 45);
+
+        let (mut lap, mut lap_label) = text_button(Text::Reset.get());
+        lap.set_size(100, 36);
+        lap.align(Align::TopMid.into(), 60, 45);
+
+        start.add_event_cb(EventCode::Clicked, {
+            let state = state.clone();
+            move |_| {
+                let mut state = state.borrow_mut();
+                match state.started_at.take() {
+                    Some(started_at) => {
+                        state.accumulated += started_at.elapsed();
+                        start_label.set_text_static(Text::Start.get());
+                        lap_label.set_text_static(Text::Reset.get());
+                    }
+                    None => {
+                        state.started_at = Some(Instant::now());
+                        start_label.set_text_static(Text::Stop.get());
+                        lap_label.set_text_static(Text::Lap.get());
+                    }
+                }
+            }
+        });
+
+        lap.add_event_cb(EventCode::Clicked, {
+            let state = state.clone();
+            move |_| {
+                let mut state = state.borrow_mut();
+                if state.started_at.is_some() {
+                    state.add_lap();
+                } else {
+                    state.reset();
+                }
+            }
+        });
+
+        let mut shown_tenths = u64::MAX;
+        let timer = Timer::new(REFRESH_PERIOD_MS, move || {
+            let elapsed = state.borrow().elapsed();
+            let tenths = elapsed.as_millis() / 100;
+            if tenths != shown_tenths {
+                shown_tenths = tenths;
+                let text = format_tenths(elapsed);
+                time_label.set_text(CString::new(text).unwrap().as_c_str());
+            }
+        });
+
+        Self {
+            _back: back,
+            _start: start,
+            _lap: lap,
+            _timer: timer,
+        }
+    }
+}
+
+impl UiModule for Stopwatch {
+    const NAME: Text = Text::Stopwatch;
+    const RESIDENT: bool = true;
+
+    fn build(home: Screen, _resources: &mut Resources) -> Self {
+        Self::new(home)
+    }
+}
+
+fn format_tenths(duration: Duration) -> String {
+    let tenths = duration.as_millis() / 100;
+    format!(
+        "{:02}:{:02}.{}",
+        tenths / 600,
+        (tenths / 10) % 60,
+        tenths % 10
+    )
+}
@@ -0,0 +1,155 @@
+use alloc::boxed::Box;
+use core::ptr::null_mut;
+use core::sync::atomic::{AtomicPtr, Ordering};
+
+use lv_bevy_ecs::events::EventCode;
+use lv_bevy_ecs::support::Align;
+use lv_bevy_ecs::sys::{
+    LV_GRAD_DIR_VER, LV_OPA_50, LV_OPA_80, LV_OPA_COVER, LV_STATE_CHECKED, lv_color_hex,
+    lv_obj_add_style, lv_obj_has_state, lv_obj_remove_style, lv_obj_set_style_bg_color,
+    lv_obj_set_style_bg_opa, lv_style_init, lv_style_set_bg_grad_color, lv_style_set_bg_grad_dir,
+    lv_style_set_opa, lv_style_set_shadow_color, lv_style_set_shadow_opa,
+    lv_style_set_shadow_spread, lv_style_set_shadow_width, lv_style_t,
+};
+use lv_bevy_ecs::widgets::{Arc, Bar, Button, Label, Obj, Slider, Spinner, Switch};
+
+use super::i18n::Text;
+use super::module::{Resources, UiModule};
+use super::screen::Screen;
+use super::timer::Timer;
+use super::{back_button, builder, fps_counter, set_visible, translated_button};
+
+static HEAVY_STYLE: AtomicPtr<lv_style_t> = AtomicPtr::new(null_mut());
+
+/// Debug screen with a toggle that puts shadows, gradients and opacity on a
+/// set of sample widgets, and the frame rate they render at.
+///
+/// The spinner keeps the display busy so the rate reflects the style cost.
+pub struct Stress {
+    _back: (Button, Label),
+    _title: Label,
+    _toggle: Switch,
+    _samples: Obj,
+    _widgets: ((Button, Label), Slider, Bar, Arc, Spinner),
+    _timer: Timer,
+}
+
+impl Stress {
+    /// Builds the stress test on the active screen. The back button loads `home`.
+    pub fn new(home: Screen) -> Self {
+        let back = back_button(home);
+
+        let title = builder::label()
+            .translated(Text::Stress)
+            .align(Align::TopMid)
+            .offset(0, 12)
+            .build();
+
+        let mut samples = Obj::new();
+        samples.set_size(310, 140);
+        samples.align(Align::BottomMid.into(), 0, -5);
+
+        let mut button = translated_button(Text::Button);
+        button.0.set_parent(&mut samples);
+        button.0.align(Align::TopLeft.into(), 0, 0);
+
+        let mut slider = Slider::new();
+        slider.set_parent(&mut samples);
+        slider.set_width(120);
+        slider.align(Align::TopLeft.into(), 10, 60);
+
+        let mut bar = Bar::new();
+        bar.set_parent(&mut samples);
+        bar.set_width(120);
+        bar.align(Align::BottomLeft.into(), 10, -5);
+
+        let mut arc = Arc::new();
+        arc.set_parent(&mut samples);
+        arc.set_size(90, 90);
+        arc.set_value(60);
+        arc.align(Align::RightMid.into(), -100, 0);
+
+        let mut spinner = Spinner::new();
+        spinner.set_parent(&mut samples);
+        spinner.set_size(70, 70);
+        spinner.align(Align::RightMid.into(), 0, 0);
+
+        let mut overlay = Obj::new();
+        overlay.set_parent(&mut samples);
+        overlay.set_size(150, 60);
+        overlay.align(Align::Center.into(), 0, 0);
+        unsafe {
+            lv_obj_set_style_bg_color(overlay.raw(), lv_color_hex(0x3949AB), 0);
+            lv_obj_set_style_bg_opa(overlay.raw(), LV_OPA_50 as _, 0);
+        }
+        set_visible(&mut overlay, false);
+
+        let style = heavy_style();
+        let targets = [
+            button.0.raw(),
+            slider.raw(),
+            bar.raw(),
+            arc.raw(),
+            spinner.raw(),
+        ];
+
+        let mut toggle = Switch::new();
+        toggle.align(Align::TopMid.into(), 70, 8);
+        let toggle_raw = toggle.raw();
+        toggle.add_event_cb(EventCode::ValueChanged, move |_| {
+            let heavy = unsafe { lv_obj_has_state(toggle_raw, LV_STATE_CHECKED) };
+            for &target in &targets {
+                unsafe {
+                    if heavy {
+                        lv_obj_add_style(target, style, 0);
+                    } else {
+                        lv_obj_remove_style(target, style, 0);
+                    }
+                }
+            }
+            set_visible(&mut overlay, heavy);
+        });
+
+        let timer = fps_counter();
+
+        Self {
+            _back: back,
+            _title: title,
+            _toggle: toggle,
+            _samples: samples,
+            _widgets: (button, slider, bar, arc, spinner),
+            _timer: timer,
+        }
+    }
+}
+
+impl UiModule for Stress {
+    const NAME: Text = Text::Stress;
+
+    fn build(home: Screen, _resources: &mut Resources) -> Self {
+        Self::new(home)
+    }
+}
+
+/// Drop shadow, vertical gradient and whole-object opacity, the most
+/// expensive things to blend in software rendering. Built on first use and
+/// shared by every build of the page.
+fn heavy_style() -> *mut lv_style_t {
+    let style = HEAVY_STYLE.load(Ordering::Relaxed);
+    if !style.is_null() {
+        return style;
+    }
+    let style: &'static mut lv_style_t = Box::leak(Box::new(unsafe { core::mem::zeroed() }));
+    unsafe {
+        lv_style_init(style);
+        lv_style_set_shadow_width(style, 20);
+        lv_style_set_shadow_spread(style, 4);
+        lv_style_set_shadow_color(style, lv_color_hex(0x000000));
+        lv_style_set_shadow_opa(style, LV_OPA_COVER as _);
+        lv_style_set_bg_grad_color(style, lv_color_hex(0xFF7043));
+        lv_style_set_bg_grad_dir(style, LV_GRAD_DIR_VER);
+        lv_style_set_opa(style, LV_OPA_80 as _);
+    }
+    HEAVY_STYLE.store(style, Ordering::Relaxed);
+    style
+}
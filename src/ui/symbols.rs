@@ -0,0 +1,25 @@
+// Font Awesome glyphs compiled into the built-in Montserrat fonts, the same
+// code points as the `LV_SYMBOL_*` macros.
+
+pub const WIFI: &str = "\u{F1EB}";
+pub const BATTERY_FULL: &str = "\u{F240}";
+pub const BATTERY_3: &str = "\u{F241}";
+pub const BATTERY_2: &str = "\u{F242}";
+pub const BATTERY_1: &str = "\u{F243}";
+pub const BATTERY_EMPTY: &str = "\u{F244}";
+pub const MUTE: &str = "\u{F026}";
+pub const VOLUME_MAX: &str = "\u{F028}";
+pub const CHARGE: &str = "\u{F0E7}";
+pub const USB: &str = "\u{F287}";
+pub const EDIT: &str = "\u{F304}";
+
+/// Battery glyph closest to `percent`
+pub fn battery(percent: u8) -> &'static str {
+    match percent {
+        0..=10 => BATTERY_EMPTY,
+        11..=35 => BATTERY_1,
+        36..=60 => BATTERY_2,
+        61..=85 => BATTERY_3,
+        _ => BATTERY_FULL,
+    }
+}
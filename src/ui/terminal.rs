@@ -0,0 +1,150 @@
+use alloc::ffi::CString;
+use alloc::rc::Rc;
+use alloc::string::String;
+use core::cell::RefCell;
+use core::ffi::CStr;
+
+use esp_hal::Blocking;
+use esp_hal::uart::{TxError, Uart};
+use lv_bevy_ecs::events::EventCode;
+use lv_bevy_ecs::support::Align;
+use lv_bevy_ecs::sys::{
+    LV_ANIM_OFF, LV_COORD_MAX, lv_font_unscii_8, lv_keyboard_set_textarea, lv_obj_scroll_to_y,
+    lv_obj_set_style_text_font, lv_textarea_get_text, lv_textarea_set_one_line,
+    lv_textarea_set_text,
+};
+use lv_bevy_ecs::widgets::{Button, Keyboard, Label, Obj, Textarea};
+
+use super::i18n::Text;
+use super::module::{Resources, UiModule};
+use super::screen::Screen;
+use super::timer::Timer;
+use super::{back_button, builder};
+
+const POLL_PERIOD_MS: u32 = 50;
+/// Oldest output is dropped beyond this many characters
+const SCROLLBACK: usize = 2048;
+
+/// Scrolling view of what arrives on a UART, with a keyboard for sending lines.
+///
+/// Sent lines end with CR LF.
+pub struct Terminal {
+    _back: (Button, Label),
+    _title: Label,
+    _output: Obj,
+    _input: Textarea,
+    _keyboard: Keyboard,
+    _timer: Timer,
+}
+
+impl Terminal {
+    /// Builds the terminal on the active screen. The back button loads `home`.
+    pub fn new(home: Screen, uart: Uart<'static, Blocking>) -> Self {
+        let back = back_button(home);
+
+        let title = builder::label()
+            .translated(Text::Terminal)
+            .align(Align::TopMid)
+            .offset(0, 12)
+            .build();
+
+        let mut output = Obj::new();
+        output.set_size(310, 48);
+        output.align(Align::TopMid.into(), 0, 38);
+
+        let mut text = Label::new();
+        text.set_parent(&mut output);
+        text.set_width(290);
+        unsafe { lv_obj_set_style_text_font(text.raw(), &raw const lv_font_unscii_8, 0) };
+
+        let mut input = Textarea::new();
+        input.set_size(310, 36);
+        input.align(Align::TopMid.into(), 0, 88);
+        unsafe { lv_textarea_set_one_line(input.raw(), true) };
+
+        let uart = Rc::new(RefCell::new(uart));
+
+        // The keyboard's OK key sends Ready to its text area
+        let input_raw = input.raw();
+        input.add_event_cb(EventCode::Ready, {
+            let uart = uart.clone();
+            move |_| {
+                let line = unsafe { CStr::from_ptr(lv_textarea_get_text(input_raw)) };
+                let mut uart = uart.borrow_mut();
+                if write_all(&mut uart, line.to_bytes()).is_err()
+                    || write_all(&mut uart, b"\r\n").is_err()
+                {
+                    defmt::error!("Could not write to the terminal UART");
+                }
+                unsafe { lv_textarea_set_text(input_raw, c"".as_ptr()) };
+            }
+        });
+
+        let mut keyboard = Keyboard::new();
+        keyboard.set_size(320, 96);
+        keyboard.align(Align::BottomMid.into(), 0, 0);
+        unsafe { lv_keyboard_set_textarea(keyboard.raw(), input_raw) };
+
+        let output_raw = output.raw();
+        let mut scrollback = String::new();
+        let mut buffer = [0u8; 128];
+        let timer = Timer::new(POLL_PERIOD_MS, move || {
+            let mut uart = uart.borrow_mut();
+            let mut received = false;
+            while uart.read_ready() {
+                let Ok(count) = uart.read_buffered(&mut buffer) else {
+                    break;
+                };
+                received |= count > 0;
+                for &byte in &buffer[..count] {
+                    match byte {
+                        b'\n' | b' '..=b'~' => scrollback.push(byte as char),
+                        b'\t' => scrollback.push(' '),
+                        b'\r' => {}
+                        _ => scrollback.push('?'),
+                    }
+                }
+            }
+            if !received {
+                return;
+            }
+
+            if scrollback.len() > SCROLLBACK {
+                let excess = scrollback.len() - SCROLLBACK;
+                let start = scrollback[excess..]
+                    .find('\n')
+                    .map_or(excess, |i| excess + i + 1);
+                scrollback.drain(..start);
+            }
+            text.set_text(CString::new(scrollback.as_str()).unwrap().as_c_str());
+            unsafe { lv_obj_scroll_to_y(output_raw, LV_COORD_MAX as _, LV_ANIM_OFF) };
+        });
+
+        Self {
+            _back: back,
+            _title: title,
+            _output: output,
+            _input: input,
+            _keyboard: keyboard,
+            _timer: timer,
+        }
+    }
+}
+
+impl UiModule for Terminal {
+    const NAME: Text = Text::Terminal;
+    const RESIDENT: bool = true;
+
+    fn build(home: Screen, resources: &mut Resources) -> Self {
+        Self::new(home, resources.take())
+    }
+}
+
+/// `Uart::write` only fills the FIFO, so keep writing until everything is queued.
+fn write_all(uart: &mut Uart<'static, Blocking>, mut data: &[u8]) -> Result<(), TxError> {
+    while !data.is_empty() {
+        let written = uart.write(data)?;
+        data = &data[written..];
+    }
+    Ok(())
+}
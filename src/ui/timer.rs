@@ -0,0 +1,62 @@
+use alloc::boxed::Box;
+use core::ffi::c_void;
+use core::ptr::NonNull;
+
+use lv_bevy_ecs::sys::{
+    lv_timer_create, lv_timer_delete, lv_timer_get_user_data, lv_timer_pause, lv_timer_resume,
+    lv_timer_set_period, lv_timer_t,
+};
+
+type Callback = Box<dyn FnMut()>;
+
+/// A periodic LVGL timer running a Rust closure.
+///
+/// The closure is called from `lv_timer_handler`, so it may touch widgets.
+/// Dropping the timer deletes it.
+pub struct Timer {
+    raw: NonNull<lv_timer_t>,
+    callback: *mut Callback,
+}
+
+impl Timer {
+    pub fn new(period_ms: u32, callback: impl FnMut() + 'static) -> Self {
+        let callback: *mut Callback = Box::into_raw(Box::new(Box::new(callback)));
+        let raw = unsafe { lv_timer_create(Some(trampoline), period_ms, callback.cast()) };
+        Self {
+            raw: NonNull::new(raw).expect("Could not create timer"),
+            callback,
+        }
+    }
+
+    pub fn pause(&mut self) {
+        unsafe { lv_timer_pause(self.raw.as_ptr()) }
+    }
+
+    pub fn resume(&mut self) {
+        unsafe { lv_timer_resume(self.raw.as_ptr()) }
+    }
+
+    pub fn set_period(&mut self, period_ms: u32) {
+        unsafe { lv_timer_set_period(self.raw.as_ptr(), period_ms) }
+    }
+
+    /// For a closure to pause its own timer, which it cannot own.
+    pub fn raw(&self) -> *mut lv_timer_t {
+        self.raw.as_ptr()
+    }
+}
+
+impl Drop for Timer {
+    fn drop(&mut self) {
+        unsafe {
+            lv_timer_delete(self.raw.as_ptr());
+            drop(Box::from_raw(self.callback));
+        }
+    }
+}
+
+unsafe extern "C" fn trampoline(timer: *mut lv_timer_t) {
+    let user_data: *mut c_void = unsafe { lv_timer_get_user_data(timer) };
+    let callback = unsafe { &mut *user_data.cast::<Callback>() };
+    callback();
+}
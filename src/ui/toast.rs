@@ -0,0 +1,179 @@
+use alloc::collections::VecDeque;
+use alloc::ffi::CString;
+use alloc::rc::Rc;
+use alloc::string::String;
+use core::cell::{Cell, RefCell};
+
+use embassy_sync::blocking_mutex::Mutex;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_time::{Duration, Instant};
+use lv_bevy_ecs::events::EventCode;
+use lv_bevy_ecs::support::{Align, LabelLongMode};
+use lv_bevy_ecs::sys::{
+    LV_OBJ_FLAG_SCROLLABLE, LV_OPA_COVER, lv_color_hex, lv_label_set_text, lv_layer_top,
+    lv_obj_remove_flag, lv_obj_set_parent, lv_obj_set_style_bg_color, lv_obj_set_style_bg_opa,
+    lv_obj_set_style_border_width, lv_obj_set_style_text_color,
+};
+use lv_bevy_ecs::widgets::{Label, Obj};
+
+use super::animate::{Animate, Animation, Easing, Property};
+use super::notifications;
+use super::status_bar;
+use super::timer::Timer;
+
+const WIDTH: i32 = 300;
+const HEIGHT: i32 = 36;
+/// Just below the status bar
+const TOP: i32 = status_bar::HEIGHT + 4;
+const SHOWN_FOR: Duration = Duration::from_secs(3);
+const SLIDE_MS: u32 = 200;
+const TICK_MS: u32 = 100;
+/// Older toasts are dropped past this, a burst of them is not worth reading
+const MAX_QUEUED: usize = 8;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub(super) fn color(self) -> u32 {
+        match self {
+            Severity::Info => 0x1E88E5,
+            Severity::Success => 0x43A047,
+            Severity::Warning => 0xFB8C00,
+            Severity::Error => 0xE53935,
+        }
+    }
+}
+
+static QUEUE: Mutex<CriticalSectionRawMutex, RefCell<VecDeque<(Severity, String)>>> =
+    Mutex::new(RefCell::new(VecDeque::new()));
+
+/// Queues a banner for [`Toasts`] to show and keeps it in the notification
+/// center. Safe to call from any task.
+pub fn show(severity: Severity, text: impl Into<String>) {
+    let text = text.into();
+    notifications::record(severity, &text);
+    QUEUE.lock(|queue| {
+        let mut queue = queue.borrow_mut();
+        if queue.len() == MAX_QUEUED {
+            queue.pop_front();
+        }
+        queue.push_back((severity, text));
+    });
+}
+
+#[derive(Clone, Copy)]
+enum Phase {
+    Idle,
+    Shown { until: Instant },
+    Leaving { until: Instant },
+}
+
+/// Shows the toasts queued with [`show`] one after another, as banners that
+/// slide down below the status bar and go away on their own or when tapped.
+pub struct Toasts {
+    // Stopped before the banner it moves is deleted
+    _animation: Rc<RefCell<Option<Animation>>>,
+    _banner: Obj,
+    _label: Label,
+    _timer: Timer,
+}
+
+impl Toasts {
+    /// Creates the banner on the top layer, above every screen.
+    pub fn new() -> Self {
+        let mut banner = Obj::new();
+        unsafe {
+            lv_obj_set_parent(banner.raw(), lv_layer_top());
+            lv_obj_set_style_bg_opa(banner.raw(), LV_OPA_COVER as _, 0);
+            lv_obj_set_style_border_width(banner.raw(), 0, 0);
+            lv_obj_set_style_text_color(banner.raw(), lv_color_hex(0xFFFFFF), 0);
+            lv_obj_remove_flag(banner.raw(), LV_OBJ_FLAG_SCROLLABLE);
+        }
+        banner.set_size(WIDTH, HEIGHT);
+        banner.align(Align::TopMid.into(), 0, -HEIGHT);
+        let banner_raw = banner.raw();
+
+        let mut label = Label::new();
+        label.set_parent(&mut banner);
+        label.set_width(WIDTH - 30);
+        label.set_long_mode(LabelLongMode::Dot.into());
+        label.center();
+        let label_raw = label.raw();
+
+        let phase = Rc::new(Cell::new(Phase::Idle));
+        banner.add_event_cb(EventCode::Clicked, {
+            let phase = phase.clone();
+            move |_| {
+                if let Phase::Shown { .. } = phase.get() {
+                    phase.set(Phase::Shown {
+                        until: Instant::now(),
+                    });
+                }
+            }
+        });
+
+        let animation = Rc::new(RefCell::new(None));
+        let timer = Timer::new(TICK_MS, {
+            let animation = animation.clone();
+            move || {
+                let slide = |from, to| {
+                    *animation.borrow_mut() = Some(
+                        Animate::new(Property::Y, from, to, SLIDE_MS)
+                            .with_easing(Easing::EaseOut)
+                            .start(banner_raw),
+                    );
+                };
+                let now = Instant::now();
+                match phase.get() {
+                    Phase::Idle => {
+                        let Some((severity, text)) =
+                            QUEUE.lock(|queue| queue.borrow_mut().pop_front())
+                        else {
+                            return;
+                        };
+                        let text = CString::new(text).unwrap_or_default();
+                        unsafe {
+                            lv_label_set_text(label_raw, text.as_ptr());
+                            lv_obj_set_style_bg_color(
+                                banner_raw,
+                                lv_color_hex(severity.color()),
+                                0,
+                            );
+                        }
+                        slide(-HEIGHT, TOP);
+                        phase.set(Phase::Shown {
+                            until: now + SHOWN_FOR,
+                        });
+                    }
+                    Phase::Shown { until } if now >= until => {
+                        slide(TOP, -HEIGHT);
+                        phase.set(Phase::Leaving {
+                            until: now + Duration::from_millis(SLIDE_MS as u64),
+                        });
+                    }
+                    Phase::Leaving { until } if now >= until => phase.set(Phase::Idle),
+                    _ => {}
+                }
+            }
+        });
+
+        Self {
+            _animation: animation,
+            _banner: banner,
+            _label: label,
+            _timer: timer,
+        }
+    }
+}
+
+impl Default for Toasts {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,152 @@
+use alloc::alloc::{Layout, dealloc};
+use alloc::boxed::Box;
+
+use esp_alloc::MemoryCapability;
+use lv_bevy_ecs::sys::{
+    LV_COLOR_FORMAT_RGB565, LV_EVENT_DELETE, LV_RESULT_OK, LV_SCREEN_LOAD_ANIM_FADE_IN,
+    LV_SCREEN_LOAD_ANIM_OVER_LEFT, LV_SCREEN_LOAD_ANIM_OVER_RIGHT, lv_draw_buf_init, lv_draw_buf_t,
+    lv_draw_buf_width_to_stride, lv_event_get_user_data, lv_event_t, lv_image_cache_drop,
+    lv_image_create, lv_image_set_src, lv_obj_add_event_cb, lv_obj_create, lv_obj_delete,
+    lv_obj_get_height, lv_obj_get_width, lv_obj_set_style_pad_all, lv_obj_t, lv_obj_update_layout,
+    lv_screen_active, lv_screen_load, lv_screen_load_anim, lv_snapshot_take_to_draw_buf,
+};
+
+use super::screen::Screen;
+use crate::power;
+
+const DURATION_MS: u32 = 250;
+/// Internal RAM left for the screen coming in, which is built while the
+/// snapshot is held
+const RESERVE: usize = 32 * 1024;
+
+/// How the next screen comes in over the one being left.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// From the right, when opening a module
+    Forward,
+    /// From the left, when going back home
+    Back,
+    /// Fading in, between two modules
+    Across,
+}
+
+/// A still copy of the screen on display, shown in its place so the screen
+/// itself can be torn down before it has been animated away.
+///
+/// The pixels take 150 KiB, which the internal RAM of the ESP32 often does
+/// not have to spare; then there is no snapshot and screens switch at once.
+/// With the `psram` feature they go to PSRAM first.
+pub struct Snapshot {
+    screen: *mut lv_obj_t,
+}
+
+struct Pixels {
+    buffer: lv_draw_buf_t,
+    data: *mut u8,
+    layout: Layout,
+}
+
+impl Drop for Pixels {
+    fn drop(&mut self) {
+        unsafe {
+            lv_image_cache_drop((&raw const self.buffer).cast());
+            dealloc(self.data, self.layout);
+        }
+    }
+}
+
+impl Snapshot {
+    /// Renders the active screen and loads a screen showing the result,
+    /// `None` when there is no memory for it or the battery saver is on.
+    pub fn take() -> Option<Self> {
+        if power::saver_active() {
+            return None;
+        }
+        unsafe {
+            let active = lv_screen_active();
+            lv_obj_update_layout(active);
+            let width = lv_obj_get_width(active) as u32;
+            let height = lv_obj_get_height(active) as u32;
+            let stride = lv_draw_buf_width_to_stride(width, LV_COLOR_FORMAT_RGB565);
+            let layout = Layout::from_size_align((stride * height) as usize, 4).ok()?;
+            let data = allocate(layout);
+            if data.is_null() {
+                defmt::debug!("No memory for a {} byte snapshot", layout.size());
+                return None;
+            }
+            let mut pixels = Box::new(Pixels {
+                buffer: core::mem::zeroed(),
+                data,
+                layout,
+            });
+            let buffer = &raw mut pixels.buffer;
+            let initialized = lv_draw_buf_init(
+                buffer,
+                width,
+                height,
+                LV_COLOR_FORMAT_RGB565,
+                stride,
+                data.cast(),
+                layout.size() as u32,
+            );
+            if initialized != LV_RESULT_OK
+                || lv_snapshot_take_to_draw_buf(active, LV_COLOR_FORMAT_RGB565, buffer)
+                    != LV_RESULT_OK
+            {
+                return None;
+            }
+
+            let screen = lv_obj_create(core::ptr::null_mut());
+            lv_obj_set_style_pad_all(screen, 0, 0);
+            let image = lv_image_create(screen);
+            lv_image_set_src(image, buffer.cast());
+            // Freed along with the image, when the screen coming in has
+            // covered it
+            lv_obj_add_event_cb(
+                image,
+                Some(free),
+                LV_EVENT_DELETE,
+                Box::into_raw(pixels).cast(),
+            );
+            lv_screen_load(screen);
+            Some(Self { screen })
+        }
+    }
+
+    /// Animates `next` in over the snapshot, which is deleted afterwards.
+    pub fn reveal(self, next: Screen, direction: Direction) {
+        let animation = match direction {
+            Direction::Forward => LV_SCREEN_LOAD_ANIM_OVER_LEFT,
+            Direction::Back => LV_SCREEN_LOAD_ANIM_OVER_RIGHT,
+            Direction::Across => LV_SCREEN_LOAD_ANIM_FADE_IN,
+        };
+        unsafe {
+            if lv_screen_active() == self.screen {
+                lv_screen_load_anim(next.raw(), animation, DURATION_MS, 0, true);
+            } else {
+                next.load();
+                lv_obj_delete(self.screen);
+            }
+        }
+    }
+}
+
+/// PSRAM when there is some, otherwise internal RAM as long as enough is
+/// left after it.
+fn allocate(layout: Layout) -> *mut u8 {
+    #[cfg(feature = "psram")]
+    {
+        let data = unsafe { esp_alloc::HEAP.alloc_caps(MemoryCapability::External.into(), layout) };
+        if !data.is_null() {
+            return data;
+        }
+    }
+    if esp_alloc::HEAP.free() < layout.size() + RESERVE {
+        return core::ptr::null_mut();
+    }
+    unsafe { esp_alloc::HEAP.alloc_caps(MemoryCapability::Internal.into(), layout) }
+}
+
+unsafe extern "C" fn free(event: *mut lv_event_t) {
+    drop(unsafe { Box::from_raw(lv_event_get_user_data(event).cast::<Pixels>()) });
+}
@@ -0,0 +1,81 @@
+use alloc::ffi::CString;
+use alloc::format;
+use alloc::vec::Vec;
+
+use lv_bevy_ecs::support::Align;
+use lv_bevy_ecs::sys::lv_obj_set_style_text_font;
+use lv_bevy_ecs::widgets::{Button, Label};
+
+use super::i18n::Text;
+use super::module::{Resources, UiModule};
+use super::screen::Screen;
+use super::{back_button, builder};
+use crate::ttf::{self, FONT_OFFSET};
+
+const SIZES: [i32; 3] = [12, 20, 32];
+
+/// Sample text rendered with a TrueType font loaded at runtime, at a few sizes.
+pub struct TtfDemo {
+    _back: (Button, Label),
+    _title: Label,
+    _samples: Vec<Label>,
+}
+
+impl TtfDemo {
+    /// Builds the demo on the active screen. The back button loads `home`.
+    pub fn new(home: Screen, font: Option<&'static [u8]>) -> Self {
+        let back = back_button(home);
+
+        let title = builder::label()
+            .translated(Text::Fonts)
+            .align(Align::TopMid)
+            .offset(0, 12)
+            .build();
+
+        let mut samples = Vec::new();
+        let Some(data) = font else {
+            let text = format!(
+                "No TrueType font in flash.\nFlash one with\nespflash write-bin {:#x} font.ttf",
+                FONT_OFFSET
+            );
+            let mut label = Label::new();
+            label.set_text(CString::new(text).unwrap().as_c_str());
+            label.center();
+            samples.push(label);
+            return Self {
+                _back: back,
+                _title: title,
+                _samples: samples,
+            };
+        };
+
+        let mut y = 45;
+        for size in SIZES {
+            let mut label = Label::new();
+            let text = CString::new(format!("{} px: The quick brown fox", size)).unwrap();
+            label.set_text(text.as_c_str());
+            label.align(Align::TopLeft.into(), 10, y);
+            match ttf::create_font(data, size) {
+                Some(font) => unsafe { lv_obj_set_style_text_font(label.raw(), font.as_ptr(), 0) },
+                None => defmt::error!("Could not create {} px font", size),
+            }
+            y += size + 16;
+            samples.push(label);
+        }
+
+        Self {
+            _back: back,
+            _title: title,
+            _samples: samples,
+        }
+    }
+}
+
+impl UiModule for TtfDemo {
+    const NAME: Text = Text::Fonts;
+    const RESIDENT: bool = true;
+
+    fn build(home: Screen, resources: &mut Resources) -> Self {
+        Self::new(home, *resources.get::<Option<&'static [u8]>>())
+    }
+}
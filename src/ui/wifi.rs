@@ -0,0 +1,252 @@
+use alloc::ffi::CString;
+use alloc::format;
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::ffi::CStr;
+
+use lv_bevy_ecs::events::EventCode;
+use lv_bevy_ecs::support::Align;
+use lv_bevy_ecs::sys::{
+    LV_ANIM_OFF, lv_bar_set_range, lv_bar_set_value, lv_keyboard_set_textarea,
+    lv_textarea_get_text, lv_textarea_set_one_line, lv_textarea_set_password_mode,
+    lv_textarea_set_text,
+};
+use lv_bevy_ecs::widgets::{Bar, Button, Keyboard, Label, List, Obj, Textarea};
+
+use super::i18n::Text;
+use super::module::{Resources, UiModule};
+use super::screen::Screen;
+use super::status_bar;
+use super::timer::Timer;
+use super::{back_button, builder, set_visible, translated_button};
+use crate::wifi::{self, Event, Network};
+
+const POLL_PERIOD_MS: u32 = 200;
+/// RSSI shown as an empty and a full bar
+const RSSI_RANGE: (i32, i32) = (-90, -40);
+
+struct Row {
+    _button: Button,
+    _ssid: Label,
+    _details: Label,
+    _signal: Bar,
+}
+
+/// Password prompt shown over the list for secured networks
+struct Prompt {
+    panel: Obj,
+    title: Label,
+    password: Textarea,
+    _keyboard: Keyboard,
+    cancel: (Button, Label),
+    ssid: String,
+}
+
+impl Prompt {
+    fn new() -> Self {
+        let mut panel = Obj::new();
+        panel.set_size(320, 240 - status_bar::HEIGHT);
+        panel.set_pos(0, 0);
+        set_visible(&mut panel, false);
+
+        let mut title = Label::new();
+        title.set_parent(&mut panel);
+        title.align(Align::TopLeft.into(), 0, 0);
+
+        let mut password = Textarea::new();
+        password.set_parent(&mut panel);
+        password.set_width(280);
+        password.align(Align::TopMid.into(), 0, 30);
+        unsafe {
+            lv_textarea_set_one_line(password.raw(), true);
+            lv_textarea_set_password_mode(password.raw(), true);
+        }
+
+        let mut keyboard = Keyboard::new();
+        keyboard.set_parent(&mut panel);
+        keyboard.set_size(300, 120);
+        keyboard.align(Align::BottomMid.into(), 0, 0);
+        unsafe { lv_keyboard_set_textarea(keyboard.raw(), password.raw()) };
+
+        let mut cancel = translated_button(Text::Cancel);
+        cancel.0.set_parent(&mut panel);
+        cancel.0.align(Align::TopRight.into(), 0, -8);
+
+        Self {
+            panel,
+            title,
+            password,
+            _keyboard: keyboard,
+            cancel,
+            ssid: String::new(),
+        }
+    }
+
+    fn open(&mut self, ssid: String) {
+        let text = format!("Password for {}", ssid);
+        self.title.set_text(label_text(&text).as_c_str());
+        unsafe { lv_textarea_set_text(self.password.raw(), c"".as_ptr()) };
+        self.ssid = ssid;
+        set_visible(&mut self.panel, true);
+    }
+
+    /// Hides the prompt when cancelled and joins the network when the
+    /// keyboard's OK key is pressed.
+    fn connect_events(prompt: &Rc<RefCell<Self>>, status: &Rc<RefCell<Label>>) {
+        let mut this = prompt.borrow_mut();
+        let password_raw = this.password.raw();
+        this.password.add_event_cb(EventCode::Ready, {
+            let prompt = prompt.clone();
+            let status = status.clone();
+            move |_| {
+                let mut prompt = prompt.borrow_mut();
+                let password = unsafe { CStr::from_ptr(lv_textarea_get_text(password_raw)) };
+                let password = String::from(password.to_str().unwrap_or_default());
+                set_visible(&mut prompt.panel, false);
+                join(&status, prompt.ssid.clone(), password);
+            }
+        });
+        let hide = || {
+            let prompt = prompt.clone();
+            move |_| set_visible(&mut prompt.borrow_mut().panel, false)
+        };
+        this.password.add_event_cb(EventCode::Cancel, hide());
+        this.cancel.0.add_event_cb(EventCode::Clicked, hide());
+    }
+}
+
+/// Networks found by the periodic scans of the WiFi task, strongest first.
+///
+/// Tapping an open network joins it right away, secured ones ask for the
+/// password first.
+pub struct WifiScanner {
+    _back: (Button, Label),
+    _title: Label,
+    _timer: Timer,
+}
+
+impl WifiScanner {
+    /// Builds the scanner on the active screen. The back button loads `home`.
+    pub fn new(home: Screen) -> Self {
+        let back = back_button(home);
+
+        let title = builder::label()
+            .translated(Text::Wifi)
+            .align(Align::TopMid)
+            .offset(0, 12)
+            .build();
+
+        let mut status = Label::new();
+        status.set_text_static(Text::Scanning.get());
+        status.align(Align::TopRight.into(), -10, 12);
+        let status = Rc::new(RefCell::new(status));
+
+        let mut list = List::new();
+        list.set_size(300, 180);
+        list.align(Align::BottomMid.into(), 0, -5);
+
+        let prompt = Rc::new(RefCell::new(Prompt::new()));
+        Prompt::connect_events(&prompt, &status);
+
+        let mut rows = Vec::new();
+        let timer = Timer::new(POLL_PERIOD_MS, move || {
+            let Ok(event) = wifi::EVENTS.try_receive() else {
+                return;
+            };
+            let text = match event {
+                Event::Scan(networks) => {
+                    rows.clear();
+                    for network in &networks {
+                        rows.push(row(&mut list, network, &status, &prompt));
+                    }
+                    return;
+                }
+                Event::Connected(ssid) => format!("Connected to {}", ssid),
+                Event::ConnectFailed(ssid) => format!("Could not join {}", ssid),
+            };
+            status.borrow_mut().set_text(label_text(&text).as_c_str());
+        });
+
+        Self {
+            _back: back,
+            _title: title,
+            _timer: timer,
+        }
+    }
+}
+
+impl UiModule for WifiScanner {
+    const NAME: Text = Text::Wifi;
+    const RESIDENT: bool = true;
+
+    fn build(home: Screen, _resources: &mut Resources) -> Self {
+        Self::new(home)
+    }
+}
+
+fn row(
+    list: &mut List,
+    network: &Network,
+    status: &Rc<RefCell<Label>>,
+    prompt: &Rc<RefCell<Prompt>>,
+) -> Row {
+    let mut button = Button::new();
+    button.set_parent(list);
+    button.set_size(280, 44);
+
+    let mut ssid = Label::new();
+    ssid.set_parent(&mut button);
+    ssid.set_text(label_text(&network.ssid).as_c_str());
+    ssid.align(Align::TopLeft.into(), 0, -4);
+
+    let mut details = Label::new();
+    details.set_parent(&mut button);
+    let text = format!(
+        "ch {}  {}  {} dBm",
+        network.channel, network.security, network.rssi
+    );
+    details.set_text(label_text(&text).as_c_str());
+    details.align(Align::BottomLeft.into(), 0, 4);
+
+    let mut signal = Bar::new();
+    signal.set_parent(&mut button);
+    signal.set_size(40, 10);
+    signal.align(Align::RightMid.into(), 0, 0);
+    unsafe {
+        lv_bar_set_range(signal.raw(), RSSI_RANGE.0, RSSI_RANGE.1);
+        lv_bar_set_value(signal.raw(), network.rssi as i32, LV_ANIM_OFF);
+    }
+
+    let (name, open) = (network.ssid.clone(), network.open);
+    button.add_event_cb(EventCode::Clicked, {
+        let status = status.clone();
+        let prompt = prompt.clone();
+        move |_| {
+            if open {
+                join(&status, name.clone(), String::new());
+                return;
+            }
+            prompt.borrow_mut().open(name.clone());
+        }
+    });
+
+    Row {
+        _button: button,
+        _ssid: ssid,
+        _details: details,
+        _signal: signal,
+    }
+}
+
+fn join(status: &Rc<RefCell<Label>>, ssid: String, password: String) {
+    let text = format!("Joining {}...", ssid);
+    status.borrow_mut().set_text(label_text(&text).as_c_str());
+    wifi::connect(ssid, password);
+}
+
+/// SSIDs are any 32 bytes, so one can hold NULs, which a label cannot show.
+fn label_text(text: &str) -> CString {
+    CString::new(text.replace('\0', "")).unwrap()
+}
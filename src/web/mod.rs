@@ -0,0 +1,202 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use embassy_futures::join::join;
+use embassy_net::Stack;
+use embassy_net::tcp::{self, TcpSocket};
+use embassy_time::Duration;
+
+use crate::ui::layout;
+use crate::{journal, json, metrics, mirror};
+
+mod websocket;
+
+pub use websocket::WebSocket;
+
+const PORT: u16 = 80;
+const TIMEOUT: Duration = Duration::from_secs(10);
+/// Enough for the request line and the headers browsers send
+const REQUEST_SIZE: usize = 1024;
+/// Holds a couple of mirrored regions in flight
+const TX_SIZE: usize = 8192;
+/// Layouts are small, larger uploads are most likely the wrong file
+const MAX_LAYOUT_SIZE: usize = 8192;
+/// Version 0.0.4 of the text exposition format
+const PROMETHEUS_TYPE: &str = "text/plain; version=0.0.4";
+
+#[derive(defmt::Format)]
+pub enum Error {
+    Tcp(tcp::Error),
+    /// Closed or malformed before the end of the headers
+    BadRequest,
+}
+
+impl From<tcp::Error> for Error {
+    fn from(error: tcp::Error) -> Self {
+        Error::Tcp(error)
+    }
+}
+
+pub(crate) struct Request<'a> {
+    pub method: &'a str,
+    /// Including the query string
+    pub path: &'a str,
+    pub websocket_key: Option<&'a str>,
+    /// `0` without a `Content-Length` header
+    pub content_length: usize,
+    /// Start of the body, read along with the headers
+    pub body: &'a [u8],
+}
+
+/// Serves two clients at a time on port 80, so a browser watching the
+/// mirror does not hold up other requests.
+///
+/// `/` is the display mirror page and `/ws` the WebSocket it reads from.
+/// A layout `PUT` to `/layout` replaces the one the Layout app shows, and
+/// `/metrics` has the frame and heap counters for Prometheus to scrape.
+#[embassy_executor::task]
+pub async fn serve(stack: Stack<'static>) {
+    stack.wait_config_up().await;
+    if let Some(config) = stack.config_v4() {
+        let [a, b, c, d] = config.address.address().octets();
+        defmt::info!("Web server at http://{}.{}.{}.{}/", a, b, c, d);
+        journal::record(format!("Web server at http://{a}.{b}.{c}.{d}/"));
+    }
+    join(accept(stack), accept(stack)).await;
+}
+
+/// Answers one connection after another, each on a socket of its own.
+async fn accept(stack: Stack<'static>) {
+    let mut rx = [0; REQUEST_SIZE];
+    let mut tx = [0; TX_SIZE];
+    loop {
+        let mut socket = TcpSocket::new(stack, &mut rx, &mut tx);
+        socket.set_timeout(Some(TIMEOUT));
+        if let Err(error) = socket.accept(PORT).await {
+            defmt::warn!("Could not accept connection: {:?}", error);
+            continue;
+        }
+        if let Err(error) = handle(&mut socket).await {
+            defmt::warn!("Web request failed: {:?}", error);
+        }
+        socket.close();
+        let _ = socket.flush().await;
+    }
+}
+
+async fn handle(socket: &mut TcpSocket<'_>) -> Result<(), Error> {
+    let mut buffer = [0; REQUEST_SIZE];
+    let request = read_request(socket, &mut buffer).await?;
+    match (request.method, request.path, request.websocket_key) {
+        (_, "/", _) => respond(socket, "200 OK", "text/html", mirror::PAGE.as_bytes()).await,
+        (_, "/ws", Some(key)) => {
+            let mut websocket = WebSocket::accept(socket, key).await?;
+            mirror::stream(&mut websocket).await
+        }
+        ("PUT" | "POST", "/layout", _) => receive_layout(socket, &request).await,
+        ("GET", "/metrics", _) => {
+            let text = metrics::snapshot().prometheus();
+            respond(socket, "200 OK", PROMETHEUS_TYPE, text.as_bytes()).await
+        }
+        _ => respond(socket, "404 Not Found", "text/plain", b"Not found").await,
+    }
+}
+
+/// Hands an uploaded layout to the Layout app, once it parses.
+async fn receive_layout(socket: &mut TcpSocket<'_>, request: &Request<'_>) -> Result<(), Error> {
+    if request.content_length > MAX_LAYOUT_SIZE {
+        return respond(socket, "413 Payload Too Large", "text/plain", b"Too large").await;
+    }
+    let mut body = Vec::from(request.body);
+    body.resize(request.content_length, 0);
+    let mut len = request.body.len().min(body.len());
+    while len < body.len() {
+        match socket.read(&mut body[len..]).await? {
+            0 => return Err(Error::BadRequest),
+            read => len += read,
+        }
+    }
+
+    let Ok(text) = String::from_utf8(body) else {
+        return respond(socket, "400 Bad Request", "text/plain", b"Not UTF-8").await;
+    };
+    if let Err(error) = json::parse(&text) {
+        let message = format!("Byte {}: {}\n", error.offset, error.message);
+        return respond(socket, "400 Bad Request", "text/plain", message.as_bytes()).await;
+    }
+    journal::record(format!("Layout of {} bytes uploaded", text.len()));
+    layout::upload(text);
+    respond(socket, "200 OK", "text/plain", b"Reloaded\n").await
+}
+
+/// Reads up to the blank line after the headers. Only what came along of the
+/// body is read, see [`Request::body`].
+pub(crate) async fn read_request<'a>(
+    socket: &mut TcpSocket<'_>,
+    buffer: &'a mut [u8],
+) -> Result<Request<'a>, Error> {
+    let mut len = 0;
+    let end = loop {
+        if let Some(end) = buffer[..len]
+            .windows(4)
+            .position(|window| window == b"\r\n\r\n")
+        {
+            break end;
+        }
+        if len == buffer.len() {
+            return Err(Error::BadRequest);
+        }
+        match socket.read(&mut buffer[len..]).await? {
+            0 => return Err(Error::BadRequest),
+            read => len += read,
+        }
+    };
+
+    let (head, body) = buffer[..len].split_at(end + 4);
+    let head = core::str::from_utf8(head).map_err(|_| Error::BadRequest)?;
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next().ok_or(Error::BadRequest)?.split(' ');
+    let method = request_line.next().ok_or(Error::BadRequest)?;
+    let path = request_line.next().ok_or(Error::BadRequest)?;
+    let mut websocket_key = None;
+    let mut content_length = 0;
+    for line in lines {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        if name.eq_ignore_ascii_case("sec-websocket-key") {
+            websocket_key = Some(value.trim());
+        } else if name.eq_ignore_ascii_case("content-length") {
+            content_length = value.trim().parse().map_err(|_| Error::BadRequest)?;
+        }
+    }
+    Ok(Request {
+        method,
+        path,
+        websocket_key,
+        content_length,
+        body,
+    })
+}
+
+pub(crate) async fn respond(
+    socket: &mut TcpSocket<'_>,
+    status: &str,
+    content_type: &str,
+    body: &[u8],
+) -> Result<(), Error> {
+    let head = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    );
+    write_all(socket, head.as_bytes()).await?;
+    write_all(socket, body).await
+}
+
+async fn write_all(socket: &mut TcpSocket<'_>, mut data: &[u8]) -> Result<(), Error> {
+    while !data.is_empty() {
+        let written = socket.write(data).await?;
+        data = &data[written..];
+    }
+    Ok(())
+}
@@ -0,0 +1,38 @@
+use alloc::format;
+use embassy_net::tcp::TcpSocket;
+use logic::websocket;
+
+use super::{Error, write_all};
+
+/// Server side of a WebSocket, send only.
+///
+/// Browsers only send on their own to close the connection, so anything
+/// received is taken as the client going away.
+pub struct WebSocket<'a, 'b> {
+    socket: &'a mut TcpSocket<'b>,
+}
+
+impl<'a, 'b> WebSocket<'a, 'b> {
+    /// Completes the upgrade handshake for a request carrying `key`.
+    pub async fn accept(socket: &'a mut TcpSocket<'b>, key: &str) -> Result<Self, Error> {
+        let accept = websocket::accept_key(key);
+        let head = format!(
+            "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {accept}\r\n\r\n"
+        );
+        write_all(socket, head.as_bytes()).await?;
+        Ok(Self { socket })
+    }
+
+    /// Sends `data` as a single unmasked binary frame.
+    pub async fn send_binary(&mut self, data: &[u8]) -> Result<(), Error> {
+        let header = websocket::binary_header(data.len());
+        write_all(self.socket, &header).await?;
+        write_all(self.socket, data).await
+    }
+
+    /// Resolves once the client closes the connection or sends anything.
+    pub async fn closed(&mut self) {
+        let mut buffer = [0; 1];
+        let _ = self.socket.read(&mut buffer).await;
+    }
+}
@@ -0,0 +1,248 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cell::Cell;
+use core::sync::atomic::{AtomicBool, AtomicI8, Ordering};
+
+use embassy_futures::select::{Either, Either3, select, select3};
+use embassy_sync::blocking_mutex::Mutex;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::channel::Channel;
+use embassy_sync::signal::Signal;
+use embassy_time::{Duration, Timer};
+use esp_radio::wifi::{
+    AccessPointConfig, AuthMethod, ClientConfig, ModeConfig, ScanConfig, WifiController, WifiError,
+};
+
+use crate::boot::{self, Stage};
+use crate::journal;
+use crate::power;
+use crate::ui::toast::{self, Severity};
+
+const SCAN_PERIOD: Duration = Duration::from_secs(10);
+const SAVER_SCAN_PERIOD: Duration = Duration::from_secs(60);
+const MAX_NETWORKS: usize = 16;
+
+/// Access point found by a scan.
+pub struct Network {
+    pub ssid: String,
+    pub channel: u8,
+    /// In dBm
+    pub rssi: i8,
+    pub security: String,
+    pub open: bool,
+}
+
+/// What the WiFi task reports back to the UI.
+pub enum Event {
+    /// Networks from the latest scan, strongest first
+    Scan(Vec<Network>),
+    Connected(String),
+    ConnectFailed(String),
+}
+
+pub static EVENTS: Channel<CriticalSectionRawMutex, Event, 4> = Channel::new();
+static CONNECT: Signal<CriticalSectionRawMutex, (String, String)> = Signal::new();
+/// RSSI of the joined network, `i8::MIN` while not connected
+static SIGNAL: AtomicI8 = AtomicI8::new(i8::MIN);
+static ENABLED: AtomicBool = AtomicBool::new(true);
+static ENABLED_CHANGED: Signal<CriticalSectionRawMutex, ()> = Signal::new();
+static ACCESS_POINT: Signal<CriticalSectionRawMutex, String> = Signal::new();
+static JOINED: Mutex<CriticalSectionRawMutex, Cell<Option<(String, String)>>> =
+    Mutex::new(Cell::new(None));
+
+/// RSSI in dBm of the joined network as of the last scan.
+pub fn signal_strength() -> Option<i8> {
+    match SIGNAL.load(Ordering::Relaxed) {
+        i8::MIN => None,
+        rssi => Some(rssi),
+    }
+}
+
+/// Asks the WiFi task to join `ssid`. Leave `password` empty for open networks.
+pub fn connect(ssid: String, password: String) {
+    CONNECT.signal((ssid, password));
+}
+
+/// Turns the radio on or off. It starts out on.
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+    ENABLED_CHANGED.signal(());
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Credentials of the last network joined, taken once by whoever stores them.
+pub fn take_joined() -> Option<(String, String)> {
+    JOINED.lock(|joined| joined.take())
+}
+
+/// Replaces station mode with an open access point named `ssid` for first
+/// boot setup. The next [`connect`] goes back to station mode, or returns to
+/// the access point if joining fails.
+pub fn start_access_point(ssid: String) {
+    ACCESS_POINT.signal(ssid);
+}
+
+/// Scans periodically and joins networks requested through [`connect`].
+#[embassy_executor::task]
+pub async fn run(mut controller: WifiController<'static>) {
+    if let Err(error) = controller.set_config(&ModeConfig::Client(ClientConfig::default())) {
+        defmt::error!("Could not configure WiFi: {:?}", error);
+        return;
+    }
+    let mut started = false;
+    let mut joined: Option<String> = None;
+    // SSID of the setup access point while it is up
+    let mut access_point: Option<String> = None;
+    loop {
+        if !is_enabled() {
+            if started {
+                if let Err(error) = controller.stop_async().await {
+                    defmt::warn!("Could not stop WiFi: {:?}", error);
+                }
+                started = false;
+                joined = None;
+                SIGNAL.store(i8::MIN, Ordering::Relaxed);
+            }
+            ENABLED_CHANGED.wait().await;
+            continue;
+        }
+        if !started {
+            if let Err(error) = controller.start_async().await {
+                defmt::error!("Could not start WiFi: {:?}", error);
+                ENABLED_CHANGED.wait().await;
+                continue;
+            }
+            started = true;
+        }
+
+        if let Some(ssid) = ACCESS_POINT.try_take() {
+            host(&mut controller, &ssid).await;
+            access_point = Some(ssid);
+        }
+        if let Some(ap_ssid) = &access_point {
+            // Scanning would take the radio off the channel of the access point
+            let Either::First((ssid, password)) =
+                select(CONNECT.wait(), ENABLED_CHANGED.wait()).await
+            else {
+                continue;
+            };
+            if let Err(error) =
+                reconfigure(&mut controller, ModeConfig::Client(ClientConfig::default())).await
+            {
+                defmt::error!("Could not leave access point mode: {:?}", error);
+                continue;
+            }
+            if join(&mut controller, ssid.clone(), password).await {
+                joined = Some(ssid);
+                access_point = None;
+            } else {
+                host(&mut controller, ap_ssid).await;
+            }
+            continue;
+        }
+
+        if !matches!(controller.is_connected(), Ok(true)) {
+            joined = None;
+            SIGNAL.store(i8::MIN, Ordering::Relaxed);
+        }
+
+        match controller
+            .scan_with_config_async(ScanConfig::default().with_max(MAX_NETWORKS))
+            .await
+        {
+            Ok(mut found) => {
+                found.sort_by_key(|ap| -(ap.signal_strength as i16));
+                if let Some(ap) = joined
+                    .as_ref()
+                    .and_then(|ssid| found.iter().find(|ap| ap.ssid.as_str() == ssid.as_str()))
+                {
+                    SIGNAL.store(ap.signal_strength.max(i8::MIN + 1), Ordering::Relaxed);
+                }
+                let networks = found
+                    .into_iter()
+                    .map(|ap| Network {
+                        open: matches!(ap.auth_method, None | Some(AuthMethod::None)),
+                        security: match ap.auth_method {
+                            Some(method) => format!("{:?}", method),
+                            None => String::from("None"),
+                        },
+                        ssid: ap.ssid.into(),
+                        channel: ap.channel,
+                        rssi: ap.signal_strength,
+                    })
+                    .collect();
+                // Drop the result rather than wait if the UI is not reading
+                let _ = EVENTS.try_send(Event::Scan(networks));
+            }
+            Err(error) => defmt::warn!("WiFi scan failed: {:?}", error),
+        }
+
+        let period = if power::saver_active() {
+            SAVER_SCAN_PERIOD
+        } else {
+            SCAN_PERIOD
+        };
+        let Either3::Second((ssid, password)) =
+            select3(Timer::after(period), CONNECT.wait(), ENABLED_CHANGED.wait()).await
+        else {
+            continue;
+        };
+        if join(&mut controller, ssid.clone(), password).await {
+            joined = Some(ssid);
+        }
+    }
+}
+
+/// Joins `ssid` and reports the result to the UI.
+async fn join(controller: &mut WifiController<'static>, ssid: String, password: String) -> bool {
+    defmt::info!("Connecting to {}", ssid.as_str());
+    let config = ClientConfig::default()
+        .with_ssid(ssid.clone())
+        .with_password(password.clone());
+    let result = match controller.set_config(&ModeConfig::Client(config)) {
+        Ok(()) => controller.connect_async().await,
+        Err(error) => Err(error),
+    };
+    let (event, ok) = match result {
+        Ok(()) => {
+            journal::record(format!("WiFi: joined {}", ssid));
+            toast::show(Severity::Success, format!("WiFi connected to {}", ssid));
+            JOINED.lock(|joined| joined.set(Some((ssid.clone(), password))));
+            (Event::Connected(ssid), true)
+        }
+        Err(error) => {
+            defmt::warn!("Could not connect: {:?}", error);
+            journal::record(format!("WiFi: could not join {}", ssid));
+            toast::show(Severity::Error, format!("Could not join {}", ssid));
+            // SNTP has no way to set the clock then
+            boot::complete(Stage::TimeSync);
+            (Event::ConnectFailed(ssid), false)
+        }
+    };
+    boot::complete(Stage::Wifi);
+    EVENTS.send(event).await;
+    ok
+}
+
+/// Brings up the open setup access point.
+async fn host(controller: &mut WifiController<'static>, ssid: &str) {
+    let config = AccessPointConfig::default().with_ssid(ssid.into());
+    match reconfigure(controller, ModeConfig::AccessPoint(config)).await {
+        Ok(()) => journal::record(format!("WiFi: setup network {}", ssid)),
+        Err(error) => defmt::error!("Could not start the access point: {:?}", error),
+    }
+}
+
+/// Restarts the radio in another mode.
+async fn reconfigure(
+    controller: &mut WifiController<'static>,
+    config: ModeConfig,
+) -> Result<(), WifiError> {
+    controller.stop_async().await?;
+    controller.set_config(&config)?;
+    controller.start_async().await
+}